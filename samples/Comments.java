@@ -0,0 +1,11 @@
+/**
+ * A class whose source is full of comments.
+ */
+public class Comments {
+    // A line comment before a method
+    public static void main(String[] args) { /* an inline block comment */
+        System.out.println("// not a comment /* either */"); // trailing comment
+        /* a block comment /* that looks nested
+           across lines */
+    }
+}
@@ -0,0 +1,34 @@
+public abstract class Declarations extends Object implements Runnable, java.lang.Comparable<Declarations> {
+    private static final String GREETING = "Hello";
+    protected transient int count, limit = 10;
+    java.util.Map<String, java.util.List<String>> index;
+
+    static {
+        System.out.println(GREETING);
+    }
+
+    {
+        System.out.println("instance");
+    }
+
+    public Declarations(final int count) {
+        System.out.println(count);
+    }
+
+    Declarations() {
+        this(10);
+    }
+
+    abstract protected void describe();
+
+    synchronized static public int[] counts(String first, String... rest)[] {
+        System.out.println(first);
+    }
+
+    @Deprecated(since = "1")
+    native long now();
+
+    static final class Nested<T extends Comparable<T>> {
+        private T value;
+    }
+}
@@ -0,0 +1,17 @@
+public interface Greeter extends Runnable, Comparable<Greeter> {
+    String GREETING = "Hello", FAREWELL = "Bye";
+
+    String name();
+
+    default void greet() {
+        System.out.println(GREETING);
+    }
+
+    static void announce() {
+        System.out.println("announcing");
+    }
+
+    private void whisper() {
+        System.out.println("psst");
+    }
+}
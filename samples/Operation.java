@@ -0,0 +1,22 @@
+public enum Operation implements Runnable {
+    @Deprecated
+    PLUS("+") {
+        public void run() {
+            System.out.println("adding");
+        }
+    },
+    TIMES("*"),
+    NONE,;
+
+    private Operation() {
+        this("?");
+    }
+
+    Operation(String symbol) {
+        System.out.println(symbol);
+    }
+
+    public void run() {
+        System.out.println("running");
+    }
+}
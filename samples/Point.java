@@ -0,0 +1,19 @@
+public record Point(int x, @Deprecated int y) implements Comparable<Point> {
+    static int created = 0;
+
+    public Point {
+        System.out.println("validating");
+    }
+
+    public Point(int x) {
+        this(x, 0);
+    }
+
+    static {
+        System.out.println("loaded");
+    }
+
+    public void describe() {
+        System.out.println("a point");
+    }
+}
@@ -1,4 +1,4 @@
-public class Simple {
+public class StringVariableAssignment {
     public static void main(String[] args) {
         String variable = "a string variable";
         System.out.println(variable);
@@ -1,4 +1,6 @@
+use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
+use crate::scanner::SourceSpan;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum AstScope {
@@ -8,13 +10,58 @@ pub enum AstScope {
     Default,
 }
 
+/// A type as written in a declaration. Type arguments are not kept, as they are erased on
+/// compilation. The name is either a keyword such as `int` or `void`, or a possibly qualified class name.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AstType<'a> {
+    name: &'a str,
+    array_dimensions: usize,
+}
+
+impl<'a> AstType<'a> {
+    pub fn new(name: &'a str, array_dimensions: usize) -> Self {
+        Self { name, array_dimensions }
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn array_dimensions(&self) -> usize {
+        self.array_dimensions
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.array_dimensions > 0
+    }
+}
+
+impl<'a> From<&'a str> for AstType<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::new(name, 0)
+    }
+}
+
 #[derive(Debug)]
 pub struct AstClass<'a> {
     name: &'a str,
     scope: AstScope,
     is_static: bool,
     is_final: bool,
+    is_abstract: bool,
+    is_interface: bool,
+    is_enum: bool,
+    is_record: bool,
+    super_class: Option<&'a str>,
+    interfaces: Vec<&'a str>,
+    enum_constants: Vec<AstEnumConstant<'a>>,
+    record_components: Vec<AstParameter<'a>>,
+    fields: Vec<AstField<'a>>,
     methods: Vec<AstMethod<'a>>,
+    constructors: Vec<AstMethod<'a>>,
+    initializers: Vec<AstInitializer<'a>>,
+    member_types: Vec<AstClass<'a>>,
+    span: SourceSpan,
 }
 
 impl<'a> AstClass<'a> {
@@ -30,10 +77,76 @@ impl<'a> AstClass<'a> {
             scope,
             is_static,
             is_final,
+            is_abstract: false,
+            is_interface: false,
+            is_enum: false,
+            is_record: false,
+            super_class: None,
+            interfaces: vec![],
+            enum_constants: vec![],
+            record_components: vec![],
+            fields: vec![],
             methods,
+            constructors: vec![],
+            initializers: vec![],
+            member_types: vec![],
+            span: SourceSpan::default(),
         }
     }
 
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn as_abstract(self) -> Self {
+        Self { is_abstract: true, ..self }
+    }
+
+    /// Interfaces are always abstract, as they are in the class file
+    pub fn as_interface(self) -> Self {
+        Self { is_interface: true, is_abstract: true, ..self }
+    }
+
+    pub fn as_enum(self) -> Self {
+        Self { is_enum: true, ..self }
+    }
+
+    pub fn with_enum_constants(self, enum_constants: Vec<AstEnumConstant<'a>>) -> Self {
+        Self { enum_constants, ..self }
+    }
+
+    pub fn as_record(self, record_components: Vec<AstParameter<'a>>) -> Self {
+        Self { is_record: true, record_components, ..self }
+    }
+
+    pub fn extending(self, super_class: &'a str) -> Self {
+        Self { super_class: Some(super_class), ..self }
+    }
+
+    pub fn implementing(self, interfaces: Vec<&'a str>) -> Self {
+        Self { interfaces, ..self }
+    }
+
+    pub fn with_fields(self, fields: Vec<AstField<'a>>) -> Self {
+        Self { fields, ..self }
+    }
+
+    pub fn with_constructors(self, constructors: Vec<AstMethod<'a>>) -> Self {
+        Self { constructors, ..self }
+    }
+
+    pub fn with_initializers(self, initializers: Vec<AstInitializer<'a>>) -> Self {
+        Self { initializers, ..self }
+    }
+
+    pub fn with_member_types(self, member_types: Vec<AstClass<'a>>) -> Self {
+        Self { member_types, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
     pub fn name(&self) -> &str {
         self.name
     }
@@ -46,9 +159,128 @@ impl<'a> AstClass<'a> {
     pub fn is_final(&self) -> bool {
         self.is_final
     }
+    pub fn is_abstract(&self) -> bool {
+        self.is_abstract
+    }
+    pub fn is_interface(&self) -> bool {
+        self.is_interface
+    }
+    pub fn is_enum(&self) -> bool {
+        self.is_enum
+    }
+    /// The constants of an enum, in declaration order
+    pub fn enum_constants(&self) -> &Vec<AstEnumConstant<'a>> {
+        &self.enum_constants
+    }
+    pub fn is_record(&self) -> bool {
+        self.is_record
+    }
+    /// The components in the header of a record, in declaration order
+    pub fn record_components(&self) -> &Vec<AstParameter<'a>> {
+        &self.record_components
+    }
+    /// The superclass as written in the `extends` clause, or `None` when there is no clause
+    pub fn super_class(&self) -> Option<&'a str> {
+        self.super_class
+    }
+    /// The interfaces as written in the `implements` clause, or the `extends` clause of an interface
+    pub fn interfaces(&self) -> &Vec<&'a str> {
+        &self.interfaces
+    }
+    pub fn fields(&self) -> &Vec<AstField<'a>> {
+        &self.fields
+    }
     pub fn methods(&self) -> &Vec<AstMethod<'a>> {
         &self.methods
     }
+    /// Constructors are methods named `<init>` returning `void`, as they are in the class file
+    pub fn constructors(&self) -> &Vec<AstMethod<'a>> {
+        &self.constructors
+    }
+    /// Instance and static initializer blocks, in declaration order
+    pub fn initializers(&self) -> &Vec<AstInitializer<'a>> {
+        &self.initializers
+    }
+    pub fn member_types(&self) -> &Vec<AstClass<'a>> {
+        &self.member_types
+    }
+}
+
+#[derive(Debug)]
+pub struct AstField<'a> {
+    name: &'a str,
+    field_type: AstType<'a>,
+    scope: AstScope,
+    is_static: bool,
+    is_final: bool,
+    is_transient: bool,
+    is_volatile: bool,
+    initializer: Option<Expression<'a>>,
+    span: SourceSpan,
+}
+
+impl<'a> AstField<'a> {
+    pub fn new(
+        name: &'a str,
+        field_type: AstType<'a>,
+        scope: AstScope,
+        is_static: bool,
+        is_final: bool,
+        initializer: Option<Expression<'a>>,
+    ) -> Self {
+        Self {
+            name,
+            field_type,
+            scope,
+            is_static,
+            is_final,
+            is_transient: false,
+            is_volatile: false,
+            initializer,
+            span: SourceSpan::default(),
+        }
+    }
+
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn as_transient(self) -> Self {
+        Self { is_transient: true, ..self }
+    }
+
+    pub fn as_volatile(self) -> Self {
+        Self { is_volatile: true, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+    pub fn field_type(&self) -> AstType<'a> {
+        self.field_type
+    }
+    pub fn scope(&self) -> AstScope {
+        self.scope
+    }
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+    pub fn is_transient(&self) -> bool {
+        self.is_transient
+    }
+    pub fn is_volatile(&self) -> bool {
+        self.is_volatile
+    }
+    pub fn initializer(&self) -> Option<&Expression<'a>> {
+        self.initializer.as_ref()
+    }
 }
 
 #[derive(Debug)]
@@ -57,9 +289,14 @@ pub struct AstMethod<'a> {
     scope: AstScope,
     is_final: bool,
     is_static: bool,
-    return_type: &'a str,
+    is_abstract: bool,
+    is_synchronized: bool,
+    is_native: bool,
+    is_compact: bool,
+    return_type: AstType<'a>,
     parameters: Vec<AstParameter<'a>>,
     statements: Vec<Statement<'a>>,
+    span: SourceSpan,
 }
 
 impl<'a> AstMethod<'a> {
@@ -68,7 +305,7 @@ impl<'a> AstMethod<'a> {
         scope: AstScope,
         is_final: bool,
         is_static: bool,
-        return_type: &'a str,
+        return_type: impl Into<AstType<'a>>,
         parameters: Vec<AstParameter<'a>>,
         statements: Vec<Statement<'a>>,
     ) -> Self {
@@ -77,12 +314,43 @@ impl<'a> AstMethod<'a> {
             scope,
             is_final,
             is_static,
-            return_type,
+            is_abstract: false,
+            is_synchronized: false,
+            is_native: false,
+            is_compact: false,
+            return_type: return_type.into(),
             parameters,
             statements,
+            span: SourceSpan::default(),
         }
     }
 
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn as_abstract(self) -> Self {
+        Self { is_abstract: true, ..self }
+    }
+
+    pub fn as_synchronized(self) -> Self {
+        Self { is_synchronized: true, ..self }
+    }
+
+    pub fn as_native(self) -> Self {
+        Self { is_native: true, ..self }
+    }
+
+    /// A compact canonical constructor of a record, which takes the record components as its
+    /// parameters and assigns them to the fields after its body
+    pub fn as_compact(self) -> Self {
+        Self { is_compact: true, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
     pub fn name(&self) -> &'a str {
         self.name
     }
@@ -99,7 +367,23 @@ impl<'a> AstMethod<'a> {
         self.is_static
     }
 
-    pub fn return_type(&self) -> &'a str {
+    pub fn is_abstract(&self) -> bool {
+        self.is_abstract
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.is_synchronized
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.is_native
+    }
+
+    pub fn is_compact(&self) -> bool {
+        self.is_compact
+    }
+
+    pub fn return_type(&self) -> AstType<'a> {
         self.return_type
     }
     pub fn parameters(&self) -> &Vec<AstParameter<'a>> {
@@ -110,30 +394,120 @@ impl<'a> AstMethod<'a> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AstParameter<'a> {
     param_name: &'a str,
-    param_type: &'a str,
-    is_array: bool,
+    param_type: AstType<'a>,
+    is_final: bool,
+    span: SourceSpan,
 }
 
 impl <'a> AstParameter<'a> {
-    pub fn new(param_name: &'a str, param_type: &'a str, is_array: bool) -> Self {
+    pub fn new(param_name: &'a str, param_type: impl Into<AstType<'a>>) -> Self {
         Self {
             param_name,
-            param_type,
-            is_array,
+            param_type: param_type.into(),
+            is_final: false,
+            span: SourceSpan::default(),
         }
     }
 
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn as_final(self) -> Self {
+        Self { is_final: true, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
     pub fn param_name(&self) -> &'a str {
         self.param_name
     }
-    pub fn param_type(&self) -> &'a str {
+    pub fn param_type(&self) -> AstType<'a> {
         self.param_type
     }
 
-    pub fn is_array(&self) -> bool {
-        self.is_array
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+}
+
+/// An instance `{ ... }` or `static { ... }` initializer block in a class body
+#[derive(Debug)]
+pub struct AstInitializer<'a> {
+    is_static: bool,
+    statements: Vec<Statement<'a>>,
+    span: SourceSpan,
+}
+
+impl<'a> AstInitializer<'a> {
+    pub fn new(is_static: bool, statements: Vec<Statement<'a>>) -> Self {
+        Self {
+            is_static,
+            statements,
+            span: SourceSpan::default(),
+        }
+    }
+
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+    pub fn statements(&self) -> &Vec<Statement<'a>> {
+        &self.statements
+    }
+}
+
+/// A constant of an enum, created with the given arguments. A constant with a class body is an
+/// instance of an anonymous subclass of the enum.
+#[derive(Debug)]
+pub struct AstEnumConstant<'a> {
+    name: &'a str,
+    arguments: Vec<Expression<'a>>,
+    body: Option<AstClass<'a>>,
+    span: SourceSpan,
+}
+
+impl<'a> AstEnumConstant<'a> {
+    pub fn new(name: &'a str, arguments: Vec<Expression<'a>>) -> Self {
+        Self {
+            name,
+            arguments,
+            body: None,
+            span: SourceSpan::default(),
+        }
+    }
+
+    pub fn with_body(self, body: AstClass<'a>) -> Self {
+        Self { body: Some(body), ..self }
+    }
+
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+    pub fn arguments(&self) -> &Vec<Expression<'a>> {
+        &self.arguments
+    }
+    pub fn body(&self) -> Option<&AstClass<'a>> {
+        self.body.as_ref()
     }
 }
@@ -1,6 +1,7 @@
-use crate::ast::class::{AstClass, AstMethod, AstParameter, AstScope};
+use crate::ast::class::{AstClass, AstEnumConstant, AstField, AstInitializer, AstMethod, AstParameter, AstScope, AstType};
 use crate::ast::class_builder::AstScope::Default;
 use crate::ast::statement::Statement;
+use crate::scanner::SourceSpan;
 
 pub trait Build<T> {
     fn build(self) -> T;
@@ -11,7 +12,20 @@ pub struct ClassBuilder<'a> {
     scope: AstScope,
     is_static: bool,
     is_final: bool,
-    methods: Vec<MethodBuilder<'a>>,
+    is_abstract: bool,
+    is_interface: bool,
+    is_enum: bool,
+    is_record: bool,
+    super_class: Option<&'a str>,
+    interfaces: Vec<&'a str>,
+    enum_constants: Vec<AstEnumConstant<'a>>,
+    record_components: Vec<AstParameter<'a>>,
+    fields: Vec<AstField<'a>>,
+    methods: Vec<AstMethod<'a>>,
+    constructors: Vec<AstMethod<'a>>,
+    initializers: Vec<AstInitializer<'a>>,
+    member_types: Vec<AstClass<'a>>,
+    span: Option<SourceSpan>,
 }
 
 impl<'a> ClassBuilder<'a> {
@@ -21,7 +35,20 @@ impl<'a> ClassBuilder<'a> {
             scope: Default,
             is_static: false,
             is_final: false,
+            is_abstract: false,
+            is_interface: false,
+            is_enum: false,
+            is_record: false,
+            super_class: None,
+            interfaces: vec![],
+            enum_constants: vec![],
+            record_components: vec![],
+            fields: vec![],
             methods: vec![],
+            constructors: vec![],
+            initializers: vec![],
+            member_types: vec![],
+            span: None,
         }
     }
 
@@ -29,29 +56,93 @@ impl<'a> ClassBuilder<'a> {
         self.scope = scope
     }
 
-    #[allow(dead_code)]
+    /// Extends the span of the class to cover the given token
+    pub fn spanning(&mut self, span: SourceSpan) {
+        self.span = Some(self.span.map_or(span, |start| start.to(span)))
+    }
+
     pub fn as_static(&mut self) {
         self.is_static = true
     }
 
-    #[allow(dead_code)]
     pub fn as_final(&mut self) {
         self.is_final = true
     }
 
-    pub fn with_new_method(&mut self) {
-        self.methods.push(MethodBuilder::new())
+    pub fn as_abstract(&mut self) {
+        self.is_abstract = true
+    }
+
+    pub fn as_interface(&mut self) {
+        self.is_interface = true
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.is_interface
+    }
+
+    pub fn as_enum(&mut self) {
+        self.is_enum = true
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.is_enum
+    }
+
+    pub fn with_enum_constant(&mut self, enum_constant: AstEnumConstant<'a>) {
+        self.enum_constants.push(enum_constant)
+    }
+
+    pub fn as_record(&mut self) {
+        self.is_record = true
+    }
+
+    pub fn is_record(&self) -> bool {
+        self.is_record
+    }
+
+    pub fn with_record_component(&mut self, record_component: AstParameter<'a>) {
+        self.record_components.push(record_component)
+    }
+
+    pub fn record_components(&self) -> &Vec<AstParameter<'a>> {
+        &self.record_components
     }
 
     pub fn named(&mut self, name: &'a str) {
         self.name = Some(name)
     }
 
-    pub fn latest_method(&mut self) -> &mut MethodBuilder<'a> {
-        match self.methods.last_mut() {
-            Some(m) => m,
-            None => panic!("Expected method to exist"),
-        }
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    pub fn extending(&mut self, super_class: &'a str) {
+        self.super_class = Some(super_class)
+    }
+
+    pub fn implementing(&mut self, interface: &'a str) {
+        self.interfaces.push(interface)
+    }
+
+    pub fn with_field(&mut self, field: AstField<'a>) {
+        self.fields.push(field)
+    }
+
+    pub fn with_method(&mut self, method: AstMethod<'a>) {
+        self.methods.push(method)
+    }
+
+    pub fn with_constructor(&mut self, constructor: AstMethod<'a>) {
+        self.constructors.push(constructor)
+    }
+
+    pub fn with_initializer(&mut self, initializer: AstInitializer<'a>) {
+        self.initializers.push(initializer)
+    }
+
+    pub fn with_member_type(&mut self, member_type: AstClass<'a>) {
+        self.member_types.push(member_type)
     }
 }
 
@@ -61,18 +152,29 @@ impl <'a> Build<AstClass<'a>> for ClassBuilder<'a> {
             panic!("Name was not set")
         };
 
-        let mut ast_methods: Vec<AstMethod> = vec![];
-        for method_builder in self.methods {
-            ast_methods.push(method_builder.build());
-        }
-
-        AstClass::new(
+        let class = AstClass::new(
             name,
             self.scope,
             self.is_static,
             self.is_final,
-            ast_methods,
+            self.methods,
         )
+        .implementing(self.interfaces)
+        .with_enum_constants(self.enum_constants)
+        .with_fields(self.fields)
+        .with_constructors(self.constructors)
+        .with_initializers(self.initializers)
+        .with_member_types(self.member_types)
+        .with_span(self.span.unwrap_or_default());
+
+        let class = match self.super_class {
+            Some(super_class) => class.extending(super_class),
+            None => class,
+        };
+        let class = if self.is_abstract { class.as_abstract() } else { class };
+        let class = if self.is_enum { class.as_enum() } else { class };
+        let class = if self.is_record { class.as_record(self.record_components) } else { class };
+        if self.is_interface { class.as_interface() } else { class }
     }
 }
 
@@ -81,21 +183,31 @@ pub struct MethodBuilder<'a> {
     scope: Option<AstScope>,
     is_static: bool,
     is_final: bool,
-    return_type: Option<&'a str>,
-    parameters: Vec<ParameterBuilder<'a>>,
+    is_abstract: bool,
+    is_synchronized: bool,
+    is_native: bool,
+    is_compact: bool,
+    return_type: Option<AstType<'a>>,
+    parameters: Vec<AstParameter<'a>>,
     statements: Vec<Statement<'a>>,
+    span: Option<SourceSpan>,
 }
 
 impl<'a> MethodBuilder<'a> {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             name: None,
             scope: None,
             is_static: false,
             is_final: false,
+            is_abstract: false,
+            is_synchronized: false,
+            is_native: false,
+            is_compact: false,
             return_type: None,
             parameters: vec![],
             statements: vec![],
+            span: None,
         }
     }
 
@@ -107,30 +219,53 @@ impl<'a> MethodBuilder<'a> {
         self.scope = Some(scope)
     }
 
+    /// Extends the span of the method to cover the given token
+    pub fn spanning(&mut self, span: SourceSpan) {
+        self.span = Some(self.span.map_or(span, |start| start.to(span)))
+    }
+
     pub fn as_static(&mut self) {
         self.is_static = true;
     }
 
-    #[allow(dead_code)]
     pub fn as_final(&mut self) {
         self.is_final = true;
     }
 
-    pub fn with_return_type(&mut self, return_type: &'a str) {
+    pub fn as_abstract(&mut self) {
+        self.is_abstract = true;
+    }
+
+    pub fn as_synchronized(&mut self) {
+        self.is_synchronized = true;
+    }
+
+    pub fn as_native(&mut self) {
+        self.is_native = true;
+    }
+
+    pub fn as_compact(&mut self) {
+        self.is_compact = true;
+    }
+
+    pub fn with_return_type(&mut self, return_type: AstType<'a>) {
         self.return_type = Some(return_type)
     }
 
-    pub fn with_new_parameter(&mut self) {
-        self.parameters.push(ParameterBuilder::new());
+    pub fn with_parameter(&mut self, parameter: AstParameter<'a>) {
+        self.parameters.push(parameter);
     }
 
-    pub fn latest_parameter(&mut self) -> &mut ParameterBuilder<'a> {
-        match self.parameters.last_mut() {
-            Some(m) => m,
-            None => panic!("Expected parameter to exist"),
-        }
+    /// Whether the parameters have the types of the record components, in order
+    pub fn is_canonical_for(&self, record_components: &[AstParameter<'a>]) -> bool {
+        self.parameters.len() == record_components.len()
+            && self
+                .parameters
+                .iter()
+                .zip(record_components)
+                .all(|(parameter, component)| parameter.param_type() == component.param_type())
     }
-    
+
     pub fn with_statements(&mut self, statements: Vec<Statement<'a>>) {
         for statement in statements {
             self.statements.push(statement);
@@ -147,65 +282,31 @@ impl <'a> Build<AstMethod<'a>> for MethodBuilder<'a> {
             panic!("Return type was not set")
         };
 
-        let mut ast_parameters: Vec<AstParameter> = vec![];
-        for parameter in self.parameters {
-            ast_parameters.push(parameter.build());
-        }
-
-        let scope = self.scope.unwrap_or(AstScope::Public);
+        let scope = self.scope.unwrap_or(AstScope::Default);
 
-        AstMethod::new(
+        let mut method = AstMethod::new(
             name,
             scope,
             self.is_final,
             self.is_static,
             return_type,
-            ast_parameters,
+            self.parameters,
             self.statements
         )
-    }
-}
-
-#[derive(Clone)]
-pub struct ParameterBuilder<'a> {
-    param_name: Option<&'a str>,
-    param_type: Option<&'a str>,
-    is_array: bool,
-}
+        .with_span(self.span.unwrap_or_default());
 
-impl <'a> ParameterBuilder<'a> {
-    fn new() -> Self {
-        Self {
-            param_name: None,
-            param_type: None,
-            is_array: false,
+        if self.is_abstract {
+            method = method.as_abstract();
         }
-    }
-
-    pub fn with_name(&mut self, name: &'a str) {
-        self.param_name = Some(name)
-    }
-
-    pub fn with_type(&mut self, param_type: &'a str) {
-        self.param_type = Some(param_type)
-    }
-
-    pub fn as_array(&mut self) {
-        self.is_array = true;
+        if self.is_synchronized {
+            method = method.as_synchronized();
+        }
+        if self.is_native {
+            method = method.as_native();
+        }
+        if self.is_compact {
+            method = method.as_compact();
+        }
+        method
     }
 }
-
-impl <'a> Build<AstParameter<'a>> for ParameterBuilder<'a> {
-    fn build(self) -> AstParameter<'a> {
-
-        let Some(param_name) = self.param_name else {
-            panic!("Param name was not set")
-        };
-
-        let Some(param_type) = self.param_type else {
-            panic!("Param type was not set")
-        };
-
-        AstParameter::new(param_name, param_type, self.is_array)
-    }
-}
\ No newline at end of file
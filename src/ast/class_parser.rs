@@ -0,0 +1,1235 @@
+use crate::ast::class::{AstClass, AstEnumConstant, AstField, AstInitializer, AstMethod, AstParameter, AstScope, AstType};
+use crate::ast::class_builder::{Build, ClassBuilder, MethodBuilder};
+use crate::ast::compilation_unit::{AstCompilationUnit, AstImport};
+use crate::ast::method_builder::AstStatementBuilder;
+use crate::ast::result::{ParseResult, SyntaxError};
+use crate::ast::statement::Statement;
+use crate::ast::AstParser;
+use crate::scanner::{ContextualKeyword, SourceSpan, Token, TokenType};
+
+const PRIMITIVE_TYPES: [TokenType; 8] = [
+    TokenType::Boolean,
+    TokenType::Byte,
+    TokenType::Char,
+    TokenType::Short,
+    TokenType::Int,
+    TokenType::Long,
+    TokenType::Float,
+    TokenType::Double,
+];
+
+const TOP_LEVEL_CLASS_MODIFIERS: [TokenType; 4] = [TokenType::Public, TokenType::Abstract, TokenType::Final, TokenType::Strictfp];
+
+const MEMBER_CLASS_MODIFIERS: [TokenType; 7] = [
+    TokenType::Public,
+    TokenType::Protected,
+    TokenType::Private,
+    TokenType::Static,
+    TokenType::Abstract,
+    TokenType::Final,
+    TokenType::Strictfp,
+];
+
+const LOCAL_CLASS_MODIFIERS: [TokenType; 3] = [TokenType::Abstract, TokenType::Final, TokenType::Strictfp];
+
+const FIELD_MODIFIERS: [TokenType; 7] = [
+    TokenType::Public,
+    TokenType::Protected,
+    TokenType::Private,
+    TokenType::Static,
+    TokenType::Final,
+    TokenType::Transient,
+    TokenType::Volatile,
+];
+
+const METHOD_MODIFIERS: [TokenType; 9] = [
+    TokenType::Public,
+    TokenType::Protected,
+    TokenType::Private,
+    TokenType::Static,
+    TokenType::Abstract,
+    TokenType::Final,
+    TokenType::Synchronized,
+    TokenType::Native,
+    TokenType::Strictfp,
+];
+
+const CONSTRUCTOR_MODIFIERS: [TokenType; 3] = [TokenType::Public, TokenType::Protected, TokenType::Private];
+
+const INTERFACE_FIELD_MODIFIERS: [TokenType; 3] = [TokenType::Public, TokenType::Static, TokenType::Final];
+
+const INTERFACE_METHOD_MODIFIERS: [TokenType; 6] = [
+    TokenType::Public,
+    TokenType::Private,
+    TokenType::Abstract,
+    TokenType::Default,
+    TokenType::Static,
+    TokenType::Strictfp,
+];
+
+/// The modifiers of a declaration, which may be written in any order
+#[derive(Default)]
+struct Modifiers<'src> {
+    keywords: Vec<Token<'src>>,
+    span: Option<SourceSpan>,
+}
+
+impl<'src> Modifiers<'src> {
+    fn has(&self, token_type: TokenType) -> bool {
+        self.keywords.iter().any(|keyword| keyword.token_type() == token_type)
+    }
+
+    fn scope(&self) -> AstScope {
+        self.keywords
+            .iter()
+            .find_map(|keyword| scope_for(keyword.token_type()))
+            .unwrap_or(AstScope::Default)
+    }
+
+    fn check_allowed(&self, allowed: &[TokenType], declaration: &str) -> ParseResult<()> {
+        match self.keywords.iter().find(|keyword| !allowed.contains(&keyword.token_type())) {
+            Some(keyword) => Err(SyntaxError::new(
+                format!("modifier '{}' not allowed on {}", keyword.lexeme(), declaration),
+                keyword.span(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_combination(&self, first: TokenType, second: TokenType) -> ParseResult<()> {
+        let first_keyword = self.keywords.iter().find(|keyword| keyword.token_type() == first);
+        let second_keyword = self.keywords.iter().find(|keyword| keyword.token_type() == second);
+        match (first_keyword, second_keyword) {
+            (Some(first_keyword), Some(second_keyword)) => Err(SyntaxError::new(
+                format!("illegal combination of modifiers '{}' and '{}'", first_keyword.lexeme(), second_keyword.lexeme()),
+                first_keyword.span(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses a compilation unit and the class bodies within it by recursive descent
+pub(crate) struct ClassParser<'p, 'src> {
+    parser: &'p mut AstParser<'src>,
+}
+
+impl<'p, 'src> ClassParser<'p, 'src> {
+    pub(crate) fn new(parser: &'p mut AstParser<'src>) -> Self {
+        Self { parser }
+    }
+
+    pub(crate) fn compilation_unit(&mut self) -> ParseResult<AstCompilationUnit<'src>> {
+        let mut package = None;
+        if self.parser.consume_if(TokenType::Package).is_some() {
+            package = Some(self.qualified_name()?.0);
+            self.parser.consume(TokenType::SemiColon)?;
+        }
+
+        let mut imports = vec![];
+        loop {
+            self.skip_semi_colons();
+            match self.parser.peek_next().token_type() {
+                TokenType::Import => imports.push(self.import()?),
+                _ => break,
+            }
+        }
+
+        let mut types = vec![];
+        while !self.parser.is_next_token(TokenType::Eof) {
+            let modifiers = self.modifiers()?;
+            types.push(self.type_declaration(modifiers, &TOP_LEVEL_CLASS_MODIFIERS)?);
+            self.skip_semi_colons();
+        }
+        Ok(AstCompilationUnit::new(package, imports, types))
+    }
+
+    fn import(&mut self) -> ParseResult<AstImport<'src>> {
+        let import_keyword = self.parser.consume(TokenType::Import)?;
+        let is_static = self.parser.consume_if(TokenType::Static).is_some();
+        let (name, _) = self.qualified_name()?;
+
+        let is_on_demand = self.parser.is_next_token(TokenType::Dot) && self.parser.peek_nth(1).token_type() == TokenType::Star;
+        if is_on_demand {
+            self.parser.next_token();
+            self.parser.next_token();
+        } else if !name.contains('.') {
+            // A single-type import names a class within a package
+            return Err(self.parser.unexpected("'.'"));
+        }
+
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+        Ok(AstImport::new(name, is_static, is_on_demand).with_span(import_keyword.span().to(semi_colon.span())))
+    }
+
+    fn skip_semi_colons(&mut self) {
+        while self.parser.consume_if(TokenType::SemiColon).is_some() {}
+    }
+
+    fn type_declaration(&mut self, modifiers: Modifiers<'src>, allowed: &[TokenType]) -> ParseResult<AstClass<'src>> {
+        let next = self.parser.peek_next();
+        let unsupported = match next.token_type() {
+            TokenType::Class => return self.class_declaration(modifiers, allowed),
+            TokenType::Interface => return self.interface_declaration(modifiers, allowed),
+            TokenType::Enum => return self.enum_declaration(modifiers, allowed),
+            TokenType::Identifier if self.is_record_declaration() => return self.record_declaration(modifiers, allowed),
+            TokenType::At => "annotation interface",
+            _ => return Err(self.parser.unexpected("a class declaration")),
+        };
+        Err(SyntaxError::new(format!("{} declarations are not supported yet", unsupported), next.span()))
+    }
+
+    fn is_record_declaration(&self) -> bool {
+        self.is_record_declaration_at(0)
+    }
+
+    fn is_record_declaration_at(&self, n: usize) -> bool {
+        let next = self.parser.peek_nth(n);
+        next.token_type() == TokenType::Identifier
+            && ContextualKeyword::from_lexeme(next.lexeme()) == Some(ContextualKeyword::Record)
+            && self.parser.peek_nth(n + 1).token_type() == TokenType::Identifier
+    }
+
+    /// Whether a block statement declares a local class, interface, enum or record, looking past
+    /// any modifiers
+    pub(crate) fn is_local_type_declaration(&self) -> bool {
+        let mut n = 0;
+        while matches!(
+            self.parser.peek_nth(n).token_type(),
+            TokenType::Abstract | TokenType::Final | TokenType::Strictfp | TokenType::Static
+        ) {
+            n += 1;
+        }
+        match self.parser.peek_nth(n).token_type() {
+            TokenType::Class | TokenType::Interface | TokenType::Enum => true,
+            _ => self.is_record_declaration_at(n),
+        }
+    }
+
+    /// Whether a block statement declares local variables: it starts with `final` or a type
+    /// followed by a name
+    pub(crate) fn is_local_variable_declaration(&self) -> bool {
+        let next = self.parser.peek_next().token_type();
+        if next == TokenType::Final || PRIMITIVE_TYPES.contains(&next) {
+            return true;
+        }
+        self.past_local_variable_type(0)
+            .is_some_and(|n| self.parser.peek_nth(n).token_type() == TokenType::Identifier)
+    }
+
+    /// Whether the header of a `for` statement starts with the variable of an enhanced `for`, as
+    /// in `for (final String name : names)`
+    pub(crate) fn is_enhanced_for_variable(&self) -> bool {
+        let mut n = 0;
+        while self.parser.peek_nth(n).token_type() == TokenType::Final {
+            n += 1;
+        }
+        self.past_local_variable_type(n).is_some_and(|n| {
+            self.parser.peek_nth(n).token_type() == TokenType::Identifier && self.parser.peek_nth(n + 1).token_type() == TokenType::Colon
+        })
+    }
+
+    /// The position of the token after the type of a local variable that starts at position `n`,
+    /// if one does
+    fn past_local_variable_type(&self, mut n: usize) -> Option<usize> {
+        let first = self.parser.peek_nth(n).token_type();
+        if PRIMITIVE_TYPES.contains(&first) {
+            n += 1;
+        } else if first == TokenType::Identifier {
+            n += 1;
+            while self.parser.peek_nth(n).token_type() == TokenType::Dot && self.parser.peek_nth(n + 1).token_type() == TokenType::Identifier {
+                n += 2;
+            }
+            if self.parser.peek_nth(n).token_type() == TokenType::Less {
+                n = self.past_type_arguments(n);
+            }
+        } else {
+            return None;
+        }
+        while self.parser.peek_nth(n).token_type() == TokenType::LeftSquareBracket
+            && self.parser.peek_nth(n + 1).token_type() == TokenType::RightSquareBracket
+        {
+            n += 2;
+        }
+        Some(n)
+    }
+
+    /// The position of the token after the type arguments starting at the `<` at position `n`,
+    /// counting `>>` and `>>>` as closing several at once
+    fn past_type_arguments(&self, mut n: usize) -> usize {
+        let mut depth = 0;
+        loop {
+            match self.parser.peek_nth(n).token_type() {
+                TokenType::Less => depth += 1,
+                TokenType::Greater => depth -= 1,
+                TokenType::GreaterGreater => depth -= 2,
+                TokenType::GreaterGreaterGreater => depth -= 3,
+                TokenType::Identifier | TokenType::Dot | TokenType::Comma | TokenType::Question | TokenType::Extends | TokenType::Super => {}
+                TokenType::LeftSquareBracket | TokenType::RightSquareBracket => {}
+                token_type if PRIMITIVE_TYPES.contains(&token_type) => {}
+                _ => return n,
+            }
+            n += 1;
+            if depth <= 0 {
+                return n;
+            }
+        }
+    }
+
+    /// Parses the declaration of one or more local variables, each with an optional initializer,
+    /// as a statement for each (JLS §14.4)
+    pub(crate) fn local_variable_declaration(&mut self) -> ParseResult<Vec<Statement<'src>>> {
+        let start = self.parser.peek_next().span();
+        let is_final = self.variable_modifiers()?;
+        let (variable_type, _) = self.type_()?;
+        let is_var = variable_type.name() == "var" && !variable_type.is_array();
+
+        let mut statements = vec![];
+        loop {
+            let name = self.parser.consume(TokenType::Identifier)?;
+            let mut end = name.span();
+            let mut dimensions = variable_type.array_dimensions();
+            while self.is_next_dimension() {
+                if is_var {
+                    return Err(SyntaxError::new("'var' is not allowed as an element type of an array", self.parser.peek_next().span()));
+                }
+                self.parser.next_token();
+                end = self.parser.next_token().span();
+                dimensions += 1;
+            }
+
+            let mut initializer = None;
+            if self.parser.consume_if(TokenType::Equal).is_some() {
+                if self.parser.is_next_token(TokenType::LeftBrace) {
+                    return Err(SyntaxError::new("array initializers are not supported yet", self.parser.peek_next().span()));
+                }
+                let expression = AstStatementBuilder::new(self.parser).expression()?;
+                end = expression.span();
+                initializer = Some(expression);
+            } else if is_var {
+                return Err(SyntaxError::new("cannot use 'var' on variable without initializer", name.span()));
+            }
+
+            let variable_type = AstType::new(variable_type.name(), dimensions);
+            statements.push(Statement::new_var_assignment(name.lexeme(), variable_type, is_final, initializer).with_span(start.to(end)));
+
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+            if is_var {
+                return Err(SyntaxError::new("'var' is not allowed in a compound declaration", self.parser.peek_next().span()));
+            }
+        }
+
+        self.parser.consume(TokenType::SemiColon)?;
+        Ok(statements)
+    }
+
+    /// Parses the variable an enhanced `for` statement declares, up to the `:` (JLS §14.14.2)
+    pub(crate) fn enhanced_for_variable(&mut self) -> ParseResult<(&'src str, AstType<'src>, bool)> {
+        let is_final = self.variable_modifiers()?;
+        let (variable_type, _) = self.type_()?;
+        let name = self.parser.consume(TokenType::Identifier)?;
+        Ok((name.lexeme(), variable_type, is_final))
+    }
+
+    /// Whether a local variable is `final`, which it can only be declared once
+    fn variable_modifiers(&mut self) -> ParseResult<bool> {
+        let is_final = self.parser.consume_if(TokenType::Final).is_some();
+        if let Some(keyword) = self.parser.consume_if(TokenType::Final) {
+            return Err(SyntaxError::new("repeated modifier 'final'", keyword.span()));
+        }
+        Ok(is_final)
+    }
+
+    /// Parses a type declared in a block (JLS §14.3)
+    pub(crate) fn local_type_declaration(&mut self) -> ParseResult<AstClass<'src>> {
+        let modifiers = self.modifiers()?;
+        self.type_declaration(modifiers, &LOCAL_CLASS_MODIFIERS)
+    }
+
+    /// Parses the body of an anonymous class, from its opening brace to its closing one. Having no
+    /// name, nothing in it can be a constructor.
+    pub(crate) fn anonymous_class_body(&mut self) -> ParseResult<AstClass<'src>> {
+        let left_brace = self.parser.consume(TokenType::LeftBrace)?;
+        let mut builder = ClassBuilder::new();
+        builder.named("");
+        builder.spanning(left_brace.span());
+        self.class_body(&mut builder)?;
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+        Ok(builder.build())
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        match self.parser.peek_next().token_type() {
+            TokenType::Class | TokenType::Interface | TokenType::Enum => true,
+            TokenType::At => self.parser.peek_nth(1).token_type() == TokenType::Interface,
+            _ => self.is_record_declaration(),
+        }
+    }
+
+    fn class_declaration(&mut self, modifiers: Modifiers<'src>, allowed: &[TokenType]) -> ParseResult<AstClass<'src>> {
+        modifiers.check_allowed(allowed, "a class")?;
+
+        let mut builder = ClassBuilder::new();
+        let class_keyword = self.parser.consume(TokenType::Class)?;
+        builder.spanning(modifiers.span.unwrap_or(class_keyword.span()));
+        builder.with_scope(modifiers.scope());
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+        if modifiers.has(TokenType::Final) {
+            builder.as_final();
+        }
+        if modifiers.has(TokenType::Abstract) {
+            builder.as_abstract();
+        }
+
+        let name = self.parser.consume(TokenType::Identifier)?;
+        builder.named(name.lexeme());
+        if self.parser.is_next_token(TokenType::Less) {
+            self.type_parameters()?;
+        }
+        if self.parser.consume_if(TokenType::Extends).is_some() {
+            let (super_class, _) = self.class_type()?;
+            builder.extending(super_class);
+        }
+        if self.parser.consume_if(TokenType::Implements).is_some() {
+            loop {
+                let (interface, _) = self.class_type()?;
+                builder.implementing(interface);
+                if self.parser.consume_if(TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+
+        self.parser.consume(TokenType::LeftBrace)?;
+        self.class_body(&mut builder)?;
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+
+        Ok(builder.build())
+    }
+
+    fn interface_declaration(&mut self, modifiers: Modifiers<'src>, allowed: &[TokenType]) -> ParseResult<AstClass<'src>> {
+        // Interfaces are abstract already, and cannot be final
+        let allowed: Vec<TokenType> = allowed.iter().copied().filter(|modifier| *modifier != TokenType::Final).collect();
+        modifiers.check_allowed(&allowed, "an interface")?;
+
+        let mut builder = ClassBuilder::new();
+        let interface_keyword = self.parser.consume(TokenType::Interface)?;
+        builder.spanning(modifiers.span.unwrap_or(interface_keyword.span()));
+        builder.with_scope(modifiers.scope());
+        builder.as_interface();
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+
+        let name = self.parser.consume(TokenType::Identifier)?;
+        builder.named(name.lexeme());
+        if self.parser.is_next_token(TokenType::Less) {
+            self.type_parameters()?;
+        }
+        if self.parser.consume_if(TokenType::Extends).is_some() {
+            loop {
+                let (interface, _) = self.class_type()?;
+                builder.implementing(interface);
+                if self.parser.consume_if(TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+
+        self.parser.consume(TokenType::LeftBrace)?;
+        self.class_body(&mut builder)?;
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+
+        Ok(builder.build())
+    }
+
+    fn enum_declaration(&mut self, modifiers: Modifiers<'src>, allowed: &[TokenType]) -> ParseResult<AstClass<'src>> {
+        // Enums are implicitly final, or abstract when their constants implement abstract methods
+        let allowed: Vec<TokenType> = allowed
+            .iter()
+            .copied()
+            .filter(|modifier| *modifier != TokenType::Final && *modifier != TokenType::Abstract)
+            .collect();
+        modifiers.check_allowed(&allowed, "an enum")?;
+
+        let mut builder = ClassBuilder::new();
+        let enum_keyword = self.parser.consume(TokenType::Enum)?;
+        builder.spanning(modifiers.span.unwrap_or(enum_keyword.span()));
+        builder.with_scope(modifiers.scope());
+        builder.as_enum();
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+
+        let name = self.parser.consume(TokenType::Identifier)?;
+        builder.named(name.lexeme());
+        if self.parser.consume_if(TokenType::Implements).is_some() {
+            loop {
+                let (interface, _) = self.class_type()?;
+                builder.implementing(interface);
+                if self.parser.consume_if(TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+
+        self.parser.consume(TokenType::LeftBrace)?;
+        self.enum_constants(&mut builder)?;
+        if self.parser.consume_if(TokenType::SemiColon).is_some() {
+            self.class_body(&mut builder)?;
+        }
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+
+        Ok(builder.build())
+    }
+
+    fn record_declaration(&mut self, modifiers: Modifiers<'src>, allowed: &[TokenType]) -> ParseResult<AstClass<'src>> {
+        // Records are implicitly final, so can never be abstract
+        let allowed: Vec<TokenType> = allowed.iter().copied().filter(|modifier| *modifier != TokenType::Abstract).collect();
+        modifiers.check_allowed(&allowed, "a record")?;
+
+        let mut builder = ClassBuilder::new();
+        let record_keyword = self.parser.consume(TokenType::Identifier)?;
+        builder.spanning(modifiers.span.unwrap_or(record_keyword.span()));
+        builder.with_scope(modifiers.scope());
+        builder.as_record();
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+        if modifiers.has(TokenType::Final) {
+            builder.as_final();
+        }
+
+        let name = self.parser.consume(TokenType::Identifier)?;
+        builder.named(name.lexeme());
+        if self.parser.is_next_token(TokenType::Less) {
+            self.type_parameters()?;
+        }
+
+        self.parser.consume(TokenType::LeftParen)?;
+        if !self.parser.is_next_token(TokenType::RightParen) {
+            loop {
+                let component = self.parameter()?;
+                if component.is_final() {
+                    return Err(SyntaxError::new("record components cannot have modifiers", component.span()));
+                }
+                builder.with_record_component(component);
+                if self.parser.consume_if(TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+        self.parser.consume(TokenType::RightParen)?;
+
+        if self.parser.consume_if(TokenType::Implements).is_some() {
+            loop {
+                let (interface, _) = self.class_type()?;
+                builder.implementing(interface);
+                if self.parser.consume_if(TokenType::Comma).is_none() {
+                    break;
+                }
+            }
+        }
+
+        self.parser.consume(TokenType::LeftBrace)?;
+        self.class_body(&mut builder)?;
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+
+        Ok(builder.build())
+    }
+
+    /// Parses the comma separated constants that start the body of an enum, which may end with a
+    /// trailing comma
+    fn enum_constants(&mut self, builder: &mut ClassBuilder<'src>) -> ParseResult<()> {
+        while matches!(self.parser.peek_next().token_type(), TokenType::Identifier | TokenType::At) {
+            let mut start = None;
+            while self.parser.is_next_token(TokenType::At) {
+                start.get_or_insert(self.parser.peek_next().span());
+                self.annotation()?;
+            }
+
+            let name = self.parser.consume(TokenType::Identifier)?;
+            let mut span = start.unwrap_or(name.span()).to(name.span());
+            let mut arguments = vec![];
+            if self.parser.is_next_token(TokenType::LeftParen) {
+                let (constant_arguments, right_paren) = AstStatementBuilder::new(self.parser).arguments()?;
+                arguments = constant_arguments;
+                span = span.to(right_paren);
+            }
+
+            let mut constant = AstEnumConstant::new(name.lexeme(), arguments);
+            if self.parser.is_next_token(TokenType::LeftBrace) {
+                let body = self.anonymous_class_body()?;
+                span = span.to(body.span());
+                constant = constant.with_body(body);
+            }
+            builder.with_enum_constant(constant.with_span(span));
+
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses members up to, but not including, the closing brace of the class body
+    fn class_body(&mut self, builder: &mut ClassBuilder<'src>) -> ParseResult<()> {
+        while !self.parser.is_next_token(TokenType::RightBrace) {
+            match self.parser.peek_next().token_type() {
+                TokenType::Eof => return Err(self.parser.unexpected("'}'")),
+                TokenType::SemiColon => {
+                    self.parser.next_token();
+                }
+                TokenType::LeftBrace | TokenType::Static if builder.is_interface() && self.is_next_initializer() => {
+                    return Err(SyntaxError::new("initializers not allowed in interfaces", self.parser.peek_next().span()));
+                }
+                TokenType::LeftBrace if builder.is_record() => {
+                    return Err(SyntaxError::new("instance initializers not allowed in records", self.parser.peek_next().span()));
+                }
+                TokenType::LeftBrace => {
+                    let initializer = self.initializer(false, self.parser.peek_next().span())?;
+                    builder.with_initializer(initializer);
+                }
+                TokenType::Static if self.parser.peek_nth(1).token_type() == TokenType::LeftBrace => {
+                    let static_keyword = self.parser.next_token();
+                    let initializer = self.initializer(true, static_keyword.span())?;
+                    builder.with_initializer(initializer);
+                }
+                _ => self.member(builder)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn is_next_initializer(&self) -> bool {
+        match self.parser.peek_next().token_type() {
+            TokenType::LeftBrace => true,
+            TokenType::Static => self.parser.peek_nth(1).token_type() == TokenType::LeftBrace,
+            _ => false,
+        }
+    }
+
+    fn initializer(&mut self, is_static: bool, start: SourceSpan) -> ParseResult<AstInitializer<'src>> {
+        let (statements, end) = self.block()?;
+        Ok(AstInitializer::new(is_static, statements).with_span(start.to(end)))
+    }
+
+    fn member(&mut self, builder: &mut ClassBuilder<'src>) -> ParseResult<()> {
+        let modifiers = self.modifiers()?;
+
+        if self.is_type_declaration() {
+            let member_type = self.type_declaration(modifiers, &MEMBER_CLASS_MODIFIERS)?;
+            builder.with_member_type(member_type);
+            return Ok(());
+        }
+
+        let mut start = modifiers.span;
+        if self.parser.is_next_token(TokenType::Less) {
+            let less = self.parser.peek_next().span();
+            start.get_or_insert(less);
+            self.type_parameters()?;
+        }
+
+        let next = self.parser.peek_next();
+        let is_compact_constructor = builder.is_record()
+            && Some(next.lexeme()) == builder.name()
+            && self.parser.peek_nth(1).token_type() == TokenType::LeftBrace;
+        if is_compact_constructor || (next.token_type() == TokenType::Identifier && self.parser.peek_nth(1).token_type() == TokenType::LeftParen) {
+            // Interfaces have no constructors
+            if builder.is_interface() || Some(next.lexeme()) != builder.name() {
+                return Err(SyntaxError::new("invalid method declaration; return type required", next.span()));
+            }
+            let name = self.parser.next_token();
+            let constructor = self.constructor(modifiers, start.unwrap_or(name.span()), builder)?;
+            builder.with_constructor(constructor);
+            return Ok(());
+        }
+
+        let (member_type, type_span) = self.type_()?;
+        let start = start.unwrap_or(type_span);
+        let name = self.parser.consume(TokenType::Identifier)?;
+
+        if self.parser.is_next_token(TokenType::LeftParen) {
+            let method = if builder.is_interface() {
+                self.interface_method(modifiers, member_type, name.lexeme(), start)?
+            } else {
+                self.method(modifiers, member_type, name.lexeme(), start)?
+            };
+            builder.with_method(method);
+        } else if builder.is_interface() {
+            for field in self.constants(modifiers, member_type, name, start)? {
+                builder.with_field(field);
+            }
+        } else {
+            // The state of a record is its components (JLS §8.10.3)
+            if builder.is_record() && !modifiers.has(TokenType::Static) {
+                return Err(SyntaxError::new("field declaration must be static", name.span()));
+            }
+            for field in self.fields(modifiers, member_type, name, start)? {
+                builder.with_field(field);
+            }
+        }
+        Ok(())
+    }
+
+    fn constructor(&mut self, modifiers: Modifiers<'src>, start: SourceSpan, class: &ClassBuilder<'src>) -> ParseResult<AstMethod<'src>> {
+        // Enum constructors are always private, as only the constants of the enum may be created
+        let is_enum = class.is_enum();
+        let allowed: &[TokenType] = if is_enum { &[TokenType::Private] } else { &CONSTRUCTOR_MODIFIERS };
+        modifiers.check_allowed(allowed, if is_enum { "an enum constructor" } else { "a constructor" })?;
+
+        let mut builder = MethodBuilder::new();
+        builder.spanning(start);
+        builder.with_name("<init>");
+        builder.with_scope(if is_enum { AstScope::Private } else { modifiers.scope() });
+        builder.with_return_type(AstType::from("void"));
+
+        // A compact constructor takes the record components as its parameters
+        let is_compact = class.is_record() && self.parser.is_next_token(TokenType::LeftBrace);
+        if is_compact {
+            builder.as_compact();
+            for component in class.record_components() {
+                builder.with_parameter(component.clone());
+            }
+        } else {
+            self.parameters(&mut builder)?;
+        }
+        self.throws()?;
+
+        self.parser.consume(TokenType::LeftBrace)?;
+        let mut statement_builder = AstStatementBuilder::new(self.parser);
+        statement_builder.constructor_call()?;
+        statement_builder.build()?;
+        let statements = statement_builder.statements();
+        if let (true, Some(Statement::ConstructorCall { is_super: true, span, .. })) = (is_enum, statements.first()) {
+            return Err(SyntaxError::new("call to super not allowed in enum constructor", *span));
+        }
+        if class.is_record() {
+            check_record_constructor_call(&statements, is_compact || builder.is_canonical_for(class.record_components()), class, start)?;
+        }
+        builder.with_statements(statements);
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        builder.spanning(right_brace.span());
+        Ok(builder.build())
+    }
+
+    fn method(
+        &mut self,
+        modifiers: Modifiers<'src>,
+        return_type: AstType<'src>,
+        name: &'src str,
+        start: SourceSpan,
+    ) -> ParseResult<AstMethod<'src>> {
+        modifiers.check_allowed(&METHOD_MODIFIERS, "a method")?;
+
+        let mut builder = MethodBuilder::new();
+        builder.spanning(start);
+        builder.with_name(name);
+        builder.with_scope(modifiers.scope());
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+        if modifiers.has(TokenType::Final) {
+            builder.as_final();
+        }
+        if modifiers.has(TokenType::Synchronized) {
+            builder.as_synchronized();
+        }
+        let is_abstract = modifiers.has(TokenType::Abstract);
+        if is_abstract {
+            builder.as_abstract();
+        }
+        let is_native = modifiers.has(TokenType::Native);
+        if is_native {
+            builder.as_native();
+        }
+
+        self.parameters(&mut builder)?;
+        // The array dimensions of the return type may also follow the parameters
+        let dimensions = self.dimensions();
+        builder.with_return_type(AstType::new(return_type.name(), return_type.array_dimensions() + dimensions));
+        self.throws()?;
+
+        if let Some(semi_colon) = self.parser.consume_if(TokenType::SemiColon) {
+            if !is_abstract && !is_native {
+                return Err(SyntaxError::new("missing method body, or declare abstract", semi_colon.span()));
+            }
+            builder.spanning(semi_colon.span());
+            return Ok(builder.build());
+        }
+
+        let body = self.parser.peek_next().span();
+        if is_abstract || is_native {
+            let kind = if is_abstract { "abstract" } else { "native" };
+            return Err(SyntaxError::new(format!("{} methods cannot have a body", kind), body));
+        }
+        let (statements, end) = self.block()?;
+        builder.with_statements(statements);
+        builder.spanning(end);
+        Ok(builder.build())
+    }
+
+    /// Parses a method of an interface, which is public unless private, and abstract unless it is
+    /// a default, static or private method with a body (JLS §9.4)
+    fn interface_method(
+        &mut self,
+        modifiers: Modifiers<'src>,
+        return_type: AstType<'src>,
+        name: &'src str,
+        start: SourceSpan,
+    ) -> ParseResult<AstMethod<'src>> {
+        modifiers.check_allowed(&INTERFACE_METHOD_MODIFIERS, "an interface method")?;
+        for (first, second) in [
+            (TokenType::Abstract, TokenType::Default),
+            (TokenType::Abstract, TokenType::Static),
+            (TokenType::Abstract, TokenType::Private),
+            (TokenType::Default, TokenType::Static),
+            (TokenType::Default, TokenType::Private),
+        ] {
+            modifiers.check_combination(first, second)?;
+        }
+
+        let mut builder = MethodBuilder::new();
+        builder.spanning(start);
+        builder.with_name(name);
+        builder.with_scope(if modifiers.has(TokenType::Private) { AstScope::Private } else { AstScope::Public });
+        if modifiers.has(TokenType::Static) {
+            builder.as_static();
+        }
+        let is_abstract = !(modifiers.has(TokenType::Default) || modifiers.has(TokenType::Static) || modifiers.has(TokenType::Private));
+        if is_abstract {
+            builder.as_abstract();
+        }
+
+        self.parameters(&mut builder)?;
+        let dimensions = self.dimensions();
+        builder.with_return_type(AstType::new(return_type.name(), return_type.array_dimensions() + dimensions));
+        self.throws()?;
+
+        if let Some(semi_colon) = self.parser.consume_if(TokenType::SemiColon) {
+            if !is_abstract {
+                return Err(SyntaxError::new("missing method body, or declare abstract", semi_colon.span()));
+            }
+            builder.spanning(semi_colon.span());
+            return Ok(builder.build());
+        }
+
+        if is_abstract {
+            return Err(SyntaxError::new("interface abstract methods cannot have a body", self.parser.peek_next().span()));
+        }
+        let (statements, end) = self.block()?;
+        builder.with_statements(statements);
+        builder.spanning(end);
+        Ok(builder.build())
+    }
+
+    fn parameters(&mut self, builder: &mut MethodBuilder<'src>) -> ParseResult<()> {
+        self.parser.consume(TokenType::LeftParen)?;
+        if self.parser.consume_if(TokenType::RightParen).is_some() {
+            return Ok(());
+        }
+
+        loop {
+            let parameter = self.parameter()?;
+            builder.with_parameter(parameter);
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+        }
+        self.parser.consume(TokenType::RightParen)?;
+        Ok(())
+    }
+
+    fn parameter(&mut self) -> ParseResult<AstParameter<'src>> {
+        let mut final_keyword: Option<Token<'src>> = None;
+        let mut start: Option<SourceSpan> = None;
+        loop {
+            if self.parser.is_next_token(TokenType::At) {
+                start.get_or_insert(self.parser.peek_next().span());
+                self.annotation()?;
+            } else if let Some(keyword) = self.parser.consume_if(TokenType::Final) {
+                if final_keyword.is_some() {
+                    return Err(SyntaxError::new("repeated modifier 'final'", keyword.span()));
+                }
+                start.get_or_insert(keyword.span());
+                final_keyword = Some(keyword);
+            } else {
+                break;
+            }
+        }
+
+        let (param_type, type_span) = self.type_()?;
+        let mut dimensions = param_type.array_dimensions();
+        // A variable arity parameter is an array
+        if self.parser.consume_if(TokenType::Ellipsis).is_some() {
+            dimensions += 1;
+        }
+
+        let name = self.parser.consume(TokenType::Identifier)?;
+        let mut end = name.span();
+        while self.is_next_dimension() {
+            self.parser.next_token();
+            end = self.parser.next_token().span();
+            dimensions += 1;
+        }
+
+        let parameter = AstParameter::new(name.lexeme(), AstType::new(param_type.name(), dimensions))
+            .with_span(start.unwrap_or(type_span).to(end));
+        Ok(if final_keyword.is_some() { parameter.as_final() } else { parameter })
+    }
+
+    fn throws(&mut self) -> ParseResult<()> {
+        if self.parser.consume_if(TokenType::Throws).is_some() {
+            self.type_()?;
+            while self.parser.consume_if(TokenType::Comma).is_some() {
+                self.type_()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fields(
+        &mut self,
+        modifiers: Modifiers<'src>,
+        field_type: AstType<'src>,
+        first_name: Token<'src>,
+        start: SourceSpan,
+    ) -> ParseResult<Vec<AstField<'src>>> {
+        modifiers.check_allowed(&FIELD_MODIFIERS, "a field")?;
+
+        let mut fields = vec![];
+        let mut name = first_name;
+        loop {
+            let mut end = name.span();
+            let mut dimensions = field_type.array_dimensions();
+            while self.is_next_dimension() {
+                self.parser.next_token();
+                end = self.parser.next_token().span();
+                dimensions += 1;
+            }
+
+            let mut initializer = None;
+            if self.parser.consume_if(TokenType::Equal).is_some() {
+                if self.parser.is_next_token(TokenType::LeftBrace) {
+                    return Err(SyntaxError::new("array initializers are not supported yet", self.parser.peek_next().span()));
+                }
+                let expression = AstStatementBuilder::new(self.parser).expression()?;
+                end = expression.span();
+                initializer = Some(expression);
+            }
+
+            let mut field = AstField::new(
+                name.lexeme(),
+                AstType::new(field_type.name(), dimensions),
+                modifiers.scope(),
+                modifiers.has(TokenType::Static),
+                modifiers.has(TokenType::Final),
+                initializer,
+            )
+            .with_span(start.to(end));
+            if modifiers.has(TokenType::Transient) {
+                field = field.as_transient();
+            }
+            if modifiers.has(TokenType::Volatile) {
+                field = field.as_volatile();
+            }
+            fields.push(field);
+
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+            name = self.parser.consume(TokenType::Identifier)?;
+        }
+
+        self.parser.consume(TokenType::SemiColon)?;
+        Ok(fields)
+    }
+
+    /// Parses the fields of an interface, which are public static final constants that must be
+    /// initialized (JLS §9.3)
+    fn constants(
+        &mut self,
+        modifiers: Modifiers<'src>,
+        field_type: AstType<'src>,
+        first_name: Token<'src>,
+        start: SourceSpan,
+    ) -> ParseResult<Vec<AstField<'src>>> {
+        modifiers.check_allowed(&INTERFACE_FIELD_MODIFIERS, "an interface field")?;
+
+        let mut constants = vec![];
+        let mut name = first_name;
+        loop {
+            let mut dimensions = field_type.array_dimensions();
+            while self.is_next_dimension() {
+                self.parser.next_token();
+                self.parser.next_token();
+                dimensions += 1;
+            }
+
+            self.parser.consume(TokenType::Equal)?;
+            if self.parser.is_next_token(TokenType::LeftBrace) {
+                return Err(SyntaxError::new("array initializers are not supported yet", self.parser.peek_next().span()));
+            }
+            let initializer = AstStatementBuilder::new(self.parser).expression()?;
+            let span = start.to(initializer.span());
+
+            let constant = AstField::new(name.lexeme(), AstType::new(field_type.name(), dimensions), AstScope::Public, true, true, Some(initializer));
+            constants.push(constant.with_span(span));
+
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+            name = self.parser.consume(TokenType::Identifier)?;
+        }
+
+        self.parser.consume(TokenType::SemiColon)?;
+        Ok(constants)
+    }
+
+    /// Parses a `{ ... }` block, returning its statements and the span of its closing brace
+    fn block(&mut self) -> ParseResult<(Vec<Statement<'src>>, SourceSpan)> {
+        self.parser.consume(TokenType::LeftBrace)?;
+        let mut statement_builder = AstStatementBuilder::new(self.parser);
+        statement_builder.build()?;
+        let statements = statement_builder.statements();
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        Ok((statements, right_brace.span()))
+    }
+
+    fn modifiers(&mut self) -> ParseResult<Modifiers<'src>> {
+        let mut modifiers = Modifiers::default();
+        loop {
+            let next = self.parser.peek_next();
+            match next.token_type() {
+                // `@interface` starts a declaration rather than an annotation
+                TokenType::At if self.parser.peek_nth(1).token_type() != TokenType::Interface => {
+                    modifiers.span.get_or_insert(next.span());
+                    self.annotation()?;
+                }
+                TokenType::Public
+                | TokenType::Protected
+                | TokenType::Private
+                | TokenType::Static
+                | TokenType::Abstract
+                | TokenType::Final
+                | TokenType::Transient
+                | TokenType::Volatile
+                | TokenType::Synchronized
+                | TokenType::Native
+                | TokenType::Strictfp
+                | TokenType::Default => {
+                    let keyword = self.parser.next_token();
+                    if modifiers.has(keyword.token_type()) {
+                        return Err(SyntaxError::new(format!("repeated modifier '{}'", keyword.lexeme()), keyword.span()));
+                    }
+                    if scope_for(keyword.token_type()).is_some() && modifiers.scope() != AstScope::Default {
+                        return Err(SyntaxError::new("conflicting access modifiers", keyword.span()));
+                    }
+                    modifiers.span.get_or_insert(keyword.span());
+                    modifiers.keywords.push(keyword);
+                }
+                _ => break,
+            }
+        }
+
+        modifiers.check_combination(TokenType::Abstract, TokenType::Final)?;
+        Ok(modifiers)
+    }
+
+    /// Annotations are not retained, so are parsed only as far as needed to skip over them
+    fn annotation(&mut self) -> ParseResult<()> {
+        self.parser.consume(TokenType::At)?;
+        self.parser.consume(TokenType::Identifier)?;
+        while self.parser.consume_if(TokenType::Dot).is_some() {
+            self.parser.consume(TokenType::Identifier)?;
+        }
+
+        if self.parser.consume_if(TokenType::LeftParen).is_some() {
+            let mut depth = 1;
+            while depth > 0 {
+                match self.parser.next_token().token_type() {
+                    TokenType::LeftParen => depth += 1,
+                    TokenType::RightParen => depth -= 1,
+                    TokenType::Eof => return Err(self.parser.unexpected("')'")),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a type, returning it along with the span it covers
+    fn type_(&mut self) -> ParseResult<(AstType<'src>, SourceSpan)> {
+        while self.parser.is_next_token(TokenType::At) {
+            self.annotation()?;
+        }
+
+        let next = self.parser.peek_next().token_type();
+        let (name, mut span) = if PRIMITIVE_TYPES.contains(&next) || next == TokenType::Void {
+            let keyword = self.parser.next_token();
+            (keyword.lexeme(), keyword.span())
+        } else if next == TokenType::Identifier {
+            self.class_type()?
+        } else {
+            return Err(self.parser.unexpected("a type"));
+        };
+
+        let mut dimensions = 0;
+        while self.is_next_dimension() {
+            self.parser.next_token();
+            span = span.to(self.parser.next_token().span());
+            dimensions += 1;
+        }
+
+        Ok((AstType::new(name, dimensions), span))
+    }
+
+    /// A possibly qualified class name. Any type arguments are dropped as they are erased.
+    pub(crate) fn class_type(&mut self) -> ParseResult<(&'src str, SourceSpan)> {
+        let first = self.parser.consume(TokenType::Identifier)?;
+        let mut last = first.clone();
+        let mut written_length = first.lexeme().len();
+        let mut span = first.span();
+
+        loop {
+            if self.parser.is_next_token(TokenType::Less) {
+                span = span.to(self.type_arguments()?);
+                if self.parser.is_next_token(TokenType::Dot) {
+                    return Err(SyntaxError::new(
+                        "type arguments on a qualifying type are not supported yet",
+                        self.parser.peek_next().span(),
+                    ));
+                }
+                break;
+            }
+            if !(self.parser.is_next_token(TokenType::Dot) && self.parser.peek_nth(1).token_type() == TokenType::Identifier) {
+                break;
+            }
+            self.parser.next_token();
+            last = self.parser.next_token();
+            written_length += 1 + last.lexeme().len();
+            span = span.to(last.span());
+        }
+
+        Ok((self.written_name(&first, &last, written_length)?, span))
+    }
+
+    /// A dot-separated name such as a package name. A trailing `.*` is left for the caller.
+    fn qualified_name(&mut self) -> ParseResult<(&'src str, SourceSpan)> {
+        let first = self.parser.consume(TokenType::Identifier)?;
+        let mut last = first.clone();
+        let mut written_length = first.lexeme().len();
+
+        while self.parser.is_next_token(TokenType::Dot) && self.parser.peek_nth(1).token_type() == TokenType::Identifier {
+            self.parser.next_token();
+            last = self.parser.next_token();
+            written_length += 1 + last.lexeme().len();
+        }
+
+        let span = first.span().to(last.span());
+        Ok((self.written_name(&first, &last, written_length)?, span))
+    }
+
+    /// The source of the identifiers and dots from `first` to `last`, which is only usable as a
+    /// name when nothing else was written between them
+    fn written_name(&self, first: &Token<'src>, last: &Token<'src>, written_length: usize) -> ParseResult<&'src str> {
+        let name = self.parser.source_between(first, last);
+        if name.len() != written_length {
+            return Err(SyntaxError::new("qualified names must not contain white space or comments", first.span().to(last.span())));
+        }
+        Ok(name)
+    }
+
+    /// Parses `<...>` type arguments, returning the span of the closing bracket
+    fn type_arguments(&mut self) -> ParseResult<SourceSpan> {
+        self.parser.consume(TokenType::Less)?;
+        loop {
+            if self.parser.consume_if(TokenType::Question).is_some() {
+                if self.parser.consume_if(TokenType::Extends).is_some() || self.parser.consume_if(TokenType::Super).is_some() {
+                    self.type_()?;
+                }
+            } else {
+                self.type_()?;
+            }
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+        }
+        Ok(self.parser.consume_closing_angle_bracket()?.span())
+    }
+
+    /// Parses `<T extends Bound & Other, ...>` type parameters, which are dropped as they are erased
+    fn type_parameters(&mut self) -> ParseResult<()> {
+        self.parser.consume(TokenType::Less)?;
+        loop {
+            while self.parser.is_next_token(TokenType::At) {
+                self.annotation()?;
+            }
+            self.parser.consume(TokenType::Identifier)?;
+            if self.parser.consume_if(TokenType::Extends).is_some() {
+                self.type_()?;
+                while self.parser.consume_if(TokenType::Amp).is_some() {
+                    self.type_()?;
+                }
+            }
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                break;
+            }
+        }
+        self.parser.consume_closing_angle_bracket()?;
+        Ok(())
+    }
+
+    fn is_next_dimension(&self) -> bool {
+        self.parser.is_next_token(TokenType::LeftSquareBracket)
+            && self.parser.peek_nth(1).token_type() == TokenType::RightSquareBracket
+    }
+
+    /// Consumes any `[]` pairs, returning how many there were
+    fn dimensions(&mut self) -> usize {
+        let mut dimensions = 0;
+        while self.is_next_dimension() {
+            self.parser.next_token();
+            self.parser.next_token();
+            dimensions += 1;
+        }
+        dimensions
+    }
+}
+
+/// The canonical constructor of a record initializes the fields itself, so may not call another
+/// constructor, while every other constructor must start by calling another of the record (JLS §8.10.4)
+fn check_record_constructor_call(statements: &[Statement], is_canonical: bool, class: &ClassBuilder, start: SourceSpan) -> ParseResult<()> {
+    match (is_canonical, statements.first()) {
+        (true, Some(Statement::ConstructorCall { span, .. })) => {
+            Err(SyntaxError::new("canonical constructor must not contain explicit constructor invocation", *span))
+        }
+        (false, Some(Statement::ConstructorCall { is_super: false, .. })) => Ok(()),
+        (false, _) => Err(SyntaxError::new(
+            format!(
+                "constructor is not canonical, so its first statement must invoke another constructor of class {}",
+                class.name().unwrap_or_default()
+            ),
+            start,
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn scope_for(token_type: TokenType) -> Option<AstScope> {
+    match token_type {
+        TokenType::Public => Some(AstScope::Public),
+        TokenType::Protected => Some(AstScope::Protected),
+        TokenType::Private => Some(AstScope::Private),
+        _ => None,
+    }
+}
@@ -0,0 +1,71 @@
+use crate::ast::class::AstClass;
+use crate::scanner::SourceSpan;
+
+/// A source file: its package, its imports and the top level types it declares
+#[derive(Debug)]
+pub struct AstCompilationUnit<'a> {
+    package: Option<&'a str>,
+    imports: Vec<AstImport<'a>>,
+    types: Vec<AstClass<'a>>,
+}
+
+impl<'a> AstCompilationUnit<'a> {
+    pub fn new(package: Option<&'a str>, imports: Vec<AstImport<'a>>, types: Vec<AstClass<'a>>) -> Self {
+        Self { package, imports, types }
+    }
+
+    /// The package name as written, such as `com.example`, or `None` for the unnamed package
+    pub fn package(&self) -> Option<&'a str> {
+        self.package
+    }
+    pub fn imports(&self) -> &Vec<AstImport<'a>> {
+        &self.imports
+    }
+    /// The top level types in declaration order
+    pub fn types(&self) -> &Vec<AstClass<'a>> {
+        &self.types
+    }
+}
+
+/// An import declaration. The name excludes any trailing `.*` of an on-demand import.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AstImport<'a> {
+    name: &'a str,
+    is_static: bool,
+    is_on_demand: bool,
+    span: SourceSpan,
+}
+
+impl<'a> AstImport<'a> {
+    pub fn new(name: &'a str, is_static: bool, is_on_demand: bool) -> Self {
+        Self {
+            name,
+            is_static,
+            is_on_demand,
+            span: SourceSpan::default(),
+        }
+    }
+
+    pub fn with_span(self, span: SourceSpan) -> Self {
+        Self { span, ..self }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+    pub fn is_on_demand(&self) -> bool {
+        self.is_on_demand
+    }
+
+    /// The last identifier of a single import, which it makes available as a simple name
+    pub fn simple_name(&self) -> &'a str {
+        self.name.rsplit('.').next().unwrap_or(self.name)
+    }
+}
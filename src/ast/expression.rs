@@ -1,27 +1,229 @@
+use crate::ast::class::AstClass;
+use crate::scanner::{SourceSpan, TokenType};
 use std::fmt::Debug;
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug)]
 pub enum Expression<'ast> {
     Call {
         target: Box<Expression<'ast>>,
         method_name: &'ast str,
         arguments: Vec<Expression<'ast>>,
+        span: SourceSpan,
     },
     StringLiteral {
-        value: &'ast str,
+        value: String,
+        span: SourceSpan,
+    },
+    BooleanLiteral {
+        value: bool,
+        span: SourceSpan,
+    },
+    CharLiteral {
+        value: u16,
+        span: SourceSpan,
+    },
+    IntLiteral {
+        value: i64,
+        span: SourceSpan,
+    },
+    LongLiteral {
+        value: i128,
+        span: SourceSpan,
+    },
+    FloatLiteral {
+        value: f32,
+        span: SourceSpan,
+    },
+    DoubleLiteral {
+        value: f64,
+        span: SourceSpan,
     },
     Variable {
         name: &'ast str,
         type_def: Option<&'ast str>,
+        span: SourceSpan,
+    },
+    /// `this`, the object an instance method or constructor runs on
+    This {
+        span: SourceSpan,
     },
     ChildIdentifier {
         parent: Box<Expression<'ast>>,
         name: &'ast str,
+        span: SourceSpan,
     },
     Assignment {
         name: &'ast str,
         type_def: Option<&'ast str>,
         value: Box<Expression<'ast>>,
+        span: SourceSpan,
+    },
+    /// `qualifier.name = value`, an assignment to a field named through a qualifier
+    FieldAssignment {
+        qualifier: Box<Expression<'ast>>,
+        name: &'ast str,
+        value: Box<Expression<'ast>>,
+        span: SourceSpan,
+    },
+    /// `new C(...)`, optionally with the body of an anonymous class extending or implementing `C`
+    New {
+        class_type: &'ast str,
+        arguments: Vec<Expression<'ast>>,
+        body: Option<Box<AstClass<'ast>>>,
+        span: SourceSpan,
+    },
+    Binary {
+        left: Box<Expression<'ast>>,
+        operator: BinaryOperator,
+        right: Box<Expression<'ast>>,
+        span: SourceSpan,
+    },
+    Unary {
+        operator: UnaryOperator,
+        operand: Box<Expression<'ast>>,
+        span: SourceSpan,
+    },
+    /// `++x` or `--x`, whose value is that of the variable after it changes
+    PrefixIncrement {
+        operand: Box<Expression<'ast>>,
+        is_decrement: bool,
+        span: SourceSpan,
+    },
+    /// `x++` or `x--`, whose value is that of the variable before it changes
+    PostfixIncrement {
+        operand: Box<Expression<'ast>>,
+        is_decrement: bool,
+        span: SourceSpan,
+    },
+    /// `condition ? when_true : when_false`
+    Conditional {
+        condition: Box<Expression<'ast>>,
+        when_true: Box<Expression<'ast>>,
+        when_false: Box<Expression<'ast>>,
+        span: SourceSpan,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BinaryOperator {
+    Multiply,
+    Divide,
+    Remainder,
+    Add,
+    Subtract,
+    ShiftLeft,
+    ShiftRight,
+    UnsignedShiftRight,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    BitwiseAnd,
+    BitwiseXor,
+    BitwiseOr,
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    pub fn from_token_type(token_type: TokenType) -> Option<Self> {
+        let operator = match token_type {
+            TokenType::Star => BinaryOperator::Multiply,
+            TokenType::Slash => BinaryOperator::Divide,
+            TokenType::Percent => BinaryOperator::Remainder,
+            TokenType::Plus => BinaryOperator::Add,
+            TokenType::Minus => BinaryOperator::Subtract,
+            TokenType::LessLess => BinaryOperator::ShiftLeft,
+            TokenType::GreaterGreater => BinaryOperator::ShiftRight,
+            TokenType::GreaterGreaterGreater => BinaryOperator::UnsignedShiftRight,
+            TokenType::Less => BinaryOperator::Less,
+            TokenType::LessEqual => BinaryOperator::LessEqual,
+            TokenType::Greater => BinaryOperator::Greater,
+            TokenType::GreaterEqual => BinaryOperator::GreaterEqual,
+            TokenType::EqualEqual => BinaryOperator::Equal,
+            TokenType::BangEqual => BinaryOperator::NotEqual,
+            TokenType::Amp => BinaryOperator::BitwiseAnd,
+            TokenType::Caret => BinaryOperator::BitwiseXor,
+            TokenType::Pipe => BinaryOperator::BitwiseOr,
+            TokenType::AmpAmp => BinaryOperator::And,
+            TokenType::PipePipe => BinaryOperator::Or,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    /// How tightly the operator binds, higher binding tighter (JLS §15.17 to §15.24). All binary
+    /// operators are left-associative.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::BitwiseOr => 3,
+            BinaryOperator::BitwiseXor => 4,
+            BinaryOperator::BitwiseAnd => 5,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 6,
+            BinaryOperator::Less | BinaryOperator::LessEqual | BinaryOperator::Greater | BinaryOperator::GreaterEqual => 7,
+            BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight | BinaryOperator::UnsignedShiftRight => 8,
+            BinaryOperator::Add | BinaryOperator::Subtract => 9,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Remainder => 10,
+        }
+    }
+
+    /// The operator as it is written in source
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Remainder => "%",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::ShiftLeft => "<<",
+            BinaryOperator::ShiftRight => ">>",
+            BinaryOperator::UnsignedShiftRight => ">>>",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::BitwiseAnd => "&",
+            BinaryOperator::BitwiseXor => "^",
+            BinaryOperator::BitwiseOr => "|",
+            BinaryOperator::And => "&&",
+            BinaryOperator::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UnaryOperator {
+    Plus,
+    Minus,
+    BitwiseComplement,
+    Not,
+}
+
+impl UnaryOperator {
+    pub fn from_token_type(token_type: TokenType) -> Option<Self> {
+        match token_type {
+            TokenType::Plus => Some(UnaryOperator::Plus),
+            TokenType::Minus => Some(UnaryOperator::Minus),
+            TokenType::Tilde => Some(UnaryOperator::BitwiseComplement),
+            TokenType::Bang => Some(UnaryOperator::Not),
+            _ => None,
+        }
+    }
+
+    /// The operator as it is written in source
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOperator::Plus => "+",
+            UnaryOperator::Minus => "-",
+            UnaryOperator::BitwiseComplement => "~",
+            UnaryOperator::Not => "!",
+        }
     }
 }
 
@@ -34,23 +236,139 @@ impl<'ast> Expression<'ast> {
         Self::Call {
             target: Box::new(target),
             method_name,
-            arguments
+            arguments,
+            span: SourceSpan::default(),
         }
     }
 
-    pub fn new_string_literal(value: &'ast str) -> Self {
-        Self::StringLiteral { value }
+    pub fn new_string_literal(value: &str) -> Self {
+        Self::StringLiteral { value: value.to_string(), span: SourceSpan::default() }
+    }
+
+    pub fn new_boolean_literal(value: bool) -> Self {
+        Self::BooleanLiteral { value, span: SourceSpan::default() }
+    }
+
+    pub fn new_char_literal(value: u16) -> Self {
+        Self::CharLiteral { value, span: SourceSpan::default() }
+    }
+
+    pub fn new_int_literal(value: i64) -> Self {
+        Self::IntLiteral { value, span: SourceSpan::default() }
+    }
+
+    pub fn new_long_literal(value: i128) -> Self {
+        Self::LongLiteral { value, span: SourceSpan::default() }
+    }
+
+    pub fn new_float_literal(value: f32) -> Self {
+        Self::FloatLiteral { value, span: SourceSpan::default() }
+    }
+
+    pub fn new_double_literal(value: f64) -> Self {
+        Self::DoubleLiteral { value, span: SourceSpan::default() }
     }
 
     pub fn new_variable(name: &'ast str, type_def: Option<&'ast str>) -> Self {
-        Self::Variable { name, type_def }
+        Self::Variable { name, type_def, span: SourceSpan::default() }
+    }
+
+    pub fn new_this() -> Self {
+        Self::This { span: SourceSpan::default() }
     }
 
     pub fn new_child_identifier(parent: Expression<'ast>, name: &'ast str) -> Self {
-        Self::ChildIdentifier { parent: Box::new(parent), name }
+        Self::ChildIdentifier { parent: Box::new(parent), name, span: SourceSpan::default() }
     }
 
     pub fn new_assignment(name: &'ast str, type_def: Option<&'ast str>, value: Expression<'ast>) -> Self {
-        Self::Assignment { name, type_def, value: Box::new(value) }
+        Self::Assignment { name, type_def, value: Box::new(value), span: SourceSpan::default() }
+    }
+
+    pub fn new_field_assignment(qualifier: Expression<'ast>, name: &'ast str, value: Expression<'ast>) -> Self {
+        Self::FieldAssignment { qualifier: Box::new(qualifier), name, value: Box::new(value), span: SourceSpan::default() }
+    }
+
+    pub fn new_instance_creation(class_type: &'ast str, arguments: Vec<Expression<'ast>>, body: Option<AstClass<'ast>>) -> Self {
+        Self::New { class_type, arguments, body: body.map(Box::new), span: SourceSpan::default() }
+    }
+
+    pub fn new_binary(left: Expression<'ast>, operator: BinaryOperator, right: Expression<'ast>) -> Self {
+        Self::Binary { left: Box::new(left), operator, right: Box::new(right), span: SourceSpan::default() }
     }
-}
\ No newline at end of file
+
+    pub fn new_unary(operator: UnaryOperator, operand: Expression<'ast>) -> Self {
+        Self::Unary { operator, operand: Box::new(operand), span: SourceSpan::default() }
+    }
+
+    pub fn new_prefix_increment(operand: Expression<'ast>, is_decrement: bool) -> Self {
+        Self::PrefixIncrement { operand: Box::new(operand), is_decrement, span: SourceSpan::default() }
+    }
+
+    pub fn new_postfix_increment(operand: Expression<'ast>, is_decrement: bool) -> Self {
+        Self::PostfixIncrement { operand: Box::new(operand), is_decrement, span: SourceSpan::default() }
+    }
+
+    pub fn new_conditional(condition: Expression<'ast>, when_true: Expression<'ast>, when_false: Expression<'ast>) -> Self {
+        Self::Conditional {
+            condition: Box::new(condition),
+            when_true: Box::new(when_true),
+            when_false: Box::new(when_false),
+            span: SourceSpan::default(),
+        }
+    }
+
+    /// Expressions are created with a default span; the parser sets the source they came from here
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        *self.span_mut() = span;
+        self
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Expression::Call { span, .. }
+            | Expression::StringLiteral { span, .. }
+            | Expression::BooleanLiteral { span, .. }
+            | Expression::CharLiteral { span, .. }
+            | Expression::IntLiteral { span, .. }
+            | Expression::LongLiteral { span, .. }
+            | Expression::FloatLiteral { span, .. }
+            | Expression::DoubleLiteral { span, .. }
+            | Expression::Variable { span, .. }
+            | Expression::This { span }
+            | Expression::ChildIdentifier { span, .. }
+            | Expression::Assignment { span, .. }
+            | Expression::FieldAssignment { span, .. }
+            | Expression::New { span, .. }
+            | Expression::Binary { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::PrefixIncrement { span, .. }
+            | Expression::PostfixIncrement { span, .. }
+            | Expression::Conditional { span, .. } => *span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut SourceSpan {
+        match self {
+            Expression::Call { span, .. }
+            | Expression::StringLiteral { span, .. }
+            | Expression::BooleanLiteral { span, .. }
+            | Expression::CharLiteral { span, .. }
+            | Expression::IntLiteral { span, .. }
+            | Expression::LongLiteral { span, .. }
+            | Expression::FloatLiteral { span, .. }
+            | Expression::DoubleLiteral { span, .. }
+            | Expression::Variable { span, .. }
+            | Expression::This { span }
+            | Expression::ChildIdentifier { span, .. }
+            | Expression::Assignment { span, .. }
+            | Expression::FieldAssignment { span, .. }
+            | Expression::New { span, .. }
+            | Expression::Binary { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::PrefixIncrement { span, .. }
+            | Expression::PostfixIncrement { span, .. }
+            | Expression::Conditional { span, .. } => span,
+        }
+    }
+}
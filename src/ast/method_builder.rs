@@ -1,145 +1,471 @@
-use crate::ast::expression::Expression;
+use crate::ast::class_parser::ClassParser;
+use crate::ast::expression::{BinaryOperator, Expression, UnaryOperator};
+use crate::ast::result::{ParseResult, SyntaxError};
 use crate::ast::statement::Statement;
 use crate::ast::AstParser;
-use crate::ast::class_builder::MethodBuilder;
-use crate::scanner::{Literal, Token, TokenType};
-
-pub struct AstStatementBuilder<'p, 'src, 'tokens, 'ast>
-where
-    'src: 'tokens,
-    'src: 'ast,
-{
-    parser: &'p mut AstParser<'src, 'tokens>,
-    statements: Vec<Statement<'ast>>,
+use crate::scanner::{Literal, SourceSpan, TokenType};
+
+pub struct AstStatementBuilder<'p, 'src> {
+    parser: &'p mut AstParser<'src>,
+    statements: Vec<Statement<'src>>,
 }
 
-impl<'p, 'src, 'tokens, 'ast> AstStatementBuilder<'p, 'src, 'tokens, 'ast>
-where
-    'src: 'tokens,
-{
-    pub(crate) fn new(parser: &'p mut AstParser<'src, 'tokens>) -> Self {
+impl<'p, 'src> AstStatementBuilder<'p, 'src> {
+    pub(crate) fn new(parser: &'p mut AstParser<'src>) -> Self {
         Self { parser, statements: vec![] }
     }
 
-    pub fn build(&mut self) {
-        while self.parser.peek_next().token_type() != TokenType::RightBrace {
-            self.next_statement();
+    /// Builds statements up to, but not including, the closing brace of the block
+    pub fn build(&mut self) -> ParseResult<()> {
+        while !self.parser.is_next_token(TokenType::RightBrace) {
+            if self.parser.is_next_token(TokenType::Eof) {
+                return Err(self.parser.unexpected("'}'"));
+            }
+            self.next_statement()?;
+        }
+        Ok(())
+    }
+
+    /// Builds the `this(...)` or `super(...)` call a constructor body may start with
+    pub fn constructor_call(&mut self) -> ParseResult<()> {
+        if !self.is_next_constructor_call() {
+            return Ok(());
         }
+
+        let keyword = self.parser.next_token();
+        let (arguments, _) = self.arguments()?;
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+
+        let is_super = keyword.token_type() == TokenType::Super;
+        let statement = Statement::new_constructor_call(is_super, arguments).with_span(keyword.span().to(semi_colon.span()));
+        self.statements.push(statement);
+        Ok(())
+    }
+
+    fn is_next_constructor_call(&self) -> bool {
+        matches!(self.parser.peek_next().token_type(), TokenType::This | TokenType::Super)
+            && self.parser.peek_nth(1).token_type() == TokenType::LeftParen
     }
 
-    pub fn statements(self) -> Vec<Statement<'ast>> {
+    pub fn statements(self) -> Vec<Statement<'src>> {
         self.statements
     }
 
-    fn next_statement(&mut self) {
-        let statement = self.expression_statement();
-        self.statements.push(statement);
+    fn next_statement(&mut self) -> ParseResult<()> {
+        let mut class_parser = ClassParser::new(self.parser);
+        if class_parser.is_local_type_declaration() {
+            let statement = self.local_class()?;
+            self.statements.push(statement);
+        } else if class_parser.is_local_variable_declaration() {
+            let declarations = class_parser.local_variable_declaration()?;
+            self.statements.extend(declarations);
+        } else {
+            let statement = self.statement()?;
+            self.statements.push(statement);
+        }
+        Ok(())
     }
 
-    fn expression_statement(&mut self) -> Statement<'ast> {
-        let expression = self.expression();
-        self.consume(TokenType::SemiColon);
+    /// Parses a statement other than a declaration (JLS §14.5)
+    fn statement(&mut self) -> ParseResult<Statement<'src>> {
+        match self.parser.peek_next().token_type() {
+            TokenType::LeftBrace => self.block(),
+            TokenType::SemiColon => {
+                let semi_colon = self.parser.next_token();
+                Ok(Statement::new_empty().with_span(semi_colon.span()))
+            }
+            TokenType::If => self.if_statement(),
+            TokenType::While => self.while_statement(),
+            TokenType::Do => self.do_statement(),
+            TokenType::For => self.for_statement(),
+            TokenType::Break | TokenType::Continue => self.jump_statement(),
+            TokenType::Return => self.return_statement(),
+            TokenType::Identifier if self.parser.peek_nth(1).token_type() == TokenType::Colon => self.labeled_statement(),
+            _ => self.expression_statement(),
+        }
+    }
 
-        Statement::new_expression_statement(expression)
+    /// The statement a loop, `if` or label applies to, which cannot be a declaration
+    fn contained_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let class_parser = ClassParser::new(self.parser);
+        if class_parser.is_local_type_declaration() || class_parser.is_local_variable_declaration() {
+            return Err(SyntaxError::new("declaration not allowed here", self.parser.peek_next().span()));
+        }
+        self.statement()
     }
 
-    fn expression(&mut self) -> Expression<'ast> {
-        self.assignment()
+    fn block(&mut self) -> ParseResult<Statement<'src>> {
+        let left_brace = self.parser.consume(TokenType::LeftBrace)?;
+        let mut block_builder = AstStatementBuilder::new(self.parser);
+        block_builder.build()?;
+        let statements = block_builder.statements();
+        let right_brace = self.parser.consume(TokenType::RightBrace)?;
+        Ok(Statement::new_block(statements).with_span(left_brace.span().to(right_brace.span())))
     }
 
-    fn assignment(&mut self) -> Expression<'ast> {
-        let mut expression = self.call();
+    fn local_class(&mut self) -> ParseResult<Statement<'src>> {
+        let class = ClassParser::new(self.parser).local_type_declaration()?;
+        let span = class.span();
+        Ok(Statement::new_local_class(class).with_span(span))
+    }
 
-        if self.parser.is_next_token(TokenType::Identifier) {
-            expression = match expression {
-                Expression::Variable { name, type_def: None } => Expression::new_variable(self.consume(TokenType::Identifier).lexeme(), Some(name)),
-                expr  => expr
+    fn if_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.consume(TokenType::If)?;
+        let condition = self.parenthesized_expression()?;
+        let then_statement = self.contained_statement()?;
+        let mut end = then_statement.span();
+        let mut else_statement = None;
+        if self.parser.consume_if(TokenType::Else).is_some() {
+            let statement = self.contained_statement()?;
+            end = statement.span();
+            else_statement = Some(statement);
+        }
+        Ok(Statement::new_if(condition, then_statement, else_statement).with_span(keyword.span().to(end)))
+    }
+
+    fn while_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.consume(TokenType::While)?;
+        let condition = self.parenthesized_expression()?;
+        let body = self.contained_statement()?;
+        let span = keyword.span().to(body.span());
+        Ok(Statement::new_while(condition, body).with_span(span))
+    }
+
+    fn do_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.consume(TokenType::Do)?;
+        let body = self.contained_statement()?;
+        self.parser.consume(TokenType::While)?;
+        let condition = self.parenthesized_expression()?;
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+        Ok(Statement::new_do_while(body, condition).with_span(keyword.span().to(semi_colon.span())))
+    }
+
+    /// A basic `for` statement, or an enhanced one when its header declares a variable followed
+    /// by `:` (JLS §14.14)
+    fn for_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.consume(TokenType::For)?;
+        self.parser.consume(TokenType::LeftParen)?;
+
+        let mut class_parser = ClassParser::new(self.parser);
+        if class_parser.is_enhanced_for_variable() {
+            let (name, var_type, is_final) = class_parser.enhanced_for_variable()?;
+            self.parser.consume(TokenType::Colon)?;
+            let iterable = self.expression()?;
+            self.parser.consume(TokenType::RightParen)?;
+            let body = self.contained_statement()?;
+            let span = keyword.span().to(body.span());
+            return Ok(Statement::new_for_each(name, var_type, is_final, iterable, body).with_span(span));
+        }
+
+        let initializers = if class_parser.is_local_variable_declaration() {
+            class_parser.local_variable_declaration()?
+        } else {
+            let expressions = self.statement_expressions(TokenType::SemiColon)?;
+            self.parser.consume(TokenType::SemiColon)?;
+            expressions
+                .into_iter()
+                .map(|expression| {
+                    let span = expression.span();
+                    Statement::new_expression_statement(expression).with_span(span)
+                })
+                .collect()
+        };
+        let condition = if self.parser.is_next_token(TokenType::SemiColon) { None } else { Some(self.expression()?) };
+        self.parser.consume(TokenType::SemiColon)?;
+        let updates = self.statement_expressions(TokenType::RightParen)?;
+        self.parser.consume(TokenType::RightParen)?;
+
+        let body = self.contained_statement()?;
+        let span = keyword.span().to(body.span());
+        Ok(Statement::new_for(initializers, condition, updates, body).with_span(span))
+    }
+
+    /// `break` or `continue`, with an optional label
+    fn jump_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.next_token();
+        let label = self.parser.consume_if(TokenType::Identifier).map(|label| label.lexeme());
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+        let statement = match keyword.token_type() {
+            TokenType::Break => Statement::new_break(label),
+            _ => Statement::new_continue(label),
+        };
+        Ok(statement.with_span(keyword.span().to(semi_colon.span())))
+    }
+
+    fn return_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let keyword = self.parser.consume(TokenType::Return)?;
+        let value = if self.parser.is_next_token(TokenType::SemiColon) { None } else { Some(self.expression()?) };
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+        Ok(Statement::new_return(value).with_span(keyword.span().to(semi_colon.span())))
+    }
+
+    fn labeled_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let label = self.parser.consume(TokenType::Identifier)?;
+        self.parser.consume(TokenType::Colon)?;
+        let statement = self.contained_statement()?;
+        let span = label.span().to(statement.span());
+        Ok(Statement::new_labeled(label.lexeme(), statement).with_span(span))
+    }
+
+    fn parenthesized_expression(&mut self) -> ParseResult<Expression<'src>> {
+        self.parser.consume(TokenType::LeftParen)?;
+        let expression = self.expression()?;
+        self.parser.consume(TokenType::RightParen)?;
+        Ok(expression)
+    }
+
+    fn expression_statement(&mut self) -> ParseResult<Statement<'src>> {
+        let expression = self.statement_expression()?;
+        let semi_colon = self.parser.consume(TokenType::SemiColon)?;
+
+        let span = expression.span().to(semi_colon.span());
+        Ok(Statement::new_expression_statement(expression).with_span(span))
+    }
+
+    /// An expression that can stand as a statement (JLS §14.8)
+    fn statement_expression(&mut self) -> ParseResult<Expression<'src>> {
+        let expression = self.expression()?;
+        if !is_statement_expression(&expression) {
+            return Err(SyntaxError::new("not a statement", expression.span()));
+        }
+        Ok(expression)
+    }
+
+    /// Statement expressions separated by commas, as the initializers and updates of a `for`
+    /// statement are, up to the token that ends them
+    fn statement_expressions(&mut self, end: TokenType) -> ParseResult<Vec<Expression<'src>>> {
+        let mut expressions = vec![];
+        if self.parser.is_next_token(end) {
+            return Ok(expressions);
+        }
+        loop {
+            expressions.push(self.statement_expression()?);
+            if self.parser.consume_if(TokenType::Comma).is_none() {
+                return Ok(expressions);
             }
         }
+    }
+
+    pub(crate) fn expression(&mut self) -> ParseResult<Expression<'src>> {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> ParseResult<Expression<'src>> {
+        let mut expression = self.conditional()?;
 
         if self.parser.is_next_token(TokenType::Equal) {
-            self.consume(TokenType::Equal);
-            let value = self.expression();
+            let equal = self.parser.consume(TokenType::Equal)?;
+            let value = self.expression()?;
             expression = match expression {
-                Expression::Variable { name, type_def } => {
-                    Expression::new_assignment(name, type_def, value)
+                Expression::Variable { name, type_def, span } => {
+                    let span = span.to(value.span());
+                    Expression::new_assignment(name, type_def, value).with_span(span)
                 }
-                expr => {
-                    panic!("unexpected expression {:?}", expr);
+                Expression::ChildIdentifier { parent, name, span } => {
+                    let span = span.to(value.span());
+                    Expression::new_field_assignment(unbox(parent), name, value).with_span(span)
                 }
+                _ => return Err(SyntaxError::new("the left-hand side of an assignment must be a variable", equal.span())),
             }
         }
 
-        return expression
+        Ok(expression)
+    }
+
+    /// `condition ? when_true : when_false`, which associates to the right
+    fn conditional(&mut self) -> ParseResult<Expression<'src>> {
+        let condition = self.binary(0)?;
+        if self.parser.consume_if(TokenType::Question).is_none() {
+            return Ok(condition);
+        }
+
+        let when_true = self.expression()?;
+        self.parser.consume(TokenType::Colon)?;
+        let when_false = self.conditional()?;
+        let span = condition.span().to(when_false.span());
+        Ok(Expression::new_conditional(condition, when_true, when_false).with_span(span))
+    }
+
+    /// Binary operators by precedence climbing: the right operand of each operator takes only the
+    /// operators that bind more tightly, so that operators of equal precedence associate to the left
+    fn binary(&mut self, min_precedence: u8) -> ParseResult<Expression<'src>> {
+        let mut left = self.unary()?;
+
+        while let Some(operator) = BinaryOperator::from_token_type(self.parser.peek_next().token_type()) {
+            if operator.precedence() < min_precedence {
+                break;
+            }
+            self.parser.next_token();
+            let right = self.binary(operator.precedence() + 1)?;
+            let span = left.span().to(right.span());
+            left = Expression::new_binary(left, operator, right).with_span(span);
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> ParseResult<Expression<'src>> {
+        let token_type = self.parser.peek_next().token_type();
+        if let Some(operator) = UnaryOperator::from_token_type(token_type) {
+            let operator_token = self.parser.next_token();
+            let operand = self.unary()?;
+            let span = operator_token.span().to(operand.span());
+            return Ok(Expression::new_unary(operator, operand).with_span(span));
+        }
+
+        if matches!(token_type, TokenType::PlusPlus | TokenType::MinusMinus) {
+            let operator_token = self.parser.next_token();
+            let operand = self.unary()?;
+            check_increment_operand(&operand)?;
+            let span = operator_token.span().to(operand.span());
+            let is_decrement = token_type == TokenType::MinusMinus;
+            return Ok(Expression::new_prefix_increment(operand, is_decrement).with_span(span));
+        }
+
+        self.postfix()
     }
 
-    fn call(&mut self) -> Expression<'ast> {
-        let mut expr = self.primary();
+    fn postfix(&mut self) -> ParseResult<Expression<'src>> {
+        let mut expression = self.call()?;
+        while matches!(self.parser.peek_next().token_type(), TokenType::PlusPlus | TokenType::MinusMinus) {
+            check_increment_operand(&expression)?;
+            let operator_token = self.parser.next_token();
+            let span = expression.span().to(operator_token.span());
+            let is_decrement = operator_token.token_type() == TokenType::MinusMinus;
+            expression = Expression::new_postfix_increment(expression, is_decrement).with_span(span);
+        }
+        Ok(expression)
+    }
+
+    fn call(&mut self) -> ParseResult<Expression<'src>> {
+        let mut expr = self.primary()?;
 
-        while true {
+        loop {
             if self.parser.is_next_token(TokenType::LeftParen) {
-                self.consume(TokenType::LeftParen);
-                let mut arguments: Vec<Expression<'ast>> = vec![];
-                let mut next_token_type = self.parser.peek_next().token_type();
-                while next_token_type != TokenType::RightParen {
-                    let arg = { self.expression() };
-                    arguments.push(arg);
-                    next_token_type = self.parser.peek_next().token_type()
-                }
-                self.consume(TokenType::RightParen);
+                let left_paren = self.parser.peek_next().span();
+                let (arguments, right_paren) = self.arguments()?;
 
-                let (parent_expr, method_name) = Self::deconstruct_method_name_from(expr);
+                let span = expr.span().to(right_paren);
+                let Expression::ChildIdentifier { parent, name, .. } = expr else {
+                    return Err(SyntaxError::new("only calls to methods of a field or class are supported", left_paren));
+                };
 
-                return Expression::new_call(
-                    parent_expr,
-                    method_name,
+                return Ok(Expression::new_call(
+                    unbox(parent),
+                    name,
                     arguments,
                 )
+                .with_span(span))
 
             } else if self.parser.is_next_token(TokenType::Dot) {
-                self.consume(TokenType::Dot);
+                self.parser.consume(TokenType::Dot)?;
 
-                expr = Expression::new_child_identifier(expr, self.consume(TokenType::Identifier).lexeme())
+                let name = self.parser.consume(TokenType::Identifier)?;
+                let span = expr.span().to(name.span());
+                expr = Expression::new_child_identifier(expr, name.lexeme()).with_span(span)
             } else {
                 break
             }
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn primary(&mut self) -> Expression<'ast> {
-        let next_token = self.parser.peek_next();
-        if next_token.token_type() == TokenType::Identifier {
-            return Expression::new_variable(self.consume(TokenType::Identifier).lexeme(), None);
-        }
-        if next_token.token_type() == TokenType::String {
-            return self.string_literal();
+    /// Builds the parenthesised arguments of a call, returning them with the span of the closing parenthesis
+    pub(crate) fn arguments(&mut self) -> ParseResult<(Vec<Expression<'src>>, SourceSpan)> {
+        self.parser.consume(TokenType::LeftParen)?;
+        let mut arguments: Vec<Expression<'src>> = vec![];
+        if !self.parser.is_next_token(TokenType::RightParen) {
+            arguments.push(self.expression()?);
+            while self.parser.consume_if(TokenType::Comma).is_some() {
+                arguments.push(self.expression()?);
+            }
         }
-
-        panic!("Unknown token {:?}", next_token);
+        let right_paren = self.parser.consume(TokenType::RightParen)?;
+        Ok((arguments, right_paren.span()))
     }
 
-    fn string_literal(&mut self) -> Expression<'ast> {
-        let token = self.consume(TokenType::String);
-        match token.literal() {
-            Literal::String(value) => Expression::new_string_literal(value),
+    fn primary(&mut self) -> ParseResult<Expression<'src>> {
+        match self.parser.peek_next().token_type() {
+            TokenType::Identifier => {
+                let name = self.parser.consume(TokenType::Identifier)?;
+                Ok(Expression::new_variable(name.lexeme(), None).with_span(name.span()))
+            }
+            TokenType::String | TokenType::CharLiteral | TokenType::IntLiteral | TokenType::LongLiteral | TokenType::FloatLiteral | TokenType::DoubleLiteral => {
+                Ok(self.literal())
+            }
+            TokenType::True | TokenType::False => {
+                let token = self.parser.next_token();
+                Ok(Expression::new_boolean_literal(token.token_type() == TokenType::True).with_span(token.span()))
+            }
+            TokenType::New => self.instance_creation(),
+            TokenType::LeftParen => {
+                let left_paren = self.parser.consume(TokenType::LeftParen)?;
+                let expression = self.expression()?;
+                let right_paren = self.parser.consume(TokenType::RightParen)?;
+                Ok(expression.with_span(left_paren.span().to(right_paren.span())))
+            }
+            TokenType::This | TokenType::Super if self.is_next_constructor_call() => {
+                let keyword = self.parser.peek_next();
+                Err(SyntaxError::new(
+                    format!("call to {} must be first statement in constructor", keyword.lexeme()),
+                    keyword.span(),
+                ))
+            }
+            TokenType::This => {
+                let keyword = self.parser.next_token();
+                Ok(Expression::new_this().with_span(keyword.span()))
+            }
+            _ => Err(self.parser.unexpected("an expression")),
         }
     }
 
-    fn consume(&mut self, expected_type: TokenType) -> Token<'src> {
-        if self.parser.peek_next().token_type() == expected_type {
-            self.parser.next_token()
-        } else {
-            panic!("Expected {:?}, but was {:?}", expected_type, self.parser.peek_next().token_type())
+    /// Builds `new C(...)`, which may be followed by the body of an anonymous class
+    fn instance_creation(&mut self) -> ParseResult<Expression<'src>> {
+        let new_keyword = self.parser.consume(TokenType::New)?;
+        let (class_type, _) = ClassParser::new(self.parser).class_type()?;
+        let (arguments, right_paren) = self.arguments()?;
+
+        let mut span = new_keyword.span().to(right_paren);
+        let mut body = None;
+        if self.parser.is_next_token(TokenType::LeftBrace) {
+            let anonymous_class = ClassParser::new(self.parser).anonymous_class_body()?;
+            span = span.to(anonymous_class.span());
+            body = Some(anonymous_class);
         }
+        Ok(Expression::new_instance_creation(class_type, arguments, body).with_span(span))
     }
 
-    fn deconstruct_method_name_from<'a>(expression: Expression<'a>) -> (Expression<'a>, &'a str) {
-        match expression {
-            Expression::ChildIdentifier { parent, name } => (unbox(parent), name),
-            _expr => panic!("Unsupported")
-        }
+    fn literal(&mut self) -> Expression<'src> {
+        let token = self.parser.next_token();
+        let expression = match token.literal() {
+            Literal::String(value) => Expression::new_string_literal(value),
+            Literal::Char(value) => Expression::new_char_literal(*value),
+            Literal::Int(value) => Expression::new_int_literal(*value),
+            Literal::Long(value) => Expression::new_long_literal(*value),
+            Literal::Float(value) => Expression::new_float_literal(*value),
+            Literal::Double(value) => Expression::new_double_literal(*value),
+        };
+        expression.with_span(token.span())
+    }
+}
+
+/// Only expressions with an effect can stand as statements (JLS §14.8)
+fn is_statement_expression(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Assignment { .. }
+            | Expression::FieldAssignment { .. }
+            | Expression::PrefixIncrement { .. }
+            | Expression::PostfixIncrement { .. }
+            | Expression::Call { .. }
+            | Expression::New { .. }
+    )
+}
+
+fn check_increment_operand(operand: &Expression) -> ParseResult<()> {
+    match operand {
+        Expression::Variable { type_def: None, .. } | Expression::ChildIdentifier { .. } => Ok(()),
+        _ => Err(SyntaxError::new("the operand of an increment or decrement must be a variable", operand.span())),
     }
 }
 
@@ -1,142 +1,100 @@
-use crate::ast::class::{AstClass, AstScope};
-use crate::ast::class_builder::{Build, ClassBuilder};
-use crate::ast::class_state_machine_factory::ClassState;
-use crate::ast::method_builder::AstStatementBuilder;
+use crate::ast::class_parser::ClassParser;
+use crate::ast::compilation_unit::AstCompilationUnit;
+use crate::ast::result::{ParseResult, SyntaxError};
 use crate::scanner::{Token, TokenType};
 
 pub mod class;
 mod class_builder;
-mod class_state_machine_factory;
+mod class_parser;
+pub mod compilation_unit;
 pub mod expression;
 mod method_builder;
-mod state_machine;
+pub mod result;
 pub mod statement;
 
-struct AstParser<'src, 'token>
-where
-    'src: 'token,
-{
+struct AstParser<'src> {
+    source: &'src str,
     position: usize,
-    tokens: &'token [Token<'src>],
+    tokens: Vec<Token<'src>>,
 }
 
-impl<'src, 'token> AstParser<'src, 'token> {
-    fn for_tokens(tokens: &'token [Token<'src>]) -> Self {
+impl<'src> AstParser<'src> {
+    fn new(source: &'src str, tokens: Vec<Token<'src>>) -> Self {
         Self {
+            source,
             position: 0,
             tokens,
         }
     }
 
+    /// Moves on to the next token. The scanner always ends the tokens with `Eof`, which is never
+    /// moved past.
     fn next_token(&mut self) -> Token<'src> {
         let i = self.position;
-        self.position = i + 1;
-        self.tokens[i]
+        if i + 1 < self.tokens.len() {
+            self.position = i + 1;
+        }
+        self.tokens[i].clone()
     }
 
-    fn peek_next(&self) -> Token<'src> {
-        self.tokens[self.position]
+    fn peek_next(&self) -> &Token<'src> {
+        self.peek_nth(0)
     }
 
-    fn has_more_tokens(&self) -> bool {
-        self.position < self.tokens.len()
+    /// Looks `n` tokens past the next one without consuming anything
+    fn peek_nth(&self, n: usize) -> &Token<'src> {
+        let i = (self.position + n).min(self.tokens.len() - 1);
+        &self.tokens[i]
     }
 
-    fn position(&self) -> usize {
-        self.position
-    }
-    
     fn is_next_token(&self, token_type: TokenType) -> bool {
         self.peek_next().token_type() == token_type
     }
 
-    fn lexemes_from_position(&self, from: usize, to: usize) -> Vec<&'src str> {
-
-        self.tokens[from..=to]
-            .iter()
-            .filter(|t| t.token_type() != TokenType::Dot)
-            .map(|t| t.lexeme())
-            .collect()
+    fn consume(&mut self, expected_type: TokenType) -> ParseResult<Token<'src>> {
+        if self.is_next_token(expected_type) {
+            Ok(self.next_token())
+        } else {
+            Err(self.unexpected(&expected_type.description()))
+        }
     }
-}
-
-pub fn to_ast<'a>(tokens: Vec<Token<'a>>) -> AstClass<'a> {
-    let mut parser = AstParser::for_tokens(&tokens);
-    let mut class_state_machine = class_state_machine_factory::load();
-    let mut class_builder = ClassBuilder::new();
 
-    while parser.has_more_tokens() {
-        let token = parser.next_token();
-        if let Some(state) = class_state_machine.on_token(token.token_type()) {
-            match state {
-                ClassState::Initial => {
-                    // No op
-                }
-                ClassState::ClassScope => class_builder.with_scope(scope_for(token.token_type())),
-                ClassState::ClassDefinition => {
-                    // TODO: Figure out how to put class builder creation here
-                }
-                ClassState::ClassName => class_builder.named(token.lexeme()),
-                ClassState::ClassBody => {
-                    // No op
-                }
-                ClassState::MethodQualifier => {
-                    class_builder.with_new_method();
-                    class_builder
-                        .latest_method()
-                        .with_scope(scope_for(token.token_type()));
-                }
-                ClassState::MethodStatic => class_builder.latest_method().as_static(),
-                ClassState::MethodReturn => {
-                    let method = class_builder.latest_method();
-                    method.with_return_type(token.lexeme())
-                }
-                ClassState::MethodName => {
-                    let method = class_builder.latest_method();
-                    method.with_name(token.lexeme())
-                }
-                ClassState::MethodParameters => {
-                    // No op
-                }
-                ClassState::MethodParameterType => {
-                    let method = class_builder.latest_method();
-                    method.with_new_parameter();
-                    method.latest_parameter().with_type(token.lexeme())
-                }
-                ClassState::MethodParameterArrayIndicatorStart => {
-                    // No op
-                }
-                ClassState::MethodParameterArrayIndicatorEnd => {
-                    let method = class_builder.latest_method();
-                    method.latest_parameter().as_array();
-                }
-                ClassState::MethodParameterName => {
-                    let method = class_builder.latest_method();
-                    method.latest_parameter().with_name(token.lexeme())
-                }
-                ClassState::MethodParametersEnd => {
-                    // No op
-                }
-                ClassState::MethodBody => {
-                    let mut statement_builder = AstStatementBuilder::new(&mut parser);
-                    statement_builder.build();
+    fn consume_if(&mut self, token_type: TokenType) -> Option<Token<'src>> {
+        if self.is_next_token(token_type) {
+            Some(self.next_token())
+        } else {
+            None
+        }
+    }
 
-                    class_builder
-                        .latest_method()
-                        .with_statements(statement_builder.statements());
-                }
-                ClassState::ClassEnd => {}
-                ClassState::Eof => {}
-            }
+    /// Consumes a single `>` closing type arguments. Maximal munch scans the end of
+    /// `List<List<String>>` as a shift operator, so it is split and only its first `>` is consumed.
+    fn consume_closing_angle_bracket(&mut self) -> ParseResult<Token<'src>> {
+        if let Some((greater, remainder)) = self.peek_next().split_leading_greater() {
+            self.tokens[self.position] = remainder;
+            return Ok(greater);
         }
+        self.consume(TokenType::Greater)
     }
 
-    class_builder.build()
-}
+    /// An error for the next token, which is not what the parser `expected`
+    fn unexpected(&self, expected: &str) -> SyntaxError {
+        let found = self.peek_next();
+        let description = match found.token_type() {
+            TokenType::Eof => "end of file".to_string(),
+            _ => format!("'{}'", found.lexeme()),
+        };
+        SyntaxError::new(format!("expected {} but found {}", expected, description), found.span())
+    }
 
-fn scope_for(token_type: TokenType) -> AstScope {
-    match token_type {
-        TokenType::Public => AstScope::Public,
-        _ => panic!("Unknown scope {:?}", token_type),
+    /// The source text from the start of `first` to the end of `last`
+    fn source_between(&self, first: &Token<'src>, last: &Token<'src>) -> &'src str {
+        &self.source[first.start()..last.end()]
     }
 }
+
+#[allow(clippy::needless_lifetimes)]
+pub fn to_ast<'a>(source: &'a str, tokens: Vec<Token<'a>>) -> ParseResult<AstCompilationUnit<'a>> {
+    let mut parser = AstParser::new(source, tokens);
+    ClassParser::new(&mut parser).compilation_unit()
+}
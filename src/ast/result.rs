@@ -0,0 +1,32 @@
+use crate::scanner::SourceSpan;
+use std::fmt::{Display, Formatter};
+
+pub type ParseResult<T> = Result<T, SyntaxError>;
+
+/// The first point at which the tokens could not be parsed
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxError {
+    message: String,
+    span: SourceSpan,
+}
+
+impl SyntaxError {
+    pub fn new(message: impl Into<String>, span: SourceSpan) -> Self {
+        Self { message: message.into(), span }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+}
+
+/// Formats as `line:column: message`, to be prefixed with the file name when reported
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.span, self.message)
+    }
+}
@@ -1,17 +1,166 @@
+use crate::ast::class::{AstClass, AstType};
 use crate::ast::expression::Expression;
+use crate::scanner::SourceSpan;
 use std::fmt::Debug;
 
 #[derive(Debug)]
 pub enum Statement<'ast> {
-    Expression { expression: Expression<'ast> },
-    VariableAssignment { name: &'ast str, var_type: &'ast str, is_final: bool, value: Option<Expression<'ast>> }
+    Expression { expression: Expression<'ast>, span: SourceSpan },
+    /// The declaration of a local variable, in scope from there to the end of the enclosing block
+    VariableAssignment { name: &'ast str, var_type: AstType<'ast>, is_final: bool, value: Option<Expression<'ast>>, span: SourceSpan },
+    /// A `{ ... }` block, whose local variables and classes go out of scope at its end
+    Block { statements: Vec<Statement<'ast>>, span: SourceSpan },
+    /// An explicit `this(...)` or `super(...)` call, which may only start a constructor body
+    ConstructorCall { is_super: bool, arguments: Vec<Expression<'ast>>, span: SourceSpan },
+    /// A class declared in a block, in scope from its declaration to the end of the block
+    LocalClass { class: Box<AstClass<'ast>>, span: SourceSpan },
+    /// A lone `;`, which does nothing
+    Empty { span: SourceSpan },
+    If { condition: Expression<'ast>, then_statement: Box<Statement<'ast>>, else_statement: Option<Box<Statement<'ast>>>, span: SourceSpan },
+    While { condition: Expression<'ast>, body: Box<Statement<'ast>>, span: SourceSpan },
+    DoWhile { body: Box<Statement<'ast>>, condition: Expression<'ast>, span: SourceSpan },
+    /// A basic `for` statement, whose initializers are either local variable declarations or
+    /// expression statements, in scope until the end of the loop
+    For {
+        initializers: Vec<Statement<'ast>>,
+        condition: Option<Expression<'ast>>,
+        updates: Vec<Expression<'ast>>,
+        body: Box<Statement<'ast>>,
+        span: SourceSpan,
+    },
+    /// An enhanced `for` statement, over the elements of an array or an `Iterable`
+    ForEach {
+        name: &'ast str,
+        var_type: AstType<'ast>,
+        is_final: bool,
+        iterable: Expression<'ast>,
+        body: Box<Statement<'ast>>,
+        span: SourceSpan,
+    },
+    /// Leaves the innermost loop, or the enclosing statement with the label
+    Break { label: Option<&'ast str>, span: SourceSpan },
+    /// Starts the next iteration of the innermost loop, or of the enclosing loop with the label
+    Continue { label: Option<&'ast str>, span: SourceSpan },
+    Labeled { label: &'ast str, statement: Box<Statement<'ast>>, span: SourceSpan },
+    /// Returns from the method or constructor, with a value unless it is `void`
+    Return { value: Option<Expression<'ast>>, span: SourceSpan },
 }
 impl <'ast> Statement<'ast> {
     pub fn new_expression_statement(expression: Expression<'ast>) -> Statement<'ast> {
-        Statement::Expression { expression }
+        Statement::Expression { expression, span: SourceSpan::default() }
     }
 
-    pub fn new_var_assignment(name: &'ast str, var_type: &'ast str, is_final: bool, value: Option<Expression<'ast>>) -> Statement<'ast> {
-        Statement::VariableAssignment { name, var_type, is_final, value }
+    pub fn new_var_assignment(name: &'ast str, var_type: AstType<'ast>, is_final: bool, value: Option<Expression<'ast>>) -> Statement<'ast> {
+        Statement::VariableAssignment { name, var_type, is_final, value, span: SourceSpan::default() }
     }
-}
\ No newline at end of file
+
+    pub fn new_constructor_call(is_super: bool, arguments: Vec<Expression<'ast>>) -> Statement<'ast> {
+        Statement::ConstructorCall { is_super, arguments, span: SourceSpan::default() }
+    }
+
+    pub fn new_block(statements: Vec<Statement<'ast>>) -> Statement<'ast> {
+        Statement::Block { statements, span: SourceSpan::default() }
+    }
+
+    pub fn new_local_class(class: AstClass<'ast>) -> Statement<'ast> {
+        Statement::LocalClass { class: Box::new(class), span: SourceSpan::default() }
+    }
+
+    pub fn new_empty() -> Statement<'ast> {
+        Statement::Empty { span: SourceSpan::default() }
+    }
+
+    pub fn new_if(condition: Expression<'ast>, then_statement: Statement<'ast>, else_statement: Option<Statement<'ast>>) -> Statement<'ast> {
+        Statement::If {
+            condition,
+            then_statement: Box::new(then_statement),
+            else_statement: else_statement.map(Box::new),
+            span: SourceSpan::default(),
+        }
+    }
+
+    pub fn new_while(condition: Expression<'ast>, body: Statement<'ast>) -> Statement<'ast> {
+        Statement::While { condition, body: Box::new(body), span: SourceSpan::default() }
+    }
+
+    pub fn new_do_while(body: Statement<'ast>, condition: Expression<'ast>) -> Statement<'ast> {
+        Statement::DoWhile { body: Box::new(body), condition, span: SourceSpan::default() }
+    }
+
+    pub fn new_for(
+        initializers: Vec<Statement<'ast>>,
+        condition: Option<Expression<'ast>>,
+        updates: Vec<Expression<'ast>>,
+        body: Statement<'ast>,
+    ) -> Statement<'ast> {
+        Statement::For { initializers, condition, updates, body: Box::new(body), span: SourceSpan::default() }
+    }
+
+    pub fn new_for_each(
+        name: &'ast str,
+        var_type: AstType<'ast>,
+        is_final: bool,
+        iterable: Expression<'ast>,
+        body: Statement<'ast>,
+    ) -> Statement<'ast> {
+        Statement::ForEach { name, var_type, is_final, iterable, body: Box::new(body), span: SourceSpan::default() }
+    }
+
+    pub fn new_break(label: Option<&'ast str>) -> Statement<'ast> {
+        Statement::Break { label, span: SourceSpan::default() }
+    }
+
+    pub fn new_continue(label: Option<&'ast str>) -> Statement<'ast> {
+        Statement::Continue { label, span: SourceSpan::default() }
+    }
+
+    pub fn new_labeled(label: &'ast str, statement: Statement<'ast>) -> Statement<'ast> {
+        Statement::Labeled { label, statement: Box::new(statement), span: SourceSpan::default() }
+    }
+
+    pub fn new_return(value: Option<Expression<'ast>>) -> Statement<'ast> {
+        Statement::Return { value, span: SourceSpan::default() }
+    }
+
+    /// Statements are created with a default span; the parser sets the source they came from here
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        match &mut self {
+            Statement::Expression { span: statement_span, .. }
+            | Statement::VariableAssignment { span: statement_span, .. }
+            | Statement::ConstructorCall { span: statement_span, .. }
+            | Statement::Block { span: statement_span, .. }
+            | Statement::LocalClass { span: statement_span, .. }
+            | Statement::Empty { span: statement_span }
+            | Statement::If { span: statement_span, .. }
+            | Statement::While { span: statement_span, .. }
+            | Statement::DoWhile { span: statement_span, .. }
+            | Statement::For { span: statement_span, .. }
+            | Statement::ForEach { span: statement_span, .. }
+            | Statement::Break { span: statement_span, .. }
+            | Statement::Continue { span: statement_span, .. }
+            | Statement::Labeled { span: statement_span, .. }
+            | Statement::Return { span: statement_span, .. } => *statement_span = span,
+        }
+        self
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Statement::Expression { span, .. }
+            | Statement::VariableAssignment { span, .. }
+            | Statement::ConstructorCall { span, .. }
+            | Statement::Block { span, .. }
+            | Statement::LocalClass { span, .. }
+            | Statement::Empty { span }
+            | Statement::If { span, .. }
+            | Statement::While { span, .. }
+            | Statement::DoWhile { span, .. }
+            | Statement::For { span, .. }
+            | Statement::ForEach { span, .. }
+            | Statement::Break { span, .. }
+            | Statement::Continue { span, .. }
+            | Statement::Labeled { span, .. }
+            | Statement::Return { span, .. } => *span,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::ast::class::{AstClass, AstField, AstMethod, AstParameter};
+use crate::ast::expression::Expression;
+use crate::ast::statement::Statement;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::CompileResult;
+use crate::compiler::CompilationContext;
+
+/// The local variables in scope that the body of a local or anonymous class uses, in the order it
+/// first uses them, including those of any local class it creates. The class captures their
+/// values: its constructors take them after the enclosing instance and keep them in synthetic
+/// `val$name` fields. Each must be definitely assigned before the class and never assigned again,
+/// being final or effectively final (JLS §8.1.3).
+pub fn captured_variables(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Vec<(String, JavaType)>> {
+    let mut free_names = FreeNames::default();
+    free_names.class(class);
+    for class_type in std::mem::take(&mut free_names.created_types) {
+        let Ok(class_name) = compilation_context.resolve_class(class_type) else {
+            continue;
+        };
+        if let Some(created_class) = compilation_context.class_loader.load(&class_name) {
+            for (name, _) in created_class.captured_variables() {
+                free_names.use_name(name);
+            }
+        }
+    }
+
+    let mut captured = vec![];
+    for name in free_names.names {
+        if let Some(variable_type) = compilation_context.locals.capture(&name)? {
+            captured.push((name, variable_type));
+        }
+    }
+    Ok(captured)
+}
+
+/// The simple names a class body uses that nothing in it declares, as the fields, parameters and
+/// local variables it declares hide any variable of the same name outside it, along with the
+/// classes it creates
+#[derive(Default)]
+struct FreeNames<'a> {
+    /// The names declared in the scope being gone through
+    declared: Vec<&'a str>,
+    names: Vec<String>,
+    created_types: Vec<&'a str>,
+}
+
+impl<'a> FreeNames<'a> {
+    fn class(&mut self, class: &AstClass<'a>) {
+        let in_scope = self.declared.len();
+        self.declared.extend(class.fields().iter().map(AstField::name));
+        self.declared.extend(class.record_components().iter().map(AstParameter::param_name));
+        for field in class.fields() {
+            if let Some(initializer) = field.initializer() {
+                self.expression(initializer);
+            }
+        }
+        for method in class.constructors().iter().chain(class.methods()) {
+            self.method(method);
+        }
+        for initializer in class.initializers() {
+            self.block(initializer.statements());
+        }
+        for member_type in class.member_types() {
+            self.class(member_type);
+        }
+        self.declared.truncate(in_scope);
+    }
+
+    fn method(&mut self, method: &AstMethod<'a>) {
+        let in_scope = self.declared.len();
+        self.declared.extend(method.parameters().iter().map(AstParameter::param_name));
+        self.block(method.statements());
+        self.declared.truncate(in_scope);
+    }
+
+    fn block(&mut self, statements: &[Statement<'a>]) {
+        let in_scope = self.declared.len();
+        for statement in statements {
+            self.statement(statement);
+        }
+        self.declared.truncate(in_scope);
+    }
+
+    fn statement(&mut self, statement: &Statement<'a>) {
+        match statement {
+            Statement::Expression { expression, .. } => self.expression(expression),
+            Statement::VariableAssignment { name, value, .. } => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+                self.declared.push(name);
+            }
+            Statement::Block { statements, .. } => self.block(statements),
+            Statement::ConstructorCall { arguments, .. } => self.expressions(arguments),
+            Statement::LocalClass { class, .. } => self.class(class),
+            Statement::If { condition, then_statement, else_statement, .. } => {
+                self.expression(condition);
+                self.block(std::slice::from_ref(then_statement));
+                if let Some(else_statement) = else_statement {
+                    self.block(std::slice::from_ref(else_statement));
+                }
+            }
+            Statement::While { condition, body, .. } | Statement::DoWhile { body, condition, .. } => {
+                self.expression(condition);
+                self.block(std::slice::from_ref(body));
+            }
+            Statement::For { initializers, condition, updates, body, .. } => {
+                let in_scope = self.declared.len();
+                for initializer in initializers {
+                    self.statement(initializer);
+                }
+                if let Some(condition) = condition {
+                    self.expression(condition);
+                }
+                self.expressions(updates);
+                self.block(std::slice::from_ref(body));
+                self.declared.truncate(in_scope);
+            }
+            Statement::ForEach { name, iterable, body, .. } => {
+                self.expression(iterable);
+                let in_scope = self.declared.len();
+                self.declared.push(name);
+                self.block(std::slice::from_ref(body));
+                self.declared.truncate(in_scope);
+            }
+            Statement::Labeled { statement, .. } => self.statement(statement),
+            Statement::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+            }
+            Statement::Empty { .. } | Statement::Break { .. } | Statement::Continue { .. } => {}
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[Expression<'a>]) {
+        for expression in expressions {
+            self.expression(expression);
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression<'a>) {
+        match expression {
+            Expression::Variable { name, .. } => self.use_name(name),
+            Expression::Assignment { name, value, .. } => {
+                self.use_name(name);
+                self.expression(value);
+            }
+            Expression::Call { target, arguments, .. } => {
+                self.expression(target);
+                self.expressions(arguments);
+            }
+            Expression::ChildIdentifier { parent, .. } => self.expression(parent),
+            Expression::FieldAssignment { qualifier, value, .. } => {
+                self.expression(qualifier);
+                self.expression(value);
+            }
+            Expression::New { class_type, arguments, body, .. } => {
+                self.created_types.push(class_type);
+                self.expressions(arguments);
+                if let Some(body) = body {
+                    self.class(body);
+                }
+            }
+            Expression::Binary { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::Unary { operand, .. } | Expression::PrefixIncrement { operand, .. } | Expression::PostfixIncrement { operand, .. } => {
+                self.expression(operand)
+            }
+            Expression::Conditional { condition, when_true, when_false, .. } => {
+                self.expression(condition);
+                self.expression(when_true);
+                self.expression(when_false);
+            }
+            Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::CharLiteral { .. }
+            | Expression::IntLiteral { .. }
+            | Expression::LongLiteral { .. }
+            | Expression::FloatLiteral { .. }
+            | Expression::DoubleLiteral { .. }
+            | Expression::This { .. } => {}
+        }
+    }
+
+    fn use_name(&mut self, name: &str) {
+        if !self.declared.contains(&name) && !self.names.iter().any(|used| used == name) {
+            self.names.push(name.to_string());
+        }
+    }
+}
@@ -1,38 +1,273 @@
-use crate::ast::class::{AstClass, AstMethod};
+use crate::ast::class::{AstClass, AstField, AstMethod, AstScope};
+use crate::compiler::descriptor::constructor_descriptor;
 use crate::compiler::result::{wrap, CompileResult};
-use crate::compiler::CompilationContext;
-use ristretto_classfile::{ClassFile, Method, JAVA_21};
-
-const DEFAULT_SUPER_CLASS: &str = "java/lang/Object";
+use crate::compiler::{descriptor, enum_builder, field_builder, initializer, method_builder, nested_class_builder, record_builder, CompilationContext};
+use crate::compiler::java_type::JavaType;
+use ristretto_classfile::attributes::Attribute;
+use ristretto_classfile::{ClassAccessFlags, ClassFile, Field, Method, JAVA_21};
 
+/// Compiles a class, along with the classes nested in it: its member types, the local and
+/// anonymous classes its code declares and the class bodies of the constants of an enum
 pub fn from(
     class: &AstClass,
-    mut compilation_context: &mut CompilationContext,
-) -> CompileResult<ClassFile> {
-    let methods = map_methods(class.methods(), &mut compilation_context)?;
-
-    let this_class = wrap(
-        compilation_context
-            .constant_pool
-            .add_class(class.name()),
-    )?;
-    let super_class = wrap(
-        compilation_context
-            .constant_pool
-            .add_class(DEFAULT_SUPER_CLASS),
-    )?;
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    compilation_context.is_enum = class.is_enum();
+    let this_class_name = compilation_context.this_class_name.clone();
+    nested_class_builder::register_member_types(class, &this_class_name, compilation_context);
+    // The class bodies of enum constants are numbered ahead of any other anonymous class
+    compilation_context.anonymous_classes = class.enum_constants().iter().filter(|constant| constant.body().is_some()).count();
+
+    // Fields come first so that code can refer to them
+    let mut fields = if class.is_enum() {
+        enum_builder::fields(class, compilation_context)?
+    } else if class.is_record() {
+        record_builder::fields(class, compilation_context)?
+    } else {
+        vec![]
+    };
+    fields.extend(map_fields(class.fields(), compilation_context)?);
+    fields.extend(nested_class_builder::outer_instance_field(compilation_context)?);
+    fields.extend(nested_class_builder::captured_variable_fields(compilation_context)?);
+
+    let this_class = compilation_context.this_class;
+    let super_class = compilation_context.super_class;
+
+    let mut methods = map_constructors(class, compilation_context)?;
+    methods.extend(map_methods(class.methods(), compilation_context)?);
+    if class.is_record() {
+        methods.extend(record_builder::methods(class, compilation_context)?);
+    }
+
+    let mut static_initialization = vec![];
+    let mut nested_classes = vec![];
+    if class.is_enum() {
+        methods.extend(enum_builder::methods(compilation_context)?);
+        let constants = enum_builder::constant_initialization(class, compilation_context)?;
+        static_initialization = constants.instructions;
+        nested_classes = enum_builder::constant_bodies(class, &constants.constructor_descriptors, compilation_context)?;
+    }
+
+    let initialization = initializer::static_initialization(class, compilation_context)?;
+    static_initialization.extend(initialization.instructions);
+    if !static_initialization.is_empty() {
+        methods.push(method_builder::from_static_initialization(
+            static_initialization,
+            initialization.max_locals,
+            compilation_context,
+        )?);
+    }
+    nested_classes.extend(nested_class_builder::member_types(class, compilation_context)?);
+
+    // Once every nested class is known, as the host lists them all
+    let mut attributes = nested_class_builder::nest_attributes(compilation_context)?;
+    if class.is_record() {
+        attributes.push(record_builder::record_attribute(class, compilation_context)?);
+    }
+    attributes.extend(bootstrap_methods(compilation_context)?);
 
     let class_file = ClassFile {
         version: JAVA_21,
+        access_flags: access_flags_from(class),
         constant_pool: compilation_context.constant_pool.to_owned(),
         this_class,
         super_class,
+        interfaces: compilation_context.interfaces.clone(),
+        fields,
         methods,
-        ..Default::default()
+        attributes,
     };
     wrap(class_file.verify())?;
 
-    Ok(class_file)
+    let mut class_files = vec![class_file];
+    class_files.append(&mut compilation_context.local_class_files);
+    class_files.extend(nested_classes);
+    Ok(class_files)
+}
+
+/// Compiles the body of an anonymous class, or the class body of an enum constant, with a
+/// constructor taking the same arguments as the superclass constructor it calls, after the
+/// enclosing instance if it has one
+pub fn from_anonymous_class(
+    body: &AstClass,
+    super_constructor_descriptor: &str,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    let this_class_name = compilation_context.this_class_name.clone();
+    nested_class_builder::register_member_types(body, &this_class_name, compilation_context);
+
+    let mut fields = map_fields(body.fields(), compilation_context)?;
+    fields.extend(nested_class_builder::outer_instance_field(compilation_context)?);
+    fields.extend(nested_class_builder::captured_variable_fields(compilation_context)?);
+
+    let this_class = compilation_context.this_class;
+    let super_class = compilation_context.super_class;
+
+    let hidden_slots: u16 = compilation_context.hidden_constructor_parameters().iter().map(JavaType::slots).sum();
+    let first_local = 1 + hidden_slots + descriptor::parameter_slots(super_constructor_descriptor);
+    let instance_initialization = initializer::instance_initialization(body, first_local, compilation_context)?;
+    let mut methods = vec![nested_class_builder::anonymous_constructor(
+        super_constructor_descriptor,
+        &instance_initialization,
+        compilation_context,
+    )?];
+    methods.extend(map_methods(body.methods(), compilation_context)?);
+
+    let static_initialization = initializer::static_initialization(body, compilation_context)?;
+    if !static_initialization.instructions.is_empty() {
+        methods.push(method_builder::from_static_initialization(
+            static_initialization.instructions,
+            static_initialization.max_locals,
+            compilation_context,
+        )?);
+    }
+    let nested_classes = nested_class_builder::member_types(body, compilation_context)?;
+
+    let mut attributes = nested_class_builder::nest_attributes(compilation_context)?;
+    attributes.extend(bootstrap_methods(compilation_context)?);
+
+    // The class bodies of enum constants are final, while other anonymous classes never are (JLS §15.9.5)
+    let access_flags = if nested_class_builder::is_enum_constant_body(compilation_context) {
+        ClassAccessFlags::FINAL | ClassAccessFlags::SUPER | ClassAccessFlags::ENUM
+    } else {
+        ClassAccessFlags::SUPER
+    };
+
+    let class_file = ClassFile {
+        version: JAVA_21,
+        access_flags,
+        constant_pool: compilation_context.constant_pool.to_owned(),
+        this_class,
+        super_class,
+        interfaces: compilation_context.interfaces.clone(),
+        fields,
+        methods,
+        attributes,
+    };
+    wrap(class_file.verify())?;
+
+    let mut class_files = vec![class_file];
+    class_files.append(&mut compilation_context.local_class_files);
+    class_files.extend(nested_classes);
+    Ok(class_files)
+}
+
+/// The `BootstrapMethods` attribute, which comes last of all once every invokedynamic
+/// instruction has added the bootstrap method it calls
+fn bootstrap_methods(compilation_context: &mut CompilationContext) -> CompileResult<Option<Attribute>> {
+    if compilation_context.bootstrap_methods.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Attribute::BootstrapMethods {
+        name_index: wrap(compilation_context.constant_pool.add_utf8("BootstrapMethods"))?,
+        methods: compilation_context.bootstrap_methods.clone(),
+    }))
+}
+
+fn access_flags_from(class: &AstClass) -> ClassAccessFlags {
+    // ACC_SUPER is set by every modern compiler, selecting the current semantics of invokespecial,
+    // but must not be set on an interface
+    let mut flags = if class.is_interface() { ClassAccessFlags::INTERFACE } else { ClassAccessFlags::SUPER };
+    // A protected member type is public in its class file, and a private one has package access,
+    // as the InnerClasses attribute records its actual access
+    if matches!(class.scope(), AstScope::Public | AstScope::Protected) {
+        flags |= ClassAccessFlags::PUBLIC;
+    }
+    if class.is_final() {
+        flags |= ClassAccessFlags::FINAL;
+    }
+    if class.is_abstract() {
+        flags |= ClassAccessFlags::ABSTRACT;
+    }
+    if class.is_enum() {
+        flags |= enum_flags_from(class);
+    }
+    // Records are implicitly final (JLS §8.10)
+    if class.is_record() {
+        flags |= ClassAccessFlags::FINAL;
+    }
+    flags
+}
+
+/// An enum is implicitly final unless a constant has a class body, which subclasses it, and is
+/// abstract when it declares an abstract method for its constants to implement (JLS §8.9)
+fn enum_flags_from(class: &AstClass) -> ClassAccessFlags {
+    let mut flags = ClassAccessFlags::ENUM;
+    if class.enum_constants().iter().all(|constant| constant.body().is_none()) {
+        flags |= ClassAccessFlags::FINAL;
+    }
+    if class.methods().iter().any(AstMethod::is_abstract) {
+        flags |= ClassAccessFlags::ABSTRACT;
+    }
+    flags
+}
+
+fn map_fields(
+    ast_fields: &Vec<AstField>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Field>> {
+    let mut fields: Vec<Field> = vec![];
+
+    for ast_field in ast_fields {
+        fields.push(field_builder::from(ast_field, compilation_context)?);
+    }
+
+    Ok(fields)
+}
+
+fn map_constructors(
+    class: &AstClass,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Method>> {
+    if class.is_interface() {
+        return Ok(vec![]);
+    }
+
+    let implicit_constructor = implicit_constructor(class);
+    let ast_constructors: Vec<&AstMethod> = class.constructors().iter().chain(implicit_constructor.as_ref()).collect();
+
+    // Every constructor is known before any is compiled, so that `this(...)` can call any of them
+    for ast_constructor in &ast_constructors {
+        let descriptor = constructor_descriptor(ast_constructor, compilation_context)?;
+        compilation_context.constructors.push(descriptor);
+    }
+
+    // Instance initialization is run by each constructor, after the parameters of any of them
+    let first_local = 1 + compilation_context.constructors.iter().map(|descriptor| descriptor::parameter_slots(descriptor)).max().unwrap_or(0);
+    let instance_initialization = initializer::instance_initialization(class, first_local, compilation_context)?;
+    let mut constructors: Vec<Method> = vec![];
+
+    for ast_constructor in ast_constructors {
+        constructors.push(method_builder::from_constructor(
+            ast_constructor,
+            &instance_initialization,
+            &record_builder::fields_to_assign(ast_constructor, class),
+            compilation_context,
+        )?);
+    }
+
+    Ok(constructors)
+}
+
+/// The constructor the compiler declares for a class, if any: the canonical constructor of a
+/// record that declares none, or the default constructor of any other class without constructors
+pub fn implicit_constructor<'a>(class: &AstClass<'a>) -> Option<AstMethod<'a>> {
+    if class.is_interface() {
+        None
+    } else if class.is_record() {
+        record_builder::implicit_canonical_constructor(class)
+    } else if class.constructors().is_empty() {
+        Some(default_constructor(class))
+    } else {
+        None
+    }
+}
+
+/// The constructor a class without any gets, taking no arguments and with the access of the
+/// class, or private for an enum (JLS §8.8.9)
+fn default_constructor<'a>(class: &AstClass<'a>) -> AstMethod<'a> {
+    let scope = if class.is_enum() { AstScope::Private } else { class.scope() };
+    AstMethod::new("<init>", scope, false, false, "void", vec![], vec![])
 }
 
 fn map_methods(
@@ -42,8 +277,8 @@ fn map_methods(
     let mut methods: Vec<Method> = vec![];
 
     for ast_method in ast_methods {
-        methods.push(crate::compiler::method_builder::from(
-            &ast_method,
+        methods.push(method_builder::from(
+            ast_method,
             compilation_context,
         )?);
     }
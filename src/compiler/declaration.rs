@@ -0,0 +1,200 @@
+use crate::ast::class::AstClass;
+use crate::compiler::class_file_builder::implicit_constructor;
+use crate::compiler::descriptor::{constructor_descriptor, field_descriptor, method_descriptor};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::name_resolver::NameResolver;
+use crate::compiler::result::{CompileError, CompileResult};
+use crate::compiler::nested_class_builder::{is_inner, EnclosingClass};
+use crate::compiler::{inheritance, CompilationContext};
+use crate::java::class::JavaClass;
+use crate::java::field::JavaField;
+use crate::java::method::JavaMethod;
+use crate::java::{as_map, ClassLoader};
+use std::collections::HashMap;
+
+/// A type to declare, with the resolver for the names used in its body
+pub struct DeclaredType<'c, 'a> {
+    pub class: &'c AstClass<'a>,
+    /// The binary name, such as `com.example.Outer$Inner`
+    pub name: String,
+    pub names: NameResolver,
+    /// The class of the enclosing instance an inner class is created with
+    pub enclosing_instance: Option<String>,
+    /// The local variables a local class captures
+    pub captured_variables: Vec<(String, JavaType)>,
+}
+
+impl<'c, 'a> DeclaredType<'c, 'a> {
+    pub fn top_level(class: &'c AstClass<'a>, names: &NameResolver) -> Self {
+        let name = names.qualify(class.name());
+        Self {
+            class,
+            names: names.within(&name, class),
+            name,
+            enclosing_instance: None,
+            captured_variables: vec![],
+        }
+    }
+
+    fn member_types(&self) -> Vec<DeclaredType<'c, 'a>> {
+        self.class
+            .member_types()
+            .iter()
+            .map(|member_type| {
+                let name = format!("{}${}", self.name, member_type.name());
+                DeclaredType {
+                    class: member_type,
+                    names: self.names.within(&name, member_type),
+                    enclosing_instance: is_inner(member_type, self.class).then(|| self.name.clone()),
+                    captured_variables: vec![],
+                    name,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Declares types, along with their member types, to the class loader before any of them is
+/// compiled, so that each can refer to the others whatever order they are declared in. Every
+/// type is declared by name first, so that the members of one can refer to any other.
+pub fn declare(types: &[DeclaredType], class_loader: &mut ClassLoader) -> CompileResult<()> {
+    let mut all_types = vec![];
+    for declared_type in types {
+        with_member_types(declared_type, &mut all_types);
+    }
+
+    let mut declared: Vec<&str> = vec![];
+    for declared_type in &all_types {
+        if declared.contains(&declared_type.name.as_str()) {
+            return Err(CompileError::DuplicateClass(declared_type.name.clone()));
+        }
+        class_loader.declare(skeleton(declared_type.class, &declared_type.name));
+        declared.push(&declared_type.name);
+    }
+
+    for declared_type in &all_types {
+        let java_class = with_members(declared_type, class_loader)?;
+        class_loader.declare(java_class);
+    }
+    Ok(())
+}
+
+fn with_member_types<'c, 'a>(declared_type: &DeclaredType<'c, 'a>, all_types: &mut Vec<DeclaredType<'c, 'a>>) {
+    all_types.push(DeclaredType {
+        class: declared_type.class,
+        name: declared_type.name.clone(),
+        names: declared_type.names.clone(),
+        enclosing_instance: declared_type.enclosing_instance.clone(),
+        captured_variables: declared_type.captured_variables.clone(),
+    });
+    for member_type in declared_type.member_types() {
+        with_member_types(&member_type, all_types);
+    }
+}
+
+/// A type without members, which is enough to resolve its name and check what extends it. It is
+/// known to its package by its binary name, such as `Outer$Inner`.
+fn skeleton(class: &AstClass, name: &str) -> JavaClass {
+    let descriptor = format!("L{};", name.replace('.', "/"));
+    let binary_name = name.rsplit('.').next().unwrap_or(name);
+    let java_class = JavaClass::new(binary_name, name, descriptor, vec![], HashMap::new());
+
+    let is_final_enum = class.is_enum() && class.enum_constants().iter().all(|constant| constant.body().is_none());
+    if class.is_interface() {
+        java_class.as_interface()
+    } else if class.is_final() || class.is_record() || is_final_enum {
+        java_class.as_final()
+    } else {
+        java_class
+    }
+}
+
+fn with_members(declared_type: &DeclaredType, class_loader: &mut ClassLoader) -> CompileResult<JavaClass> {
+    let (class, name, names) = (declared_type.class, &declared_type.name, &declared_type.names);
+    let super_class = inheritance::super_class(class, name, names, class_loader)?;
+    let interfaces = inheritance::interfaces(class, name, names, class_loader)?;
+
+    // Descriptors are resolved the same way as when the type is compiled
+    let mut context = CompilationContext::new(names.clone(), class_loader.clone(), name.clone(), super_class.clone(), &interfaces)?;
+    context.is_enum = class.is_enum();
+    if let Some(enclosing_class) = &declared_type.enclosing_instance {
+        context.enclosing = Some(Box::new(EnclosingClass::new(enclosing_class, HashMap::new(), true, None)));
+    }
+    context.captured_variables = declared_type.captured_variables.clone();
+
+    let mut methods = vec![];
+    let implicit_constructor = implicit_constructor(class);
+    for constructor in class.constructors().iter().chain(implicit_constructor.as_ref()) {
+        methods.push(JavaMethod::new("<init>", "void", constructor_descriptor(constructor, &mut context)?));
+    }
+    let (other_methods, fields) = members(class, &mut context)?;
+    methods.extend(other_methods);
+
+    let mut java_class = skeleton(class, name).with_members(methods, as_map(fields)).implementing(interfaces);
+    if !class.is_interface() {
+        java_class = java_class.extending(super_class);
+    }
+    if let Some(enclosing_class) = &declared_type.enclosing_instance {
+        java_class = java_class.with_enclosing_instance(enclosing_class);
+    }
+    if !declared_type.captured_variables.is_empty() {
+        let captured_variables = declared_type.captured_variables.iter().map(|(name, variable_type)| (name.clone(), variable_type.descriptor()));
+        java_class = java_class.with_captured_variables(captured_variables.collect());
+    }
+    Ok(java_class)
+}
+
+/// The methods and fields of a class other than its constructors, including those the compiler
+/// declares for enums and records
+fn members(class: &AstClass, context: &mut CompilationContext) -> CompileResult<(Vec<JavaMethod>, Vec<JavaField>)> {
+    let name = context.this_class_name.clone();
+    let mut methods = vec![];
+    for method in class.methods() {
+        let descriptor = method_descriptor(method, context)?;
+        let java_method = JavaMethod::new(method.name(), return_type_of(&descriptor), descriptor);
+        methods.push(if method.is_static() { java_method.as_static() } else { java_method });
+    }
+
+    let mut fields = vec![];
+    for field in class.fields() {
+        let descriptor = field_descriptor(field.field_type(), context)?;
+        let mut java_field = JavaField::new(field.name(), field_type_of(&descriptor), descriptor);
+        if field.is_static() {
+            java_field = java_field.as_static();
+        }
+        if field.is_final() {
+            java_field = java_field.as_final();
+        }
+        fields.push(java_field);
+    }
+
+    if class.is_enum() {
+        let descriptor = format!("L{};", name.replace('.', "/"));
+        methods.push(JavaMethod::new("values", format!("{}[]", name), format!("()[{}", descriptor)).as_static());
+        methods.push(JavaMethod::new("valueOf", name.as_str(), format!("(Ljava/lang/String;){}", descriptor)).as_static());
+        for constant in class.enum_constants() {
+            fields.push(JavaField::new(constant.name(), name.as_str(), descriptor.as_str()).as_static().as_final());
+        }
+    }
+    for component in class.record_components() {
+        let descriptor = field_descriptor(component.param_type(), context)?;
+        fields.push(JavaField::new(component.param_name(), field_type_of(&descriptor), descriptor).as_final());
+        if class.methods().iter().any(|method| method.name() == component.param_name() && method.parameters().is_empty()) {
+            continue;
+        }
+        let descriptor = format!("(){}", field_descriptor(component.param_type(), context)?);
+        methods.push(JavaMethod::new(component.param_name(), return_type_of(&descriptor), descriptor));
+    }
+
+    Ok((methods, fields))
+}
+
+fn return_type_of(method_descriptor: &str) -> String {
+    JavaType::from_method_descriptor(method_descriptor)
+        .map(|(_, return_type)| return_type.name())
+        .unwrap_or_default()
+}
+
+fn field_type_of(field_descriptor: &str) -> String {
+    JavaType::from_descriptor(field_descriptor).map(|field_type| field_type.name()).unwrap_or_default()
+}
@@ -0,0 +1,74 @@
+use crate::ast::class::{AstMethod, AstType};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::CompileResult;
+use crate::compiler::CompilationContext;
+
+/// The descriptor of a method, such as `([Ljava/lang/String;)V` (JVMS §4.3.3)
+pub fn method_descriptor(ast_method: &AstMethod, compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let mut result = String::new();
+
+    result.push('(');
+
+    for parameter in ast_method.parameters() {
+        parameter_str(parameter.param_type(), &mut result, compilation_context)?;
+    }
+
+    result.push(')');
+    parameter_str(ast_method.return_type(), &mut result, compilation_context)?;
+    Ok(result)
+}
+
+/// The descriptor of a constructor. Those of an enum take the name and ordinal of the constant
+/// ahead of the declared parameters, such as `(Ljava/lang/String;ID)V`, and those of an inner
+/// class its enclosing instance, such as `(Lcom/example/Outer;I)V`, followed by the variables a
+/// local class captures.
+pub fn constructor_descriptor(ast_constructor: &AstMethod, compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let descriptor = method_descriptor(ast_constructor, compilation_context)?;
+    let prefix: String = compilation_context.hidden_constructor_parameters().iter().map(JavaType::descriptor).collect();
+    Ok(descriptor.replacen('(', &format!("({}", prefix), 1))
+}
+
+/// The descriptor of a field or parameter type, such as `[I` (JVMS §4.3.2)
+pub fn field_descriptor(field_type: AstType, compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let mut result = String::new();
+    parameter_str(field_type, &mut result, compilation_context)?;
+    Ok(result)
+}
+
+fn parameter_str(param_type: AstType, result: &mut String, compilation_context: &mut CompilationContext) -> CompileResult<()> {
+    for _ in 0..param_type.array_dimensions() {
+        result.push('[');
+    }
+    parameter_str_after_array_resolution(param_type.name(), result, compilation_context)
+}
+
+fn parameter_str_after_array_resolution(param_type: &str, result: &mut String, compilation_context: &mut CompilationContext) -> CompileResult<()> {
+    match param_type {
+        "boolean" => result.push('Z'),
+        "byte" => result.push('B'),
+        "char" => result.push('C'),
+        "short" => result.push('S'),
+        "int" => result.push('I'),
+        "long" => result.push('J'),
+        "float" => result.push('F'),
+        "double" => result.push('D'),
+        "void" => result.push('V'),
+        s => handle_non_primitive(s, result, compilation_context)?,
+    }
+    Ok(())
+}
+
+fn handle_non_primitive(s: &str, result: &mut String, compilation_context: &mut CompilationContext) -> CompileResult<()> {
+    let class = compilation_context.resolve_class(s)?;
+    result.push('L');
+    result.push_str(&class.replace('.', "/"));
+    result.push(';');
+    Ok(())
+}
+
+/// The local variable slots the parameters of a method descriptor take, where long and double take two
+pub fn parameter_slots(method_descriptor: &str) -> u16 {
+    JavaType::from_method_descriptor(method_descriptor)
+        .map(|(parameter_types, _)| parameter_types.iter().map(JavaType::slots).sum())
+        .unwrap_or_default()
+}
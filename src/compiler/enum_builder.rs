@@ -0,0 +1,164 @@
+use crate::ast::class::AstClass;
+use crate::compiler::field_builder::DeclaredField;
+use crate::compiler::instruction::{from_enum_constant_creation, from_field_read, from_int_literal, store_field};
+use crate::compiler::method_builder::code;
+use crate::compiler::result::{wrap, CompileResult};
+use crate::compiler::{nested_class_builder, CompilationContext};
+use ristretto_classfile::attributes::Instruction;
+use ristretto_classfile::{ClassFile, Field, FieldAccessFlags, FieldType, Method, MethodAccessFlags};
+
+const VALUES_FIELD: &str = "$VALUES";
+const JAVA_LANG_ENUM: &str = "java/lang/Enum";
+
+/// The code `<clinit>` runs to create the constants of an enum before its own static initialization
+pub struct ConstantInitialization {
+    pub instructions: Vec<Instruction>,
+    /// The descriptor of the constructor each constant is created with, in declaration order
+    pub constructor_descriptors: Vec<String>,
+}
+
+/// A `public static final` field for each constant, and the synthetic `$VALUES` array of them
+/// that `values()` copies
+pub fn fields(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Field>> {
+    let descriptor = format!("L{};", internal_name(compilation_context));
+    let constant_flags = FieldAccessFlags::PUBLIC | FieldAccessFlags::STATIC | FieldAccessFlags::FINAL | FieldAccessFlags::ENUM;
+
+    let mut fields = vec![];
+    for constant in class.enum_constants() {
+        fields.push(field(constant.name(), &descriptor, constant_flags, compilation_context)?);
+    }
+    let values_flags = FieldAccessFlags::PRIVATE | FieldAccessFlags::STATIC | FieldAccessFlags::FINAL | FieldAccessFlags::SYNTHETIC;
+    fields.push(field(VALUES_FIELD, &format!("[{}", descriptor), values_flags, compilation_context)?);
+    Ok(fields)
+}
+
+fn field(
+    name: &str,
+    descriptor: &str,
+    access_flags: FieldAccessFlags,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Field> {
+    compilation_context.fields.insert(name.to_string(), DeclaredField::new(descriptor.to_string(), true, true, false));
+
+    Ok(Field {
+        access_flags,
+        name_index: wrap(compilation_context.constant_pool.add_utf8(name))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(descriptor))?,
+        field_type: wrap(FieldType::parse(descriptor))?,
+        attributes: vec![],
+    })
+}
+
+/// The `values()` and `valueOf(String)` methods every enum has (JLS §8.9.3)
+pub fn methods(compilation_context: &mut CompilationContext) -> CompileResult<Vec<Method>> {
+    let this_class = compilation_context.this_class;
+    let array_name = format!("[L{};", internal_name(compilation_context));
+
+    let mut values = from_field_read(VALUES_FIELD, compilation_context)?;
+    let array_class = compilation_context.add_class(&array_name)?;
+    let clone_ref = wrap(compilation_context.constant_pool.add_method_ref(array_class, "clone", "()Ljava/lang/Object;"))?;
+    values.extend([Instruction::Invokevirtual(clone_ref), Instruction::Checkcast(array_class), Instruction::Areturn]);
+
+    let enum_class = compilation_context.add_class(JAVA_LANG_ENUM)?;
+    let value_of_ref = wrap(compilation_context.constant_pool.add_method_ref(
+        enum_class,
+        "valueOf",
+        "(Ljava/lang/Class;Ljava/lang/String;)Ljava/lang/Enum;",
+    ))?;
+    let value_of = vec![
+        Instruction::Ldc_w(this_class),
+        Instruction::Aload_0,
+        Instruction::Invokestatic(value_of_ref),
+        Instruction::Checkcast(this_class),
+        Instruction::Areturn,
+    ];
+
+    let value_of_descriptor = format!("(Ljava/lang/String;)L{};", internal_name(compilation_context));
+    Ok(vec![
+        static_method("values", &format!("(){}", array_name), values, 0, compilation_context)?,
+        static_method("valueOf", &value_of_descriptor, value_of, 1, compilation_context)?,
+    ])
+}
+
+fn static_method(
+    name: &str,
+    descriptor: &str,
+    instructions: Vec<Instruction>,
+    max_locals: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Method> {
+    Ok(Method {
+        access_flags: MethodAccessFlags::PUBLIC | MethodAccessFlags::STATIC,
+        name_index: wrap(compilation_context.constant_pool.add_utf8(name))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(descriptor))?,
+        attributes: vec![code(name, descriptor, true, instructions, max_locals, compilation_context)?],
+    })
+}
+
+/// Creates each constant in turn, then the `$VALUES` array holding them all
+pub fn constant_initialization(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<ConstantInitialization> {
+    compilation_context.is_static_context = true;
+    let mut instructions = vec![];
+    let mut constructor_descriptors = vec![];
+    let mut body_count = 0;
+
+    for (ordinal, constant) in class.enum_constants().iter().enumerate() {
+        let class_ref = match constant.body() {
+            Some(_) => {
+                body_count += 1;
+                let body_name = body_class_name(body_count, compilation_context);
+                compilation_context.add_class(body_name.replace('.', "/"))?
+            }
+            None => compilation_context.this_class,
+        };
+
+        let (creation, descriptor) = from_enum_constant_creation(constant.name(), ordinal, class_ref, constant.arguments(), compilation_context)?;
+        instructions.extend(creation);
+        constructor_descriptors.push(descriptor);
+    }
+
+    let constants = class.enum_constants();
+    instructions.extend(from_int_literal(constants.len() as i64, compilation_context)?);
+    instructions.push(Instruction::Anewarray(compilation_context.this_class));
+    for (ordinal, constant) in constants.iter().enumerate() {
+        instructions.push(Instruction::Dup);
+        instructions.extend(from_int_literal(ordinal as i64, compilation_context)?);
+        instructions.extend(from_field_read(constant.name(), compilation_context)?);
+        instructions.push(Instruction::Aastore);
+    }
+    instructions.push(store_field(VALUES_FIELD, compilation_context)?);
+
+    Ok(ConstantInitialization {
+        instructions,
+        constructor_descriptors,
+    })
+}
+
+/// Compiles the class body of each constant that has one to an anonymous subclass of the enum,
+/// named as javac does with the position of the body among them, such as `Operation$1`
+pub fn constant_bodies(
+    class: &AstClass,
+    constructor_descriptors: &[String],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    let mut class_files = vec![];
+    let bodies = class
+        .enum_constants()
+        .iter()
+        .zip(constructor_descriptors)
+        .filter_map(|(constant, descriptor)| constant.body().map(|body| (body, descriptor)));
+
+    for (index, (body, descriptor)) in bodies.enumerate() {
+        let body_name = body_class_name(index + 1, compilation_context);
+        class_files.extend(nested_class_builder::enum_constant_body(body, body_name, descriptor, compilation_context)?);
+    }
+    Ok(class_files)
+}
+
+fn body_class_name(index: usize, compilation_context: &CompilationContext) -> String {
+    format!("{}${}", compilation_context.this_class_name, index)
+}
+
+fn internal_name(compilation_context: &CompilationContext) -> String {
+    compilation_context.this_class_name.replace('.', "/")
+}
@@ -0,0 +1,158 @@
+use crate::ast::class::{AstField, AstScope};
+use crate::ast::expression::Expression;
+use crate::compiler::result::{wrap, CompileError, CompileResult};
+use crate::compiler::{descriptor, CompilationContext};
+use ristretto_classfile::attributes::Attribute;
+use ristretto_classfile::{Field, FieldAccessFlags, FieldType};
+
+const JAVA_LANG_STRING: &str = "java.lang.String";
+
+/// What code referring to a field of the class being compiled needs to know about it
+#[derive(Clone)]
+pub struct DeclaredField {
+    descriptor: String,
+    is_static: bool,
+    is_final: bool,
+    is_constant: bool,
+}
+
+impl DeclaredField {
+    pub fn new(descriptor: String, is_static: bool, is_final: bool, is_constant: bool) -> Self {
+        Self { descriptor, is_static, is_final, is_constant }
+    }
+
+    pub fn descriptor(&self) -> &str {
+        &self.descriptor
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// Constant variables are given their value by the `ConstantValue` attribute, not by code
+    pub fn is_constant(&self) -> bool {
+        self.is_constant
+    }
+}
+
+pub fn from(
+    ast_field: &AstField,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Field> {
+    let descriptor = descriptor::field_descriptor(ast_field.field_type(), compilation_context)?;
+    let field_type = wrap(FieldType::parse(&descriptor))?;
+
+    let name_index = wrap(compilation_context.constant_pool.add_utf8(ast_field.name()))?;
+    let descriptor_index = wrap(compilation_context.constant_pool.add_utf8(&descriptor))?;
+
+    let mut attributes = vec![];
+    let constant_value = if ast_field.is_final() { constant_value_of(ast_field, compilation_context)? } else { None };
+    if let Some(constant_value_index) = constant_value {
+        attributes.push(Attribute::ConstantValue {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("ConstantValue"))?,
+            constant_value_index,
+        });
+    }
+
+    compilation_context.fields.insert(
+        ast_field.name().to_string(),
+        DeclaredField {
+            descriptor,
+            is_static: ast_field.is_static(),
+            is_final: ast_field.is_final(),
+            is_constant: constant_value.is_some(),
+        },
+    );
+
+    Ok(Field {
+        access_flags: access_flags_from(ast_field),
+        name_index,
+        descriptor_index,
+        field_type,
+        attributes,
+    })
+}
+
+fn access_flags_from(ast_field: &AstField) -> FieldAccessFlags {
+    let mut flags = match ast_field.scope() {
+        AstScope::Public => FieldAccessFlags::PUBLIC,
+        AstScope::Protected => FieldAccessFlags::PROTECTED,
+        AstScope::Private => FieldAccessFlags::PRIVATE,
+        AstScope::Default => FieldAccessFlags::empty(),
+    };
+    if ast_field.is_static() {
+        flags |= FieldAccessFlags::STATIC;
+    }
+    if ast_field.is_final() {
+        flags |= FieldAccessFlags::FINAL;
+    }
+    if ast_field.is_transient() {
+        flags |= FieldAccessFlags::TRANSIENT;
+    }
+    if ast_field.is_volatile() {
+        flags |= FieldAccessFlags::VOLATILE;
+    }
+    flags
+}
+
+/// The constant pool entry holding the value of a final field initialized with a literal of its
+/// type, which makes it a constant variable (JLS §4.12.4)
+fn constant_value_of(ast_field: &AstField, compilation_context: &mut CompilationContext) -> CompileResult<Option<u16>> {
+    let field_type = ast_field.field_type();
+    let Some(initializer) = ast_field.initializer() else {
+        return Ok(None);
+    };
+    if field_type.is_array() {
+        return Ok(None);
+    }
+
+    let is_string = compilation_context.resolve_class(field_type.name()).is_ok_and(|name| name == JAVA_LANG_STRING);
+    let constant_pool = &mut compilation_context.constant_pool;
+    let index = match (field_type.name(), initializer) {
+        ("boolean", Expression::BooleanLiteral { value, .. }) => wrap(constant_pool.add_integer(*value as i32))?,
+        ("byte" | "short" | "char" | "int", Expression::IntLiteral { value, .. }) => {
+            let value = narrow(*value, field_type.name())?;
+            wrap(constant_pool.add_integer(value))?
+        }
+        ("byte" | "short" | "char" | "int", Expression::CharLiteral { value, .. }) => {
+            let value = narrow(*value as i64, field_type.name())?;
+            wrap(constant_pool.add_integer(value))?
+        }
+        ("long", Expression::IntLiteral { value, .. }) => wrap(constant_pool.add_long(*value))?,
+        ("long", Expression::CharLiteral { value, .. }) => wrap(constant_pool.add_long(*value as i64))?,
+        ("long", Expression::LongLiteral { value, .. }) => {
+            let value = i64::try_from(*value).map_err(|_| CompileError::LiteralOutOfRange(format!("{:} is out of range for a long", value)))?;
+            wrap(constant_pool.add_long(value))?
+        }
+        ("float", Expression::IntLiteral { value, .. }) => wrap(constant_pool.add_float(*value as f32))?,
+        ("float", Expression::FloatLiteral { value, .. }) => wrap(constant_pool.add_float(*value))?,
+        ("double", Expression::IntLiteral { value, .. }) => wrap(constant_pool.add_double(*value as f64))?,
+        ("double", Expression::FloatLiteral { value, .. }) => wrap(constant_pool.add_double(*value as f64))?,
+        ("double", Expression::DoubleLiteral { value, .. }) => wrap(constant_pool.add_double(*value))?,
+        (_, Expression::StringLiteral { value, .. }) if is_string => wrap(constant_pool.add_string(value))?,
+        _ => return Ok(None),
+    };
+    Ok(Some(index))
+}
+
+/// An `int` constant may be assigned to a narrower integral type when it fits (JLS §5.2)
+fn narrow(value: i64, type_name: &str) -> CompileResult<i32> {
+    let value = i32::try_from(value).map_err(|_| CompileError::LiteralOutOfRange(format!("{:} is out of range for an int", value)))?;
+    let fits = match type_name {
+        "byte" => i8::try_from(value).is_ok(),
+        "short" => i16::try_from(value).is_ok(),
+        "char" => u16::try_from(value).is_ok(),
+        _ => true,
+    };
+    if !fits {
+        return Err(CompileError::IncompatibleTypes {
+            expected: type_name.to_string(),
+            found: "int".to_string(),
+        });
+    }
+    Ok(value)
+}
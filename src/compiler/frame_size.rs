@@ -0,0 +1,214 @@
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::{wrap, CompileError, CompileResult};
+use ristretto_classfile::attributes::{ExceptionTableEntry, Instruction};
+use ristretto_classfile::{Constant, ConstantPool};
+
+/// The `max_stack` of a method: the most slots its operand stack holds at any point, where long
+/// and double take two. Every path through the code is followed, including into exception
+/// handlers, which start with the exception alone on the stack (JVMS §4.9.2). Code that is reached
+/// with different depths, takes more from the stack than is on it, or runs off its end was
+/// generated wrongly, and is reported rather than emitted.
+pub fn max_stack(code: &[Instruction], exception_table: &[ExceptionTableEntry], constant_pool: &ConstantPool) -> CompileResult<u16> {
+    let mut depths: Vec<Option<u16>> = vec![None; code.len()];
+    let mut pending = vec![];
+    let mut max_stack = 0;
+    reach(0, 0, &mut depths, &mut pending)?;
+
+    while let Some(index) = pending.pop() {
+        let depth = depths[index].unwrap_or_default();
+        let (popped, pushed) = stack_effect(&code[index], constant_pool)?;
+        if popped > depth {
+            return Err(internal_error(format!("{:?} at {} takes {} slots from a stack of {}", code[index], index, popped, depth)));
+        }
+        let depth_after = depth - popped + pushed;
+        max_stack = max_stack.max(depth).max(depth_after);
+
+        for handler in exception_table.iter().filter(|entry| entry.range_pc.contains(&(index as u16))) {
+            reach(handler.handler_pc as usize, 1, &mut depths, &mut pending)?;
+        }
+        for successor in successors(&code[index], index)? {
+            reach(successor, depth_after, &mut depths, &mut pending)?;
+        }
+    }
+    Ok(max_stack)
+}
+
+/// Checks that the code reads and writes no local variable slot beyond the `max_locals` of its
+/// frame, which the local variable table worked out
+pub fn check_max_locals(code: &[Instruction], max_locals: u16) -> CompileResult<()> {
+    for (index, instruction) in code.iter().enumerate() {
+        let Some(last_slot) = wrap(instruction.max_locals_index())? else {
+            continue;
+        };
+        if last_slot >= max_locals {
+            return Err(internal_error(format!("{:?} at {} uses slot {} of {} local variable slots", instruction, index, last_slot, max_locals)));
+        }
+    }
+    Ok(())
+}
+
+fn reach(index: usize, depth: u16, depths: &mut [Option<u16>], pending: &mut Vec<usize>) -> CompileResult<()> {
+    match depths.get(index) {
+        None => Err(internal_error(format!("the code runs off its end at {}", index))),
+        Some(None) => {
+            depths[index] = Some(depth);
+            pending.push(index);
+            Ok(())
+        }
+        Some(Some(existing)) if *existing != depth => {
+            Err(internal_error(format!("the stack at {} holds {} slots on one path but {} on another", index, existing, depth)))
+        }
+        Some(Some(_)) => Ok(()),
+    }
+}
+
+/// The instructions that can run after the one at the index. Branches hold the index of their
+/// target, and switches how far it is from them.
+pub fn successors(instruction: &Instruction, index: usize) -> CompileResult<Vec<usize>> {
+    let relative = |offset: i32| (index as i64 + offset as i64) as usize;
+    let successors = match instruction {
+        Instruction::Goto(target) => vec![*target as usize],
+        Instruction::Goto_w(target) => vec![*target as usize],
+        Instruction::Ifeq(target)
+        | Instruction::Ifne(target)
+        | Instruction::Iflt(target)
+        | Instruction::Ifge(target)
+        | Instruction::Ifgt(target)
+        | Instruction::Ifle(target)
+        | Instruction::If_icmpeq(target)
+        | Instruction::If_icmpne(target)
+        | Instruction::If_icmplt(target)
+        | Instruction::If_icmpge(target)
+        | Instruction::If_icmpgt(target)
+        | Instruction::If_icmple(target)
+        | Instruction::If_acmpeq(target)
+        | Instruction::If_acmpne(target)
+        | Instruction::Ifnull(target)
+        | Instruction::Ifnonnull(target) => vec![index + 1, *target as usize],
+        Instruction::Tableswitch(table_switch) => {
+            let mut targets = vec![relative(table_switch.default)];
+            targets.extend(table_switch.offsets.iter().map(|offset| relative(*offset)));
+            targets
+        }
+        Instruction::Lookupswitch(lookup_switch) => {
+            let mut targets = vec![relative(lookup_switch.default)];
+            targets.extend(lookup_switch.pairs.values().map(|offset| relative(*offset)));
+            targets
+        }
+        Instruction::Ireturn
+        | Instruction::Lreturn
+        | Instruction::Freturn
+        | Instruction::Dreturn
+        | Instruction::Areturn
+        | Instruction::Return
+        | Instruction::Athrow => vec![],
+        Instruction::Jsr(_) | Instruction::Jsr_w(_) | Instruction::Ret(_) | Instruction::Ret_w(_) => {
+            return Err(internal_error(format!("subroutines are not generated, but found {:?} at {}", instruction, index)));
+        }
+        _ => vec![index + 1],
+    };
+    Ok(successors)
+}
+
+/// The slots an instruction takes from the operand stack, and then the slots it puts on it
+fn stack_effect(instruction: &Instruction, constant_pool: &ConstantPool) -> CompileResult<(u16, u16)> {
+    use Instruction::*;
+    let effect = match instruction {
+        Nop | Iinc(..) | Iinc_w(..) | Goto(_) | Goto_w(_) | Return | Wide | Breakpoint | Impdep1 | Impdep2 | Ret(_) | Ret_w(_) => (0, 0),
+        Aconst_null | Iconst_m1 | Iconst_0 | Iconst_1 | Iconst_2 | Iconst_3 | Iconst_4 | Iconst_5 | Fconst_0 | Fconst_1 | Fconst_2
+        | Bipush(_) | Sipush(_) | Ldc(_) | Ldc_w(_) | New(_) | Jsr(_) | Jsr_w(_) => (0, 1),
+        Lconst_0 | Lconst_1 | Dconst_0 | Dconst_1 | Ldc2_w(_) => (0, 2),
+
+        Iload(_) | Fload(_) | Aload(_) | Iload_w(_) | Fload_w(_) | Aload_w(_) | Iload_0 | Iload_1 | Iload_2 | Iload_3 | Fload_0
+        | Fload_1 | Fload_2 | Fload_3 | Aload_0 | Aload_1 | Aload_2 | Aload_3 => (0, 1),
+        Lload(_) | Dload(_) | Lload_w(_) | Dload_w(_) | Lload_0 | Lload_1 | Lload_2 | Lload_3 | Dload_0 | Dload_1 | Dload_2
+        | Dload_3 => (0, 2),
+        Istore(_) | Fstore(_) | Astore(_) | Istore_w(_) | Fstore_w(_) | Astore_w(_) | Istore_0 | Istore_1 | Istore_2 | Istore_3
+        | Fstore_0 | Fstore_1 | Fstore_2 | Fstore_3 | Astore_0 | Astore_1 | Astore_2 | Astore_3 => (1, 0),
+        Lstore(_) | Dstore(_) | Lstore_w(_) | Dstore_w(_) | Lstore_0 | Lstore_1 | Lstore_2 | Lstore_3 | Dstore_0 | Dstore_1
+        | Dstore_2 | Dstore_3 => (2, 0),
+
+        Iaload | Faload | Aaload | Baload | Caload | Saload => (2, 1),
+        Laload | Daload => (2, 2),
+        Iastore | Fastore | Aastore | Bastore | Castore | Sastore => (3, 0),
+        Lastore | Dastore => (4, 0),
+
+        Pop => (1, 0),
+        Pop2 => (2, 0),
+        Dup => (1, 2),
+        Dup_x1 => (2, 3),
+        Dup_x2 => (3, 4),
+        Dup2 => (2, 4),
+        Dup2_x1 => (3, 5),
+        Dup2_x2 => (4, 6),
+        Swap => (2, 2),
+
+        Iadd | Isub | Imul | Idiv | Irem | Ishl | Ishr | Iushr | Iand | Ior | Ixor | Fadd | Fsub | Fmul | Fdiv | Frem => (2, 1),
+        Ladd | Lsub | Lmul | Ldiv | Lrem | Land | Lor | Lxor | Dadd | Dsub | Dmul | Ddiv | Drem => (4, 2),
+        Lshl | Lshr | Lushr => (3, 2),
+        Ineg | Fneg | I2f | F2i | I2b | I2c | I2s => (1, 1),
+        Lneg | Dneg | L2d | D2l => (2, 2),
+        I2l | I2d | F2l | F2d => (1, 2),
+        L2i | L2f | D2i | D2f => (2, 1),
+        Lcmp | Dcmpl | Dcmpg => (4, 1),
+        Fcmpl | Fcmpg => (2, 1),
+
+        Ifeq(_) | Ifne(_) | Iflt(_) | Ifge(_) | Ifgt(_) | Ifle(_) | Ifnull(_) | Ifnonnull(_) => (1, 0),
+        If_icmpeq(_) | If_icmpne(_) | If_icmplt(_) | If_icmpge(_) | If_icmpgt(_) | If_icmple(_) | If_acmpeq(_) | If_acmpne(_) => (2, 0),
+        Tableswitch(_) | Lookupswitch(_) => (1, 0),
+        Ireturn | Freturn | Areturn | Athrow | Monitorenter | Monitorexit => (1, 0),
+        Lreturn | Dreturn => (2, 0),
+
+        Getstatic(field_ref) => (0, field_slots(*field_ref, constant_pool)?),
+        Putstatic(field_ref) => (field_slots(*field_ref, constant_pool)?, 0),
+        Getfield(field_ref) => (1, field_slots(*field_ref, constant_pool)?),
+        Putfield(field_ref) => (1 + field_slots(*field_ref, constant_pool)?, 0),
+        Invokevirtual(method_ref) | Invokespecial(method_ref) | Invokeinterface(method_ref, _) => {
+            let (parameter_slots, return_slots) = method_slots(*method_ref, constant_pool)?;
+            (1 + parameter_slots, return_slots)
+        }
+        Invokestatic(method_ref) | Invokedynamic(method_ref) => method_slots(*method_ref, constant_pool)?,
+
+        Newarray(_) | Anewarray(_) | Arraylength | Checkcast(_) | Instanceof(_) => (1, 1),
+        Multianewarray(_, dimensions) => (*dimensions as u16, 1),
+    };
+    Ok(effect)
+}
+
+fn field_slots(field_ref: u16, constant_pool: &ConstantPool) -> CompileResult<u16> {
+    let descriptor = member_descriptor(field_ref, constant_pool)?;
+    JavaType::from_descriptor(descriptor)
+        .map(|field_type| field_type.slots())
+        .ok_or_else(|| internal_error(format!("bad field descriptor {}", descriptor)))
+}
+
+/// The slots the arguments of a method take, and those of what it returns
+fn method_slots(method_ref: u16, constant_pool: &ConstantPool) -> CompileResult<(u16, u16)> {
+    let descriptor = member_descriptor(method_ref, constant_pool)?;
+    JavaType::from_method_descriptor(descriptor)
+        .map(|(parameter_types, return_type)| (parameter_types.iter().map(JavaType::slots).sum(), return_type.slots()))
+        .ok_or_else(|| internal_error(format!("bad method descriptor {}", descriptor)))
+}
+
+fn member_descriptor(index: u16, constant_pool: &ConstantPool) -> CompileResult<&str> {
+    member_name_and_descriptor(index, constant_pool).map(|(_, descriptor)| descriptor)
+}
+
+/// The name and descriptor of the field, method or call site an instruction refers to
+pub fn member_name_and_descriptor(index: u16, constant_pool: &ConstantPool) -> CompileResult<(&str, &str)> {
+    let name_and_type_index = match constant_pool.get(index) {
+        Some(
+            Constant::FieldRef { name_and_type_index, .. }
+            | Constant::MethodRef { name_and_type_index, .. }
+            | Constant::InterfaceMethodRef { name_and_type_index, .. }
+            | Constant::InvokeDynamic { name_and_type_index, .. },
+        ) => *name_and_type_index,
+        other => return Err(internal_error(format!("constant {} is not a member but {:?}", index, other))),
+    };
+    let (name_index, descriptor_index) = wrap(constant_pool.try_get_name_and_type(name_and_type_index))?;
+    Ok((wrap(constant_pool.try_get_utf8(*name_index))?, wrap(constant_pool.try_get_utf8(*descriptor_index))?))
+}
+
+pub fn internal_error(message: String) -> CompileError {
+    CompileError::Internal(message)
+}
@@ -0,0 +1,106 @@
+use crate::ast::class::AstClass;
+use crate::compiler::name_resolver::NameResolver;
+use crate::compiler::result::{CompileError, CompileResult};
+use crate::java::ClassLoader;
+
+const DEFAULT_SUPER_CLASS: &str = "java.lang.Object";
+const ENUM_SUPER_CLASS: &str = "java.lang.Enum";
+const RECORD_SUPER_CLASS: &str = "java.lang.Record";
+
+/// The fully qualified name of the superclass of a class, which must be a class that is neither
+/// final nor the class itself, directly or through its own superclasses (JLS §8.1.4). Every enum
+/// extends `java.lang.Enum`, and every record `java.lang.Record`.
+pub fn super_class(class: &AstClass, this_class: &str, names: &NameResolver, class_loader: &mut ClassLoader) -> CompileResult<String> {
+    if class.is_enum() {
+        return Ok(ENUM_SUPER_CLASS.to_string());
+    }
+    if class.is_record() {
+        return Ok(RECORD_SUPER_CLASS.to_string());
+    }
+    let Some(written_name) = class.super_class() else {
+        return Ok(DEFAULT_SUPER_CLASS.to_string());
+    };
+    let super_class = resolve(written_name, names, class_loader)?;
+    if super_class == this_class {
+        return Err(CompileError::CyclicInheritance(this_class.to_string()));
+    }
+
+    let Some(loaded) = class_loader.load(&super_class) else {
+        return Err(CompileError::UnknownClass(written_name.to_string()));
+    };
+    if loaded.is_interface() {
+        return Err(CompileError::UnexpectedInterface(super_class));
+    }
+    if loaded.is_final() {
+        return Err(CompileError::FinalSuperclass(super_class));
+    }
+
+    // A cycle among other classes compiled alongside is reported when compiling those classes
+    let mut ancestors = vec![super_class.clone()];
+    let mut ancestor = loaded.super_class().map(str::to_string);
+    while let Some(name) = ancestor {
+        if name == this_class {
+            return Err(CompileError::CyclicInheritance(this_class.to_string()));
+        }
+        if ancestors.contains(&name) {
+            break;
+        }
+        ancestor = class_loader.load(&name).and_then(|class| class.super_class()).map(str::to_string);
+        ancestors.push(name);
+    }
+
+    Ok(super_class)
+}
+
+/// The fully qualified names of the interfaces a class implements, each of which must be an
+/// interface named only once (JLS §8.1.5). An interface must not extend itself, directly or
+/// through its superinterfaces (JLS §9.1.3).
+pub fn interfaces(class: &AstClass, this_class: &str, names: &NameResolver, class_loader: &mut ClassLoader) -> CompileResult<Vec<String>> {
+    let mut interfaces: Vec<String> = vec![];
+
+    for written_name in class.interfaces() {
+        let interface = resolve(written_name, names, class_loader)?;
+        if is_subtype(&interface, this_class, class_loader) {
+            return Err(CompileError::CyclicInheritance(this_class.to_string()));
+        }
+
+        let Some(loaded) = class_loader.load(&interface) else {
+            return Err(CompileError::UnknownClass(written_name.to_string()));
+        };
+        if !loaded.is_interface() {
+            return Err(CompileError::InterfaceExpected(interface));
+        }
+        if interfaces.contains(&interface) {
+            return Err(CompileError::RepeatedInterface(interface));
+        }
+        interfaces.push(interface);
+    }
+
+    Ok(interfaces)
+}
+
+/// Whether a class is, extends or implements another, directly or through its supertypes
+pub fn is_subtype(class_name: &str, target: &str, class_loader: &mut ClassLoader) -> bool {
+    let mut supertypes = vec![class_name.to_string()];
+    let mut visited: Vec<String> = vec![];
+    while let Some(supertype) = supertypes.pop() {
+        if supertype == target {
+            return true;
+        }
+        if visited.contains(&supertype) {
+            continue;
+        }
+        if let Some(class) = class_loader.load(&supertype) {
+            supertypes.extend(class.super_class().map(str::to_string));
+            supertypes.extend(class.interfaces().iter().cloned());
+        }
+        visited.push(supertype);
+    }
+    false
+}
+
+fn resolve(written_name: &str, names: &NameResolver, class_loader: &mut ClassLoader) -> CompileResult<String> {
+    names
+        .resolve_class(written_name, class_loader)
+        .ok_or_else(|| CompileError::UnknownClass(written_name.to_string()))
+}
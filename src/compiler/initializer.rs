@@ -0,0 +1,108 @@
+use crate::ast::class::{AstClass, AstField, AstInitializer};
+use crate::compiler::instruction::{from_assigned_expression, store_field};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::jump_targets::JumpTargets;
+use crate::compiler::local_variables::LocalVariables;
+use crate::compiler::method_builder::build_instructions;
+use crate::compiler::result::{CompileError, CompileResult};
+use crate::compiler::CompilationContext;
+use ristretto_classfile::attributes::Instruction;
+
+/// A field initializer or an initializer block, which run in the order they were declared
+enum Initialization<'c, 'a> {
+    Field(&'c AstField<'a>),
+    Block(&'c AstInitializer<'a>),
+}
+
+/// The code of the initializers, with the local variable slots their blocks take
+pub struct InitializationCode {
+    pub instructions: Vec<Instruction>,
+    pub max_locals: u16,
+}
+
+/// The code for `<clinit>`: the static field initializers and static blocks. Constant variables
+/// are left out, as they take their value from their `ConstantValue` attribute.
+pub fn static_initialization(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<InitializationCode> {
+    compilation_context.is_static_context = true;
+    initialization(class, true, 0, compilation_context)
+}
+
+/// The code each constructor runs once the superclass constructor has returned: the instance
+/// field initializers and instance initializer blocks. As it is copied into every constructor,
+/// its local variables take the slots after the parameters of all of them.
+pub fn instance_initialization(class: &AstClass, first_local: u16, compilation_context: &mut CompilationContext) -> CompileResult<InitializationCode> {
+    compilation_context.is_static_context = false;
+    initialization(class, false, first_local, compilation_context)
+}
+
+fn initialization(
+    class: &AstClass,
+    is_static: bool,
+    first_local: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<InitializationCode> {
+    let mut initializations: Vec<Initialization> = class
+        .fields()
+        .iter()
+        .filter(|field| field.is_static() == is_static && field.initializer().is_some())
+        .map(Initialization::Field)
+        .chain(
+            class
+                .initializers()
+                .iter()
+                .filter(|initializer| initializer.is_static() == is_static)
+                .map(Initialization::Block),
+        )
+        .collect();
+    // Fields and blocks are kept apart in the AST, so their source positions give the declaration order
+    initializations.sort_by_key(|initialization| match initialization {
+        Initialization::Field(field) => field.span().start().offset,
+        Initialization::Block(block) => block.span().start().offset,
+    });
+
+    compilation_context.locals = LocalVariables::starting_at(first_local);
+    compilation_context.jumps = JumpTargets::default();
+    let mut instructions = vec![];
+    for initialization in initializations {
+        match initialization {
+            Initialization::Field(field) => instructions.extend(
+                field_initialization(field, compilation_context)
+                    .map_err(|error| error.located(&compilation_context.method_name(), field.span()))?,
+            ),
+            Initialization::Block(block) => {
+                instructions.extend(build_instructions(block.statements(), compilation_context)?);
+                if !compilation_context.jumps.is_reachable() {
+                    return Err(CompileError::InitializerCannotComplete);
+                }
+            }
+        }
+    }
+    Ok(InitializationCode {
+        instructions,
+        max_locals: compilation_context.locals.max_locals(),
+    })
+}
+
+fn field_initialization(field: &AstField, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let Some(initializer) = field.initializer() else {
+        return Ok(vec![]);
+    };
+    let Some(declared_field) = compilation_context.fields.get(field.name()) else {
+        return Err(CompileError::UnknownField {
+            class: compilation_context.this_class_name.clone(),
+            field: field.name().to_string(),
+        });
+    };
+    if field.is_static() && declared_field.is_constant() {
+        return Ok(vec![]);
+    }
+    let field_type = JavaType::from_descriptor(declared_field.descriptor()).ok_or_else(|| CompileError::UnknownClass(field.field_type().name().to_string()))?;
+
+    let mut instructions = vec![];
+    if !field.is_static() {
+        instructions.push(Instruction::Aload_0);
+    }
+    instructions.extend(from_assigned_expression(initializer, &field_type, compilation_context)?);
+    instructions.push(store_field(field.name(), compilation_context)?);
+    Ok(instructions)
+}
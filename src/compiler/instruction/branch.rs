@@ -0,0 +1,128 @@
+use ristretto_classfile::attributes::Instruction;
+
+/// Code that jumps to a point past its end when a condition has a given value, and otherwise
+/// falls through to its end. Where it jumps to is only known once the code that follows it is.
+#[derive(Default)]
+pub struct ConditionalJump {
+    instructions: Vec<Instruction>,
+    /// The positions of the branches that leave the code
+    exits: Vec<usize>,
+}
+
+impl ConditionalJump {
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn extend(&mut self, instructions: impl IntoIterator<Item = Instruction>) {
+        self.instructions.extend(instructions);
+    }
+
+    /// Leaves the code with a branch such as `Instruction::Ifeq`
+    pub fn exit(&mut self, branch: fn(u16) -> Instruction) {
+        self.exits.push(self.instructions.len());
+        self.instructions.push(branch(0));
+    }
+
+    /// Follows the code with another that leaves to the same place
+    pub fn append(&mut self, other: ConditionalJump) {
+        let offset = self.instructions.len();
+        self.exits.extend(other.exits.iter().map(|exit| exit + offset));
+        self.instructions.extend(other.instructions);
+    }
+
+    /// The code, leaving to the instruction `distance` instructions past its end
+    pub fn to(self, distance: usize) -> Vec<Instruction> {
+        let end = self.instructions.len();
+        self.leaving_to((end + distance) as isize)
+    }
+
+    /// The code, leaving to the instruction `distance` instructions before its start, as a loop
+    /// does to repeat itself
+    pub fn back_to(self, distance: usize) -> Vec<Instruction> {
+        self.leaving_to(-(distance as isize))
+    }
+
+    fn leaving_to(mut self, target: isize) -> Vec<Instruction> {
+        for exit in self.exits {
+            if let Some(offset) = branch_offset(&mut self.instructions[exit]) {
+                *offset = relative(target - exit as isize);
+            }
+        }
+        self.instructions
+    }
+}
+
+/// Jumps over the given number of instructions that follow it
+pub fn skip(instructions: usize) -> Instruction {
+    Instruction::Goto(relative(instructions as isize + 1))
+}
+
+/// Jumps back to the instruction the given number of instructions before it
+pub fn back(instructions: usize) -> Instruction {
+    Instruction::Goto(relative(-(instructions as isize)))
+}
+
+/// Jumps to a label of a statement still being compiled, which `break` and `continue` do before
+/// it is known where the statement ends. Wide jumps are never otherwise made, so one holding the
+/// number of the label stands for the jump until `resolve_label` replaces it.
+pub fn to_label(label: u16) -> Instruction {
+    Instruction::Goto_w(i32::from(label))
+}
+
+/// Points the jumps to the label at the position in the code
+pub fn resolve_label(code: &mut [Instruction], label: u16, position: usize) {
+    for (index, instruction) in code.iter_mut().enumerate() {
+        if *instruction == to_label(label) {
+            *instruction = Instruction::Goto(relative(position as isize - index as isize));
+        }
+    }
+}
+
+/// Branches are built with their targets counted in instructions from the branch itself, so that
+/// code can be built in pieces and joined without moving them. Once the code of a method is
+/// complete, they become the positions in it that ristretto expects.
+///
+/// ristretto misplaces the frame after one at the very start of the code, so code that jumps back
+/// to its start begins with a `nop` instead.
+pub fn resolve_branches(mut instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let jumps_to_start = instructions.iter_mut().enumerate().any(|(index, instruction)| {
+        branch_offset(instruction).is_some_and(|offset| index as i32 + *offset as i16 as i32 == 0)
+    });
+    if jumps_to_start {
+        instructions.insert(0, Instruction::Nop);
+    }
+    for (index, instruction) in instructions.iter_mut().enumerate() {
+        if let Some(offset) = branch_offset(instruction) {
+            *offset = (index as i32 + *offset as i16 as i32) as u16;
+        }
+    }
+    instructions
+}
+
+fn relative(offset: isize) -> u16 {
+    offset as i16 as u16
+}
+
+fn branch_offset(instruction: &mut Instruction) -> Option<&mut u16> {
+    match instruction {
+        Instruction::Ifeq(offset)
+        | Instruction::Ifne(offset)
+        | Instruction::Iflt(offset)
+        | Instruction::Ifge(offset)
+        | Instruction::Ifgt(offset)
+        | Instruction::Ifle(offset)
+        | Instruction::If_icmpeq(offset)
+        | Instruction::If_icmpne(offset)
+        | Instruction::If_icmplt(offset)
+        | Instruction::If_icmpge(offset)
+        | Instruction::If_icmpgt(offset)
+        | Instruction::If_icmple(offset)
+        | Instruction::If_acmpeq(offset)
+        | Instruction::If_acmpne(offset)
+        | Instruction::Goto(offset)
+        | Instruction::Ifnull(offset)
+        | Instruction::Ifnonnull(offset) => Some(offset),
+        _ => None,
+    }
+}
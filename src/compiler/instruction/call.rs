@@ -1,5 +1,8 @@
 use crate::ast::expression::Expression;
-use crate::compiler::instruction::expression::from_expression;
+use crate::compiler::instruction::expression::{from_expression, type_of};
+use crate::compiler::instruction::variable::is_variable;
+use crate::compiler::instruction::from_assigned_expression;
+use crate::compiler::java_type::JavaType;
 use crate::compiler::{wrap, CompilationContext, CompileError, CompileResult};
 use crate::java::class::JavaClass;
 use crate::java::ClassLoader;
@@ -11,42 +14,193 @@ pub fn from_call_expression(
     arguments: &Vec<Expression>,
     compilation_context: &mut CompilationContext,
 ) -> CompileResult<Vec<Instruction>> {
+    if is_instance_expression(target, compilation_context) {
+        return from_instance_call(target, method_name, arguments, compilation_context);
+    }
+
     let mut object_path = String::new();
-    extract_object_path(target, &mut object_path);
+    extract_object_path(target, &mut object_path)?;
+    let object_path = qualify_object_path(object_path, compilation_context);
 
     if let Some((class_path, suffix)) = parse_object_path(object_path.as_str(), &mut compilation_context.class_loader) {
         let class_descriptor = class_path.replace('.', "/");
-        let class_id = wrap(compilation_context.constant_pool.add_class(&class_descriptor))?;
+        let class_id = compilation_context.add_class(&class_descriptor)?;
 
         if suffix.is_empty() {
-            todo!("Static methods on classes not yet supported")
+            from_static_method_on_class(class_path, class_id, method_name, arguments, compilation_context)
         } else if suffix.len() == 1 {
             return from_static_field_on_class(class_path, class_id, suffix.first().unwrap(), method_name, arguments, compilation_context);
         } else {
-            todo!("Multiple nested static fields not yet supported")
+            // A field of a static field, such as `Outer.field.other`, is read like any other object
+            from_instance_call(target, method_name, arguments, compilation_context)
         }
     } else {
         Err(CompileError::UnknownClass(object_path))
     }
 }
 
-fn extract_object_path(expression: &Expression, result: &mut String) {
+pub fn return_type_of_call_expression(
+    target: &Expression,
+    method_name: &str,
+    arguments: &Vec<Expression>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    let (class_path, method_descriptor) = if is_instance_expression(target, compilation_context) {
+        instance_call_target(target, method_name, arguments, compilation_context)?
+    } else {
+        let mut object_path = String::new();
+        extract_object_path(target, &mut object_path)?;
+        let object_path = qualify_object_path(object_path, compilation_context);
+        match static_call_target(&object_path, method_name, arguments, compilation_context)? {
+            Some(target) => target,
+            None => instance_call_target(target, method_name, arguments, compilation_context)?,
+        }
+    };
+    JavaType::from_method_descriptor(method_descriptor.as_str())
+        .map(|(_, return_type)| return_type)
+        .ok_or_else(|| CompileError::UnknownMethod {
+            class: class_path,
+            method: method_name.to_string(),
+        })
+}
+
+/// The class and the descriptor of the instance method a call on an object selects
+fn instance_call_target(
+    target: &Expression,
+    method_name: &str,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<(String, String)> {
+    let class_path = class_of(target, method_name, compilation_context)?;
+    let method_descriptor = lookup_method_descriptor(&class_path, method_name, false, arguments, compilation_context)?;
+    Ok((class_path, method_descriptor))
+}
+
+/// The class and the descriptor of the method a call on a class, or on a static field of one,
+/// selects. A call on a field of a static field is a call on an object, for which there is none.
+fn static_call_target(
+    object_path: &str,
+    method_name: &str,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Option<(String, String)>> {
+    let target = match parse_object_path(object_path, &mut compilation_context.class_loader) {
+        Some((class_path, suffix)) if suffix.is_empty() => {
+            let class_path = class_path.to_string();
+            let method_descriptor = lookup_method_descriptor(&class_path, method_name, true, arguments, compilation_context)?;
+            (class_path, method_descriptor)
+        }
+        Some((class_path, suffix)) if suffix.len() == 1 => {
+            let (field_class_path, _, _) = lookup_field_on_class(class_path, suffix.first().unwrap(), compilation_context)?;
+            let method_descriptor = lookup_method_descriptor(field_class_path.as_str(), method_name, false, arguments, compilation_context)?;
+            (field_class_path, method_descriptor)
+        }
+        Some(_) => return Ok(None),
+        None => return Err(CompileError::UnknownClass(object_path.to_string())),
+    };
+    Ok(Some(target))
+}
+
+/// Whether the target of a call is an object to invoke an instance method on, rather than a
+/// class or a static field named by a path. A lone name is an object when it names a variable in
+/// scope, and a qualified name when its first name does, as in `this.list` or `point.x`.
+fn is_instance_expression(target: &Expression, compilation_context: &mut CompilationContext) -> bool {
+    match target {
+        Expression::Variable { name, .. } => is_variable(name, compilation_context),
+        Expression::ChildIdentifier { parent, .. } => is_instance_expression(parent, compilation_context),
+        _ => true,
+    }
+}
+
+/// Invokes an instance method, declared by the class of the target or inherited by it, on the
+/// object the target evaluates to
+fn from_instance_call(
+    target: &Expression,
+    method_name: &str,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let class_path = class_of(target, method_name, compilation_context)?;
+    let mut instructions = from_expression(target, compilation_context)?;
+
+    let method_descriptor = lookup_method_descriptor(&class_path, method_name, false, arguments, compilation_context)?;
+    let parameter_types = parameter_types_of(&method_descriptor);
+    for (argument, parameter_type) in arguments.iter().zip(parameter_types.iter()) {
+        instructions.extend(from_assigned_expression(argument, parameter_type, compilation_context)?);
+    }
+
+    let class_id = compilation_context.add_class(class_path.replace('.', "/"))?;
+    instructions.push(invoke_instance_method(&class_path, class_id, method_name, &method_descriptor, compilation_context)?);
+    Ok(instructions)
+}
+
+/// The class whose methods a call on the target can select
+fn class_of(target: &Expression, method_name: &str, compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    match type_of(target, compilation_context)? {
+        JavaType::Reference(class_name) => Ok(class_name.replace('/', ".")),
+        target_type => Err(CompileError::UnknownMethod {
+            class: target_type.name(),
+            method: method_name.to_string(),
+        }),
+    }
+}
+
+/// The dotted path of names a call is made on, such as `System.out`
+pub(super) fn extract_object_path(expression: &Expression, result: &mut String) -> CompileResult<()> {
     match expression {
-        Expression::Call { .. } => todo!(),
-        Expression::StringLiteral { .. } => todo!("Not yet supported"),
-        Expression::Variable { name, type_def } => result.push_str(name),
-        Expression::ChildIdentifier { parent, name } => {
-            extract_object_path(parent, result);
+        Expression::Variable { name, .. } => result.push_str(name),
+        Expression::ChildIdentifier { parent, name, .. } => {
+            extract_object_path(parent, result)?;
             result.push('.');
             result.push_str(name);
         }
-        Expression::Assignment { .. } => panic!("Cannot convert assignment to path. Perhaps assignment should be a statement?")
+        _ => return Err(CompileError::Unsupported("call on an expression that is not a name".to_string())),
+    }
+    Ok(())
+}
+
+/// Replaces the first name of the path with its fully qualified class, whether it names a class
+/// itself or a statically imported member of one
+pub(super) fn qualify_object_path(object_path: String, compilation_context: &mut CompilationContext) -> String {
+    let first_name = object_path.split('.').next().unwrap_or_default();
+    let names = &compilation_context.names;
+    let class_loader = &mut compilation_context.class_loader;
+
+    if object_path.contains('.')
+        && let Some(class_name) = names.resolve_class(first_name, class_loader)
+        && class_loader.load(&class_name).is_some()
+    {
+        return with_member_types(class_name, &object_path[first_name.len() + 1..], class_loader);
+    }
+    if let Some(class_name) = names.resolve_static_member(first_name, class_loader) {
+        return format!("{}.{}", class_name, object_path);
+    }
+    // A lone name that is not a variable may name the class of a static method
+    match names.resolve_class(first_name, class_loader) {
+        Some(class_name) if class_loader.load(&class_name).is_some() => class_name,
+        _ => object_path,
+    }
+}
+
+/// Joins the names of the member types that follow a class in a path to it with `$`, as in
+/// `Outer$Inner.field`
+fn with_member_types(class_name: String, rest: &str, class_loader: &mut ClassLoader) -> String {
+    let mut class_name = class_name;
+    let mut names = rest.split('.').peekable();
+    while let Some(name) = names.peek() {
+        let member_type = format!("{}${}", class_name, name);
+        if class_loader.load(&member_type).is_none() {
+            break;
+        }
+        class_name = member_type;
+        names.next();
     }
+    names.fold(class_name, |path, name| format!("{}.{}", path, name))
 }
 
 fn extract_method_name<'a>(expression: &'a Expression) -> &'a str {
     match expression {
-        Expression::ChildIdentifier { name, parent } => name,
+        Expression::ChildIdentifier { name, parent, .. } => name,
         expr => panic!("Unsupported")
     }
 }
@@ -63,41 +217,160 @@ fn from_static_field_on_class(
 
     let (field_class_path, field_class_name, field_class_descriptor) = lookup_field_on_class(class, field_name, compilation_context)?;
 
-    let field_class_id = wrap(compilation_context.constant_pool.add_class(&field_class_name))?;
+    let field_class_id = compilation_context.add_class(&field_class_name)?;
     let field_ref = add_field_ref(field_name, &field_class_descriptor, class_id, compilation_context)?;
     instructions.push(Instruction::Getstatic(field_ref));
 
-    let method_descriptor = lookup_method_descriptor(field_class_path.as_str(), method_name, compilation_context)?;
-    let method_ref = wrap(
-        compilation_context
-            .constant_pool
-            .add_method_ref(field_class_id, method_name, method_descriptor.as_str()),
-    )?;
+    let method_descriptor = lookup_method_descriptor(field_class_path.as_str(), method_name, false, arguments, compilation_context)?;
+    let parameter_types = parameter_types_of(&method_descriptor);
+    for (argument, parameter_type) in arguments.iter().zip(parameter_types.iter()) {
+        instructions.extend(from_assigned_expression(argument, parameter_type, compilation_context)?);
+    }
 
-    for argument in arguments {
-        let argument_instructions = from_expression(argument, compilation_context)?;
-        for argument_instruction in argument_instructions {
-            instructions.push(argument_instruction);
-        }
+    instructions.push(invoke_instance_method(&field_class_path, field_class_id, method_name, &method_descriptor, compilation_context)?);
+
+    Ok(instructions)
+}
+
+/// Invokes an instance method on the object beneath its arguments, through an interface when the
+/// object is known only by one
+fn invoke_instance_method(
+    class_path: &str,
+    class_id: u16,
+    method_name: &str,
+    method_descriptor: &str,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Instruction> {
+    if is_interface(class_path, compilation_context) {
+        let method_ref = wrap(compilation_context.constant_pool.add_interface_method_ref(class_id, method_name, method_descriptor))?;
+        // The count includes the object the method is invoked on
+        let count = 1 + parameter_types_of(method_descriptor).iter().map(slots_of).sum::<u8>();
+        Ok(Instruction::Invokeinterface(method_ref, count))
+    } else {
+        let method_ref = wrap(compilation_context.constant_pool.add_method_ref(class_id, method_name, method_descriptor))?;
+        Ok(Instruction::Invokevirtual(method_ref))
+    }
+}
+
+fn from_static_method_on_class(
+    class: &str,
+    class_id: u16,
+    method_name: &str,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let mut instructions: Vec<Instruction> = vec![];
+
+    let method_descriptor = lookup_method_descriptor(class, method_name, true, arguments, compilation_context)?;
+    for (argument, parameter_type) in arguments.iter().zip(parameter_types_of(&method_descriptor).iter()) {
+        instructions.extend(from_assigned_expression(argument, parameter_type, compilation_context)?);
     }
 
-    instructions.push(Instruction::Invokevirtual(method_ref));
-    instructions.push(Instruction::Return);
+    // Static methods of interfaces are referred to by an InterfaceMethodref (JVMS §4.4.2)
+    let method_ref = if is_interface(class, compilation_context) {
+        wrap(compilation_context.constant_pool.add_interface_method_ref(class_id, method_name, method_descriptor.as_str()))?
+    } else {
+        wrap(compilation_context.constant_pool.add_method_ref(class_id, method_name, method_descriptor.as_str()))?
+    };
+    instructions.push(Instruction::Invokestatic(method_ref));
 
     Ok(instructions)
 }
 
-fn lookup_method_descriptor(class_path: &str, method_name: &str, compilation_context: &mut CompilationContext) -> Result<String, CompileError> {
-    let method = compilation_context
-        .class_loader
-        .load(class_path)
-        .and_then(|field_class| field_class.method_named(method_name))
+fn is_interface(class_path: &str, compilation_context: &mut CompilationContext) -> bool {
+    compilation_context.class_loader.load(class_path).is_some_and(JavaClass::is_interface)
+}
+
+fn parameter_types_of(method_descriptor: &str) -> Vec<JavaType> {
+    JavaType::from_method_descriptor(method_descriptor)
+        .map(|(parameter_types, _)| parameter_types)
+        .unwrap_or_default()
+}
+
+fn slots_of(java_type: &JavaType) -> u8 {
+    match java_type {
+        JavaType::Long | JavaType::Double => 2,
+        _ => 1,
+    }
+}
+
+/// Looks up the static or instance method that a call with the given arguments selects
+fn lookup_method_descriptor(
+    class_path: &str,
+    method_name: &str,
+    is_static: bool,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> Result<String, CompileError> {
+    let argument_types = arguments
+        .iter()
+        .map(|argument| type_of(argument, compilation_context))
+        .collect::<CompileResult<Vec<JavaType>>>()?;
+
+    // Instance methods are inherited from every supertype
+    let classes = if is_static { vec![class_path.to_string()] } else { supertypes_of(class_path, &mut compilation_context.class_loader) };
+    let mut descriptors: Vec<String> = vec![];
+    for class_name in &classes {
+        let Some(class) = compilation_context.class_loader.load(class_name) else {
+            continue;
+        };
+        let methods = class.methods_named(method_name).into_iter().filter(|method| method.is_static() == is_static);
+        descriptors.extend(methods.map(|method| method.descriptor().to_string()));
+    }
+
+    select_overload(descriptors.iter().map(String::as_str), &argument_types)
+        .map(str::to_string)
         .ok_or_else(|| CompileError::UnknownMethod {
             class: class_path.to_string(),
             method: method_name.to_string(),
-        })?;
+        })
+}
+
+/// A class followed by its superclasses, then the interfaces of them all
+pub(super) fn supertypes_of(class_path: &str, class_loader: &mut ClassLoader) -> Vec<String> {
+    let mut classes = vec![];
+    let mut class_name = Some(class_path.to_string());
+    while let Some(name) = class_name {
+        class_name = class_loader.load(&name).and_then(|class| class.super_class()).map(str::to_string);
+        classes.push(name);
+    }
 
-    Ok(method.descriptor().to_string())
+    let mut index = 0;
+    while index < classes.len() {
+        let interfaces = class_loader.load(&classes[index]).map(|class| class.interfaces().clone()).unwrap_or_default();
+        classes.extend(interfaces.into_iter().filter(|interface| !classes.contains(interface)).collect::<Vec<_>>());
+        index += 1;
+    }
+    classes
+}
+
+/// Picks the descriptor of the most specific applicable method for the given argument types
+/// (JLS §15.12.2), considering only widening conversions; boxing and varargs are not yet supported.
+pub(super) fn select_overload<'d>(descriptors: impl IntoIterator<Item = &'d str>, argument_types: &[JavaType]) -> Option<&'d str> {
+    let applicable: Vec<(&str, Vec<JavaType>)> = descriptors
+        .into_iter()
+        .filter_map(|descriptor| {
+            let (parameter_types, _) = JavaType::from_method_descriptor(descriptor)?;
+            let is_applicable = parameter_types.len() == argument_types.len()
+                && argument_types
+                    .iter()
+                    .zip(parameter_types.iter())
+                    .all(|(argument, parameter)| argument.is_assignable_to(parameter));
+            is_applicable.then_some((descriptor, parameter_types))
+        })
+        .collect();
+
+    applicable
+        .iter()
+        .find(|(_, parameter_types)| {
+            applicable.iter().all(|(_, other_parameter_types)| {
+                parameter_types
+                    .iter()
+                    .zip(other_parameter_types.iter())
+                    .all(|(parameter, other)| parameter.is_assignable_to(other))
+            })
+        })
+        .map(|(descriptor, _)| *descriptor)
 }
 
 fn add_field_ref(field_name: &str, field_class_descriptor: &str, class_ref: u16, compilation_context: &mut CompilationContext) -> CompileResult<u16> {
@@ -110,7 +383,11 @@ fn add_field_ref(field_name: &str, field_class_descriptor: &str, class_ref: u16,
     Ok(field_ref)
 }
 
-fn parse_object_path<'a>(path: &'a str, class_loader: &mut ClassLoader) -> Option<(&'a str, Vec<&'a str>)> {
+pub(super) fn parse_object_path<'a>(path: &'a str, class_loader: &mut ClassLoader) -> Option<(&'a str, Vec<&'a str>)> {
+    if class_loader.load(path).is_some() {
+        return Some((path, vec![]));
+    }
+
     let mut end = path.len();
 
     let mut split_idx = path[0..end].rfind('.');
@@ -119,9 +396,9 @@ fn parse_object_path<'a>(path: &'a str, class_loader: &mut ClassLoader) -> Optio
 
         let (prefix, suffix) = path.split_at(split_idx?);
 
-        if let Some(class) = class_loader.load(prefix) {
+        if class_loader.load(prefix).is_some() {
             let suffix_parts: Vec<&str> = suffix[1..].split('.').collect();
-            return Some((class.path(), suffix_parts));
+            return Some((prefix, suffix_parts));
         }
 
         end = split_idx?;
@@ -0,0 +1,155 @@
+use ristretto_classfile::attributes::Instruction;
+use crate::ast::expression::Expression;
+use crate::compiler::instruction::call::select_overload;
+use crate::compiler::instruction::expression::type_of;
+use crate::compiler::instruction::{from_assigned_expression, store_field};
+use crate::compiler::instruction::numeric_literal::from_int_literal;
+use crate::compiler::instruction::string_literal::from_string_literal;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::instruction::instance_creation::enclosing_instance_of;
+use crate::compiler::{inheritance, wrap, CompilationContext, CompileError, CompileResult};
+use crate::java::method::JavaMethod;
+
+const CONSTRUCTOR_NAME: &str = "<init>";
+
+/// Calls a constructor of the superclass or, for `this(...)`, another constructor of the class
+/// being compiled on the object under construction
+pub fn from_constructor_call(
+    is_super: bool,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    // The object cannot be used until a superclass constructor has run (JLS §8.8.7.1)
+    let is_static_context = compilation_context.is_static_context;
+    compilation_context.is_static_context = true;
+    let result = constructor_call_instructions(is_super, arguments, compilation_context);
+    compilation_context.is_static_context = is_static_context;
+    result
+}
+
+/// Creates an enum constant, as an instance of the enum being compiled or of the class generated
+/// for its body, and stores it in its field. Returns the descriptor of the constructor it calls.
+pub fn from_enum_constant_creation(
+    name: &str,
+    ordinal: usize,
+    class_ref: u16,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<(Vec<Instruction>, String)> {
+    let descriptor = enum_constructor_descriptor(arguments, compilation_context)?;
+
+    let mut instructions = vec![Instruction::New(class_ref), Instruction::Dup];
+    instructions.extend(from_string_literal(name, compilation_context)?);
+    instructions.extend(from_int_literal(ordinal as i64, compilation_context)?);
+    instructions.extend(argument_instructions(arguments, &descriptor, enum_parameters().len(), compilation_context)?);
+
+    let constructor_ref = wrap(compilation_context.constant_pool.add_method_ref(class_ref, CONSTRUCTOR_NAME, &descriptor))?;
+    instructions.push(Instruction::Invokespecial(constructor_ref));
+    instructions.push(store_field(name, compilation_context)?);
+    Ok((instructions, descriptor))
+}
+
+/// The constructor of the enum being compiled that takes the arguments of a constant
+fn enum_constructor_descriptor(arguments: &[Expression], compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let mut argument_types = enum_parameters();
+    for argument in arguments {
+        argument_types.push(type_of(argument, compilation_context)?);
+    }
+
+    let descriptors = compilation_context.constructors.iter().map(String::as_str);
+    select_overload(descriptors, &argument_types)
+        .map(str::to_string)
+        .ok_or_else(|| CompileError::UnknownMethod {
+            class: compilation_context.this_class_name.clone(),
+            method: CONSTRUCTOR_NAME.to_string(),
+        })
+}
+
+fn constructor_call_instructions(
+    is_super: bool,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let (mut argument_types, hidden_arguments) = hidden_arguments(is_super, compilation_context)?;
+    let hidden_parameters = argument_types.len();
+    for argument in arguments {
+        argument_types.push(type_of(argument, compilation_context)?);
+    }
+
+    let (class_ref, class_name, descriptor) = if is_super {
+        let class_name = compilation_context.super_class_name.clone();
+        let descriptor = compilation_context.class_loader.load(&class_name).and_then(|class| {
+            let descriptors = class.methods_named(CONSTRUCTOR_NAME).into_iter().map(JavaMethod::descriptor);
+            select_overload(descriptors, &argument_types).map(str::to_string)
+        });
+        (compilation_context.super_class, class_name, descriptor)
+    } else {
+        let descriptors = compilation_context.constructors.iter().map(String::as_str);
+        let descriptor = select_overload(descriptors, &argument_types).map(str::to_string);
+        (compilation_context.this_class, compilation_context.this_class_name.clone(), descriptor)
+    };
+    let descriptor = descriptor.ok_or(CompileError::UnknownMethod {
+        class: class_name,
+        method: CONSTRUCTOR_NAME.to_string(),
+    })?;
+
+    let mut instructions = vec![Instruction::Aload_0];
+    instructions.extend(hidden_arguments);
+    instructions.extend(argument_instructions(arguments, &descriptor, hidden_parameters, compilation_context)?);
+
+    let constructor_ref = wrap(compilation_context.constant_pool.add_method_ref(class_ref, CONSTRUCTOR_NAME, &descriptor))?;
+    instructions.push(Instruction::Invokespecial(constructor_ref));
+    Ok(instructions)
+}
+
+/// What a constructor passes on ahead of its arguments, which it was given itself. The constructors
+/// of an enum and of `java.lang.Enum` take the name and ordinal of the constant, and those of an
+/// inner class the enclosing instance, which is also that of an inner superclass.
+fn hidden_arguments(is_super: bool, compilation_context: &mut CompilationContext) -> CompileResult<(Vec<JavaType>, Vec<Instruction>)> {
+    let hidden_parameters = compilation_context.hidden_constructor_parameters();
+    if !is_super || compilation_context.is_enum {
+        let mut instructions = vec![];
+        let mut slot = 1;
+        for parameter_type in &hidden_parameters {
+            instructions.push(parameter_type.load_instruction(slot));
+            slot += parameter_type.slots();
+        }
+        return Ok((hidden_parameters, instructions));
+    }
+
+    let super_class_name = compilation_context.super_class_name.clone();
+    if compilation_context.class_loader.load(&super_class_name).is_some_and(|class| !class.captured_variables().is_empty()) {
+        return Err(CompileError::Unsupported("a local class extending a local class that captures variables".to_string()));
+    }
+    let Some(enclosing_class) = enclosing_instance_of(&super_class_name, compilation_context) else {
+        return Ok((vec![], vec![]));
+    };
+    let has_enclosing_instance = compilation_context
+        .enclosing_instance()
+        .map(str::to_string)
+        .is_some_and(|own_enclosing_class| inheritance::is_subtype(&own_enclosing_class, &enclosing_class, &mut compilation_context.class_loader));
+    if !has_enclosing_instance {
+        return Err(CompileError::NonStaticReference(format!("{}.this", enclosing_class)));
+    }
+    Ok((vec![JavaType::Reference(enclosing_class.replace('.', "/"))], vec![Instruction::Aload_1]))
+}
+
+/// Leaves the arguments on the stack as the types of the parameters that follow the hidden ones
+fn argument_instructions(
+    arguments: &[Expression],
+    descriptor: &str,
+    hidden_parameters: usize,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let parameter_types = JavaType::from_method_descriptor(descriptor).map(|(parameter_types, _)| parameter_types).unwrap_or_default();
+    let mut instructions = vec![];
+    for (argument, parameter_type) in arguments.iter().zip(parameter_types.iter().skip(hidden_parameters)) {
+        instructions.extend(from_assigned_expression(argument, parameter_type, compilation_context)?);
+    }
+    Ok(instructions)
+}
+
+/// The name and ordinal an enum constructor takes ahead of its declared parameters
+fn enum_parameters() -> Vec<JavaType> {
+    vec![JavaType::string(), JavaType::Int]
+}
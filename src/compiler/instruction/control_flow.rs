@@ -0,0 +1,409 @@
+use crate::ast::class::AstType;
+use crate::ast::expression::Expression;
+use crate::ast::statement::Statement;
+use crate::compiler::frame_size::internal_error;
+use crate::compiler::instruction::branch::{back, resolve_label, skip, to_label, ConditionalJump};
+use crate::compiler::instruction::expression::{from_expression, from_expression_statement, type_of};
+use crate::compiler::instruction::operator::from_condition;
+use crate::compiler::instruction::variable_assignment::declared_type;
+use crate::compiler::instruction::{from, from_assigned_expression, is_assignable, widening};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::jump_targets::JumpTarget;
+use crate::compiler::local_variables::assigned_on_both;
+use crate::compiler::{inheritance, wrap, CompilationContext, CompileError, CompileResult};
+use ristretto_classfile::attributes::Instruction;
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+const ITERATOR: &str = "java/util/Iterator";
+
+/// The code after an `if` statement is reached when either branch completes, or without an `else`
+/// when the condition is false
+pub fn from_if(
+    condition: &Expression,
+    then_statement: &Statement,
+    else_statement: Option<&Statement>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let condition = from_condition(condition, false, compilation_context)?;
+    let after_condition = compilation_context.locals.assigned();
+    let then_instructions = from(then_statement, compilation_context)?;
+    let then_completes = completion(compilation_context);
+
+    let Some(else_statement) = else_statement else {
+        let mut instructions = condition.to(then_instructions.len());
+        instructions.extend(then_instructions);
+        carry_on(assigned_on_both(then_completes, Some(after_condition)), compilation_context);
+        return Ok(instructions);
+    };
+
+    compilation_context.jumps.set_reachable(true);
+    compilation_context.locals.set_assigned(&after_condition);
+    let else_instructions = from(else_statement, compilation_context)?;
+    let else_completes = completion(compilation_context);
+
+    // A branch that cannot complete never reaches the jump over the other
+    let skips_else = then_completes.is_some();
+    let mut instructions = condition.to(then_instructions.len() + usize::from(skips_else));
+    instructions.extend(then_instructions);
+    if skips_else {
+        instructions.push(skip(else_instructions.len()));
+    }
+    instructions.extend(else_instructions);
+    carry_on(assigned_on_both(then_completes, else_completes), compilation_context);
+    Ok(instructions)
+}
+
+/// Compiles a loop, which `continue` can go on with. A label on it is the one `break` and
+/// `continue` name to leave it from a loop inside it.
+pub fn from_loop(label: Option<&str>, statement: &Statement, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    match statement {
+        Statement::While { condition, body, .. } => from_while(label, condition, body, compilation_context),
+        Statement::DoWhile { body, condition, .. } => from_do_while(label, body, condition, compilation_context),
+        Statement::For { initializers, condition, updates, body, .. } => {
+            compilation_context.locals.enter_block();
+            let instructions = from_for(label, initializers, condition.as_ref(), updates, body, compilation_context);
+            compilation_context.locals.exit_block();
+            instructions
+        }
+        Statement::ForEach { name, var_type, is_final, iterable, body, .. } => {
+            compilation_context.locals.enter_block();
+            let instructions = from_for_each(label, name, var_type, *is_final, iterable, body, compilation_context);
+            compilation_context.locals.exit_block();
+            instructions
+        }
+        _ => Err(internal_error(format!("{:?} is not a loop", statement))),
+    }
+}
+
+/// A labeled loop is one `continue` can name. Any other statement can only be left early, by a
+/// `break` to its label.
+pub fn from_labeled(label: &str, statement: &Statement, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    if matches!(statement, Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } | Statement::ForEach { .. }) {
+        return from_loop(Some(label), statement, compilation_context);
+    }
+
+    let (mut instructions, target) = within_target(Some(label), false, compilation_context, |compilation_context| {
+        from(statement, compilation_context)
+    })?;
+    let end = instructions.len();
+    resolve_label(&mut instructions, target.break_label(), end);
+    let completes = completion(compilation_context);
+    carry_on(assigned_on_both(completes, target.break_assigned().cloned()), compilation_context);
+    Ok(instructions)
+}
+
+pub fn from_break(label: Option<&str>, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let assigned = compilation_context.locals.assigned();
+    let target = compilation_context.jumps.break_to(label, assigned)?;
+    compilation_context.jumps.set_reachable(false);
+    Ok(vec![to_label(target)])
+}
+
+pub fn from_continue(label: Option<&str>, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let assigned = compilation_context.locals.assigned();
+    let target = compilation_context.jumps.continue_to(label, assigned)?;
+    compilation_context.jumps.set_reachable(false);
+    Ok(vec![to_label(target)])
+}
+
+/// Returns the value, if the method has a return type, as that type. Nothing after a `return`
+/// runs.
+pub fn from_return(value: Option<&Expression>, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let Some((_, descriptor)) = &compilation_context.enclosing_method else {
+        return Err(CompileError::ReturnOutsideMethod);
+    };
+    let return_type = JavaType::from_method_descriptor(descriptor)
+        .map(|(_, return_type)| return_type)
+        .ok_or_else(|| internal_error(format!("{} is not a method descriptor", descriptor)))?;
+
+    // A constructor must have assigned the fields it is required to before it returns
+    if let Some(field) = compilation_context.locals.unassigned_field() {
+        return Err(CompileError::uninitialized_variable(field));
+    }
+    let mut instructions = match (value, &return_type) {
+        (None, JavaType::Void) => vec![],
+        (None, _) => return Err(CompileError::MissingReturnValue),
+        (Some(_), JavaType::Void) => return Err(CompileError::UnexpectedReturnValue),
+        (Some(value), _) => from_assigned_expression(value, &return_type, compilation_context)?,
+    };
+    instructions.push(return_type.return_instruction());
+    compilation_context.jumps.set_reachable(false);
+    Ok(instructions)
+}
+
+/// `while (true)` runs until something leaves it, and the body of `while (false)` can never run
+/// (JLS §14.22)
+fn from_while(
+    label: Option<&str>,
+    condition: &Expression,
+    body: &Statement,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    if is_literal(condition, false) {
+        return Err(CompileError::unreachable_statement(body.span()));
+    }
+    let condition = if is_literal(condition, true) { None } else { Some(from_condition(condition, false, compilation_context)?) };
+    let after_condition = compilation_context.locals.assigned();
+
+    let (body, target) = within_target(label, true, compilation_context, |compilation_context| from(body, compilation_context))?;
+    let goes_back = assigned_on_both(completion(compilation_context), target.continue_assigned().cloned()).is_some();
+
+    let when_finished = condition.is_some().then_some(after_condition);
+    let instructions = repeated(condition, body, vec![], goes_back, &target);
+    carry_on(assigned_on_both(when_finished, target.break_assigned().cloned()), compilation_context);
+    Ok(instructions)
+}
+
+/// The condition of a `do` statement is tested after each run of its body, and so is only reached
+/// if the body completes or is continued
+fn from_do_while(
+    label: Option<&str>,
+    body: &Statement,
+    condition: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let (mut instructions, target) = within_target(label, true, compilation_context, |compilation_context| from(body, compilation_context))?;
+    let body_completes = completion(compilation_context);
+    let condition_start = instructions.len();
+
+    let Some(before_condition) = assigned_on_both(body_completes, target.continue_assigned().cloned()) else {
+        // The condition is still checked, though it is never tested
+        from_condition(condition, true, compilation_context)?;
+        resolve_label(&mut instructions, target.break_label(), condition_start);
+        carry_on(target.break_assigned().cloned(), compilation_context);
+        return Ok(instructions);
+    };
+
+    compilation_context.locals.set_assigned(&before_condition);
+    let when_finished = if is_literal(condition, true) {
+        instructions.push(back(condition_start));
+        None
+    } else {
+        let condition = from_condition(condition, true, compilation_context)?;
+        instructions.extend(condition.back_to(condition_start));
+        Some(compilation_context.locals.assigned())
+    };
+
+    resolve_label(&mut instructions, target.continue_label(), condition_start);
+    let end = instructions.len();
+    resolve_label(&mut instructions, target.break_label(), end);
+    carry_on(assigned_on_both(when_finished, target.break_assigned().cloned()), compilation_context);
+    Ok(instructions)
+}
+
+/// A basic `for` statement without a condition, or with `true`, runs until something leaves it.
+/// Its updates follow the body, and are where `continue` goes on from.
+fn from_for(
+    label: Option<&str>,
+    initializers: &[Statement],
+    condition: Option<&Expression>,
+    updates: &[Expression],
+    body: &Statement,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let mut instructions = vec![];
+    for initializer in initializers {
+        instructions.extend(from(initializer, compilation_context)?);
+    }
+
+    let condition = match condition {
+        Some(condition) if is_literal(condition, false) => return Err(CompileError::unreachable_statement(body.span())),
+        Some(condition) if !is_literal(condition, true) => Some(from_condition(condition, false, compilation_context)?),
+        _ => None,
+    };
+    let after_condition = compilation_context.locals.assigned();
+
+    let (body, target) = within_target(label, true, compilation_context, |compilation_context| from(body, compilation_context))?;
+    let body_completes = completion(compilation_context);
+
+    let before_updates = assigned_on_both(body_completes, target.continue_assigned().cloned());
+    if let Some(before_updates) = &before_updates {
+        compilation_context.locals.set_assigned(before_updates);
+    }
+    let mut update_instructions = vec![];
+    for update in updates {
+        update_instructions.extend(from_expression_statement(update, compilation_context)?);
+    }
+
+    let when_finished = condition.is_some().then_some(after_condition);
+    instructions.extend(repeated(condition, body, update_instructions, before_updates.is_some(), &target));
+    carry_on(assigned_on_both(when_finished, target.break_assigned().cloned()), compilation_context);
+    Ok(instructions)
+}
+
+/// An enhanced `for` statement goes through an array by its index, and through anything else
+/// `Iterable` with its iterator (JLS §14.14.2). Both, and the length of an array, are kept in
+/// slots of their own ahead of the variable the body is given each element in.
+fn from_for_each(
+    label: Option<&str>,
+    name: &str,
+    var_type: &AstType,
+    is_final: bool,
+    iterable: &Expression,
+    body: &Statement,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let is_inferred = var_type.name() == "var" && !var_type.is_array();
+    let iterable_type = type_of(iterable, compilation_context)?;
+    let mut instructions = from_expression(iterable, compilation_context)?;
+
+    let (condition, mut element, variable_type, step) = match &iterable_type {
+        JavaType::Array(component_type) => {
+            let variable_type = if is_inferred { (**component_type).clone() } else { declared_type(var_type, compilation_context)? };
+            if !is_assignable(component_type, &variable_type, compilation_context) {
+                return Err(CompileError::IncompatibleTypes {
+                    expected: variable_type.name(),
+                    found: component_type.name(),
+                });
+            }
+
+            let array = compilation_context.locals.reserve(iterable_type.clone());
+            let length = compilation_context.locals.reserve(JavaType::Int);
+            let index = compilation_context.locals.reserve(JavaType::Int);
+            instructions.extend([
+                iterable_type.store_instruction(array),
+                iterable_type.load_instruction(array),
+                Instruction::Arraylength,
+                JavaType::Int.store_instruction(length),
+                Instruction::Iconst_0,
+                JavaType::Int.store_instruction(index),
+            ]);
+
+            let mut condition = ConditionalJump::default();
+            condition.extend([JavaType::Int.load_instruction(index), JavaType::Int.load_instruction(length)]);
+            condition.exit(Instruction::If_icmpge);
+            let mut element = vec![
+                iterable_type.load_instruction(array),
+                JavaType::Int.load_instruction(index),
+                component_type.array_load_instruction(),
+            ];
+            element.extend(widening(component_type, &variable_type));
+            let step = match u8::try_from(index) {
+                Ok(index) => Instruction::Iinc(index, 1),
+                Err(_) => Instruction::Iinc_w(index, 1),
+            };
+            (condition, element, variable_type, step)
+        }
+        JavaType::Reference(class_name)
+            if inheritance::is_subtype(&class_name.replace('/', "."), "java.lang.Iterable", &mut compilation_context.class_loader) =>
+        {
+            // Without type arguments, the elements are only known to be objects
+            let object_type = JavaType::Reference(JAVA_LANG_OBJECT.to_string());
+            let variable_type = if is_inferred { object_type.clone() } else { declared_type(var_type, compilation_context)? };
+            if !variable_type.is_reference() {
+                return Err(CompileError::IncompatibleTypes {
+                    expected: variable_type.name(),
+                    found: object_type.name(),
+                });
+            }
+
+            let iterator_type = JavaType::Reference(ITERATOR.to_string());
+            let iterator = compilation_context.locals.reserve(iterator_type.clone());
+            instructions.extend([
+                invoke_interface("java/lang/Iterable", "iterator", "()Ljava/util/Iterator;", compilation_context)?,
+                iterator_type.store_instruction(iterator),
+            ]);
+
+            let mut condition = ConditionalJump::default();
+            condition.extend([
+                iterator_type.load_instruction(iterator),
+                invoke_interface(ITERATOR, "hasNext", "()Z", compilation_context)?,
+            ]);
+            condition.exit(Instruction::Ifeq);
+            let mut element = vec![
+                iterator_type.load_instruction(iterator),
+                invoke_interface(ITERATOR, "next", "()Ljava/lang/Object;", compilation_context)?,
+            ];
+            if variable_type != object_type {
+                let class = match &variable_type {
+                    JavaType::Reference(class_name) => class_name.clone(),
+                    array_type => array_type.descriptor(),
+                };
+                element.push(Instruction::Checkcast(compilation_context.add_class(class)?));
+            }
+            (condition, element, variable_type, Instruction::Nop)
+        }
+        _ => return Err(CompileError::NotIterable(iterable_type.name())),
+    };
+
+    let variable = compilation_context.locals.declare(name, variable_type.clone(), is_final, true)?;
+    element.push(variable_type.store_instruction(variable));
+    let before_body = compilation_context.locals.assigned();
+
+    let (body, target) = within_target(label, true, compilation_context, |compilation_context| from(body, compilation_context))?;
+    let goes_back = assigned_on_both(completion(compilation_context), target.continue_assigned().cloned()).is_some();
+
+    element.extend(body);
+    let step = if step == Instruction::Nop { vec![] } else { vec![step] };
+    instructions.extend(repeated(Some(condition), element, step, goes_back, &target));
+    carry_on(assigned_on_both(Some(before_body), target.break_assigned().cloned()), compilation_context);
+    Ok(instructions)
+}
+
+/// Code that runs a body for as long as a condition holds, or until something leaves it when there
+/// is none. Each run ends with the updates, and a jump back to test the condition again if the
+/// body completes or is continued; `continue` goes to the updates, and `break` to the end.
+fn repeated(
+    condition: Option<ConditionalJump>,
+    body: Vec<Instruction>,
+    updates: Vec<Instruction>,
+    goes_back: bool,
+    target: &JumpTarget,
+) -> Vec<Instruction> {
+    let repeat_length = if goes_back { updates.len() + 1 } else { 0 };
+    let mut instructions = match condition {
+        Some(condition) => condition.to(body.len() + repeat_length),
+        None => vec![],
+    };
+    instructions.extend(body);
+    let updates_start = instructions.len();
+    if goes_back {
+        instructions.extend(updates);
+        instructions.push(back(instructions.len()));
+    }
+
+    resolve_label(&mut instructions, target.continue_label(), updates_start);
+    let end = instructions.len();
+    resolve_label(&mut instructions, target.break_label(), end);
+    instructions
+}
+
+/// Compiles a statement that can be jumped out of, returning its code with the jumps to its labels
+/// still to be resolved
+fn within_target(
+    label: Option<&str>,
+    is_loop: bool,
+    compilation_context: &mut CompilationContext,
+    compile: impl FnOnce(&mut CompilationContext) -> CompileResult<Vec<Instruction>>,
+) -> CompileResult<(Vec<Instruction>, JumpTarget)> {
+    compilation_context.jumps.enter(label, is_loop)?;
+    let instructions = compile(compilation_context)?;
+    let target = compilation_context
+        .jumps
+        .exit()
+        .ok_or_else(|| internal_error("a jump target was left more than once".to_string()))?;
+    Ok((instructions, target))
+}
+
+/// The local variables assigned at the end of the code compiled so far, if it can complete
+fn completion(compilation_context: &CompilationContext) -> Option<Vec<bool>> {
+    compilation_context.jumps.is_reachable().then(|| compilation_context.locals.assigned())
+}
+
+/// Goes on after a statement with what is assigned on the paths that complete it, if there are any
+fn carry_on(assigned: Option<Vec<bool>>, compilation_context: &mut CompilationContext) {
+    compilation_context.jumps.set_reachable(assigned.is_some());
+    if let Some(assigned) = assigned {
+        compilation_context.locals.set_assigned(&assigned);
+    }
+}
+
+fn is_literal(condition: &Expression, literal: bool) -> bool {
+    matches!(condition, Expression::BooleanLiteral { value, .. } if *value == literal)
+}
+
+fn invoke_interface(interface: &str, name: &str, descriptor: &str, compilation_context: &mut CompilationContext) -> CompileResult<Instruction> {
+    let class = compilation_context.add_class(interface)?;
+    let method = wrap(compilation_context.constant_pool.add_interface_method_ref(class, name, descriptor))?;
+    Ok(Instruction::Invokeinterface(method, 1))
+}
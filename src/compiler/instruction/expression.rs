@@ -1,7 +1,17 @@
 use crate::ast::expression::Expression;
-use crate::compiler::instruction::call::from_call_expression;
+use crate::compiler::instruction::call::{from_call_expression, return_type_of_call_expression};
+use crate::compiler::instruction::field::{from_qualified_field_read, from_this, type_of_qualified_field, type_of_this};
+use crate::compiler::instruction::instance_creation::{from_instance_creation, type_of_instance_creation};
+use crate::compiler::instruction::operator::{
+    from_binary, from_conditional, from_increment, from_unary, type_of_binary, type_of_conditional, type_of_increment, type_of_unary,
+    IncrementValue,
+};
+use crate::compiler::instruction::numeric_literal::{from_char_literal, from_double_literal, from_float_literal, from_int_literal, from_long_literal};
 use crate::compiler::instruction::string_literal::from_string_literal;
-use crate::compiler::result::CompileResult;
+use crate::compiler::instruction::variable::{from_variable_read, type_of_variable};
+use crate::compiler::instruction::variable_assignment::{from_assignment, from_field_assignment};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::{CompileError, CompileResult};
 use crate::compiler::CompilationContext;
 use ristretto_classfile::attributes::Instruction;
 
@@ -14,15 +24,101 @@ pub fn from_expression(
             target,
             method_name,
             arguments,
+            ..
         } => from_call_expression(unbox(target), method_name, arguments, compilation_context),
-        Expression::StringLiteral { value } => from_string_literal(value, compilation_context),
-        Expression::ChildIdentifier { parent, name } => todo!("Not supported"),
-        Expression::Variable { name, type_def } => todo!("Not supported"),
-        Expression::Assignment { name, type_def, value } => todo!("Not supported"),
+        Expression::StringLiteral { value, .. } => from_string_literal(value, compilation_context),
+        Expression::BooleanLiteral { value, .. } => from_int_literal(*value as i64, compilation_context),
+        Expression::CharLiteral { value, .. } => from_char_literal(*value, compilation_context),
+        Expression::IntLiteral { value, .. } => from_int_literal(*value, compilation_context),
+        Expression::LongLiteral { value, .. } => from_long_literal(*value, compilation_context),
+        Expression::FloatLiteral { value, .. } => from_float_literal(*value, compilation_context),
+        Expression::DoubleLiteral { value, .. } => from_double_literal(*value, compilation_context),
+        Expression::This { .. } => from_this(compilation_context),
+        Expression::ChildIdentifier { parent, name, .. } => from_qualified_field_read(parent, name, compilation_context),
+        Expression::Variable { name, type_def: None, .. } => from_variable_read(name, compilation_context),
+        Expression::Variable { .. } => Err(CompileError::Unsupported("declaration used as an expression".to_string())),
+        Expression::Assignment { name, value, .. } => from_assignment(name, value, true, compilation_context),
+        Expression::FieldAssignment { qualifier, name, value, .. } => from_field_assignment(qualifier, name, value, true, compilation_context),
+        Expression::New { class_type, arguments, body, .. } => {
+            from_instance_creation(class_type, arguments, body.as_deref(), compilation_context)
+        }
+        Expression::Binary { left, operator, right, .. } => from_binary(left, *operator, right, compilation_context),
+        Expression::Unary { operator, operand, .. } => from_unary(*operator, operand, compilation_context),
+        Expression::PrefixIncrement { operand, is_decrement, .. } => {
+            from_increment(operand, *is_decrement, IncrementValue::Updated, compilation_context)
+        }
+        Expression::PostfixIncrement { operand, is_decrement, .. } => {
+            from_increment(operand, *is_decrement, IncrementValue::Original, compilation_context)
+        }
+        Expression::Conditional { condition, when_true, when_false, .. } => {
+            from_conditional(condition, when_true, when_false, compilation_context)
+        }
+    }
+}
+
+pub fn type_of(
+    expression: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    match expression {
+        Expression::Call {
+            target,
+            method_name,
+            arguments,
+            ..
+        } => return_type_of_call_expression(unbox(target), method_name, arguments, compilation_context),
+        Expression::StringLiteral { .. } => Ok(JavaType::string()),
+        Expression::BooleanLiteral { .. } => Ok(JavaType::Boolean),
+        Expression::CharLiteral { .. } => Ok(JavaType::Char),
+        Expression::IntLiteral { .. } => Ok(JavaType::Int),
+        Expression::LongLiteral { .. } => Ok(JavaType::Long),
+        Expression::FloatLiteral { .. } => Ok(JavaType::Float),
+        Expression::DoubleLiteral { .. } => Ok(JavaType::Double),
+        Expression::This { .. } => Ok(type_of_this(compilation_context)),
+        Expression::ChildIdentifier { parent, name, .. } => type_of_qualified_field(parent, name, compilation_context),
+        Expression::Variable { name, type_def: None, .. } => type_of_variable(name, compilation_context),
+        Expression::Variable { .. } => Err(CompileError::Unsupported("declaration used as an expression".to_string())),
+        Expression::Assignment { name, .. } => type_of_variable(name, compilation_context),
+        Expression::FieldAssignment { qualifier, name, .. } => type_of_qualified_field(qualifier, name, compilation_context),
+        Expression::New { class_type, body, .. } => type_of_instance_creation(class_type, body.as_deref(), compilation_context),
+        Expression::Binary { left, operator, right, .. } => type_of_binary(left, *operator, right, compilation_context),
+        Expression::Unary { operator, operand, .. } => type_of_unary(*operator, operand, compilation_context),
+        Expression::PrefixIncrement { operand, is_decrement, .. } | Expression::PostfixIncrement { operand, is_decrement, .. } => {
+            type_of_increment(operand, *is_decrement, compilation_context)
+        }
+        Expression::Conditional { when_true, when_false, .. } => type_of_conditional(when_true, when_false, compilation_context),
+    }
+}
+
+/// Evaluates an expression statement for its effect, leaving nothing on the stack
+pub fn from_expression_statement(
+    expression: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    match expression {
+        Expression::PrefixIncrement { operand, is_decrement, .. } | Expression::PostfixIncrement { operand, is_decrement, .. } => {
+            from_increment(operand, *is_decrement, IncrementValue::Discarded, compilation_context)
+        }
+        Expression::Assignment { name, value, .. } => from_assignment(name, value, false, compilation_context),
+        Expression::FieldAssignment { qualifier, name, value, .. } => from_field_assignment(qualifier, name, value, false, compilation_context),
+        Expression::Call { .. } => {
+            let mut instructions = from_expression(expression, compilation_context)?;
+            match type_of(expression, compilation_context)?.slots() {
+                0 => {}
+                1 => instructions.push(Instruction::Pop),
+                _ => instructions.push(Instruction::Pop2),
+            }
+            Ok(instructions)
+        }
+        Expression::New { .. } => {
+            let mut instructions = from_expression(expression, compilation_context)?;
+            instructions.push(Instruction::Pop);
+            Ok(instructions)
+        }
+        _ => from_expression(expression, compilation_context),
     }
 }
 
 fn unbox<T>(value: &Box<T>) -> &T {
     &**value
 }
-
@@ -0,0 +1,379 @@
+use ristretto_classfile::attributes::Instruction;
+use crate::ast::expression::Expression;
+use crate::compiler::field_builder::DeclaredField;
+use crate::compiler::instruction::call::{extract_object_path, parse_object_path, qualify_object_path, supertypes_of};
+use crate::compiler::instruction::expression::{from_expression, type_of};
+use crate::compiler::instruction::variable::is_variable;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::nested_class_builder::{captured_variable_ref, outer_instance_ref};
+use crate::compiler::{inheritance, wrap, CompilationContext, CompileError, CompileResult};
+use crate::java::field::JavaField;
+
+/// Reads a field by its simple name, declared or inherited by the class being compiled, or declared
+/// by a class it is nested in. An instance field of an enclosing class is read from the enclosing
+/// instance, and a local variable a local or anonymous class captures from its `val$` field.
+pub fn from_field_read(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    match simple_field(name, compilation_context) {
+        Some(SimpleField::Enclosing(depth, class_name, field)) => {
+            return from_enclosing_field_read(name, depth, &class_name, &field, compilation_context);
+        }
+        Some(SimpleField::Captured(descriptor)) => {
+            let mut instructions = from_this(compilation_context).map_err(|_| CompileError::NonStaticReference(name.to_string()))?;
+            instructions.push(Instruction::Getfield(captured_variable_ref(name, &descriptor, compilation_context)?));
+            return Ok(instructions);
+        }
+        _ => {}
+    }
+    let (field_ref, is_static) = field_ref(name, compilation_context)?;
+    if is_static {
+        Ok(vec![Instruction::Getstatic(field_ref)])
+    } else {
+        Ok(vec![Instruction::Aload_0, Instruction::Getfield(field_ref)])
+    }
+}
+
+/// Reads a field through a qualifier: a static field of the class the qualifier names, as in
+/// `Other.CONST`, or a field of the object it evaluates to, as in `this.x` or `new P().x`
+pub fn from_qualified_field_read(qualifier: &Expression, name: &str, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    match qualified_field_access(qualifier, name, compilation_context)? {
+        FieldAccess::ArrayLength(mut instructions) => {
+            instructions.push(Instruction::Arraylength);
+            Ok(instructions)
+        }
+        FieldAccess::Field { mut object, field_ref, is_static, .. } => {
+            object.push(if is_static { Instruction::Getstatic(field_ref) } else { Instruction::Getfield(field_ref) });
+            Ok(object)
+        }
+    }
+}
+
+/// How a field named through a qualifier is reached
+pub enum FieldAccess {
+    /// The `length` of an array, with the instructions that load the array
+    ArrayLength(Vec<Instruction>),
+    /// A field, with the instructions that load the object it belongs to. A static field has no
+    /// object, although a qualifier that is an expression is still evaluated.
+    Field { object: Vec<Instruction>, field_ref: u16, is_static: bool, is_final: bool },
+}
+
+pub fn qualified_field_access(qualifier: &Expression, name: &str, compilation_context: &mut CompilationContext) -> CompileResult<FieldAccess> {
+    let mut object = vec![];
+    let (class_name, field) = match qualifier_of(qualifier, compilation_context)? {
+        Qualifier::Class(class_name) => {
+            let (class_name, field) = lookup_field(&class_name, name, compilation_context)?;
+            if !field.is_static() {
+                return Err(CompileError::NonStaticReference(format!("{}.{}", class_name, name)));
+            }
+            (class_name, field)
+        }
+        Qualifier::Object(JavaType::Array(_)) if name == "length" => {
+            return Ok(FieldAccess::ArrayLength(from_expression(qualifier, compilation_context)?));
+        }
+        Qualifier::Object(object_type) => {
+            object.extend(from_expression(qualifier, compilation_context)?);
+            let (class_name, field) = lookup_field(&class_of(&object_type, name)?, name, compilation_context)?;
+            if field.is_static() {
+                object.push(Instruction::Pop);
+            }
+            (class_name, field)
+        }
+    };
+
+    let class_ref = compilation_context.add_class(class_name.replace('.', "/"))?;
+    let field_ref = wrap(compilation_context.constant_pool.add_field_ref(class_ref, name, field.descriptor()))?;
+    Ok(FieldAccess::Field {
+        object,
+        field_ref,
+        is_static: field.is_static(),
+        is_final: field.is_final(),
+    })
+}
+
+pub fn type_of_qualified_field(qualifier: &Expression, name: &str, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let class_name = match qualifier_of(qualifier, compilation_context)? {
+        Qualifier::Class(class_name) => class_name,
+        Qualifier::Object(JavaType::Array(_)) if name == "length" => return Ok(JavaType::Int),
+        Qualifier::Object(object_type) => class_of(&object_type, name)?,
+    };
+    let (_, field) = lookup_field(&class_name, name, compilation_context)?;
+    JavaType::from_descriptor(field.descriptor()).ok_or_else(|| CompileError::UnknownField {
+        class: class_name,
+        field: name.to_string(),
+    })
+}
+
+/// Loads `this`, which code in a static context does not have
+pub fn from_this(compilation_context: &CompilationContext) -> CompileResult<Vec<Instruction>> {
+    if compilation_context.is_static_context {
+        return Err(CompileError::NonStaticReference("this".to_string()));
+    }
+    Ok(vec![Instruction::Aload_0])
+}
+
+pub fn type_of_this(compilation_context: &CompilationContext) -> JavaType {
+    JavaType::Reference(compilation_context.this_class_name.replace('.', "/"))
+}
+
+/// Stores the value on top of the stack into a field of the class being compiled. An instance
+/// field also needs `this` beneath the value.
+pub fn store_field(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<Instruction> {
+    let (field_ref, is_static) = field_ref(name, compilation_context)?;
+    if is_static {
+        Ok(Instruction::Putstatic(field_ref))
+    } else {
+        Ok(Instruction::Putfield(field_ref))
+    }
+}
+
+/// A final field of the class being compiled may only be assigned while the class, for a static
+/// field, or an instance of it, for an instance field, is being initialized (JLS §8.3.1.2). A final
+/// field declared by any other class may not be assigned at all.
+pub fn check_field_assignable(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<()> {
+    let field = match simple_field(name, compilation_context) {
+        Some(SimpleField::Declared(field)) if field.is_final() => field,
+        Some(SimpleField::Captured(_)) => return Err(CompileError::not_effectively_final(name)),
+        Some(field) if field.is_final() => return Err(CompileError::assignment_to_final(name)),
+        _ => return Ok(()),
+    };
+    let is_initializer = match &compilation_context.enclosing_method {
+        Some((method_name, _)) => method_name == "<init>",
+        None => true,
+    };
+    if is_initializer && field.is_static() == compilation_context.is_static_context {
+        Ok(())
+    } else {
+        Err(CompileError::assignment_to_final(name))
+    }
+}
+
+/// Whether a simple name refers to a final field in scope
+pub fn is_final_field(name: &str, compilation_context: &mut CompilationContext) -> bool {
+    simple_field(name, compilation_context).is_some_and(|field| field.is_final())
+}
+
+pub fn type_of_field(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    simple_field(name, compilation_context)
+        .and_then(|field| JavaType::from_descriptor(field.descriptor()))
+        .ok_or_else(|| unknown_field(name, compilation_context))
+}
+
+/// Whether a simple name refers to a field in scope
+pub fn is_field(name: &str, compilation_context: &mut CompilationContext) -> bool {
+    simple_field(name, compilation_context).is_some()
+}
+
+/// Loads `this`, or the innermost enclosing instance that is an instance of the given class
+pub fn from_enclosing_instance(class_name: &str, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let instances = reachable_instances(compilation_context);
+    let depth = instances
+        .iter()
+        .position(|instance| inheritance::is_subtype(instance, class_name, &mut compilation_context.class_loader))
+        .ok_or_else(|| CompileError::NonStaticReference(format!("{}.this", class_name)))?;
+    outer_instance_path(&instances[..depth], compilation_context)
+}
+
+/// The field a simple name refers to, searched for in the class being compiled, including the
+/// fields it inherits from its superclasses and the constants of its superinterfaces, and then in
+/// each class it is nested in, and finally among the statically imported fields (JLS §6.4.1, §7.5.3,
+/// §8.3, §9.3). The local variables a local or anonymous class captures come before the fields of
+/// the classes it is nested in, as they are declared within them.
+enum SimpleField {
+    Declared(DeclaredField),
+    Inherited(JavaField),
+    /// A local variable captured by the class being compiled, with its descriptor
+    Captured(String),
+    /// Declared by the class that many classes out, with its name
+    Enclosing(usize, String, DeclaredField),
+    /// Imported from the class with the given name
+    Imported(String, JavaField),
+}
+
+impl SimpleField {
+    fn descriptor(&self) -> &str {
+        match self {
+            SimpleField::Declared(field) | SimpleField::Enclosing(_, _, field) => field.descriptor(),
+            SimpleField::Inherited(field) | SimpleField::Imported(_, field) => field.descriptor(),
+            SimpleField::Captured(descriptor) => descriptor,
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        match self {
+            SimpleField::Declared(field) | SimpleField::Enclosing(_, _, field) => field.is_static(),
+            SimpleField::Inherited(field) | SimpleField::Imported(_, field) => field.is_static(),
+            SimpleField::Captured(_) => false,
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        match self {
+            SimpleField::Declared(field) | SimpleField::Enclosing(_, _, field) => field.is_final(),
+            SimpleField::Inherited(field) | SimpleField::Imported(_, field) => field.is_final(),
+            SimpleField::Captured(_) => true,
+        }
+    }
+}
+
+fn simple_field(name: &str, compilation_context: &mut CompilationContext) -> Option<SimpleField> {
+    if let Some(field) = compilation_context.fields.get(name) {
+        return Some(SimpleField::Declared(field.clone()));
+    }
+    let this_class_name = compilation_context.this_class_name.clone();
+    if let Ok((_, field)) = lookup_field(&this_class_name, name, compilation_context) {
+        return Some(SimpleField::Inherited(field));
+    }
+    if let Some((_, variable_type)) = compilation_context.captured_variables.iter().find(|(variable_name, _)| variable_name == name) {
+        return Some(SimpleField::Captured(variable_type.descriptor()));
+    }
+    if let Some((depth, class_name, field)) = enclosing_field(name, compilation_context) {
+        return Some(SimpleField::Enclosing(depth, class_name, field));
+    }
+    let class_loader = &mut compilation_context.class_loader;
+    let class_name = compilation_context.names.resolve_static_member(name, class_loader)?;
+    let field = class_loader.load(&class_name)?.field_named(name)?.clone();
+    Some(SimpleField::Imported(class_name, field))
+}
+
+/// What the qualifier of a field access denotes
+enum Qualifier {
+    Class(String),
+    Object(JavaType),
+}
+
+fn qualifier_of(qualifier: &Expression, compilation_context: &mut CompilationContext) -> CompileResult<Qualifier> {
+    match class_named_by(qualifier, compilation_context) {
+        Some(class_name) => Ok(Qualifier::Class(class_name)),
+        None => Ok(Qualifier::Object(type_of(qualifier, compilation_context)?)),
+    }
+}
+
+/// The class a simple or qualified name denotes, unless its first name is a variable in scope,
+/// which hides any class of the same name (JLS §6.4.2)
+fn class_named_by(expression: &Expression, compilation_context: &mut CompilationContext) -> Option<String> {
+    let mut path = String::new();
+    extract_object_path(expression, &mut path).ok()?;
+    let first_name = path.split('.').next().unwrap_or_default();
+    if is_variable(first_name, compilation_context) {
+        return None;
+    }
+
+    let path = qualify_object_path(path, compilation_context);
+    match parse_object_path(&path, &mut compilation_context.class_loader) {
+        Some((class_name, suffix)) if suffix.is_empty() => Some(class_name.to_string()),
+        _ => None,
+    }
+}
+
+fn class_of(object_type: &JavaType, field_name: &str) -> CompileResult<String> {
+    match object_type {
+        JavaType::Reference(class_name) => Ok(class_name.replace('/', ".")),
+        _ => Err(CompileError::UnknownField {
+            class: object_type.name(),
+            field: field_name.to_string(),
+        }),
+    }
+}
+
+/// The field a class declares or inherits, along with the class declaring it
+fn lookup_field(class_name: &str, field_name: &str, compilation_context: &mut CompilationContext) -> CompileResult<(String, JavaField)> {
+    let class_loader = &mut compilation_context.class_loader;
+    for supertype in supertypes_of(class_name, class_loader) {
+        if let Some(field) = class_loader.load(&supertype).and_then(|class| class.field_named(field_name)) {
+            return Ok((supertype, field.clone()));
+        }
+    }
+    Err(CompileError::UnknownField {
+        class: class_name.to_string(),
+        field: field_name.to_string(),
+    })
+}
+
+/// A reference to a field the class being compiled declares or inherits, or one it imports. An
+/// inherited field is still referred to through the class being compiled, as javac does (JLS §13.1).
+fn field_ref(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<(u16, bool)> {
+    let (class_ref, descriptor, is_static) = match simple_field(name, compilation_context) {
+        Some(field @ (SimpleField::Declared(_) | SimpleField::Inherited(_))) => {
+            (compilation_context.this_class, field.descriptor().to_string(), field.is_static())
+        }
+        Some(SimpleField::Imported(class_name, field)) => {
+            let class_ref = compilation_context.add_class(class_name.replace('.', "/"))?;
+            (class_ref, field.descriptor().to_string(), field.is_static())
+        }
+        _ => return Err(unknown_field(name, compilation_context)),
+    };
+    if !is_static && compilation_context.is_static_context {
+        return Err(CompileError::NonStaticReference(name.to_string()));
+    }
+
+    let field_ref = wrap(compilation_context.constant_pool.add_field_ref(class_ref, name, descriptor.as_str()))?;
+    Ok((field_ref, is_static))
+}
+
+/// Nestmates read each other's private fields directly (JVMS §5.4.4)
+fn from_enclosing_field_read(
+    name: &str,
+    depth: usize,
+    class_name: &str,
+    field: &DeclaredField,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let class_ref = compilation_context.add_class(class_name.replace('.', "/"))?;
+    let field_ref = wrap(compilation_context.constant_pool.add_field_ref(class_ref, name, field.descriptor()))?;
+    if field.is_static() {
+        return Ok(vec![Instruction::Getstatic(field_ref)]);
+    }
+
+    let instances = reachable_instances(compilation_context);
+    if instances.len() <= depth {
+        return Err(CompileError::NonStaticReference(name.to_string()));
+    }
+    let mut instructions = outer_instance_path(&instances[..depth], compilation_context)?;
+    instructions.push(Instruction::Getfield(field_ref));
+    Ok(instructions)
+}
+
+/// The innermost enclosing class declaring a field, how many classes out it is, and the field
+fn enclosing_field(name: &str, compilation_context: &CompilationContext) -> Option<(usize, String, DeclaredField)> {
+    let mut enclosing = compilation_context.enclosing.as_deref();
+    let mut depth = 1;
+    while let Some(enclosing_class) = enclosing {
+        if let Some(field) = enclosing_class.fields().get(name) {
+            return Some((depth, enclosing_class.name().to_string(), field.clone()));
+        }
+        enclosing = enclosing_class.enclosing();
+        depth += 1;
+    }
+    None
+}
+
+/// The classes of `this` and of each enclosing instance held in turn, which code outside a static
+/// context can reach
+fn reachable_instances(compilation_context: &CompilationContext) -> Vec<String> {
+    if compilation_context.is_static_context {
+        return vec![];
+    }
+    let mut instances = vec![compilation_context.this_class_name.clone()];
+    let mut enclosing = compilation_context.enclosing.as_deref();
+    while let Some(enclosing_class) = enclosing.filter(|enclosing_class| enclosing_class.has_instance()) {
+        instances.push(enclosing_class.name().to_string());
+        enclosing = enclosing_class.enclosing();
+    }
+    instances
+}
+
+/// Loads `this`, then the enclosing instance each of the given classes holds in its `this$0` field
+fn outer_instance_path(classes: &[String], compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let mut instructions = vec![Instruction::Aload_0];
+    for class_name in classes {
+        let class_ref = compilation_context.add_class(class_name.replace('.', "/"))?;
+        instructions.push(Instruction::Getfield(outer_instance_ref(class_ref, class_name, compilation_context)?));
+    }
+    Ok(instructions)
+}
+
+fn unknown_field(name: &str, compilation_context: &CompilationContext) -> CompileError {
+    CompileError::UnknownField {
+        class: compilation_context.this_class_name.clone(),
+        field: name.to_string(),
+    }
+}
@@ -0,0 +1,127 @@
+use ristretto_classfile::attributes::Instruction;
+use crate::ast::class::AstClass;
+use crate::ast::expression::Expression;
+use crate::compiler::instruction::call::select_overload;
+use crate::compiler::instruction::expression::type_of;
+use crate::compiler::instruction::field::from_enclosing_instance;
+use crate::compiler::instruction::from_assigned_expression;
+use crate::compiler::instruction::variable::from_variable_read;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::nested_class_builder;
+use crate::compiler::{wrap, CompilationContext, CompileError, CompileResult};
+use crate::java::method::JavaMethod;
+
+const CONSTRUCTOR_NAME: &str = "<init>";
+
+/// What a constructor is given ahead of the arguments written in the source
+pub enum HiddenArgument {
+    /// The innermost enclosing instance of the given class
+    EnclosingInstance(String),
+    /// The value of a local variable that a local or anonymous class captures
+    CapturedVariable(String),
+}
+
+/// Creates an instance of a class with `new`, first compiling the class of its body if it is an
+/// anonymous class. An inner class is given the innermost enclosing instance of the class it is
+/// declared in, and an anonymous class the instance of the class being compiled, if it has one,
+/// followed by the local variables a local or anonymous class captures.
+pub fn from_instance_creation(
+    class_type: &str,
+    arguments: &[Expression],
+    body: Option<&AstClass>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let (class_name, descriptor, hidden_arguments) = match body {
+        Some(body) => nested_class_builder::anonymous_class(body, class_type, arguments, compilation_context)?,
+        None => {
+            let class_name = instantiable_class(class_type, compilation_context)?;
+            let descriptor = select_constructor(&class_name, arguments, compilation_context)?;
+            let hidden_arguments = hidden_arguments_of(&class_name, compilation_context);
+            (class_name, descriptor, hidden_arguments)
+        }
+    };
+
+    let class_ref = compilation_context.add_class(class_name.replace('.', "/"))?;
+    let mut instructions = vec![Instruction::New(class_ref), Instruction::Dup];
+    for hidden_argument in &hidden_arguments {
+        instructions.extend(match hidden_argument {
+            HiddenArgument::EnclosingInstance(enclosing_class) => from_enclosing_instance(enclosing_class, compilation_context)?,
+            HiddenArgument::CapturedVariable(name) => from_variable_read(name, compilation_context)?,
+        });
+    }
+
+    let parameter_types = JavaType::from_method_descriptor(&descriptor).map(|(parameter_types, _)| parameter_types).unwrap_or_default();
+    for (argument, parameter_type) in arguments.iter().zip(parameter_types.iter().skip(hidden_arguments.len())) {
+        instructions.extend(from_assigned_expression(argument, parameter_type, compilation_context)?);
+    }
+
+    let constructor_ref = wrap(compilation_context.constant_pool.add_method_ref(class_ref, CONSTRUCTOR_NAME, &descriptor))?;
+    instructions.push(Instruction::Invokespecial(constructor_ref));
+    Ok(instructions)
+}
+
+/// The class created, which is the class or interface named for an anonymous class
+pub fn type_of_instance_creation(class_type: &str, body: Option<&AstClass>, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let class_name = match body {
+        Some(_) => compilation_context.resolve_class(class_type)?,
+        None => instantiable_class(class_type, compilation_context)?,
+    };
+    Ok(JavaType::Reference(class_name.replace('.', "/")))
+}
+
+/// The descriptor of the constructor of a class that takes the given arguments, after the
+/// enclosing instance of an inner class and the variables a local class captures
+pub fn select_constructor(class_name: &str, arguments: &[Expression], compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let mut argument_types: Vec<JavaType> = enclosing_instance_of(class_name, compilation_context)
+        .map(|enclosing_class| JavaType::Reference(enclosing_class.replace('.', "/")))
+        .into_iter()
+        .collect();
+    if let Some(class) = compilation_context.class_loader.load(class_name) {
+        argument_types.extend(class.captured_variables().iter().filter_map(|(_, descriptor)| JavaType::from_descriptor(descriptor)));
+    }
+    for argument in arguments {
+        argument_types.push(type_of(argument, compilation_context)?);
+    }
+
+    compilation_context
+        .class_loader
+        .load(class_name)
+        .and_then(|class| {
+            let descriptors = class.methods_named(CONSTRUCTOR_NAME).into_iter().map(JavaMethod::descriptor);
+            select_overload(descriptors, &argument_types).map(str::to_string)
+        })
+        .ok_or_else(|| CompileError::UnknownMethod {
+            class: class_name.to_string(),
+            method: CONSTRUCTOR_NAME.to_string(),
+        })
+}
+
+/// What the constructors of a class are given ahead of their arguments: the enclosing instance of
+/// an inner class, then the variables a local class captures
+pub fn hidden_arguments_of(class_name: &str, compilation_context: &mut CompilationContext) -> Vec<HiddenArgument> {
+    let Some(class) = compilation_context.class_loader.load(class_name) else {
+        return vec![];
+    };
+    let enclosing_instance = class.enclosing_instance().map(|enclosing_class| HiddenArgument::EnclosingInstance(enclosing_class.to_string()));
+    let captured_variables = class.captured_variables().iter().map(|(name, _)| HiddenArgument::CapturedVariable(name.clone()));
+    enclosing_instance.into_iter().chain(captured_variables).collect()
+}
+
+/// The class of the enclosing instance an inner class is created with
+pub fn enclosing_instance_of(class_name: &str, compilation_context: &mut CompilationContext) -> Option<String> {
+    compilation_context
+        .class_loader
+        .load(class_name)
+        .and_then(|class| class.enclosing_instance())
+        .map(str::to_string)
+}
+
+/// An interface can only be instantiated through an anonymous class implementing it (JLS §15.9.1)
+fn instantiable_class(class_type: &str, compilation_context: &mut CompilationContext) -> CompileResult<String> {
+    let class_name = compilation_context.resolve_class(class_type)?;
+    match compilation_context.class_loader.load(&class_name) {
+        Some(class) if class.is_interface() => Err(CompileError::UnexpectedInterface(class_name)),
+        Some(_) => Ok(class_name),
+        None => Err(CompileError::UnknownClass(class_type.to_string())),
+    }
+}
@@ -1,18 +1,103 @@
 mod expression;
+mod branch;
 mod call;
+mod constructor_call;
+mod control_flow;
+mod field;
+mod instance_creation;
+mod numeric_literal;
+mod operator;
+mod string_concatenation;
 mod string_literal;
+mod variable;
 mod variable_assignment;
 
 use ristretto_classfile::attributes::Instruction;
 use crate::ast::statement::Statement;
-use crate::compiler::{CompilationContext, CompileResult};
-use crate::compiler::instruction::expression::from_expression;
+use crate::compiler::{inheritance, method_builder, nested_class_builder, CompilationContext, CompileResult};
+use crate::ast::expression::Expression;
+use crate::compiler::instruction::expression::{from_expression, from_expression_statement, type_of};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::CompileError;
+use crate::compiler::instruction::constructor_call::from_constructor_call;
+use crate::compiler::instruction::control_flow::{from_break, from_continue, from_if, from_labeled, from_loop, from_return};
 use crate::compiler::instruction::variable_assignment::from_variable_assignment;
 
+pub use crate::compiler::instruction::branch::resolve_branches;
+pub use crate::compiler::instruction::constructor_call::from_enum_constant_creation;
+pub use crate::compiler::instruction::field::{from_field_read, store_field};
+pub use crate::compiler::instruction::instance_creation::{hidden_arguments_of, select_constructor, HiddenArgument};
+pub use crate::compiler::instruction::numeric_literal::from_int_literal;
+
 pub fn from(statement: &Statement, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
     match statement {
-        Statement::Expression { expression } => from_expression(expression, compilation_context),
-        Statement::VariableAssignment { name, var_type, is_final, value } =>
-            from_variable_assignment(name, var_type, *is_final, value)
+        Statement::Expression { expression, .. } => from_expression_statement(expression, compilation_context),
+        Statement::VariableAssignment { name, var_type, is_final, value, .. } =>
+            from_variable_assignment(name, var_type, *is_final, value, compilation_context),
+        Statement::Block { statements, .. } => method_builder::build_instructions(statements, compilation_context),
+        Statement::ConstructorCall { is_super, arguments, .. } => from_constructor_call(*is_super, arguments, compilation_context),
+        Statement::LocalClass { class, .. } => {
+            nested_class_builder::local_class(class, compilation_context)?;
+            Ok(vec![])
+        }
+        Statement::Empty { .. } => Ok(vec![]),
+        Statement::If { condition, then_statement, else_statement, .. } => {
+            from_if(condition, then_statement, else_statement.as_deref(), compilation_context)
+        }
+        Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } | Statement::ForEach { .. } => {
+            from_loop(None, statement, compilation_context)
+        }
+        Statement::Break { label, .. } => from_break(*label, compilation_context),
+        Statement::Continue { label, .. } => from_continue(*label, compilation_context),
+        Statement::Labeled { label, statement, .. } => from_labeled(label, statement, compilation_context),
+        Statement::Return { value, .. } => from_return(value.as_ref(), compilation_context),
+    }
+}
+
+
+/// Leaves the value of the expression on the stack as the target type, widening it where an
+/// assignment allows (JLS §5.2)
+pub fn from_assigned_expression(
+    expression: &Expression,
+    target_type: &JavaType,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let expression_type = type_of(expression, compilation_context)?;
+    let is_narrowed_constant = matches!(target_type, JavaType::Byte | JavaType::Short | JavaType::Char)
+        && matches!(expression, Expression::IntLiteral { .. } | Expression::CharLiteral { .. });
+
+    if !is_narrowed_constant && !is_assignable(&expression_type, target_type, compilation_context) {
+        return Err(CompileError::IncompatibleTypes {
+            expected: target_type.name(),
+            found: expression_type.name(),
+        });
+    }
+
+    let mut instructions = from_expression(expression, compilation_context)?;
+    instructions.extend(widening(&expression_type, target_type));
+    Ok(instructions)
+}
+
+/// Whether a value of one type can be assigned to a variable of another, by identity or widening
+/// conversions, or as a class to one of its supertypes
+fn is_assignable(from: &JavaType, to: &JavaType, compilation_context: &mut CompilationContext) -> bool {
+    let is_subtype = match (from, to) {
+        (JavaType::Reference(class_name), JavaType::Reference(target)) => {
+            inheritance::is_subtype(&class_name.replace('/', "."), &target.replace('/', "."), &mut compilation_context.class_loader)
+        }
+        _ => false,
+    };
+    is_subtype || from.is_assignable_to(to)
+}
+
+fn widening(from: &JavaType, to: &JavaType) -> Option<Instruction> {
+    match (from, to) {
+        (JavaType::Byte | JavaType::Short | JavaType::Char | JavaType::Int, JavaType::Long) => Some(Instruction::I2l),
+        (JavaType::Byte | JavaType::Short | JavaType::Char | JavaType::Int, JavaType::Float) => Some(Instruction::I2f),
+        (JavaType::Byte | JavaType::Short | JavaType::Char | JavaType::Int, JavaType::Double) => Some(Instruction::I2d),
+        (JavaType::Long, JavaType::Float) => Some(Instruction::L2f),
+        (JavaType::Long, JavaType::Double) => Some(Instruction::L2d),
+        (JavaType::Float, JavaType::Double) => Some(Instruction::F2d),
+        _ => None,
     }
 }
@@ -0,0 +1,90 @@
+use ristretto_classfile::attributes::Instruction;
+use crate::compiler::{wrap, CompilationContext, CompileError, CompileResult};
+
+pub fn from_int_literal(
+    value: i64,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let value = i32::try_from(value).map_err(|_| CompileError::LiteralOutOfRange(format!("{:} is out of range for an int", value)))?;
+
+    let instruction = match value {
+        -1 => Instruction::Iconst_m1,
+        0 => Instruction::Iconst_0,
+        1 => Instruction::Iconst_1,
+        2 => Instruction::Iconst_2,
+        3 => Instruction::Iconst_3,
+        4 => Instruction::Iconst_4,
+        5 => Instruction::Iconst_5,
+        v if i8::try_from(v).is_ok() => Instruction::Bipush(v as i8),
+        v if i16::try_from(v).is_ok() => Instruction::Sipush(v as i16),
+        v => ldc(wrap(compilation_context.constant_pool.add_integer(v))?),
+    };
+
+    Ok(vec![instruction])
+}
+
+/// `char` is an integral type, so its constants are loaded exactly like an `int` with the same value
+pub fn from_char_literal(
+    value: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    from_int_literal(value as i64, compilation_context)
+}
+
+pub fn from_long_literal(
+    value: i128,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let value = i64::try_from(value).map_err(|_| CompileError::LiteralOutOfRange(format!("{:} is out of range for a long", value)))?;
+
+    let instruction = match value {
+        0 => Instruction::Lconst_0,
+        1 => Instruction::Lconst_1,
+        v => Instruction::Ldc2_w(wrap(compilation_context.constant_pool.add_long(v))?),
+    };
+
+    Ok(vec![instruction])
+}
+
+pub fn from_float_literal(
+    value: f32,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    if value.is_infinite() {
+        return Err(CompileError::LiteralOutOfRange("floating-point number too large for a float".to_string()));
+    }
+
+    // Compare bit patterns so that -0.0f is not mistaken for fconst_0
+    let instruction = match value.to_bits() {
+        bits if bits == 0f32.to_bits() => Instruction::Fconst_0,
+        bits if bits == 1f32.to_bits() => Instruction::Fconst_1,
+        bits if bits == 2f32.to_bits() => Instruction::Fconst_2,
+        _ => ldc(wrap(compilation_context.constant_pool.add_float(value))?),
+    };
+
+    Ok(vec![instruction])
+}
+
+pub fn from_double_literal(
+    value: f64,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    if value.is_infinite() {
+        return Err(CompileError::LiteralOutOfRange("floating-point number too large for a double".to_string()));
+    }
+
+    let instruction = match value.to_bits() {
+        bits if bits == 0f64.to_bits() => Instruction::Dconst_0,
+        bits if bits == 1f64.to_bits() => Instruction::Dconst_1,
+        _ => Instruction::Ldc2_w(wrap(compilation_context.constant_pool.add_double(value))?),
+    };
+
+    Ok(vec![instruction])
+}
+
+fn ldc(index: u16) -> Instruction {
+    match u8::try_from(index) {
+        Ok(index) => Instruction::Ldc(index),
+        Err(_) => Instruction::Ldc_w(index),
+    }
+}
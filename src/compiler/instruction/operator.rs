@@ -0,0 +1,540 @@
+use crate::ast::expression::{BinaryOperator, Expression, UnaryOperator};
+use crate::compiler::instruction::branch::{skip, ConditionalJump};
+use crate::compiler::instruction::expression::{from_expression, type_of};
+use crate::compiler::instruction::field::{from_field_read, is_final_field, qualified_field_access, store_field, FieldAccess};
+use crate::compiler::instruction::numeric_literal::{from_double_literal, from_float_literal, from_int_literal, from_long_literal};
+use crate::compiler::instruction::string_concatenation::from_string_concatenation;
+use crate::compiler::instruction::variable::from_variable_read;
+use crate::compiler::instruction::{from_assigned_expression, widening};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::local_variables::assigned_on_both;
+use crate::compiler::{inheritance, CompilationContext, CompileError, CompileResult};
+use ristretto_classfile::attributes::Instruction;
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+
+/// The value an increment or decrement leaves on the stack
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum IncrementValue {
+    /// The expression is a statement, so its value is not needed
+    Discarded,
+    /// The value of the variable before it changes, as `x++` has
+    Original,
+    /// The value of the variable after it changes, as `++x` has
+    Updated,
+}
+
+pub fn from_binary(
+    left: &Expression,
+    operator: BinaryOperator,
+    right: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let operand_type = operand_type(left, operator, right, compilation_context)?;
+    if is_condition(operator) {
+        let condition = binary_condition(left, operator, right, false, compilation_context)?;
+        return Ok(from_boolean_value(condition));
+    }
+    if operand_type == JavaType::string() {
+        return from_string_concatenation(left, right, compilation_context);
+    }
+
+    let mut instructions = from_operand(left, &operand_type, compilation_context)?;
+    if is_shift(operator) {
+        // The distance of a shift is promoted on its own, and only ever an int
+        let distance_type = type_of(right, compilation_context)?.promoted();
+        instructions.extend(from_operand(right, &distance_type, compilation_context)?);
+        if distance_type == JavaType::Long {
+            instructions.push(Instruction::L2i);
+        }
+    } else {
+        instructions.extend(from_operand(right, &operand_type, compilation_context)?);
+    }
+    instructions.push(arithmetic_instruction(operator, &operand_type));
+    Ok(instructions)
+}
+
+pub fn type_of_binary(
+    left: &Expression,
+    operator: BinaryOperator,
+    right: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    let operand_type = operand_type(left, operator, right, compilation_context)?;
+    if is_condition(operator) {
+        Ok(JavaType::Boolean)
+    } else {
+        Ok(operand_type)
+    }
+}
+
+/// The type both operands of a binary operator are converted to before it is applied, which is
+/// also the type of its result unless it compares them (JLS §15.17 to §15.24)
+fn operand_type(
+    left: &Expression,
+    operator: BinaryOperator,
+    right: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    let left_type = type_of(left, compilation_context)?;
+    let right_type = type_of(right, compilation_context)?;
+    let are_boolean = left_type == JavaType::Boolean && right_type == JavaType::Boolean;
+    let are_integral = left_type.is_integral() && right_type.is_integral();
+
+    let operand_type = match operator {
+        BinaryOperator::Add if left_type == JavaType::string() || right_type == JavaType::string() => Some(JavaType::string()),
+        BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight | BinaryOperator::UnsignedShiftRight => {
+            are_integral.then(|| left_type.promoted())
+        }
+        BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::BitwiseAnd | BinaryOperator::BitwiseXor | BinaryOperator::BitwiseOr
+            if are_boolean =>
+        {
+            Some(JavaType::Boolean)
+        }
+        BinaryOperator::Equal | BinaryOperator::NotEqual if left_type.is_reference() && right_type.is_reference() => Some(left_type.clone()),
+        BinaryOperator::BitwiseAnd | BinaryOperator::BitwiseXor | BinaryOperator::BitwiseOr => {
+            left_type.promoted_with(&right_type).filter(|_| are_integral)
+        }
+        BinaryOperator::And | BinaryOperator::Or => are_boolean.then_some(JavaType::Boolean),
+        _ => left_type.promoted_with(&right_type),
+    };
+
+    operand_type.ok_or_else(|| CompileError::BadOperandTypes {
+        operator: operator.symbol().to_string(),
+        left: left_type.name(),
+        right: right_type.name(),
+    })
+}
+
+/// Jumps past the end of the code it returns when the condition has the given value. `&&`, `||`,
+/// `!` and comparisons become branches themselves, rather than producing a value to test.
+pub fn from_condition(
+    expression: &Expression,
+    jump_when: bool,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<ConditionalJump> {
+    match expression {
+        Expression::Binary { left, operator, right, .. } if is_condition(*operator) => {
+            binary_condition(left, *operator, right, jump_when, compilation_context)
+        }
+        Expression::Unary { operator: UnaryOperator::Not, operand, .. } => {
+            type_of(expression, compilation_context)?;
+            from_condition(operand, !jump_when, compilation_context)
+        }
+        _ => {
+            let condition_type = type_of(expression, compilation_context)?;
+            if condition_type != JavaType::Boolean {
+                return Err(CompileError::IncompatibleTypes {
+                    expected: JavaType::Boolean.name(),
+                    found: condition_type.name(),
+                });
+            }
+            let mut jump = ConditionalJump::default();
+            jump.extend(from_expression(expression, compilation_context)?);
+            jump.exit(if jump_when { Instruction::Ifne } else { Instruction::Ifeq });
+            Ok(jump)
+        }
+    }
+}
+
+fn binary_condition(
+    left: &Expression,
+    operator: BinaryOperator,
+    right: &Expression,
+    jump_when: bool,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<ConditionalJump> {
+    let operand_type = operand_type(left, operator, right, compilation_context)?;
+
+    if matches!(operator, BinaryOperator::And | BinaryOperator::Or) {
+        // `a && b` is known to be false once `a` is, and `a || b` to be true once `a` is
+        let decided_when = operator == BinaryOperator::Or;
+        let mut jump = from_condition(left, decided_when, compilation_context)?;
+        // The right operand may not be evaluated, so nothing it assigns is known to be afterwards
+        let before_right = compilation_context.locals.assigned();
+        let right_jump = from_condition(right, jump_when, compilation_context)?;
+        compilation_context.locals.set_assigned(&before_right);
+        if decided_when == jump_when {
+            jump.append(right_jump);
+            return Ok(jump);
+        }
+        let mut skipping_right = ConditionalJump::default();
+        skipping_right.extend(jump.to(right_jump.len()));
+        skipping_right.append(right_jump);
+        return Ok(skipping_right);
+    }
+
+    let mut jump = ConditionalJump::default();
+    jump.extend(from_operand(left, &operand_type, compilation_context)?);
+    jump.extend(from_operand(right, &operand_type, compilation_context)?);
+
+    let comparison = if jump_when { operator } else { negated(operator) };
+    // NaN makes every comparison but `!=` false, so it compares as whichever of greater or less
+    // fails the comparison as written
+    let is_less = matches!(operator, BinaryOperator::Less | BinaryOperator::LessEqual);
+    match operand_type {
+        JavaType::Long => {
+            jump.extend([Instruction::Lcmp]);
+            jump.exit(compare_with_zero(comparison));
+        }
+        JavaType::Float => {
+            jump.extend([if is_less { Instruction::Fcmpg } else { Instruction::Fcmpl }]);
+            jump.exit(compare_with_zero(comparison));
+        }
+        JavaType::Double => {
+            jump.extend([if is_less { Instruction::Dcmpg } else { Instruction::Dcmpl }]);
+            jump.exit(compare_with_zero(comparison));
+        }
+        JavaType::Reference(_) | JavaType::Array(_) => {
+            jump.exit(if comparison == BinaryOperator::Equal { Instruction::If_acmpeq } else { Instruction::If_acmpne });
+        }
+        _ => jump.exit(compare_ints(comparison)),
+    }
+    Ok(jump)
+}
+
+/// Leaves `true` or `false` on the stack for a condition compiled to branches
+fn from_boolean_value(condition: ConditionalJump) -> Vec<Instruction> {
+    let mut instructions = condition.to(2);
+    instructions.extend([Instruction::Iconst_1, skip(1), Instruction::Iconst_0]);
+    instructions
+}
+
+pub fn from_unary(
+    operator: UnaryOperator,
+    operand: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let result_type = type_of_unary(operator, operand, compilation_context)?;
+
+    // A negative literal is a constant of its own, which is how `-2147483648` can be an int
+    if operator == UnaryOperator::Minus {
+        match operand {
+            Expression::IntLiteral { value, .. } => return from_int_literal(-value, compilation_context),
+            Expression::LongLiteral { value, .. } => return from_long_literal(-value, compilation_context),
+            Expression::FloatLiteral { value, .. } => return from_float_literal(-value, compilation_context),
+            Expression::DoubleLiteral { value, .. } => return from_double_literal(-value, compilation_context),
+            _ => {}
+        }
+    }
+
+    let mut instructions = from_operand(operand, &result_type, compilation_context)?;
+    match operator {
+        UnaryOperator::Plus => {}
+        UnaryOperator::Minus => {
+            instructions.push(typed(&result_type, Instruction::Ineg, Instruction::Lneg, Instruction::Fneg, Instruction::Dneg));
+        }
+        UnaryOperator::BitwiseComplement => {
+            if result_type == JavaType::Long {
+                instructions.extend(from_long_literal(-1, compilation_context)?);
+                instructions.push(Instruction::Lxor);
+            } else {
+                instructions.extend([Instruction::Iconst_m1, Instruction::Ixor]);
+            }
+        }
+        UnaryOperator::Not => instructions.extend([Instruction::Iconst_1, Instruction::Ixor]),
+    }
+    Ok(instructions)
+}
+
+pub fn type_of_unary(
+    operator: UnaryOperator,
+    operand: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    let operand_type = type_of(operand, compilation_context)?;
+    let is_valid = match operator {
+        UnaryOperator::Plus | UnaryOperator::Minus => operand_type.is_numeric(),
+        UnaryOperator::BitwiseComplement => operand_type.is_integral(),
+        UnaryOperator::Not => operand_type == JavaType::Boolean,
+    };
+    if !is_valid {
+        return Err(CompileError::BadOperandType {
+            operator: operator.symbol().to_string(),
+            operand: operand_type.name(),
+        });
+    }
+    Ok(operand_type.promoted())
+}
+
+/// Adds one to, or takes one from, a local variable or a field, named simply or through a
+/// qualifier. The result is converted back to the type of the variable, as with compound
+/// assignment (JLS §15.14.2).
+pub fn from_increment(
+    operand: &Expression,
+    is_decrement: bool,
+    value: IncrementValue,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let operand_type = type_of_increment(operand, is_decrement, compilation_context)?;
+    let (mut instructions, store, is_static) = match operand {
+        Expression::Variable { name, .. } if compilation_context.locals.get(name).is_some() => {
+            return from_local_increment(name, &operand_type, is_decrement, value, compilation_context);
+        }
+        // Incrementing a final field would assign it a second time
+        Expression::Variable { name, .. } if is_final_field(name, compilation_context) => {
+            return Err(CompileError::assignment_to_final(name));
+        }
+        Expression::Variable { name, .. } => {
+            let store = store_field(name, compilation_context)?;
+            let is_static = matches!(store, Instruction::Putstatic(_));
+            let mut instructions = from_field_read(name, compilation_context)?;
+            if !is_static {
+                // Keep `this` to store the field back into
+                instructions.insert(1, Instruction::Dup);
+            }
+            (instructions, store, is_static)
+        }
+        Expression::ChildIdentifier { parent, name, .. } => match qualified_field_access(parent, name, compilation_context)? {
+            FieldAccess::Field { is_final: true, .. } | FieldAccess::ArrayLength(_) => return Err(CompileError::assignment_to_final(name)),
+            FieldAccess::Field { mut object, field_ref, is_static: true, .. } => {
+                object.push(Instruction::Getstatic(field_ref));
+                (object, Instruction::Putstatic(field_ref), true)
+            }
+            FieldAccess::Field { mut object, field_ref, is_static: false, .. } => {
+                object.extend([Instruction::Dup, Instruction::Getfield(field_ref)]);
+                (object, Instruction::Putfield(field_ref), false)
+            }
+        },
+        _ => return Err(CompileError::Unsupported("increment of an expression that is not a variable".to_string())),
+    };
+    let duplicate = || match (operand_type.slots(), is_static) {
+        (2, true) => Instruction::Dup2,
+        (2, false) => Instruction::Dup2_x1,
+        (_, true) => Instruction::Dup,
+        (_, false) => Instruction::Dup_x1,
+    };
+
+    if value == IncrementValue::Original {
+        instructions.push(duplicate());
+    }
+    instructions.extend(one_added(&operand_type, is_decrement));
+    if value == IncrementValue::Updated {
+        instructions.push(duplicate());
+    }
+    instructions.push(store);
+    Ok(instructions)
+}
+
+/// An int local variable is incremented in place with `iinc`, and any other is loaded, changed
+/// and stored back
+fn from_local_increment(
+    name: &str,
+    operand_type: &JavaType,
+    is_decrement: bool,
+    value: IncrementValue,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let load = from_variable_read(name, compilation_context)?;
+    compilation_context.locals.assign(name)?;
+    let slot = compilation_context.locals.get(name).map(|variable| variable.slot()).unwrap_or_default();
+
+    let mut instructions = vec![];
+    if *operand_type == JavaType::Int {
+        let increment = if is_decrement { -1 } else { 1 };
+        if value == IncrementValue::Original {
+            instructions.extend(load.clone());
+        }
+        instructions.push(match u8::try_from(slot) {
+            Ok(slot) => Instruction::Iinc(slot, increment),
+            Err(_) => Instruction::Iinc_w(slot, increment as i16),
+        });
+        if value == IncrementValue::Updated {
+            instructions.extend(load);
+        }
+        return Ok(instructions);
+    }
+
+    let duplicate = if operand_type.slots() == 2 { Instruction::Dup2 } else { Instruction::Dup };
+    instructions.extend(load);
+    if value == IncrementValue::Original {
+        instructions.push(duplicate.clone());
+    }
+    instructions.extend(one_added(operand_type, is_decrement));
+    if value == IncrementValue::Updated {
+        instructions.push(duplicate);
+    }
+    instructions.push(operand_type.store_instruction(slot));
+    Ok(instructions)
+}
+
+/// Adds one to, or takes one from, the value on top of the stack, keeping it the same type
+fn one_added(operand_type: &JavaType, is_decrement: bool) -> Vec<Instruction> {
+    let promoted_type = operand_type.promoted();
+    let mut instructions = vec![
+        typed(&promoted_type, Instruction::Iconst_1, Instruction::Lconst_1, Instruction::Fconst_1, Instruction::Dconst_1),
+        if is_decrement {
+            typed(&promoted_type, Instruction::Isub, Instruction::Lsub, Instruction::Fsub, Instruction::Dsub)
+        } else {
+            typed(&promoted_type, Instruction::Iadd, Instruction::Ladd, Instruction::Fadd, Instruction::Dadd)
+        },
+    ];
+    match operand_type {
+        JavaType::Byte => instructions.push(Instruction::I2b),
+        JavaType::Short => instructions.push(Instruction::I2s),
+        JavaType::Char => instructions.push(Instruction::I2c),
+        _ => {}
+    }
+    instructions
+}
+
+pub fn type_of_increment(operand: &Expression, is_decrement: bool, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let operand_type = type_of(operand, compilation_context)?;
+    if !operand_type.is_numeric() {
+        return Err(CompileError::BadOperandType {
+            operator: if is_decrement { "--" } else { "++" }.to_string(),
+            operand: operand_type.name(),
+        });
+    }
+    Ok(operand_type)
+}
+
+pub fn from_conditional(
+    condition: &Expression,
+    when_true: &Expression,
+    when_false: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let result_type = type_of_conditional(when_true, when_false, compilation_context)?;
+    let condition = from_condition(condition, false, compilation_context)?;
+    let after_condition = compilation_context.locals.assigned();
+    let when_true = from_assigned_expression(when_true, &result_type, compilation_context)?;
+    let assigned_when_true = compilation_context.locals.assigned();
+    compilation_context.locals.set_assigned(&after_condition);
+    let when_false = from_assigned_expression(when_false, &result_type, compilation_context)?;
+    if let Some(assigned) = assigned_on_both(Some(assigned_when_true), Some(compilation_context.locals.assigned())) {
+        compilation_context.locals.set_assigned(&assigned);
+    }
+
+    let mut instructions = condition.to(when_true.len() + 1);
+    instructions.extend(when_true);
+    instructions.push(skip(when_false.len()));
+    instructions.extend(when_false);
+    Ok(instructions)
+}
+
+/// Numeric operands are promoted to a common type, and of two classes the result is the one the
+/// other extends, or else `Object` (JLS §15.25)
+pub fn type_of_conditional(
+    when_true: &Expression,
+    when_false: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<JavaType> {
+    let true_type = type_of(when_true, compilation_context)?;
+    let false_type = type_of(when_false, compilation_context)?;
+    if true_type == false_type {
+        return Ok(true_type);
+    }
+    if let Some(promoted_type) = true_type.promoted_with(&false_type) {
+        return Ok(promoted_type);
+    }
+
+    match (&true_type, &false_type) {
+        (JavaType::Reference(true_class), JavaType::Reference(false_class)) => {
+            let (true_class, false_class) = (true_class.replace('/', "."), false_class.replace('/', "."));
+            if inheritance::is_subtype(&false_class, &true_class, &mut compilation_context.class_loader) {
+                Ok(true_type)
+            } else if inheritance::is_subtype(&true_class, &false_class, &mut compilation_context.class_loader) {
+                Ok(false_type)
+            } else {
+                Ok(JavaType::Reference(JAVA_LANG_OBJECT.to_string()))
+            }
+        }
+        _ if true_type.is_reference() && false_type.is_reference() => Ok(JavaType::Reference(JAVA_LANG_OBJECT.to_string())),
+        _ => Err(CompileError::IncompatibleTypes {
+            expected: true_type.name(),
+            found: false_type.name(),
+        }),
+    }
+}
+
+/// Leaves the value of an operand on the stack, widened to the type its operator works on
+fn from_operand(operand: &Expression, operand_type: &JavaType, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let expression_type = type_of(operand, compilation_context)?;
+    let mut instructions = from_expression(operand, compilation_context)?;
+    instructions.extend(widening(&expression_type, operand_type));
+    Ok(instructions)
+}
+
+fn arithmetic_instruction(operator: BinaryOperator, operand_type: &JavaType) -> Instruction {
+    match operator {
+        BinaryOperator::Multiply => typed(operand_type, Instruction::Imul, Instruction::Lmul, Instruction::Fmul, Instruction::Dmul),
+        BinaryOperator::Divide => typed(operand_type, Instruction::Idiv, Instruction::Ldiv, Instruction::Fdiv, Instruction::Ddiv),
+        BinaryOperator::Remainder => typed(operand_type, Instruction::Irem, Instruction::Lrem, Instruction::Frem, Instruction::Drem),
+        BinaryOperator::Add => typed(operand_type, Instruction::Iadd, Instruction::Ladd, Instruction::Fadd, Instruction::Dadd),
+        BinaryOperator::Subtract => typed(operand_type, Instruction::Isub, Instruction::Lsub, Instruction::Fsub, Instruction::Dsub),
+        BinaryOperator::ShiftLeft => integral(operand_type, Instruction::Ishl, Instruction::Lshl),
+        BinaryOperator::ShiftRight => integral(operand_type, Instruction::Ishr, Instruction::Lshr),
+        BinaryOperator::UnsignedShiftRight => integral(operand_type, Instruction::Iushr, Instruction::Lushr),
+        BinaryOperator::BitwiseAnd => integral(operand_type, Instruction::Iand, Instruction::Land),
+        BinaryOperator::BitwiseXor => integral(operand_type, Instruction::Ixor, Instruction::Lxor),
+        _ => integral(operand_type, Instruction::Ior, Instruction::Lor),
+    }
+}
+
+/// The instruction for a value of the type, where `int` stands for the types narrower than it
+fn typed(java_type: &JavaType, int: Instruction, long: Instruction, float: Instruction, double: Instruction) -> Instruction {
+    match java_type {
+        JavaType::Long => long,
+        JavaType::Float => float,
+        JavaType::Double => double,
+        _ => int,
+    }
+}
+
+fn integral(java_type: &JavaType, int: Instruction, long: Instruction) -> Instruction {
+    if *java_type == JavaType::Long { long } else { int }
+}
+
+/// Whether the operator results in a boolean worked out with branches
+fn is_condition(operator: BinaryOperator) -> bool {
+    matches!(
+        operator,
+        BinaryOperator::Less
+            | BinaryOperator::LessEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual
+            | BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::And
+            | BinaryOperator::Or
+    )
+}
+
+fn is_shift(operator: BinaryOperator) -> bool {
+    matches!(operator, BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight | BinaryOperator::UnsignedShiftRight)
+}
+
+fn negated(comparison: BinaryOperator) -> BinaryOperator {
+    match comparison {
+        BinaryOperator::Less => BinaryOperator::GreaterEqual,
+        BinaryOperator::LessEqual => BinaryOperator::Greater,
+        BinaryOperator::Greater => BinaryOperator::LessEqual,
+        BinaryOperator::GreaterEqual => BinaryOperator::Less,
+        BinaryOperator::Equal => BinaryOperator::NotEqual,
+        _ => BinaryOperator::Equal,
+    }
+}
+
+/// Compares the result of `lcmp`, `fcmpl` or the like with zero
+fn compare_with_zero(comparison: BinaryOperator) -> fn(u16) -> Instruction {
+    match comparison {
+        BinaryOperator::Less => Instruction::Iflt,
+        BinaryOperator::LessEqual => Instruction::Ifle,
+        BinaryOperator::Greater => Instruction::Ifgt,
+        BinaryOperator::GreaterEqual => Instruction::Ifge,
+        BinaryOperator::Equal => Instruction::Ifeq,
+        _ => Instruction::Ifne,
+    }
+}
+
+fn compare_ints(comparison: BinaryOperator) -> fn(u16) -> Instruction {
+    match comparison {
+        BinaryOperator::Less => Instruction::If_icmplt,
+        BinaryOperator::LessEqual => Instruction::If_icmple,
+        BinaryOperator::Greater => Instruction::If_icmpgt,
+        BinaryOperator::GreaterEqual => Instruction::If_icmpge,
+        BinaryOperator::Equal => Instruction::If_icmpeq,
+        _ => Instruction::If_icmpne,
+    }
+}
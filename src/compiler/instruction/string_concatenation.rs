@@ -0,0 +1,77 @@
+use crate::ast::expression::{BinaryOperator, Expression};
+use crate::compiler::instruction::expression::{from_expression, type_of};
+use crate::compiler::instruction::string_literal::from_string_literal;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::{wrap, CompilationContext, CompileResult};
+use ristretto_classfile::attributes::Instruction;
+use ristretto_classfile::ReferenceKind;
+
+const STRING_CONCAT_FACTORY: &str = "java/lang/invoke/StringConcatFactory";
+const BOOTSTRAP_DESCRIPTOR: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;\
+    Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite;";
+/// Marks where an argument goes in the recipe `makeConcatWithConstants` is given
+const ARGUMENT_TAG: char = '\u{1}';
+const CONSTANT_TAG: char = '\u{2}';
+
+/// Joins the operands of a chain of `+` into a string as javac does, with `invokedynamic` and
+/// `StringConcatFactory.makeConcatWithConstants`. String literals go into the recipe, and the
+/// values of the other operands are passed as they are.
+pub fn from_string_concatenation(
+    left: &Expression,
+    right: &Expression,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let mut operands = vec![];
+    concatenated_operands(left, &mut operands, compilation_context)?;
+    concatenated_operands(right, &mut operands, compilation_context)?;
+
+    let mut recipe = String::new();
+    let mut argument_descriptors = String::new();
+    let mut instructions = vec![];
+    for operand in operands {
+        match operand {
+            Expression::StringLiteral { value, .. } if !value.contains([ARGUMENT_TAG, CONSTANT_TAG]) => recipe.push_str(value),
+            _ => {
+                argument_descriptors.push_str(&type_of(operand, compilation_context)?.descriptor());
+                instructions.extend(from_expression(operand, compilation_context)?);
+                recipe.push(ARGUMENT_TAG);
+            }
+        }
+    }
+    if instructions.is_empty() {
+        return from_string_literal(&recipe, compilation_context);
+    }
+
+    let bootstrap_method = make_concat_with_constants(recipe, compilation_context)?;
+    let descriptor = format!("({}){}", argument_descriptors, JavaType::string().descriptor());
+    let call_site = wrap(compilation_context.constant_pool.add_invoke_dynamic(bootstrap_method, "makeConcatWithConstants", &descriptor))?;
+    instructions.push(Instruction::Invokedynamic(call_site));
+    Ok(instructions)
+}
+
+/// The operands of an expression that is joined into the string, where a nested `+` of strings
+/// contributes its own operands
+fn concatenated_operands<'e, 'a>(
+    expression: &'e Expression<'a>,
+    operands: &mut Vec<&'e Expression<'a>>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<()> {
+    match expression {
+        Expression::Binary { left, operator: BinaryOperator::Add, right, .. } if type_of(expression, compilation_context)? == JavaType::string() => {
+            concatenated_operands(left, operands, compilation_context)?;
+            concatenated_operands(right, operands, compilation_context)
+        }
+        _ => {
+            operands.push(expression);
+            Ok(())
+        }
+    }
+}
+
+fn make_concat_with_constants(recipe: String, compilation_context: &mut CompilationContext) -> CompileResult<u16> {
+    let factory = compilation_context.add_class(STRING_CONCAT_FACTORY)?;
+    let bootstrap_ref = wrap(compilation_context.constant_pool.add_method_ref(factory, "makeConcatWithConstants", BOOTSTRAP_DESCRIPTOR))?;
+    let bootstrap_handle = wrap(compilation_context.constant_pool.add_method_handle(ReferenceKind::InvokeStatic, bootstrap_ref))?;
+    let recipe = wrap(compilation_context.constant_pool.add_string(recipe))?;
+    Ok(compilation_context.add_bootstrap_method(bootstrap_handle, vec![recipe]))
+}
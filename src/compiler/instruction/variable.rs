@@ -0,0 +1,26 @@
+use crate::compiler::instruction::field::{from_field_read, is_field, type_of_field};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::{CompilationContext, CompileError, CompileResult};
+use ristretto_classfile::attributes::Instruction;
+
+/// Reads a variable by its simple name: a parameter or local variable in scope, which hides any
+/// field of the same name, or otherwise a field (JLS §6.4.1)
+pub fn from_variable_read(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    match compilation_context.locals.get(name) {
+        Some(variable) if !variable.is_assigned() => Err(CompileError::uninitialized_variable(name)),
+        Some(variable) => Ok(vec![variable.variable_type().load_instruction(variable.slot())]),
+        None => from_field_read(name, compilation_context),
+    }
+}
+
+pub fn type_of_variable(name: &str, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    match compilation_context.locals.get(name) {
+        Some(variable) => Ok(variable.variable_type().clone()),
+        None => type_of_field(name, compilation_context),
+    }
+}
+
+/// Whether a simple name refers to a variable in scope, rather than to a class
+pub fn is_variable(name: &str, compilation_context: &mut CompilationContext) -> bool {
+    compilation_context.locals.get(name).is_some() || is_field(name, compilation_context)
+}
@@ -1,7 +1,124 @@
-use ristretto_classfile::attributes::Instruction;
+use crate::ast::class::AstType;
 use crate::ast::expression::Expression;
-use crate::compiler::CompileResult;
+use crate::compiler::descriptor::field_descriptor;
+use crate::compiler::instruction::expression::type_of;
+use crate::compiler::instruction::field::{check_field_assignable, qualified_field_access, store_field, type_of_qualified_field, FieldAccess};
+use crate::compiler::instruction::from_assigned_expression;
+use crate::compiler::instruction::variable::type_of_variable;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::{CompilationContext, CompileError, CompileResult};
+use ristretto_classfile::attributes::Instruction;
+
+/// Declares a local variable, storing the value of its initializer if it has one. The variable is
+/// in scope in its own initializer, where it has not yet been assigned (JLS §6.3). The type of a
+/// `var` is that of its initializer (JLS §14.4.1).
+pub fn from_variable_assignment(
+    name: &str,
+    var_type: &AstType,
+    is_final: bool,
+    value: &Option<Expression>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let variable_type = match value {
+        Some(initializer) if var_type.name() == "var" && !var_type.is_array() => inferred_type(initializer, compilation_context)?,
+        _ => declared_type(var_type, compilation_context)?,
+    };
+    let slot = compilation_context.locals.declare(name, variable_type.clone(), is_final, false)?;
+
+    let Some(initializer) = value else {
+        return Ok(vec![]);
+    };
+    let mut instructions = from_assigned_expression(initializer, &variable_type, compilation_context)?;
+    compilation_context.locals.assign(name)?;
+    instructions.push(variable_type.store_instruction(slot));
+    Ok(instructions)
+}
+
+/// Assigns a value to a local variable, or else to a field of the class being compiled. The value
+/// of the assignment is that of the variable afterwards, and is left on the stack when it is used.
+pub fn from_assignment(
+    name: &str,
+    value: &Expression,
+    is_value_used: bool,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let variable_type = type_of_variable(name, compilation_context)?;
+    if let Some(slot) = compilation_context.locals.get(name).map(|variable| variable.slot()) {
+        let mut instructions = from_assigned_expression(value, &variable_type, compilation_context)?;
+        compilation_context.locals.assign(name)?;
+        if is_value_used {
+            instructions.push(if variable_type.slots() == 2 { Instruction::Dup2 } else { Instruction::Dup });
+        }
+        instructions.push(variable_type.store_instruction(slot));
+        return Ok(instructions);
+    }
+
+    check_field_assignable(name, compilation_context)?;
+    compilation_context.locals.assign_field(name)?;
+    let store = store_field(name, compilation_context)?;
+    let is_static = matches!(store, Instruction::Putstatic(_));
+    let mut instructions = if is_static { vec![] } else { vec![Instruction::Aload_0] };
+    instructions.extend(from_assigned_expression(value, &variable_type, compilation_context)?);
+    if is_value_used {
+        instructions.push(match (variable_type.slots(), is_static) {
+            (2, true) => Instruction::Dup2,
+            (2, false) => Instruction::Dup2_x1,
+            (_, true) => Instruction::Dup,
+            (_, false) => Instruction::Dup_x1,
+        });
+    }
+    instructions.push(store);
+    Ok(instructions)
+}
+
+/// Assigns a value to a field named through a qualifier, as in `this.x = x` or `Counter.total = 0`.
+/// A final field may only be assigned as `this.x`, while its own class initializes it.
+pub fn from_field_assignment(
+    qualifier: &Expression,
+    name: &str,
+    value: &Expression,
+    is_value_used: bool,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let field_type = type_of_qualified_field(qualifier, name, compilation_context)?;
+    let FieldAccess::Field { object: mut instructions, field_ref, is_static, is_final } = qualified_field_access(qualifier, name, compilation_context)? else {
+        return Err(CompileError::assignment_to_final(name));
+    };
+    if is_final {
+        match qualifier {
+            Expression::This { .. } if compilation_context.fields.contains_key(name) => {
+                check_field_assignable(name, compilation_context)?;
+                compilation_context.locals.assign_field(name)?;
+            }
+            _ => return Err(CompileError::assignment_to_final(name)),
+        }
+    }
+
+    instructions.extend(from_assigned_expression(value, &field_type, compilation_context)?);
+    if is_value_used {
+        instructions.push(match (field_type.slots(), is_static) {
+            (2, true) => Instruction::Dup2,
+            (2, false) => Instruction::Dup2_x1,
+            (_, true) => Instruction::Dup,
+            (_, false) => Instruction::Dup_x1,
+        });
+    }
+    instructions.push(if is_static { Instruction::Putstatic(field_ref) } else { Instruction::Putfield(field_ref) });
+    Ok(instructions)
+}
+
+pub fn declared_type(var_type: &AstType, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let descriptor = field_descriptor(*var_type, compilation_context)?;
+    JavaType::from_descriptor(&descriptor).ok_or_else(|| CompileError::UnknownClass(var_type.name().to_string()))
+}
 
-pub fn from_variable_assignment(name: &str, var_type: &str, is_final: bool, value: &Option<Expression>) -> CompileResult<Vec<Instruction>> {
-    panic!("Not implemented")
-}
\ No newline at end of file
+fn inferred_type(initializer: &Expression, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let initializer_type = type_of(initializer, compilation_context)?;
+    if initializer_type == JavaType::Void {
+        return Err(CompileError::IncompatibleTypes {
+            expected: "var".to_string(),
+            found: initializer_type.name(),
+        });
+    }
+    Ok(initializer_type)
+}
@@ -0,0 +1,236 @@
+use ristretto_classfile::attributes::Instruction;
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+const JAVA_LANG_STRING: &str = "java/lang/String";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JavaType {
+    Boolean,
+    Byte,
+    Char,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+    Void,
+    Reference(String),
+    Array(Box<JavaType>),
+}
+
+impl JavaType {
+    pub fn string() -> Self {
+        JavaType::Reference(JAVA_LANG_STRING.to_string())
+    }
+
+    pub fn from_descriptor(descriptor: &str) -> Option<JavaType> {
+        match Self::parse_field_descriptor(descriptor) {
+            Some((java_type, "")) => Some(java_type),
+            _ => None,
+        }
+    }
+
+    /// Splits a method descriptor such as `(ILjava/lang/String;)V` into its parameter and return types.
+    pub fn from_method_descriptor(descriptor: &str) -> Option<(Vec<JavaType>, JavaType)> {
+        let mut remaining = descriptor.strip_prefix('(')?;
+        let mut parameters = vec![];
+        while !remaining.starts_with(')') {
+            let (parameter, rest) = Self::parse_field_descriptor(remaining)?;
+            parameters.push(parameter);
+            remaining = rest;
+        }
+        let return_type = Self::from_descriptor(&remaining[1..])?;
+        Some((parameters, return_type))
+    }
+
+    fn parse_field_descriptor(descriptor: &str) -> Option<(JavaType, &str)> {
+        let first = descriptor.chars().next()?;
+        let rest = &descriptor[1..];
+        let java_type = match first {
+            'Z' => JavaType::Boolean,
+            'B' => JavaType::Byte,
+            'C' => JavaType::Char,
+            'S' => JavaType::Short,
+            'I' => JavaType::Int,
+            'J' => JavaType::Long,
+            'F' => JavaType::Float,
+            'D' => JavaType::Double,
+            'V' => JavaType::Void,
+            'L' => {
+                let end = rest.find(';')?;
+                return Some((JavaType::Reference(rest[..end].to_string()), &rest[end + 1..]));
+            }
+            '[' => {
+                let (component, rest) = Self::parse_field_descriptor(rest)?;
+                return Some((JavaType::Array(Box::new(component)), rest));
+            }
+            _ => return None,
+        };
+        Some((java_type, rest))
+    }
+
+    /// The type as it is written in source, such as `int[]` or `java.lang.String`
+    pub fn name(&self) -> String {
+        match self {
+            JavaType::Boolean => "boolean".to_string(),
+            JavaType::Byte => "byte".to_string(),
+            JavaType::Char => "char".to_string(),
+            JavaType::Short => "short".to_string(),
+            JavaType::Int => "int".to_string(),
+            JavaType::Long => "long".to_string(),
+            JavaType::Float => "float".to_string(),
+            JavaType::Double => "double".to_string(),
+            JavaType::Void => "void".to_string(),
+            JavaType::Reference(class) => class.replace('/', "."),
+            JavaType::Array(component) => format!("{}[]", component.name()),
+        }
+    }
+
+    /// The descriptor of the type, such as `[I` or `Ljava/lang/String;` (JVMS §4.3.2)
+    pub fn descriptor(&self) -> String {
+        match self {
+            JavaType::Boolean => "Z".to_string(),
+            JavaType::Byte => "B".to_string(),
+            JavaType::Char => "C".to_string(),
+            JavaType::Short => "S".to_string(),
+            JavaType::Int => "I".to_string(),
+            JavaType::Long => "J".to_string(),
+            JavaType::Float => "F".to_string(),
+            JavaType::Double => "D".to_string(),
+            JavaType::Void => "V".to_string(),
+            JavaType::Reference(class) => format!("L{};", class),
+            JavaType::Array(component) => format!("[{}", component.descriptor()),
+        }
+    }
+
+    /// The local variable slots, or operand stack entries, a value of the type takes
+    pub fn slots(&self) -> u16 {
+        match self {
+            JavaType::Long | JavaType::Double => 2,
+            JavaType::Void => 0,
+            _ => 1,
+        }
+    }
+
+    /// Loads a local variable of the type, such as a parameter, onto the stack, with the shortest
+    /// form of the instruction for its slot
+    pub fn load_instruction(&self, slot: u16) -> Instruction {
+        use Instruction::*;
+        let forms = match self {
+            JavaType::Boolean | JavaType::Byte | JavaType::Char | JavaType::Short | JavaType::Int => {
+                [Iload_0, Iload_1, Iload_2, Iload_3, Iload(slot as u8), Iload_w(slot)]
+            }
+            JavaType::Long => [Lload_0, Lload_1, Lload_2, Lload_3, Lload(slot as u8), Lload_w(slot)],
+            JavaType::Float => [Fload_0, Fload_1, Fload_2, Fload_3, Fload(slot as u8), Fload_w(slot)],
+            JavaType::Double => [Dload_0, Dload_1, Dload_2, Dload_3, Dload(slot as u8), Dload_w(slot)],
+            _ => [Aload_0, Aload_1, Aload_2, Aload_3, Aload(slot as u8), Aload_w(slot)],
+        };
+        local_variable_form(forms, slot)
+    }
+
+    /// Stores the value on top of the stack into a local variable of the type
+    pub fn store_instruction(&self, slot: u16) -> Instruction {
+        use Instruction::*;
+        let forms = match self {
+            JavaType::Boolean | JavaType::Byte | JavaType::Char | JavaType::Short | JavaType::Int => {
+                [Istore_0, Istore_1, Istore_2, Istore_3, Istore(slot as u8), Istore_w(slot)]
+            }
+            JavaType::Long => [Lstore_0, Lstore_1, Lstore_2, Lstore_3, Lstore(slot as u8), Lstore_w(slot)],
+            JavaType::Float => [Fstore_0, Fstore_1, Fstore_2, Fstore_3, Fstore(slot as u8), Fstore_w(slot)],
+            JavaType::Double => [Dstore_0, Dstore_1, Dstore_2, Dstore_3, Dstore(slot as u8), Dstore_w(slot)],
+            _ => [Astore_0, Astore_1, Astore_2, Astore_3, Astore(slot as u8), Astore_w(slot)],
+        };
+        local_variable_form(forms, slot)
+    }
+
+    /// Returns a value of the type from the current method
+    pub fn return_instruction(&self) -> Instruction {
+        match self {
+            JavaType::Boolean | JavaType::Byte | JavaType::Char | JavaType::Short | JavaType::Int => Instruction::Ireturn,
+            JavaType::Long => Instruction::Lreturn,
+            JavaType::Float => Instruction::Freturn,
+            JavaType::Double => Instruction::Dreturn,
+            JavaType::Void => Instruction::Return,
+            _ => Instruction::Areturn,
+        }
+    }
+
+    /// Loads an element of an array of the type, from the array and index on the stack
+    pub fn array_load_instruction(&self) -> Instruction {
+        match self {
+            JavaType::Boolean | JavaType::Byte => Instruction::Baload,
+            JavaType::Char => Instruction::Caload,
+            JavaType::Short => Instruction::Saload,
+            JavaType::Int => Instruction::Iaload,
+            JavaType::Long => Instruction::Laload,
+            JavaType::Float => Instruction::Faload,
+            JavaType::Double => Instruction::Daload,
+            _ => Instruction::Aaload,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integral() || matches!(self, JavaType::Float | JavaType::Double)
+    }
+
+    pub fn is_integral(&self) -> bool {
+        matches!(self, JavaType::Byte | JavaType::Short | JavaType::Char | JavaType::Int | JavaType::Long)
+    }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(self, JavaType::Reference(_) | JavaType::Array(_))
+    }
+
+    /// The type an operand of a numeric operator is widened to on its own (JLS §5.6)
+    pub fn promoted(&self) -> JavaType {
+        match self {
+            JavaType::Byte | JavaType::Short | JavaType::Char => JavaType::Int,
+            java_type => java_type.clone(),
+        }
+    }
+
+    /// The type both operands of a numeric operator are widened to, if both are numeric (JLS §5.6)
+    pub fn promoted_with(&self, other: &JavaType) -> Option<JavaType> {
+        if !self.is_numeric() || !other.is_numeric() {
+            return None;
+        }
+        let promoted = match (self.promoted(), other.promoted()) {
+            (JavaType::Double, _) | (_, JavaType::Double) => JavaType::Double,
+            (JavaType::Float, _) | (_, JavaType::Float) => JavaType::Float,
+            (JavaType::Long, _) | (_, JavaType::Long) => JavaType::Long,
+            _ => JavaType::Int,
+        };
+        Some(promoted)
+    }
+
+    /// Whether a value of this type can be passed where `target` is expected, following the
+    /// identity, widening primitive and widening reference conversions of JLS §5.3.
+    pub fn is_assignable_to(&self, target: &JavaType) -> bool {
+        if self == target {
+            return true;
+        }
+        match (self, target) {
+            (JavaType::Reference(_) | JavaType::Array(_), JavaType::Reference(class)) => class == JAVA_LANG_OBJECT,
+            (JavaType::Byte, JavaType::Short | JavaType::Int | JavaType::Long | JavaType::Float | JavaType::Double) => true,
+            (JavaType::Short | JavaType::Char, JavaType::Int | JavaType::Long | JavaType::Float | JavaType::Double) => true,
+            (JavaType::Int, JavaType::Long | JavaType::Float | JavaType::Double) => true,
+            (JavaType::Long, JavaType::Float | JavaType::Double) => true,
+            (JavaType::Float, JavaType::Double) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Picks from the forms of a local variable instruction: those for slots 0 to 3, the one taking
+/// a byte operand, and the `wide` one for the slots beyond
+fn local_variable_form(forms: [Instruction; 6], slot: u16) -> Instruction {
+    let [slot_0, slot_1, slot_2, slot_3, byte_form, wide_form] = forms;
+    match slot {
+        0 => slot_0,
+        1 => slot_1,
+        2 => slot_2,
+        3 => slot_3,
+        4..=255 => byte_form,
+        _ => wide_form,
+    }
+}
@@ -0,0 +1,121 @@
+use crate::compiler::local_variables::assigned_on_both;
+use crate::compiler::result::{CompileError, CompileResult};
+
+/// A statement that `break` can leave: a loop, or any statement with a label. Only a loop can be
+/// gone on with by `continue`.
+#[derive(Debug)]
+pub struct JumpTarget {
+    label: Option<String>,
+    is_loop: bool,
+    /// How deeply the statement is nested in the others being compiled, which numbers its labels
+    depth: u16,
+    /// The local variables assigned on every `break` out of the statement, if any reaches it
+    break_assigned: Option<Vec<bool>>,
+    /// The local variables assigned on every `continue` of the loop, if any reaches it
+    continue_assigned: Option<Vec<bool>>,
+}
+
+impl JumpTarget {
+    /// The label `break` jumps to, at the end of the statement
+    pub fn break_label(&self) -> u16 {
+        self.depth * 2
+    }
+
+    /// The label `continue` jumps to, where the loop goes on to its next iteration
+    pub fn continue_label(&self) -> u16 {
+        self.depth * 2 + 1
+    }
+
+    pub fn break_assigned(&self) -> Option<&Vec<bool>> {
+        self.break_assigned.as_ref()
+    }
+
+    pub fn continue_assigned(&self) -> Option<&Vec<bool>> {
+        self.continue_assigned.as_ref()
+    }
+}
+
+/// The statements enclosing the code being compiled that `break` and `continue` can jump out of,
+/// innermost last, along with whether the code can be reached at all (JLS §14.22). Jumps out of
+/// a statement go to one of its labels until the statement is complete, and where they land is
+/// known.
+#[derive(Debug)]
+pub struct JumpTargets {
+    targets: Vec<JumpTarget>,
+    is_reachable: bool,
+}
+
+impl Default for JumpTargets {
+    fn default() -> Self {
+        Self { targets: vec![], is_reachable: true }
+    }
+}
+
+impl JumpTargets {
+    pub fn is_reachable(&self) -> bool {
+        self.is_reachable
+    }
+
+    pub fn set_reachable(&mut self, is_reachable: bool) {
+        self.is_reachable = is_reachable;
+    }
+
+    /// Starts a statement that can be jumped out of. A label cannot be used again inside the
+    /// statement it labels (JLS §14.7).
+    pub fn enter(&mut self, label: Option<&str>, is_loop: bool) -> CompileResult<()> {
+        if let Some(label) = label
+            && self.targets.iter().any(|target| target.label.as_deref() == Some(label))
+        {
+            return Err(CompileError::DuplicateLabel(label.to_string()));
+        }
+        self.targets.push(JumpTarget {
+            label: label.map(str::to_string),
+            is_loop,
+            depth: self.targets.len() as u16,
+            break_assigned: None,
+            continue_assigned: None,
+        });
+        Ok(())
+    }
+
+    /// Ends the innermost statement
+    pub fn exit(&mut self) -> Option<JumpTarget> {
+        self.targets.pop()
+    }
+
+    /// The label a `break` jumps to: the end of the statement with its label, or without one the
+    /// innermost loop. The local variables assigned where it is are kept for the code after it.
+    pub fn break_to(&mut self, label: Option<&str>, assigned: Vec<bool>) -> CompileResult<u16> {
+        let target = match label {
+            Some(label) => self.labeled(label)?,
+            None => self.innermost_loop().ok_or(CompileError::BreakOutsideLoop)?,
+        };
+        target.break_assigned = assigned_on_both(target.break_assigned.take(), Some(assigned));
+        Ok(target.break_label())
+    }
+
+    /// The label a `continue` jumps to, in the loop with its label or else the innermost loop
+    pub fn continue_to(&mut self, label: Option<&str>, assigned: Vec<bool>) -> CompileResult<u16> {
+        let target = match label {
+            Some(label) => self.labeled(label)?,
+            None => self.innermost_loop().ok_or(CompileError::ContinueOutsideLoop)?,
+        };
+        if !target.is_loop {
+            return Err(CompileError::NotALoopLabel(label.unwrap_or_default().to_string()));
+        }
+        target.continue_assigned = assigned_on_both(target.continue_assigned.take(), Some(assigned));
+        Ok(target.continue_label())
+    }
+
+    fn labeled(&mut self, label: &str) -> CompileResult<&mut JumpTarget> {
+        self.targets
+            .iter_mut()
+            .rev()
+            .find(|target| target.label.as_deref() == Some(label))
+            .ok_or_else(|| CompileError::UndefinedLabel(label.to_string()))
+    }
+
+    fn innermost_loop(&mut self) -> Option<&mut JumpTarget> {
+        self.targets.iter_mut().rev().find(|target| target.is_loop)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::{CompileError, CompileResult};
+
+/// A parameter or local variable, held in one slot of the frame, or two for a long or double
+#[derive(Clone, Debug)]
+pub struct LocalVariable {
+    slot: u16,
+    variable_type: JavaType,
+    is_final: bool,
+    /// Whether it has been given a value, which it must have been before it is read (JLS §16)
+    is_assigned: bool,
+    /// Whether it has been given a value after its first, so that it is not effectively final
+    is_reassigned: bool,
+    /// Whether a local or anonymous class uses it, after which it cannot be given another value
+    is_captured: bool,
+}
+
+impl LocalVariable {
+    pub fn slot(&self) -> u16 {
+        self.slot
+    }
+
+    pub fn variable_type(&self) -> &JavaType {
+        &self.variable_type
+    }
+
+    pub fn is_assigned(&self) -> bool {
+        self.is_assigned
+    }
+}
+
+/// The local variables in scope in the code of a method, in the order they were declared. Each
+/// takes the slots after those of the variable before it, so that the slots of the variables of
+/// a block are free to be reused once it ends.
+#[derive(Clone, Debug, Default)]
+pub struct LocalVariables {
+    variables: Vec<(String, LocalVariable)>,
+    /// The number of variables in scope when each of the blocks being compiled began
+    blocks: Vec<usize>,
+    /// The final fields a constructor must assign before it completes, and whether each has been
+    /// (JLS §16.9). They take no slots, but are tracked along with the variables.
+    fields: Vec<(String, bool)>,
+    /// The slot the first variable takes, after `this` and anything else the code is given
+    first_slot: u16,
+    max_locals: u16,
+}
+
+impl LocalVariables {
+    pub fn starting_at(first_slot: u16) -> Self {
+        Self {
+            first_slot,
+            max_locals: first_slot,
+            ..Self::default()
+        }
+    }
+
+    /// Declares a variable in the current block, returning its slot. A local variable cannot
+    /// have the name of another in scope (JLS §6.4).
+    pub fn declare(&mut self, name: &str, variable_type: JavaType, is_final: bool, is_assigned: bool) -> CompileResult<u16> {
+        if self.get(name).is_some() {
+            return Err(CompileError::duplicate_variable(name));
+        }
+        let slot = self.next_slot();
+        self.max_locals = self.max_locals.max(slot + variable_type.slots());
+        self.variables.push((name.to_string(), LocalVariable { slot, variable_type, is_final, is_assigned, is_reassigned: false, is_captured: false }));
+        Ok(slot)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LocalVariable> {
+        self.variables.iter().rev().find(|(variable_name, _)| variable_name == name).map(|(_, variable)| variable)
+    }
+
+    /// Marks a variable as given a value. A final variable can only be given one once, and a
+    /// variable a local or anonymous class uses none after it was captured.
+    pub fn assign(&mut self, name: &str) -> CompileResult<()> {
+        let Some((_, variable)) = self.variables.iter_mut().rev().find(|(variable_name, _)| variable_name == name) else {
+            return Ok(());
+        };
+        if variable.is_final && variable.is_assigned {
+            return Err(CompileError::assignment_to_final(name));
+        }
+        if variable.is_captured {
+            return Err(CompileError::not_effectively_final(name));
+        }
+        variable.is_reassigned |= variable.is_assigned;
+        variable.is_assigned = true;
+        Ok(())
+    }
+
+    /// Marks a variable as used by a local or anonymous class, returning its type, or nothing if
+    /// there is no such variable. It must have been given its only value by then (JLS §8.1.3).
+    pub fn capture(&mut self, name: &str) -> CompileResult<Option<JavaType>> {
+        let Some((_, variable)) = self.variables.iter_mut().rev().find(|(variable_name, _)| variable_name == name) else {
+            return Ok(None);
+        };
+        if !variable.is_assigned {
+            return Err(CompileError::uninitialized_variable(name));
+        }
+        if variable.is_reassigned {
+            return Err(CompileError::not_effectively_final(name));
+        }
+        variable.is_captured = true;
+        Ok(Some(variable.variable_type.clone()))
+    }
+
+    /// Requires a final field to be assigned before the constructor being compiled completes
+    pub fn require_field(&mut self, name: &str) {
+        self.fields.push((name.to_string(), false));
+    }
+
+    /// Marks a required field as assigned, which it can only be once
+    pub fn assign_field(&mut self, name: &str) -> CompileResult<()> {
+        let Some((_, is_assigned)) = self.fields.iter_mut().find(|(field_name, _)| field_name == name) else {
+            return Ok(());
+        };
+        if *is_assigned {
+            return Err(CompileError::assignment_to_final(name));
+        }
+        *is_assigned = true;
+        Ok(())
+    }
+
+    /// The first required field not yet assigned
+    pub fn unassigned_field(&self) -> Option<&str> {
+        self.fields.iter().find(|(_, is_assigned)| !is_assigned).map(|(name, _)| name.as_str())
+    }
+
+    pub fn enter_block(&mut self) {
+        self.blocks.push(self.variables.len());
+    }
+
+    /// Ends the innermost block, after which the variables it declared are out of scope
+    pub fn exit_block(&mut self) {
+        if let Some(in_scope) = self.blocks.pop() {
+            self.variables.truncate(in_scope);
+        }
+    }
+
+    /// Takes slots for a value the compiler keeps in the frame, which no code can name, such as the
+    /// array an enhanced `for` statement goes through
+    pub fn reserve(&mut self, variable_type: JavaType) -> u16 {
+        let slot = self.next_slot();
+        self.max_locals = self.max_locals.max(slot + variable_type.slots());
+        self.variables.push((String::new(), LocalVariable { slot, variable_type, is_final: false, is_assigned: true, is_reassigned: false, is_captured: false }));
+        slot
+    }
+
+    /// Whether each required field, then each variable in scope, has been assigned, in the order
+    /// they were declared. Where code may or may not run, what is known to be assigned afterwards
+    /// is put back with `set_assigned`.
+    pub fn assigned(&self) -> Vec<bool> {
+        let fields = self.fields.iter().map(|(_, is_assigned)| *is_assigned);
+        fields.chain(self.variables.iter().map(|(_, variable)| variable.is_assigned)).collect()
+    }
+
+    /// Puts back which required fields and variables are assigned, for those still in scope
+    pub fn set_assigned(&mut self, assigned: &[bool]) {
+        let (fields, variables) = assigned.split_at(self.fields.len().min(assigned.len()));
+        for ((_, field_assigned), is_assigned) in self.fields.iter_mut().zip(fields) {
+            *field_assigned = *is_assigned;
+        }
+        for ((_, variable), is_assigned) in self.variables.iter_mut().zip(variables) {
+            variable.is_assigned = *is_assigned;
+        }
+    }
+
+    /// The slots the frame of the method needs for the variables in scope at any point
+    pub fn max_locals(&self) -> u16 {
+        self.max_locals
+    }
+
+    fn next_slot(&self) -> u16 {
+        match self.variables.last() {
+            Some((_, variable)) => variable.slot + variable.variable_type.slots(),
+            None => self.first_slot,
+        }
+    }
+}
+
+/// The variables assigned on both of two paths that join. A path that cannot be reached is left
+/// out, as every variable counts as assigned on it (JLS §16).
+pub fn assigned_on_both(first: Option<Vec<bool>>, second: Option<Vec<bool>>) -> Option<Vec<bool>> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.iter().zip(&second).map(|(first, second)| *first && *second).collect()),
+        (first, None) => first,
+        (None, second) => second,
+    }
+}
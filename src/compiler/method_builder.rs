@@ -1,10 +1,15 @@
 use crate::ast::class::{AstMethod, AstScope};
+use crate::ast::statement::Statement;
 use ristretto_classfile::{Method, MethodAccessFlags};
+use ristretto_classfile::attributes::Attribute;
 use ristretto_classfile::attributes::Attribute::Code;
 use ristretto_classfile::attributes::Instruction;
-use crate::compiler::{instruction, CompilationContext};
-use crate::compiler::resolved_class::ResolvedClass;
-use crate::compiler::result::{wrap, CompileResult};
+use crate::compiler::initializer::InitializationCode;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::jump_targets::JumpTargets;
+use crate::compiler::local_variables::LocalVariables;
+use crate::compiler::{descriptor, frame_size, instruction, nested_class_builder, record_builder, stack_map, CompilationContext};
+use crate::compiler::result::{wrap, CompileError, CompileResult};
 
 pub fn from(
     ast_method: &AstMethod,
@@ -14,45 +19,248 @@ pub fn from(
         append_scope_flag_from(ast_method.scope(),
             append_final_flag_from(ast_method.is_final(),
                 append_static_flag_from(ast_method.is_static(),
-                    MethodAccessFlags::empty()
+                    append_implementation_flags_from(ast_method,
+                        MethodAccessFlags::empty()
+                    )
                 )
             )
         );
 
-    let main_method_code = wrap(compilation_context.constant_pool.add_utf8("Code"))?;
+    let name_index = wrap(compilation_context.constant_pool.add_utf8(ast_method.name()))?;
+    let descriptor = descriptor::method_descriptor(ast_method, compilation_context)?;
+    let descriptor_index = wrap(compilation_context.constant_pool.add_utf8(&descriptor))?;
 
-    let instructions: Vec<Instruction> = build_instructions(ast_method, compilation_context)?;
+    // Abstract and native methods have no code of their own
+    if ast_method.is_abstract() || ast_method.is_native() {
+        return Ok(Method {
+            access_flags: method_access_flags,
+            name_index,
+            descriptor_index,
+            attributes: vec![],
+        });
+    }
+
+    compilation_context.is_static_context = ast_method.is_static();
+    compilation_context.locals = parameters(ast_method, &descriptor, ast_method.is_static(), compilation_context)?;
+    compilation_context.enclosing_method = Some((ast_method.name().to_string(), descriptor.clone()));
+    compilation_context.jumps = JumpTargets::default();
+    let instructions = build_instructions(ast_method.statements(), compilation_context);
+    compilation_context.enclosing_method = None;
+    let mut instructions: Vec<Instruction> = instructions?;
+    // Only a void method can complete without a return statement, and returns at its end
+    if compilation_context.jumps.is_reachable() {
+        match JavaType::from_method_descriptor(&descriptor) {
+            Some((_, JavaType::Void)) => instructions.push(Instruction::Return),
+            _ => {
+                return Err(CompileError::MissingReturn {
+                    method: ast_method.name().to_string(),
+                    span: ast_method.span().last_character(),
+                })
+            }
+        }
+    }
 
     Ok(Method {
         access_flags: method_access_flags,
-        name_index: wrap(compilation_context.constant_pool.add_utf8(ast_method.name()))?,
-        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(method_parameters_str(ast_method)))?,
-        attributes: vec![Code {
-            name_index: main_method_code,
-            max_stack: 2, // need space for println parameters
-            max_locals: 1, // args[]
-            code: instructions,
-            exception_table: vec![],
-            attributes: vec![],
-        }],
+        name_index,
+        descriptor_index,
+        attributes: vec![code(
+            ast_method.name(),
+            &descriptor,
+            ast_method.is_static(),
+            instructions,
+            compilation_context.locals.max_locals(),
+            compilation_context,
+        )?],
+    })
+}
+
+/// Compiles a constructor to an `<init>` method. It starts by calling another constructor, the
+/// no-argument constructor of the superclass unless it calls one itself. Unless that was `this(...)`,
+/// which has already done so, it then runs the instance initialization before its own body.
+pub fn from_constructor(
+    ast_constructor: &AstMethod,
+    instance_initialization: &InitializationCode,
+    fields_to_assign: &[&str],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Method> {
+    let access_flags = append_scope_flag_from(ast_constructor.scope(), MethodAccessFlags::empty());
+    let name_index = wrap(compilation_context.constant_pool.add_utf8(ast_constructor.name()))?;
+    let descriptor = descriptor::constructor_descriptor(ast_constructor, compilation_context)?;
+    let descriptor_index = wrap(compilation_context.constant_pool.add_utf8(&descriptor))?;
+
+    compilation_context.is_static_context = false;
+    compilation_context.locals = parameters(ast_constructor, &descriptor, false, compilation_context)?;
+    for field in fields_to_assign {
+        compilation_context.locals.require_field(field);
+    }
+    compilation_context.enclosing_method = Some((ast_constructor.name().to_string(), descriptor.clone()));
+    compilation_context.jumps = JumpTargets::default();
+    let instructions = constructor_instructions(ast_constructor, &instance_initialization.instructions, compilation_context);
+    compilation_context.enclosing_method = None;
+    let instructions = instructions?;
+
+    let max_locals = compilation_context.locals.max_locals().max(instance_initialization.max_locals);
+    Ok(Method {
+        access_flags,
+        name_index,
+        descriptor_index,
+        attributes: vec![code(ast_constructor.name(), &descriptor, false, instructions, max_locals, compilation_context)?],
+    })
+}
+
+/// An inner class stores its enclosing instance, and a local class the variables it captures,
+/// before calling the superclass constructor, so that anything the superclass constructor calls
+/// can use them
+fn constructor_instructions(
+    ast_constructor: &AstMethod,
+    instance_initialization: &[Instruction],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Instruction>> {
+    let implicit_super_call = Statement::new_constructor_call(true, vec![]);
+    let (constructor_call, body) = match ast_constructor.statements().split_first() {
+        Some((call @ Statement::ConstructorCall { .. }, body)) => (call, body),
+        _ => (&implicit_super_call, ast_constructor.statements().as_slice()),
+    };
+
+    let is_super_call = matches!(constructor_call, Statement::ConstructorCall { is_super: true, .. });
+
+    let mut instructions = vec![];
+    if is_super_call && compilation_context.enclosing_instance().is_some() {
+        instructions.extend([Instruction::Aload_0, Instruction::Aload_1, nested_class_builder::store_outer_instance(compilation_context)?]);
+    }
+    if is_super_call {
+        instructions.extend(nested_class_builder::store_captured_variables(compilation_context)?);
+    }
+    instructions.extend(instruction::from(constructor_call, compilation_context)?);
+    if is_super_call {
+        instructions.extend_from_slice(instance_initialization);
+    }
+    instructions.extend(build_instructions(body, compilation_context)?);
+    if compilation_context.jumps.is_reachable()
+        && let Some(field) = compilation_context.locals.unassigned_field()
+    {
+        return Err(CompileError::UninitializedVariable {
+            name: field.to_string(),
+            method: ast_constructor.name().to_string(),
+            span: ast_constructor.span().last_character(),
+        });
+    }
+    if ast_constructor.is_compact() {
+        instructions.extend(record_builder::component_assignments(ast_constructor, compilation_context)?);
+    }
+    if compilation_context.jumps.is_reachable() {
+        instructions.push(Instruction::Return);
+    }
+    Ok(instructions)
+}
+
+/// The `<clinit>` method, run when the class is initialized
+pub fn from_static_initialization(
+    static_initialization: Vec<Instruction>,
+    max_locals: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Method> {
+    let mut instructions = static_initialization;
+    instructions.push(Instruction::Return);
+
+    Ok(Method {
+        access_flags: MethodAccessFlags::STATIC,
+        name_index: wrap(compilation_context.constant_pool.add_utf8("<clinit>"))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8("()V"))?,
+        attributes: vec![code("<clinit>", "()V", true, instructions, max_locals, compilation_context)?],
     })
 }
 
-fn build_instructions(method: &AstMethod, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+/// Compiles the statements of a block. The local variables and classes it declares go out of
+/// scope at its end.
+pub fn build_instructions(statements: &[Statement], compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let names = compilation_context.names.clone();
+    compilation_context.locals.enter_block();
+    let instructions = block_instructions(statements, compilation_context);
+    compilation_context.locals.exit_block();
+    compilation_context.names = names;
+    instructions
+}
+
+fn block_instructions(statements: &[Statement], compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
     let mut instructions: Vec<Instruction> = vec![];
 
-    for statement in method.statements() {
-        let statement_instructions = instruction::from(statement, compilation_context)?;
+    for statement in statements {
+        // A statement after one that cannot complete is never run (JLS §14.22)
+        if !compilation_context.jumps.is_reachable() {
+            return Err(CompileError::UnreachableStatement { method: compilation_context.method_name(), span: statement.span() });
+        }
+        let statement_instructions = instruction::from(statement, compilation_context)
+            .map_err(|error| error.located(&compilation_context.method_name(), statement.span()))?;
         for statement_instruction in statement_instructions {
             instructions.push(statement_instruction);
         }
     }
 
-    println!("{:?}", instructions);
-
     Ok(instructions)
 }
 
+/// The `Code` attribute of a method, with the size of the operand stack worked out from its code,
+/// and the `StackMapTable` the verifier needs once it branches
+pub fn code(
+    name: &str,
+    descriptor: &str,
+    is_static: bool,
+    instructions: Vec<Instruction>,
+    max_locals: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Attribute> {
+    let code = instruction::resolve_branches(instructions);
+    let exception_table = vec![];
+    let max_stack = frame_size::max_stack(&code, &exception_table, &compilation_context.constant_pool)?;
+    frame_size::check_max_locals(&code, max_locals)?;
+
+    let frames = stack_map::stack_map_frames(name, descriptor, is_static, &code, &exception_table, max_locals, compilation_context)?;
+    let mut attributes = vec![];
+    if !frames.is_empty() {
+        attributes.push(Attribute::StackMapTable {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("StackMapTable"))?,
+            frames,
+        });
+    }
+
+    Ok(Code {
+        name_index: wrap(compilation_context.constant_pool.add_utf8("Code"))?,
+        max_stack,
+        max_locals,
+        code,
+        exception_table,
+        attributes,
+    })
+}
+
+/// The declared parameters of a method or constructor, as the first of its local variables. They
+/// follow `this`, and whatever a constructor takes ahead of them, such as the name and ordinal of
+/// an enum constant or the enclosing instance of an inner class.
+fn parameters(
+    ast_method: &AstMethod,
+    descriptor: &str,
+    is_static: bool,
+    compilation_context: &CompilationContext,
+) -> CompileResult<LocalVariables> {
+    let parameter_types = JavaType::from_method_descriptor(descriptor)
+        .map(|(parameter_types, _)| parameter_types)
+        .ok_or_else(|| CompileError::UnknownMethod {
+            class: compilation_context.this_class_name.clone(),
+            method: ast_method.name().to_string(),
+        })?;
+    let hidden_parameters = parameter_types.len() - ast_method.parameters().len();
+    let hidden_slots: u16 = parameter_types[..hidden_parameters].iter().map(JavaType::slots).sum();
+
+    let mut locals = LocalVariables::starting_at(if is_static { 0 } else { 1 } + hidden_slots);
+    for (parameter, parameter_type) in ast_method.parameters().iter().zip(&parameter_types[hidden_parameters..]) {
+        locals
+            .declare(parameter.param_name(), parameter_type.clone(), false, true)
+            .map_err(|error| error.located(ast_method.name(), parameter.span()))?;
+    }
+    Ok(locals)
+}
 fn append_scope_flag_from(
     scope: AstScope,
     method_access_flags: MethodAccessFlags,
@@ -87,46 +295,19 @@ fn append_final_flag_from(
     }
 }
 
-fn method_parameters_str(ast_method: &AstMethod) -> String {
-    let mut result = String::new();
-
-    result.push('(');
-
-    for parameter in ast_method.parameters() {
-        parameter_str(parameter.param_type(), parameter.is_array(), &mut result);
-    }
-
-    result.push(')');
-    parameter_str(ast_method.return_type(), false, &mut result);
-    result
-}
-
-fn parameter_str(param_type: &str, is_array: bool, result: &mut String) {
-    if is_array {
-        result.push('[');
+fn append_implementation_flags_from(
+    ast_method: &AstMethod,
+    method_access_flags: MethodAccessFlags,
+) -> MethodAccessFlags {
+    let mut flags = method_access_flags;
+    if ast_method.is_abstract() {
+        flags |= MethodAccessFlags::ABSTRACT;
     }
-    parameter_str_after_array_resolution(param_type, result);
-}
-
-fn parameter_str_after_array_resolution(param_type: &str, result: &mut String) {
-    match param_type {
-        "bool" => result.push('Z'),
-        "byte" => result.push('B'),
-        "char" => result.push('C'),
-        "short" => result.push('S'),
-        "int" => result.push('I'),
-        "long" => result.push('J'),
-        "float" => result.push('F'),
-        "double" => result.push('D'),
-        "void" => result.push('V'),
-        s => handle_non_primitive(s, result),
+    if ast_method.is_synchronized() {
+        flags |= MethodAccessFlags::SYNCHRONIZED;
     }
-}
-
-fn handle_non_primitive(s: &str, result: &mut String) {
-    if let Some(class) = ResolvedClass::from_name(s) {
-        result.push('L');
-        result.push_str(class.fully_qualified());
-        result.push(';');
+    if ast_method.is_native() {
+        flags |= MethodAccessFlags::NATIVE;
     }
+    flags
 }
@@ -1,27 +1,208 @@
+mod captured_variables;
 mod class_file_builder;
+mod declaration;
+mod descriptor;
+mod enum_builder;
+mod field_builder;
+mod frame_size;
+mod inheritance;
+mod initializer;
 mod instruction;
+mod java_type;
+mod jump_targets;
+mod local_variables;
 mod method_builder;
-mod resolved_class;
+mod name_resolver;
+mod nested_class_builder;
+mod record_builder;
 mod result;
+mod stack_map;
 
-use crate::ast::class::AstClass;
+use crate::ast::compilation_unit::AstCompilationUnit;
 use crate::compiler::class_file_builder::from;
+use crate::compiler::declaration::DeclaredType;
+use crate::compiler::field_builder::DeclaredField;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::jump_targets::JumpTargets;
+use crate::compiler::local_variables::LocalVariables;
+use crate::compiler::name_resolver::NameResolver;
+use crate::compiler::nested_class_builder::{EnclosingClass, NestedClass};
 pub use crate::compiler::result::{wrap, CompileError, CompileResult};
+use ristretto_classfile::attributes::BootstrapMethod;
 use ristretto_classfile::{ClassFile, ConstantPool};
+use std::collections::HashMap;
 use crate::java::{new_class_loader, ClassLoader};
 
 pub struct CompilationContext {
     constant_pool: ConstantPool,
     class_loader: ClassLoader,
+    names: NameResolver,
+    this_class: u16,
+    /// The fully qualified binary name of the class being compiled, such as `com.example.Main$1`
+    this_class_name: String,
+    super_class: u16,
+    super_class_name: String,
+    interfaces: Vec<u16>,
+    /// The descriptors of the constructors declared by the class being compiled
+    constructors: Vec<String>,
+    fields: HashMap<String, DeclaredField>,
+    is_static_context: bool,
+    /// The parameters and local variables in scope in the code being compiled
+    locals: LocalVariables,
+    /// The statements that `break` and `continue` in the code being compiled can jump out of
+    jumps: JumpTargets,
+    /// The constructors of an enum take the name and ordinal of the constant before their declared parameters
+    is_enum: bool,
+    /// The entries of the `BootstrapMethods` attribute, referred to by `invokedynamic` instructions
+    bootstrap_methods: Vec<BootstrapMethod>,
+    /// The top level class whose nest the class being compiled belongs to
+    host_class_name: String,
+    /// The class the class being compiled is nested in, if it is
+    enclosing: Option<Box<EnclosingClass>>,
+    /// The local variables a local or anonymous class captures, with their types
+    captured_variables: Vec<(String, JavaType)>,
+    /// The nested classes of the nest found so far, for the `InnerClasses` and `NestMembers` attributes
+    nested_classes: Vec<NestedClass>,
+    /// The classes compiled for the local and anonymous classes declared in code
+    local_class_files: Vec<ClassFile>,
+    /// How many anonymous classes have been numbered, along with how many local classes of each name
+    anonymous_classes: usize,
+    local_classes: HashMap<String, usize>,
+    /// The name and descriptor of the method whose code is being compiled, which encloses any local
+    /// or anonymous class declared in it
+    enclosing_method: Option<(String, String)>,
+    /// The index of the class constant of each class named in the constant pool, as the pool itself
+    /// adds a new constant each time, and frames compare their object types by index
+    class_constants: HashMap<String, u16>,
 }
 
-pub fn compile(class: &AstClass) -> CompileResult<ClassFile> {
-    let constant_pool = ConstantPool::default();
-    let packages = new_class_loader();
-    let mut compilation_context = CompilationContext {
-        constant_pool,
-        class_loader: packages,
-    };
+impl CompilationContext {
+    fn new(
+        names: NameResolver,
+        class_loader: ClassLoader,
+        this_class_name: String,
+        super_class_name: String,
+        interface_names: &[String],
+    ) -> CompileResult<Self> {
+        let mut context = Self {
+            constant_pool: ConstantPool::default(),
+            class_loader,
+            names,
+            this_class: 0,
+            host_class_name: this_class_name.clone(),
+            this_class_name,
+            super_class: 0,
+            super_class_name,
+            interfaces: vec![],
+            constructors: vec![],
+            fields: HashMap::new(),
+            is_static_context: false,
+            locals: LocalVariables::default(),
+            jumps: JumpTargets::default(),
+            is_enum: false,
+            bootstrap_methods: vec![],
+            enclosing: None,
+            captured_variables: vec![],
+            nested_classes: vec![],
+            local_class_files: vec![],
+            anonymous_classes: 0,
+            local_classes: HashMap::new(),
+            enclosing_method: None,
+            class_constants: HashMap::new(),
+        };
+        context.this_class = context.add_class(context.this_class_name.replace('.', "/"))?;
+        context.super_class = context.add_class(context.super_class_name.replace('.', "/"))?;
+        for interface in interface_names {
+            let interface = context.add_class(interface.replace('.', "/"))?;
+            context.interfaces.push(interface);
+        }
+        Ok(context)
+    }
 
-    from(class, &mut compilation_context)
+    /// The index of the class constant for a class, given by its internal name, added unless it
+    /// already is
+    fn add_class(&mut self, name: impl AsRef<str>) -> CompileResult<u16> {
+        let name = name.as_ref();
+        if let Some(index) = self.class_constants.get(name) {
+            return Ok(*index);
+        }
+        let index = wrap(self.constant_pool.add_class(name))?;
+        self.class_constants.insert(name.to_string(), index);
+        Ok(index)
+    }
+
+    /// The class of the enclosing instance the class being compiled holds in its `this$0` field,
+    /// when it is an inner class
+    fn enclosing_instance(&self) -> Option<&str> {
+        self.enclosing.as_ref().filter(|enclosing| enclosing.has_instance()).map(|enclosing| enclosing.name())
+    }
+
+    /// The name of the method being compiled, or of the initialization method that runs the
+    /// initializer being compiled
+    fn method_name(&self) -> String {
+        match &self.enclosing_method {
+            Some((name, _)) => name.clone(),
+            None if self.is_static_context => "<clinit>".to_string(),
+            None => "<init>".to_string(),
+        }
+    }
+
+    /// What constructors take ahead of their declared parameters: the name and ordinal of an enum
+    /// constant, or the enclosing instance of an inner class followed by the local variables a
+    /// local class captures
+    fn hidden_constructor_parameters(&self) -> Vec<JavaType> {
+        if self.is_enum {
+            return vec![JavaType::string(), JavaType::Int];
+        }
+        let enclosing_instance = self.enclosing_instance().map(|enclosing| JavaType::Reference(enclosing.replace('.', "/")));
+        let captured_variables = self.captured_variables.iter().map(|(_, variable_type)| variable_type.clone());
+        enclosing_instance.into_iter().chain(captured_variables).collect()
+    }
+
+    /// The index of a bootstrap method, added unless an identical one already is
+    fn add_bootstrap_method(&mut self, bootstrap_method_ref: u16, arguments: Vec<u16>) -> u16 {
+        let bootstrap_method = BootstrapMethod { bootstrap_method_ref, arguments };
+        let index = match self.bootstrap_methods.iter().position(|existing| *existing == bootstrap_method) {
+            Some(index) => index,
+            None => {
+                self.bootstrap_methods.push(bootstrap_method);
+                self.bootstrap_methods.len() - 1
+            }
+        };
+        index as u16
+    }
+
+    /// The fully qualified name of a class name as written in the source
+    fn resolve_class(&mut self, name: &str) -> CompileResult<String> {
+        self.names
+            .resolve_class(name, &mut self.class_loader)
+            .ok_or_else(|| CompileError::UnknownClass(name.to_string()))
+    }
+}
+
+/// Compiles the types of a set of compilation units together, along with any classes generated for
+/// them such as those of the class bodies of enum constants. Every type is declared before any is
+/// compiled, so that they can refer to each other.
+pub fn compile(compilation_units: &[AstCompilationUnit]) -> CompileResult<Vec<ClassFile>> {
+    let mut packages = new_class_loader();
+    let types: Vec<DeclaredType> = compilation_units
+        .iter()
+        .flat_map(|compilation_unit| {
+            let names = NameResolver::from(compilation_unit);
+            compilation_unit.types().iter().map(move |class| DeclaredType::top_level(class, &names))
+        })
+        .collect();
+    declaration::declare(&types, &mut packages)?;
+
+    let mut class_files = vec![];
+    for declared_type in types {
+        let class = declared_type.class;
+        let super_class_name = inheritance::super_class(class, &declared_type.name, &declared_type.names, &mut packages)?;
+        let interface_names = inheritance::interfaces(class, &declared_type.name, &declared_type.names, &mut packages)?;
+
+        let mut compilation_context =
+            CompilationContext::new(declared_type.names, packages.clone(), declared_type.name, super_class_name, &interface_names)?;
+        class_files.extend(from(class, &mut compilation_context)?);
+    }
+    Ok(class_files)
 }
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use crate::ast::class::AstClass;
+use crate::ast::compilation_unit::AstCompilationUnit;
+use crate::java::ClassLoader;
+
+const JAVA_LANG: &str = "java.lang";
+
+/// Resolves the simple names used in a compilation unit to fully qualified class names. A simple
+/// name is looked up in the member and local classes in scope, the types the unit declares, then
+/// single-type imports, then the other types of its package, then on-demand imports and finally
+/// `java.lang`, which every compilation unit imports implicitly (JLS §6.4.1, §7.5).
+#[derive(Clone)]
+pub struct NameResolver {
+    package: Option<String>,
+    type_names: Vec<String>,
+    /// The simple and binary names of the member and local classes in scope, innermost first
+    scoped_types: Vec<(String, String)>,
+    single_type_imports: HashMap<String, String>,
+    on_demand_imports: Vec<String>,
+    single_static_imports: HashMap<String, String>,
+    static_on_demand_imports: Vec<String>,
+}
+
+impl NameResolver {
+    pub fn from(compilation_unit: &AstCompilationUnit) -> Self {
+        let mut resolver = Self {
+            package: compilation_unit.package().map(str::to_string),
+            type_names: compilation_unit.types().iter().map(|class| class.name().to_string()).collect(),
+            scoped_types: vec![],
+            single_type_imports: HashMap::new(),
+            on_demand_imports: vec![],
+            single_static_imports: HashMap::new(),
+            static_on_demand_imports: vec![],
+        };
+
+        for import in compilation_unit.imports() {
+            let name = import.name().to_string();
+            match (import.is_static(), import.is_on_demand()) {
+                (false, false) => {
+                    resolver.single_type_imports.insert(import.simple_name().to_string(), name);
+                }
+                (false, true) => resolver.on_demand_imports.push(name),
+                (true, false) => {
+                    let class_name = &import.name()[..import.name().len() - import.simple_name().len() - 1];
+                    resolver.single_static_imports.insert(import.simple_name().to_string(), class_name.to_string());
+                }
+                (true, true) => resolver.static_on_demand_imports.push(name),
+            }
+        }
+        resolver
+    }
+
+    /// A resolver for the body of a class, in which the simple names of its member types resolve
+    /// to them ahead of any other type
+    pub fn within(&self, class_name: &str, class: &AstClass) -> Self {
+        let mut resolver = self.clone();
+        for member_type in class.member_types() {
+            resolver.declare_local(member_type.name(), &format!("{}${}", class_name, member_type.name()));
+        }
+        resolver
+    }
+
+    /// Brings a class into scope ahead of those already in scope
+    pub fn declare_local(&mut self, simple_name: &str, class_name: &str) {
+        self.scoped_types.insert(0, (simple_name.to_string(), class_name.to_string()));
+    }
+
+    /// The fully qualified name of a class name as written in the source. A qualified name is
+    /// taken to be fully qualified already, unless it starts with a class that declares the member
+    /// types it goes on to name.
+    pub fn resolve_class(&self, name: &str, class_loader: &mut ClassLoader) -> Option<String> {
+        if let Some((first_name, member_names)) = name.split_once('.') {
+            let member_type = self
+                .resolve_class(first_name, class_loader)
+                .map(|class_name| format!("{}${}", class_name, member_names.replace('.', "$")))
+                .filter(|class_name| class_loader.load(class_name).is_some());
+            return Some(member_type.unwrap_or_else(|| name.to_string()));
+        }
+        if let Some((_, class_name)) = self.scoped_types.iter().find(|(simple_name, _)| simple_name == name) {
+            return Some(class_name.clone());
+        }
+        if self.type_names.iter().any(|type_name| type_name == name) {
+            return Some(self.qualify(name));
+        }
+        if let Some(imported) = self.single_type_imports.get(name) {
+            return Some(imported.clone());
+        }
+        let in_package = self.qualify(name);
+        if class_loader.load(&in_package).is_some() {
+            return Some(in_package);
+        }
+
+        self.on_demand_imports
+            .iter()
+            .map(String::as_str)
+            .chain([JAVA_LANG])
+            .map(|package| format!("{}.{}", package, name))
+            .find(|candidate| class_loader.load(candidate).is_some())
+    }
+
+    /// The fully qualified name of the class declaring a statically imported field or method
+    pub fn resolve_static_member(&self, name: &str, class_loader: &mut ClassLoader) -> Option<String> {
+        if let Some(class_name) = self.single_static_imports.get(name) {
+            return Some(class_name.clone());
+        }
+
+        self.static_on_demand_imports
+            .iter()
+            .find(|class_name| {
+                class_loader
+                    .load(class_name)
+                    .is_some_and(|class| class.field_named(name).is_some() || !class.methods_named(name).is_empty())
+            })
+            .cloned()
+    }
+
+    /// The fully qualified name of a top level type of this compilation unit's package, such as
+    /// `com.example.Main`
+    pub fn qualify(&self, simple_name: &str) -> String {
+        match &self.package {
+            Some(package) => format!("{}.{}", package, simple_name),
+            None => simple_name.to_string(),
+        }
+    }
+}
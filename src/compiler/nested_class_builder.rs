@@ -0,0 +1,582 @@
+use crate::ast::class::{AstClass, AstScope};
+use crate::ast::expression::Expression;
+use crate::compiler::captured_variables::captured_variables;
+use crate::compiler::declaration::{declare, DeclaredType};
+use crate::compiler::field_builder::DeclaredField;
+use crate::compiler::initializer::InitializationCode;
+use crate::compiler::instruction::{hidden_arguments_of, select_constructor, HiddenArgument};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::method_builder::code;
+use crate::compiler::name_resolver::NameResolver;
+use crate::compiler::result::{wrap, CompileError, CompileResult};
+use crate::compiler::{class_file_builder, inheritance, CompilationContext};
+use crate::java::class::JavaClass;
+use ristretto_classfile::attributes::{Attribute, InnerClass, Instruction, NestedClassAccessFlags};
+use ristretto_classfile::{ClassFile, Constant, Field, FieldAccessFlags, FieldType, Method, MethodAccessFlags};
+use std::collections::HashMap;
+
+const JAVA_LANG_OBJECT: &str = "java.lang.Object";
+const OUTER_INSTANCE_FIELD: &str = "this$0";
+const CAPTURED_VARIABLE_FIELD_PREFIX: &str = "val$";
+
+/// A class that the class being compiled is nested in, and what its code can reach of it
+#[derive(Clone)]
+pub struct EnclosingClass {
+    name: String,
+    /// The fields it declares, which code of the classes nested in it refers to by their simple names
+    fields: HashMap<String, DeclaredField>,
+    /// Whether the class nested in it holds an instance of it, as an inner class does
+    has_instance: bool,
+    enclosing: Option<Box<EnclosingClass>>,
+}
+
+impl EnclosingClass {
+    pub fn new(name: &str, fields: HashMap<String, DeclaredField>, has_instance: bool, enclosing: Option<Box<EnclosingClass>>) -> Self {
+        Self {
+            name: name.to_string(),
+            fields,
+            has_instance,
+            enclosing,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &HashMap<String, DeclaredField> {
+        &self.fields
+    }
+
+    pub fn has_instance(&self) -> bool {
+        self.has_instance
+    }
+
+    pub fn enclosing(&self) -> Option<&EnclosingClass> {
+        self.enclosing.as_deref()
+    }
+}
+
+/// A class of the nest being compiled other than its host, as the `InnerClasses` attribute
+/// describes it (JVMS §4.7.6)
+#[derive(Clone)]
+pub struct NestedClass {
+    name: String,
+    /// The class a member class is declared in; local and anonymous classes have none
+    outer: Option<String>,
+    /// Anonymous classes have no simple name
+    simple_name: Option<String>,
+    access_flags: NestedClassAccessFlags,
+    /// The class and, unless it is an initializer, the name and descriptor of the method a local or
+    /// anonymous class is declared in, for its `EnclosingMethod` attribute (JVMS §4.7.7)
+    enclosing_method: Option<(String, Option<(String, String)>)>,
+}
+
+/// Whether a member type is an inner class, whose instances hold an instance of the class it is
+/// declared in. Member interfaces, enums and records are implicitly static, as is every member of
+/// an interface (JLS §8.1.3, §9.5).
+pub fn is_inner(member_type: &AstClass, outer: &AstClass) -> bool {
+    !(member_type.is_static() || is_implicitly_static(member_type) || outer.is_interface())
+}
+
+fn is_implicitly_static(class: &AstClass) -> bool {
+    class.is_interface() || class.is_enum() || class.is_record()
+}
+
+/// Adds the member types of a class, and theirs in turn, to the classes of the nest, so that any
+/// class of the nest can refer to them whatever order they are compiled in
+pub fn register_member_types(class: &AstClass, class_name: &str, compilation_context: &mut CompilationContext) {
+    for member_type in class.member_types() {
+        let name = format!("{}${}", class_name, member_type.name());
+        if compilation_context.nested_classes.iter().any(|nested_class| nested_class.name == name) {
+            continue;
+        }
+
+        let mut access_flags = access_flags_from(member_type);
+        access_flags |= match member_type.scope() {
+            AstScope::Public => NestedClassAccessFlags::PUBLIC,
+            AstScope::Protected => NestedClassAccessFlags::PROTECTED,
+            AstScope::Private => NestedClassAccessFlags::PRIVATE,
+            // Members of an interface are implicitly public
+            AstScope::Default if class.is_interface() => NestedClassAccessFlags::PUBLIC,
+            AstScope::Default => NestedClassAccessFlags::empty(),
+        };
+        if !is_inner(member_type, class) {
+            access_flags |= NestedClassAccessFlags::STATIC;
+        }
+        compilation_context.nested_classes.push(NestedClass {
+            name: name.clone(),
+            outer: Some(class_name.to_string()),
+            simple_name: Some(member_type.name().to_string()),
+            access_flags,
+            enclosing_method: None,
+        });
+        register_member_types(member_type, &name, compilation_context);
+    }
+}
+
+fn access_flags_from(class: &AstClass) -> NestedClassAccessFlags {
+    let mut flags = NestedClassAccessFlags::empty();
+    if class.is_interface() {
+        flags |= NestedClassAccessFlags::INTERFACE | NestedClassAccessFlags::ABSTRACT;
+    } else if class.is_abstract() {
+        flags |= NestedClassAccessFlags::ABSTRACT;
+    }
+    if class.is_enum() {
+        flags |= NestedClassAccessFlags::ENUM;
+        if class.enum_constants().iter().all(|constant| constant.body().is_none()) {
+            flags |= NestedClassAccessFlags::FINAL;
+        }
+    }
+    if class.is_final() || class.is_record() {
+        flags |= NestedClassAccessFlags::FINAL;
+    }
+    if class.is_enum() || class.is_record() {
+        flags |= NestedClassAccessFlags::STATIC;
+    }
+    flags
+}
+
+/// Compiles the member types of a class, each to its own class file such as `Outer$Inner.class`
+pub fn member_types(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Vec<ClassFile>> {
+    let mut class_files = vec![];
+    for member_type in class.member_types() {
+        let name = format!("{}${}", compilation_context.this_class_name, member_type.name());
+        let names = compilation_context.names.within(&name, member_type);
+        let has_instance = is_inner(member_type, class);
+        class_files.extend(compile_nested(member_type, name, names, has_instance, vec![], compilation_context)?);
+    }
+    Ok(class_files)
+}
+
+/// Compiles a class declared in a block, named as javac does with a number that tells it apart
+/// from other local classes of the same name, such as `Outer$1Local`. It is in scope, and so
+/// declared to the class loader, from here to the end of the block. Unless it is implicitly static,
+/// it captures the local variables in scope that it uses.
+pub fn local_class(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<()> {
+    let captured_variables = if is_implicitly_static(class) {
+        vec![]
+    } else {
+        captured_variables(class, compilation_context)?
+    };
+    let count = compilation_context.local_classes.entry(class.name().to_string()).or_insert(0);
+    *count += 1;
+    let name = format!("{}${}{}", compilation_context.this_class_name, count, class.name());
+    compilation_context.names.declare_local(class.name(), &name);
+
+    let has_instance = !compilation_context.is_static_context && !is_implicitly_static(class);
+    let declared_type = DeclaredType {
+        class,
+        name: name.clone(),
+        names: compilation_context.names.within(&name, class),
+        enclosing_instance: has_instance.then(|| compilation_context.this_class_name.clone()),
+        captured_variables: captured_variables.clone(),
+    };
+    declare(&[declared_type], &mut compilation_context.class_loader)?;
+
+    let mut access_flags = access_flags_from(class);
+    if class.is_interface() {
+        access_flags |= NestedClassAccessFlags::STATIC;
+    }
+    register_local(&name, Some(class.name()), access_flags, compilation_context);
+
+    let names = compilation_context.names.within(&name, class);
+    let class_files = compile_nested(class, name, names, has_instance, captured_variables, compilation_context)?;
+    compilation_context.local_class_files.extend(class_files);
+    Ok(())
+}
+
+/// Compiles the body of an anonymous class that extends a class, or implements an interface and
+/// extends `Object`, named as javac does with its position among the anonymous classes of the
+/// class being compiled, such as `Outer$1`. Returns the name of the class, the descriptor of its
+/// constructor and what that constructor takes ahead of the arguments: the instance of the class
+/// being compiled, unless in a static context, and the local variables it captures, then what the
+/// superclass constructor takes ahead of them.
+pub fn anonymous_class(
+    body: &AstClass,
+    class_type: &str,
+    arguments: &[Expression],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<(String, String, Vec<HiddenArgument>)> {
+    let type_name = compilation_context.resolve_class(class_type)?;
+    let Some(supertype) = compilation_context.class_loader.load(&type_name) else {
+        return Err(CompileError::UnknownClass(class_type.to_string()));
+    };
+    if supertype.is_final() {
+        return Err(CompileError::FinalSuperclass(type_name));
+    }
+    let (super_class, interfaces) = if supertype.is_interface() {
+        (JAVA_LANG_OBJECT.to_string(), vec![type_name])
+    } else {
+        (type_name, vec![])
+    };
+    let super_descriptor = select_constructor(&super_class, arguments, compilation_context)?;
+    let captured_variables = captured_variables(body, compilation_context)?;
+
+    compilation_context.anonymous_classes += 1;
+    let name = format!("{}${}", compilation_context.this_class_name, compilation_context.anonymous_classes);
+    let has_instance = !compilation_context.is_static_context;
+    register_local(&name, None, NestedClassAccessFlags::empty(), compilation_context);
+
+    let mut hidden_arguments: Vec<HiddenArgument> = has_instance
+        .then(|| HiddenArgument::EnclosingInstance(compilation_context.this_class_name.clone()))
+        .into_iter()
+        .collect();
+    hidden_arguments.extend(captured_variables.iter().map(|(name, _)| HiddenArgument::CapturedVariable(name.clone())));
+    hidden_arguments.extend(hidden_arguments_of(&super_class, compilation_context));
+    let enclosing_instance = has_instance.then_some(compilation_context.this_class_name.as_str());
+    let descriptor = anonymous_constructor_descriptor(&super_descriptor, enclosing_instance, &captured_variables);
+
+    let binary_name = name.rsplit('.').next().unwrap_or(&name);
+    let anonymous_class = JavaClass::new(binary_name, name.as_str(), format!("L{};", name.replace('.', "/")), vec![], HashMap::new())
+        .extending(super_class.as_str())
+        .implementing(interfaces.clone());
+    compilation_context.class_loader.declare(anonymous_class);
+
+    let class_files = compile_anonymous(
+        body,
+        &name,
+        (&super_class, &interfaces),
+        &super_descriptor,
+        has_instance,
+        captured_variables,
+        compilation_context,
+    )?;
+    compilation_context.local_class_files.extend(class_files);
+    Ok((name, descriptor, hidden_arguments))
+}
+
+/// Compiles the class body of an enum constant to an anonymous subclass of the enum, whose
+/// constructor passes all it is given on to the enum constructor with the same descriptor
+pub fn enum_constant_body(
+    body: &AstClass,
+    name: String,
+    constructor_descriptor: &str,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    let access_flags = NestedClassAccessFlags::FINAL | NestedClassAccessFlags::ENUM;
+    register_local(&name, None, access_flags, compilation_context);
+
+    let super_class = compilation_context.this_class_name.clone();
+    compile_anonymous(body, &name, (&super_class, &[]), constructor_descriptor, false, vec![], compilation_context)
+}
+
+/// Local and anonymous classes are enclosed by the method whose code declares them
+fn register_local(name: &str, simple_name: Option<&str>, access_flags: NestedClassAccessFlags, compilation_context: &mut CompilationContext) {
+    compilation_context.nested_classes.push(NestedClass {
+        name: name.to_string(),
+        outer: None,
+        simple_name: simple_name.map(str::to_string),
+        access_flags,
+        enclosing_method: Some((compilation_context.this_class_name.clone(), compilation_context.enclosing_method.clone())),
+    });
+}
+
+fn anonymous_constructor_descriptor(super_descriptor: &str, enclosing_instance: Option<&str>, captured_variables: &[(String, JavaType)]) -> String {
+    let mut prefix: String = enclosing_instance.map(|enclosing_class| format!("L{};", enclosing_class.replace('.', "/"))).unwrap_or_default();
+    prefix.extend(captured_variables.iter().map(|(_, variable_type)| variable_type.descriptor()));
+    super_descriptor.replacen('(', &format!("({}", prefix), 1)
+}
+
+fn compile_nested(
+    class: &AstClass,
+    name: String,
+    names: NameResolver,
+    has_instance: bool,
+    captured_variables: Vec<(String, JavaType)>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    let super_class = inheritance::super_class(class, &name, &names, &mut compilation_context.class_loader)?;
+    let interfaces = inheritance::interfaces(class, &name, &names, &mut compilation_context.class_loader)?;
+
+    let mut nested_context = nested_context(name, names, super_class, &interfaces, has_instance, compilation_context)?;
+    nested_context.captured_variables = captured_variables;
+    let result = class_file_builder::from(class, &mut nested_context);
+    compilation_context.nested_classes = nested_context.nested_classes;
+    result
+}
+
+fn compile_anonymous(
+    body: &AstClass,
+    name: &str,
+    (super_class, interfaces): (&str, &[String]),
+    super_descriptor: &str,
+    has_instance: bool,
+    captured_variables: Vec<(String, JavaType)>,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<ClassFile>> {
+    let names = compilation_context.names.within(name, body);
+    let mut nested_context = nested_context(name.to_string(), names, super_class.to_string(), interfaces, has_instance, compilation_context)?;
+    nested_context.captured_variables = captured_variables;
+    let result = class_file_builder::from_anonymous_class(body, super_descriptor, &mut nested_context);
+    compilation_context.nested_classes = nested_context.nested_classes;
+    result
+}
+
+/// The context a nested class is compiled in, which shares the classes of the nest found so far
+/// with the class it is nested in until it has been compiled
+fn nested_context(
+    name: String,
+    names: NameResolver,
+    super_class: String,
+    interfaces: &[String],
+    has_instance: bool,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<CompilationContext> {
+    let mut nested_context = CompilationContext::new(names, compilation_context.class_loader.clone(), name, super_class, interfaces)?;
+    nested_context.host_class_name = compilation_context.host_class_name.clone();
+    nested_context.enclosing = Some(Box::new(EnclosingClass::new(
+        &compilation_context.this_class_name,
+        compilation_context.fields.clone(),
+        has_instance,
+        compilation_context.enclosing.clone(),
+    )));
+    nested_context.nested_classes = std::mem::take(&mut compilation_context.nested_classes);
+    Ok(nested_context)
+}
+
+/// The constructor of an anonymous class. It stores the enclosing instance, if any, and the
+/// variables it captures, then passes the rest of what it is given on to the superclass
+/// constructor before running the instance initialization of the body.
+pub fn anonymous_constructor(
+    super_descriptor: &str,
+    instance_initialization: &InitializationCode,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Method> {
+    let parameter_types = JavaType::from_method_descriptor(super_descriptor)
+        .map(|(parameter_types, _)| parameter_types)
+        .ok_or_else(|| CompileError::UnknownMethod {
+            class: compilation_context.super_class_name.clone(),
+            method: "<init>".to_string(),
+        })?;
+
+    let mut instructions = vec![];
+    if compilation_context.enclosing_instance().is_some() {
+        instructions.extend([Instruction::Aload_0, Instruction::Aload_1, store_outer_instance(compilation_context)?]);
+    }
+    instructions.extend(store_captured_variables(compilation_context)?);
+    let mut slot: u16 = 1 + compilation_context.hidden_constructor_parameters().iter().map(JavaType::slots).sum::<u16>();
+    instructions.push(Instruction::Aload_0);
+    for parameter_type in &parameter_types {
+        instructions.push(parameter_type.load_instruction(slot));
+        slot += parameter_type.slots();
+    }
+    let super_class = compilation_context.super_class;
+    let constructor_ref = wrap(compilation_context.constant_pool.add_method_ref(super_class, "<init>", super_descriptor))?;
+    instructions.push(Instruction::Invokespecial(constructor_ref));
+    instructions.extend_from_slice(&instance_initialization.instructions);
+    instructions.push(Instruction::Return);
+
+    let descriptor =
+        anonymous_constructor_descriptor(super_descriptor, compilation_context.enclosing_instance(), &compilation_context.captured_variables);
+    Ok(Method {
+        access_flags: MethodAccessFlags::empty(),
+        name_index: wrap(compilation_context.constant_pool.add_utf8("<init>"))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(&descriptor))?,
+        attributes: vec![code(
+            "<init>",
+            &descriptor,
+            false,
+            instructions,
+            slot.max(instance_initialization.max_locals),
+            compilation_context,
+        )?],
+    })
+}
+
+/// Whether the class being compiled is the class body of an enum constant
+pub fn is_enum_constant_body(compilation_context: &CompilationContext) -> bool {
+    compilation_context.nested_classes.iter().any(|nested_class| {
+        nested_class.name == compilation_context.this_class_name && nested_class.access_flags.contains(NestedClassAccessFlags::ENUM)
+    })
+}
+
+/// The synthetic field an inner class keeps its enclosing instance in
+pub fn outer_instance_field(compilation_context: &mut CompilationContext) -> CompileResult<Option<Field>> {
+    let Some(enclosing_class) = compilation_context.enclosing_instance() else {
+        return Ok(None);
+    };
+    let descriptor = format!("L{};", enclosing_class.replace('.', "/"));
+    Ok(Some(Field {
+        access_flags: FieldAccessFlags::FINAL | FieldAccessFlags::SYNTHETIC,
+        name_index: wrap(compilation_context.constant_pool.add_utf8(OUTER_INSTANCE_FIELD))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(&descriptor))?,
+        field_type: wrap(FieldType::parse(&descriptor))?,
+        attributes: vec![],
+    }))
+}
+
+/// The synthetic fields a local or anonymous class keeps the variables it captures in
+pub fn captured_variable_fields(compilation_context: &mut CompilationContext) -> CompileResult<Vec<Field>> {
+    let mut fields = vec![];
+    for (name, variable_type) in compilation_context.captured_variables.clone() {
+        let descriptor = variable_type.descriptor();
+        fields.push(Field {
+            access_flags: FieldAccessFlags::PRIVATE | FieldAccessFlags::FINAL | FieldAccessFlags::SYNTHETIC,
+            name_index: wrap(compilation_context.constant_pool.add_utf8(format!("{}{}", CAPTURED_VARIABLE_FIELD_PREFIX, name)))?,
+            descriptor_index: wrap(compilation_context.constant_pool.add_utf8(&descriptor))?,
+            field_type: wrap(FieldType::parse(&descriptor))?,
+            attributes: vec![],
+        });
+    }
+    Ok(fields)
+}
+
+/// Stores the variables a local or anonymous class captures, which its constructors are given
+/// after any enclosing instance, in their fields. Like the enclosing instance, they are stored
+/// before the superclass constructor is called, so that anything it calls can use them.
+pub fn store_captured_variables(compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let mut instructions = vec![];
+    let mut slot: u16 = 1 + compilation_context.enclosing_instance().map_or(0, |_| 1);
+    for (name, variable_type) in compilation_context.captured_variables.clone() {
+        instructions.extend([Instruction::Aload_0, variable_type.load_instruction(slot)]);
+        instructions.push(Instruction::Putfield(captured_variable_ref(&name, &variable_type.descriptor(), compilation_context)?));
+        slot += variable_type.slots();
+    }
+    Ok(instructions)
+}
+
+/// The `val$name` field of the class being compiled that holds a variable it captures
+pub fn captured_variable_ref(name: &str, descriptor: &str, compilation_context: &mut CompilationContext) -> CompileResult<u16> {
+    let field_name = format!("{}{}", CAPTURED_VARIABLE_FIELD_PREFIX, name);
+    let this_class = compilation_context.this_class;
+    wrap(compilation_context.constant_pool.add_field_ref(this_class, field_name.as_str(), descriptor))
+}
+
+/// Stores the enclosing instance beneath it on the stack in the field of the object beneath that
+pub fn store_outer_instance(compilation_context: &mut CompilationContext) -> CompileResult<Instruction> {
+    let this_class = compilation_context.this_class;
+    let this_class_name = compilation_context.this_class_name.clone();
+    Ok(Instruction::Putfield(outer_instance_ref(this_class, &this_class_name, compilation_context)?))
+}
+
+/// The `this$0` field of a class, which must hold an enclosing instance
+pub fn outer_instance_ref(class_ref: u16, class_name: &str, compilation_context: &mut CompilationContext) -> CompileResult<u16> {
+    let outer_class = outer_instance_class(class_name, compilation_context)
+        .ok_or_else(|| CompileError::UnknownField {
+            class: class_name.to_string(),
+            field: OUTER_INSTANCE_FIELD.to_string(),
+        })?;
+    let descriptor = format!("L{};", outer_class.replace('.', "/"));
+    wrap(compilation_context.constant_pool.add_field_ref(class_ref, OUTER_INSTANCE_FIELD, &descriptor))
+}
+
+/// The class of the enclosing instance a class of the nest holds, found along the classes that
+/// enclose the class being compiled
+fn outer_instance_class(class_name: &str, compilation_context: &CompilationContext) -> Option<String> {
+    let mut class = class_name == compilation_context.this_class_name;
+    let mut enclosing = compilation_context.enclosing.as_deref();
+    while let Some(enclosing_class) = enclosing {
+        if class {
+            return enclosing_class.has_instance.then(|| enclosing_class.name.clone());
+        }
+        class = class_name == enclosing_class.name;
+        enclosing = enclosing_class.enclosing.as_deref();
+    }
+    None
+}
+
+/// The attributes that make the classes of a nest one another's nestmates, so that they can
+/// refer to each other's private members (JVMS §5.4.4), and that describe the nested classes the
+/// class being compiled refers to. The host lists the members of the nest, while every member
+/// names its host, along with the method enclosing it if it is a local or anonymous class.
+pub fn nest_attributes(compilation_context: &mut CompilationContext) -> CompileResult<Vec<Attribute>> {
+    let this_class_name = compilation_context.this_class_name.clone();
+    let mut attributes = vec![];
+
+    if compilation_context.host_class_name != this_class_name {
+        let host_class_index = compilation_context.add_class(compilation_context.host_class_name.replace('.', "/"))?;
+        attributes.push(Attribute::NestHost {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("NestHost"))?,
+            host_class_index,
+        });
+    } else if !compilation_context.nested_classes.is_empty() {
+        let mut class_indexes = vec![];
+        for nested_class in compilation_context.nested_classes.clone() {
+            class_indexes.push(compilation_context.add_class(nested_class.name.replace('.', "/"))?);
+        }
+        attributes.push(Attribute::NestMembers {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("NestMembers"))?,
+            class_indexes,
+        });
+    }
+
+    let this_entry = compilation_context.nested_classes.iter().find(|nested_class| nested_class.name == this_class_name).cloned();
+    if let Some((class_name, method)) = this_entry.and_then(|nested_class| nested_class.enclosing_method) {
+        let class_index = compilation_context.add_class(class_name.replace('.', "/"))?;
+        let method_index = match method {
+            Some((name, descriptor)) => wrap(compilation_context.constant_pool.add_name_and_type(&name, &descriptor))?,
+            None => 0,
+        };
+        attributes.push(Attribute::EnclosingMethod {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("EnclosingMethod"))?,
+            class_index,
+            method_index,
+        });
+    }
+
+    // A class lists its own member classes, whether or not its code refers to them
+    let members: Vec<String> = compilation_context
+        .nested_classes
+        .iter()
+        .filter(|nested_class| nested_class.outer.as_deref() == Some(this_class_name.as_str()))
+        .map(|nested_class| nested_class.name.clone())
+        .collect();
+    for member in members {
+        compilation_context.add_class(member.replace('.', "/"))?;
+    }
+
+    let classes = inner_classes(compilation_context)?;
+    if !classes.is_empty() {
+        attributes.push(Attribute::InnerClasses {
+            name_index: wrap(compilation_context.constant_pool.add_utf8("InnerClasses"))?,
+            classes,
+        });
+    }
+    Ok(attributes)
+}
+
+/// An entry for every nested class the constant pool refers to. The entry of a member class refers
+/// to the class it is declared in, which may be nested too, so this goes on until no entry adds
+/// another class to the constant pool.
+fn inner_classes(compilation_context: &mut CompilationContext) -> CompileResult<Vec<InnerClass>> {
+    let mut classes: Vec<InnerClass> = vec![];
+    loop {
+        let constant_pool = &compilation_context.constant_pool;
+        let referred: Vec<(u16, String)> = (1..=constant_pool.len() as u16)
+            .filter_map(|index| match constant_pool.get(index) {
+                Some(Constant::Class(name_index)) => Some((index, constant_pool.try_get_utf8(*name_index).ok()?.replace('/', "."))),
+                _ => None,
+            })
+            .collect();
+
+        let new_entries: Vec<(u16, NestedClass)> = referred
+            .into_iter()
+            .filter(|(index, _)| classes.iter().all(|entry| entry.class_info_index != *index))
+            .filter_map(|(index, name)| {
+                let nested_class = compilation_context.nested_classes.iter().find(|nested_class| nested_class.name == name)?;
+                Some((index, nested_class.clone()))
+            })
+            .collect();
+        if new_entries.is_empty() {
+            return Ok(classes);
+        }
+
+        for (class_info_index, nested_class) in new_entries {
+            let outer_class_info_index = match &nested_class.outer {
+                Some(outer) => compilation_context.add_class(outer.replace('.', "/"))?,
+                None => 0,
+            };
+            let name_index = match &nested_class.simple_name {
+                Some(simple_name) => wrap(compilation_context.constant_pool.add_utf8(simple_name))?,
+                None => 0,
+            };
+            classes.push(InnerClass {
+                class_info_index,
+                outer_class_info_index,
+                name_index,
+                access_flags: nested_class.access_flags,
+            });
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::ast::class::{AstClass, AstMethod, AstType};
+use crate::compiler::descriptor::field_descriptor;
+use crate::compiler::field_builder::DeclaredField;
+use crate::compiler::instruction::{from_field_read, store_field};
+use crate::compiler::java_type::JavaType;
+use crate::compiler::method_builder::code;
+use crate::compiler::result::{wrap, CompileError, CompileResult};
+use crate::compiler::CompilationContext;
+use ristretto_classfile::attributes::{Attribute, Instruction, Record};
+use ristretto_classfile::{Field, FieldAccessFlags, FieldType, Method, MethodAccessFlags, ReferenceKind};
+
+const OBJECT_METHODS: &str = "java/lang/runtime/ObjectMethods";
+const BOOTSTRAP_DESCRIPTOR: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/TypeDescriptor;\
+    Ljava/lang/Class;Ljava/lang/String;[Ljava/lang/invoke/MethodHandle;)Ljava/lang/Object;";
+
+/// A `private final` field for each record component
+pub fn fields(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Field>> {
+    let mut fields = vec![];
+    for component in class.record_components() {
+        let descriptor = field_descriptor(component.param_type(), compilation_context)?;
+        compilation_context.fields.insert(component.param_name().to_string(), DeclaredField::new(descriptor.clone(), false, true, false));
+
+        fields.push(Field {
+            access_flags: FieldAccessFlags::PRIVATE | FieldAccessFlags::FINAL,
+            name_index: wrap(compilation_context.constant_pool.add_utf8(component.param_name()))?,
+            descriptor_index: wrap(compilation_context.constant_pool.add_utf8(&descriptor))?,
+            field_type: wrap(FieldType::parse(&descriptor))?,
+            attributes: vec![],
+        });
+    }
+    Ok(fields)
+}
+
+/// The canonical constructor of a record that does not declare one, which has the access of the
+/// record and assigns each component to its field (JLS §8.10.4)
+pub fn implicit_canonical_constructor<'a>(class: &AstClass<'a>) -> Option<AstMethod<'a>> {
+    let is_declared = class
+        .constructors()
+        .iter()
+        .any(|constructor| constructor.is_compact() || is_canonical(constructor, class));
+    if is_declared {
+        return None;
+    }
+
+    let parameters = class.record_components().clone();
+    Some(AstMethod::new("<init>", class.scope(), false, false, "void", parameters, vec![]).as_compact())
+}
+
+/// The component fields a constructor must assign itself: those of a record, in its canonical
+/// constructor unless that is compact, when they are assigned after its body (JLS §8.10.4)
+pub fn fields_to_assign<'a>(constructor: &AstMethod, class: &AstClass<'a>) -> Vec<&'a str> {
+    if !class.is_record() || constructor.is_compact() || !is_canonical(constructor, class) {
+        return vec![];
+    }
+    class.record_components().iter().map(|component| component.param_name()).collect()
+}
+
+fn is_canonical(constructor: &AstMethod, class: &AstClass) -> bool {
+    let parameter_types = constructor.parameters().iter().map(|parameter| parameter.param_type());
+    let component_types = class.record_components().iter().map(|component| component.param_type());
+    parameter_types.eq(component_types)
+}
+
+/// Assigns the parameters of a compact canonical constructor to the fields of the same name,
+/// which it does once its body has run
+pub fn component_assignments(ast_constructor: &AstMethod, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Instruction>> {
+    let mut instructions = vec![];
+    let mut slot: u16 = 1;
+    for parameter in ast_constructor.parameters() {
+        let parameter_type = java_type_of(parameter.param_type(), compilation_context)?;
+        instructions.push(Instruction::Aload_0);
+        instructions.push(parameter_type.load_instruction(slot));
+        instructions.push(store_field(parameter.param_name(), compilation_context)?);
+        slot += parameter_type.slots();
+    }
+    Ok(instructions)
+}
+
+/// The accessor of each component, and `toString`, `hashCode` and `equals`, unless the record
+/// declares them itself
+pub fn methods(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Vec<Method>> {
+    let mut methods = vec![];
+    for component in class.record_components() {
+        if is_declared(class, component.param_name(), &[]) {
+            continue;
+        }
+        let descriptor = field_descriptor(component.param_type(), compilation_context)?;
+        let component_type = java_type_of(component.param_type(), compilation_context)?;
+
+        compilation_context.is_static_context = false;
+        let mut instructions = from_field_read(component.param_name(), compilation_context)?;
+        instructions.push(component_type.return_instruction());
+        methods.push(method(component.param_name(), &format!("(){}", descriptor), MethodAccessFlags::PUBLIC, instructions, 1, compilation_context)?);
+    }
+
+    let object_methods = [
+        ("toString", &[][..], "Ljava/lang/String;", Instruction::Areturn),
+        ("hashCode", &[][..], "I", Instruction::Ireturn),
+        ("equals", &["Object"][..], "Z", Instruction::Ireturn),
+    ];
+    let this_descriptor = format!("L{};", compilation_context.this_class_name.replace('.', "/"));
+    for (name, parameters, return_descriptor, return_instruction) in object_methods {
+        if is_declared(class, name, parameters) {
+            continue;
+        }
+        let parameter_descriptors = if parameters.is_empty() { "" } else { "Ljava/lang/Object;" };
+        let bootstrap_method = object_methods_bootstrap(class, compilation_context)?;
+        let call_site = wrap(compilation_context.constant_pool.add_invoke_dynamic(
+            bootstrap_method,
+            name,
+            &format!("({}{}){}", this_descriptor, parameter_descriptors, return_descriptor),
+        ))?;
+
+        let mut instructions = vec![Instruction::Aload_0];
+        if !parameters.is_empty() {
+            instructions.push(Instruction::Aload_1);
+        }
+        instructions.extend([Instruction::Invokedynamic(call_site), return_instruction]);
+
+        let descriptor = format!("({}){}", parameter_descriptors, return_descriptor);
+        let access_flags = MethodAccessFlags::PUBLIC | MethodAccessFlags::FINAL;
+        methods.push(method(name, &descriptor, access_flags, instructions, 1 + parameters.len() as u16, compilation_context)?);
+    }
+    Ok(methods)
+}
+
+/// Whether the record declares a method, whose parameters are given by their simple type names
+fn is_declared(class: &AstClass, name: &str, parameters: &[&str]) -> bool {
+    class.methods().iter().any(|method| {
+        method.name() == name
+            && method.parameters().len() == parameters.len()
+            && method
+                .parameters()
+                .iter()
+                .zip(parameters)
+                .all(|(parameter, type_name)| parameter.param_type().name().rsplit('.').next() == Some(*type_name))
+    })
+}
+
+/// `ObjectMethods.bootstrap`, given the record class, its component names separated by `;` and
+/// a getter for each of its fields
+fn object_methods_bootstrap(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<u16> {
+    let this_class = compilation_context.this_class;
+    let object_methods = compilation_context.add_class(OBJECT_METHODS)?;
+    let bootstrap_ref = wrap(compilation_context.constant_pool.add_method_ref(object_methods, "bootstrap", BOOTSTRAP_DESCRIPTOR))?;
+    let bootstrap_handle = wrap(compilation_context.constant_pool.add_method_handle(ReferenceKind::InvokeStatic, bootstrap_ref))?;
+
+    let names = class.record_components().iter().map(|component| component.param_name()).collect::<Vec<&str>>().join(";");
+    let mut arguments = vec![this_class, wrap(compilation_context.constant_pool.add_string(names))?];
+    for component in class.record_components() {
+        let descriptor = field_descriptor(component.param_type(), compilation_context)?;
+        let field_ref = wrap(compilation_context.constant_pool.add_field_ref(this_class, component.param_name(), &descriptor))?;
+        arguments.push(wrap(compilation_context.constant_pool.add_method_handle(ReferenceKind::GetField, field_ref))?);
+    }
+
+    Ok(compilation_context.add_bootstrap_method(bootstrap_handle, arguments))
+}
+
+/// The `Record` attribute, which lists the components of the record for reflection
+pub fn record_attribute(class: &AstClass, compilation_context: &mut CompilationContext) -> CompileResult<Attribute> {
+    let mut records = vec![];
+    for component in class.record_components() {
+        let descriptor = field_descriptor(component.param_type(), compilation_context)?;
+        records.push(Record {
+            name_index: wrap(compilation_context.constant_pool.add_utf8(component.param_name()))?,
+            descriptor_index: wrap(compilation_context.constant_pool.add_utf8(&descriptor))?,
+            attributes: vec![],
+        });
+    }
+
+    Ok(Attribute::Record {
+        name_index: wrap(compilation_context.constant_pool.add_utf8("Record"))?,
+        records,
+    })
+}
+
+fn method(
+    name: &str,
+    descriptor: &str,
+    access_flags: MethodAccessFlags,
+    instructions: Vec<Instruction>,
+    max_locals: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Method> {
+    Ok(Method {
+        access_flags,
+        name_index: wrap(compilation_context.constant_pool.add_utf8(name))?,
+        descriptor_index: wrap(compilation_context.constant_pool.add_utf8(descriptor))?,
+        attributes: vec![code(
+            name,
+            descriptor,
+            access_flags.contains(MethodAccessFlags::STATIC),
+            instructions,
+            max_locals,
+            compilation_context,
+        )?],
+    })
+}
+
+fn java_type_of(ast_type: AstType, compilation_context: &mut CompilationContext) -> CompileResult<JavaType> {
+    let descriptor = field_descriptor(ast_type, compilation_context)?;
+    JavaType::from_descriptor(&descriptor).ok_or_else(|| CompileError::UnknownClass(ast_type.name().to_string()))
+}
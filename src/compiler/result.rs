@@ -1,3 +1,6 @@
+use crate::ast::result::SyntaxError;
+use crate::scanner::{FileId, LexicalDiagnostic, SourceSpan};
+use std::fmt::{Display, Formatter};
 use std::io::Error;
 
 pub type CompileResult<T> = Result<T, CompileError>;
@@ -5,10 +8,216 @@ pub type CompileResult<T> = Result<T, CompileError>;
 #[derive(Debug)]
 pub enum CompileError {
     Ristretto(ristretto_classfile::Error),
+    /// A fault in the compiler itself, such as code it generated that would not verify
+    Internal(String),
     FileSystem(Error),
+    /// A construct the compiler does not compile yet
+    Unsupported(String),
     UnknownClass(String),
     UnknownMethod { class: String, method: String },
     UnknownField { class: String, field: String },
+    LiteralOutOfRange(String),
+    IncompatibleTypes { expected: String, found: String },
+    BadOperandTypes { operator: String, left: String, right: String },
+    BadOperandType { operator: String, operand: String },
+    NonStaticReference(String),
+    /// A local variable declared again while it is in scope
+    DuplicateVariable { name: String, method: String, span: SourceSpan },
+    /// A local variable read before it has definitely been assigned (JLS §16)
+    UninitializedVariable { name: String, method: String, span: SourceSpan },
+    /// A final variable assigned a second time
+    AssignmentToFinal { name: String, method: String, span: SourceSpan },
+    /// A local variable that a local or anonymous class uses, but that is assigned more than once
+    /// (JLS §8.1.3)
+    NotEffectivelyFinal { name: String, method: String, span: SourceSpan },
+    /// A statement that no path through the code reaches (JLS §14.22)
+    UnreachableStatement { method: String, span: SourceSpan },
+    /// An initializer block that cannot complete normally (JLS §8.6)
+    InitializerCannotComplete,
+    BreakOutsideLoop,
+    ContinueOutsideLoop,
+    UndefinedLabel(String),
+    /// A label used again inside the statement it labels
+    DuplicateLabel(String),
+    /// A `continue` naming a label that is not on a loop
+    NotALoopLabel(String),
+    /// A method with a return type whose body can complete without returning a value (JLS §8.4.7),
+    /// found at its closing brace
+    MissingReturn { method: String, span: SourceSpan },
+    /// A `return` without a value in a method that has a return type
+    MissingReturnValue,
+    /// A `return` with a value in a `void` method or a constructor
+    UnexpectedReturnValue,
+    /// A `return` in an initializer, which cannot return (JLS §14.17)
+    ReturnOutsideMethod,
+    /// The expression of an enhanced `for` statement, being neither an array nor an `Iterable`
+    NotIterable(String),
+    FinalSuperclass(String),
+    CyclicInheritance(String),
+    UnexpectedInterface(String),
+    InterfaceExpected(String),
+    RepeatedInterface(String),
+    DuplicateClass(String),
+    /// A public top level type must be declared in a file of the same name (JLS §7.6)
+    PublicTypeInWrongFile { type_name: String, path: String },
+    Lexical(Vec<LexicalDiagnostic>),
+    Syntax(SyntaxError),
+    /// An error found in one of several source files compiled together
+    Source { path: String, error: Box<CompileError> },
+    /// An error found in the code of a method, at the statement or declaration being compiled
+    /// when it was found
+    Located { method: String, span: SourceSpan, error: Box<CompileError> },
+}
+
+impl CompileError {
+    pub fn duplicate_variable(name: &str) -> Self {
+        CompileError::DuplicateVariable { name: name.to_string(), method: String::new(), span: SourceSpan::default() }
+    }
+
+    pub fn uninitialized_variable(name: &str) -> Self {
+        CompileError::UninitializedVariable { name: name.to_string(), method: String::new(), span: SourceSpan::default() }
+    }
+
+    pub fn assignment_to_final(name: &str) -> Self {
+        CompileError::AssignmentToFinal { name: name.to_string(), method: String::new(), span: SourceSpan::default() }
+    }
+
+    pub fn not_effectively_final(name: &str) -> Self {
+        CompileError::NotEffectivelyFinal { name: name.to_string(), method: String::new(), span: SourceSpan::default() }
+    }
+
+    pub fn unreachable_statement(span: SourceSpan) -> Self {
+        CompileError::UnreachableStatement { method: String::new(), span }
+    }
+
+    /// Places an error found in the code of a method, at the statement or declaration being
+    /// compiled when it was found, unless it already knows where it is
+    pub fn located(mut self, method_name: &str, location: SourceSpan) -> Self {
+        if let Some((method, span)) = self.location_mut() {
+            if method.is_empty() {
+                *method = method_name.to_string();
+            }
+            if *span == SourceSpan::default() {
+                *span = location;
+            }
+            return self;
+        }
+        match self {
+            CompileError::FileSystem(_)
+            | CompileError::Lexical(_)
+            | CompileError::Syntax(_)
+            | CompileError::Source { .. }
+            | CompileError::Located { .. } => self,
+            error => CompileError::Located {
+                method: method_name.to_string(),
+                span: location,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// The error itself, apart from the file and method it was found in
+    pub fn kind(&self) -> &CompileError {
+        match self {
+            CompileError::Source { error, .. } | CompileError::Located { error, .. } => error.kind(),
+            error => error,
+        }
+    }
+
+    /// The file the error was found in, for errors that know where in the source they are
+    pub fn file_id(&self) -> Option<FileId> {
+        match self {
+            CompileError::Lexical(diagnostics) => diagnostics.first().map(|diagnostic| diagnostic.span().file_id()),
+            CompileError::Syntax(error) => Some(error.span().file_id()),
+            CompileError::DuplicateVariable { span, .. }
+            | CompileError::UninitializedVariable { span, .. }
+            | CompileError::AssignmentToFinal { span, .. }
+            | CompileError::NotEffectivelyFinal { span, .. }
+            | CompileError::UnreachableStatement { span, .. }
+            | CompileError::MissingReturn { span, .. }
+            | CompileError::Located { span, .. } if *span != SourceSpan::default() => Some(span.file_id()),
+            _ => None,
+        }
+    }
+
+    fn location_mut(&mut self) -> Option<(&mut String, &mut SourceSpan)> {
+        match self {
+            CompileError::DuplicateVariable { method, span, .. }
+            | CompileError::UninitializedVariable { method, span, .. }
+            | CompileError::AssignmentToFinal { method, span, .. }
+            | CompileError::NotEffectivelyFinal { method, span, .. }
+            | CompileError::UnreachableStatement { method, span }
+            | CompileError::MissingReturn { method, span } => Some((method, span)),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that know where they are found format as `line:column: message`, as syntax errors do,
+/// to be prefixed with the file name when reported. The messages follow those of javac.
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileError::Ristretto(error) => write!(f, "cannot write class file: {}", error),
+            CompileError::Internal(message) => write!(f, "internal compiler error: {}", message),
+            CompileError::FileSystem(error) => write!(f, "{}", error),
+            CompileError::Unsupported(construct) => write!(f, "not supported yet: {}", construct),
+            CompileError::UnknownClass(class) => write!(f, "cannot find class {}", class),
+            CompileError::UnknownMethod { class, method } => write!(f, "cannot find method {} in class {}", method, class),
+            CompileError::UnknownField { class, field } => write!(f, "cannot find field {} in class {}", field, class),
+            CompileError::LiteralOutOfRange(message) => write!(f, "{}", message),
+            CompileError::IncompatibleTypes { expected, found } => {
+                write!(f, "incompatible types: {} cannot be converted to {}", found, expected)
+            }
+            CompileError::BadOperandTypes { operator, left, right } => {
+                write!(f, "bad operand types for binary operator '{}': {} and {}", operator, left, right)
+            }
+            CompileError::BadOperandType { operator, operand } => write!(f, "bad operand type {} for unary operator '{}'", operand, operator),
+            CompileError::NonStaticReference(name) => write!(f, "non-static {} cannot be referenced from a static context", name),
+            CompileError::DuplicateVariable { name, method, span } => {
+                write!(f, "{}: variable {} is already defined in method {}", span, name, method)
+            }
+            CompileError::UninitializedVariable { name, method, span } => {
+                write!(f, "{}: variable {} might not have been initialized in method {}", span, name, method)
+            }
+            CompileError::AssignmentToFinal { name, method, span } => {
+                write!(f, "{}: cannot assign a value to final variable {} in method {}", span, name, method)
+            }
+            CompileError::NotEffectivelyFinal { name, method, span } => write!(
+                f,
+                "{}: local variable {} is used in a local or anonymous class, so must be final or effectively final, in method {}",
+                span, name, method
+            ),
+            CompileError::UnreachableStatement { method, span } => write!(f, "{}: unreachable statement in method {}", span, method),
+            CompileError::InitializerCannotComplete => write!(f, "initializer must be able to complete normally"),
+            CompileError::BreakOutsideLoop => write!(f, "break outside switch or loop"),
+            CompileError::ContinueOutsideLoop => write!(f, "continue outside of loop"),
+            CompileError::UndefinedLabel(label) => write!(f, "undefined label: {}", label),
+            CompileError::DuplicateLabel(label) => write!(f, "label {} already in use", label),
+            CompileError::NotALoopLabel(label) => write!(f, "not a loop label: {}", label),
+            CompileError::MissingReturn { method, span } => write!(f, "{}: missing return statement in method {}", span, method),
+            CompileError::MissingReturnValue => write!(f, "missing return value"),
+            CompileError::UnexpectedReturnValue => write!(f, "unexpected return value"),
+            CompileError::ReturnOutsideMethod => write!(f, "return outside method"),
+            CompileError::NotIterable(found) => write!(f, "for-each not applicable to expression type {}", found),
+            CompileError::FinalSuperclass(class) => write!(f, "cannot inherit from final {}", class),
+            CompileError::CyclicInheritance(class) => write!(f, "cyclic inheritance involving {}", class),
+            CompileError::UnexpectedInterface(class) => write!(f, "no interface expected here: {}", class),
+            CompileError::InterfaceExpected(class) => write!(f, "interface expected here: {}", class),
+            CompileError::RepeatedInterface(class) => write!(f, "repeated interface {}", class),
+            CompileError::DuplicateClass(class) => write!(f, "duplicate class {}", class),
+            CompileError::PublicTypeInWrongFile { type_name, path } => {
+                write!(f, "{}: class {} is public, should be declared in a file named {}.java", path, type_name, type_name)
+            }
+            CompileError::Lexical(diagnostics) => {
+                let diagnostics: Vec<String> = diagnostics.iter().map(LexicalDiagnostic::to_string).collect();
+                write!(f, "{}", diagnostics.join("\n"))
+            }
+            CompileError::Syntax(error) => write!(f, "{}", error),
+            CompileError::Source { path, error } => write!(f, "{}:{}", path, error),
+            CompileError::Located { method, span, error } => write!(f, "{}: {} in method {}", span, error, method),
+        }
+    }
 }
 
 pub fn wrap<T>(result: ristretto_classfile::Result<T>) -> CompileResult<T> {
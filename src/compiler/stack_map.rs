@@ -0,0 +1,605 @@
+use crate::compiler::frame_size::{internal_error, member_name_and_descriptor, successors};
+use crate::compiler::inheritance;
+use crate::compiler::java_type::JavaType;
+use crate::compiler::result::{wrap, CompileResult};
+use crate::compiler::CompilationContext;
+use crate::java::ClassLoader;
+use ristretto_classfile::attributes::{ArrayType, ExceptionTableEntry, Instruction, StackFrame, VerificationType};
+use ristretto_classfile::{Constant, ConstantPool};
+use std::io::Cursor;
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+const JAVA_LANG_THROWABLE: &str = "java/lang/Throwable";
+
+/// The type of a local variable or operand stack entry as the verifier sees it (JVMS §4.10.1.2).
+/// Classes and arrays are held by their internal names, such as `java/lang/String` or `[I`.
+#[derive(Clone, Debug, PartialEq)]
+enum FrameType {
+    Top,
+    Int,
+    Float,
+    Long,
+    Double,
+    Null,
+    UninitializedThis,
+    /// An object made by the `new` at the index, whose constructor has not been called yet
+    Uninitialized(usize),
+    Object(String),
+}
+
+impl FrameType {
+    fn from_java_type(java_type: &JavaType) -> Self {
+        match java_type {
+            JavaType::Boolean | JavaType::Byte | JavaType::Char | JavaType::Short | JavaType::Int => FrameType::Int,
+            JavaType::Long => FrameType::Long,
+            JavaType::Float => FrameType::Float,
+            JavaType::Double => FrameType::Double,
+            JavaType::Void => FrameType::Top,
+            JavaType::Reference(class) => FrameType::Object(class.clone()),
+            JavaType::Array(_) => FrameType::Object(java_type.descriptor()),
+        }
+    }
+
+    fn slots(&self) -> usize {
+        match self {
+            FrameType::Long | FrameType::Double => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// The types in the local variables and on the operand stack before an instruction runs
+#[derive(Clone, Debug, PartialEq)]
+struct Frame {
+    /// One for each slot, where the second slot of a long or double is `Top`
+    locals: Vec<FrameType>,
+    /// One for each value, whatever slots it takes
+    stack: Vec<FrameType>,
+}
+
+impl Frame {
+    fn push(&mut self, frame_type: FrameType) {
+        self.stack.push(frame_type);
+    }
+
+    fn pop(&mut self) -> CompileResult<FrameType> {
+        self.stack.pop().ok_or_else(|| internal_error("a value is taken from an empty stack".to_string()))
+    }
+
+    fn pop_values(&mut self, count: usize) -> CompileResult<()> {
+        for _ in 0..count {
+            self.pop()?;
+        }
+        Ok(())
+    }
+
+    /// Takes the values that fill the slots at the top of the stack, for the instructions that
+    /// work on slots rather than values, such as `pop2` and `dup_x1`
+    fn pop_slots(&mut self, slots: usize) -> CompileResult<Vec<FrameType>> {
+        let mut values = vec![];
+        let mut taken = 0;
+        while taken < slots {
+            let value = self.pop()?;
+            taken += value.slots();
+            values.insert(0, value);
+        }
+        if taken != slots {
+            return Err(internal_error(format!("{} slots of the stack split a long or double", slots)));
+        }
+        Ok(values)
+    }
+
+    /// Copies the values in the top slots of the stack beneath those in the slots under them
+    fn duplicate(&mut self, top_slots: usize, under_slots: usize) -> CompileResult<()> {
+        let top = self.pop_slots(top_slots)?;
+        let under = self.pop_slots(under_slots)?;
+        self.stack.extend(top.iter().cloned());
+        self.stack.extend(under);
+        self.stack.extend(top);
+        Ok(())
+    }
+
+    fn load(&mut self, slot: impl Into<usize>) -> CompileResult<()> {
+        let slot = slot.into();
+        let local = self.locals.get(slot).cloned().ok_or_else(|| internal_error(format!("there is no local variable slot {}", slot)))?;
+        self.push(local);
+        Ok(())
+    }
+
+    /// Stores the value at the top of the stack, which overwrites any long or double it overlaps
+    fn store(&mut self, slot: impl Into<usize>) -> CompileResult<()> {
+        let slot = slot.into();
+        let value = self.pop()?;
+        if slot + value.slots() > self.locals.len() {
+            return Err(internal_error(format!("there is no local variable slot {}", slot + value.slots() - 1)));
+        }
+        if slot > 0 && self.locals[slot - 1].slots() == 2 {
+            self.locals[slot - 1] = FrameType::Top;
+        }
+        if value.slots() == 2 {
+            self.locals[slot + 1] = FrameType::Top;
+        }
+        self.locals[slot] = value;
+        Ok(())
+    }
+
+    /// Once its constructor has been called, every copy of an uninitialized object is an instance
+    /// of its class
+    fn initialize(&mut self, uninitialized: &FrameType, class: &str) {
+        for frame_type in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+            if frame_type == uninitialized {
+                *frame_type = FrameType::Object(class.to_string());
+            }
+        }
+    }
+}
+
+/// The frames of the `StackMapTable` of a method (JVMS §4.7.4), which the verifier checks the
+/// code against. The types of the local variables and operand stack are followed along every path
+/// through the code, merging them where paths join, and a frame is made at each instruction that
+/// is jumped to, or that handles an exception. Each frame is encoded by how it differs from the
+/// frame before it, in the most compact form that says so.
+pub fn stack_map_frames(
+    name: &str,
+    descriptor: &str,
+    is_static: bool,
+    code: &[Instruction],
+    exception_table: &[ExceptionTableEntry],
+    max_locals: u16,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<StackFrame>> {
+    let this_class = compilation_context.this_class_name.replace('.', "/");
+    let entry = entry_frame(name, descriptor, is_static, &this_class, max_locals)?;
+    let frames = follow_paths(code, exception_table, &entry, &this_class, compilation_context)?;
+
+    let mut needs_frame = vec![false; code.len()];
+    for (index, instruction) in code.iter().enumerate() {
+        for target in jump_targets(instruction, index)? {
+            needs_frame[target] = true;
+        }
+    }
+    for handler in exception_table {
+        needs_frame[handler.handler_pc as usize] = true;
+    }
+
+    let byte_offsets = byte_offsets(code)?;
+    let mut previous_locals = verification_types(&listed_locals(&entry), &byte_offsets, compilation_context)?;
+    let mut previous_index = None;
+    let mut stack_frames = vec![];
+    for (index, frame) in frames.iter().enumerate().filter(|(index, _)| needs_frame[*index]) {
+        let locals = verification_types(&listed_locals(frame), &byte_offsets, compilation_context)?;
+        let stack = verification_types(&frame.stack, &byte_offsets, compilation_context)?;
+        // The first frame is at its offset, and each after it one more than its delta past the last
+        let (instruction_delta, byte_delta) = match previous_index {
+            None => (index as u16, byte_offsets[index]),
+            Some(previous) => ((index - previous - 1) as u16, byte_offsets[index] - byte_offsets[previous] - 1),
+        };
+        stack_frames.push(stack_frame(&previous_locals, &locals, stack, instruction_delta, byte_delta));
+        previous_locals = locals;
+        previous_index = Some(index);
+    }
+    Ok(stack_frames)
+}
+
+/// The frame the code starts with, which holds `this` unless the method is static, and then the
+/// parameters. `this` has not been initialized until a constructor calls another.
+fn entry_frame(name: &str, descriptor: &str, is_static: bool, this_class: &str, max_locals: u16) -> CompileResult<Frame> {
+    let (parameter_types, _) = JavaType::from_method_descriptor(descriptor)
+        .ok_or_else(|| internal_error(format!("bad method descriptor {}", descriptor)))?;
+    let mut locals = vec![];
+    if !is_static {
+        locals.push(if name == "<init>" { FrameType::UninitializedThis } else { FrameType::Object(this_class.to_string()) });
+    }
+    for parameter_type in &parameter_types {
+        let frame_type = FrameType::from_java_type(parameter_type);
+        if frame_type.slots() == 2 {
+            locals.extend([frame_type, FrameType::Top]);
+        } else {
+            locals.push(frame_type);
+        }
+    }
+    if locals.len() > max_locals as usize {
+        return Err(internal_error(format!("the parameters take {} slots of {}", locals.len(), max_locals)));
+    }
+    locals.resize(max_locals as usize, FrameType::Top);
+    Ok(Frame { locals, stack: vec![] })
+}
+
+/// The frame before each instruction, merged from every path that reaches it
+fn follow_paths(
+    code: &[Instruction],
+    exception_table: &[ExceptionTableEntry],
+    entry: &Frame,
+    this_class: &str,
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<Frame>> {
+    let mut frames: Vec<Option<Frame>> = vec![None; code.len()];
+    let mut pending = vec![];
+    reach(0, entry.clone(), &mut frames, &mut pending, &mut compilation_context.class_loader)?;
+
+    while let Some(index) = pending.pop() {
+        let Some(before) = frames[index].clone() else {
+            continue;
+        };
+        let after = execute(code, index, before.clone(), this_class, &compilation_context.constant_pool)?;
+
+        for handler in exception_table.iter().filter(|entry| entry.range_pc.contains(&(index as u16))) {
+            let exception = match handler.catch_type {
+                0 => JAVA_LANG_THROWABLE.to_string(),
+                catch_type => wrap(compilation_context.constant_pool.try_get_class(catch_type))?.to_string(),
+            };
+            // A handler can be reached before or after the instruction changes a local variable
+            for locals in [&before.locals, &after.locals] {
+                let frame = Frame { locals: locals.clone(), stack: vec![FrameType::Object(exception.clone())] };
+                reach(handler.handler_pc as usize, frame, &mut frames, &mut pending, &mut compilation_context.class_loader)?;
+            }
+        }
+        for successor in successors(&code[index], index)? {
+            reach(successor, after.clone(), &mut frames, &mut pending, &mut compilation_context.class_loader)?;
+        }
+    }
+
+    frames
+        .into_iter()
+        .enumerate()
+        .map(|(index, frame)| frame.ok_or_else(|| internal_error(format!("the code at {} is never reached", index))))
+        .collect()
+}
+
+fn reach(
+    index: usize,
+    frame: Frame,
+    frames: &mut [Option<Frame>],
+    pending: &mut Vec<usize>,
+    class_loader: &mut ClassLoader,
+) -> CompileResult<()> {
+    let merged = match frames.get(index) {
+        None => return Err(internal_error(format!("the code runs off its end at {}", index))),
+        Some(None) => frame,
+        Some(Some(existing)) => merge(existing, &frame, index, class_loader)?,
+    };
+    if frames[index].as_ref() != Some(&merged) {
+        frames[index] = Some(merged);
+        pending.push(index);
+    }
+    Ok(())
+}
+
+/// The frame that holds what both frames do, for an instruction reached by paths with each
+fn merge(existing: &Frame, incoming: &Frame, index: usize, class_loader: &mut ClassLoader) -> CompileResult<Frame> {
+    if existing.stack.len() != incoming.stack.len() {
+        return Err(internal_error(format!(
+            "the stack at {} holds {} values on one path but {} on another",
+            index,
+            existing.stack.len(),
+            incoming.stack.len()
+        )));
+    }
+    let mut merge_all = |first: &[FrameType], second: &[FrameType]| -> Vec<FrameType> {
+        first.iter().zip(second).map(|(first, second)| merge_types(first, second, class_loader)).collect()
+    };
+    let frame = Frame {
+        locals: merge_all(&existing.locals, &incoming.locals),
+        stack: merge_all(&existing.stack, &incoming.stack),
+    };
+    if frame.stack.contains(&FrameType::Top) {
+        return Err(internal_error(format!("the stack at {} holds values of different types on different paths", index)));
+    }
+    Ok(frame)
+}
+
+/// A local variable that holds values of unrelated types on different paths cannot be used
+/// where they join, and is `Top`
+fn merge_types(first: &FrameType, second: &FrameType, class_loader: &mut ClassLoader) -> FrameType {
+    match (first, second) {
+        _ if first == second => first.clone(),
+        (FrameType::Null, FrameType::Object(_)) => second.clone(),
+        (FrameType::Object(_), FrameType::Null) => first.clone(),
+        (FrameType::Object(first), FrameType::Object(second)) => FrameType::Object(common_supertype(first, second, class_loader)),
+        _ => FrameType::Top,
+    }
+}
+
+/// The nearest type both classes are assignable to: one of them, if it is a supertype of the
+/// other, or otherwise the nearest superclass of the first that the second extends. The verifier
+/// takes any class as an interface, so different arrays and anything unknown are `Object`.
+fn common_supertype(first: &str, second: &str, class_loader: &mut ClassLoader) -> String {
+    if first.starts_with('[') || second.starts_with('[') {
+        return JAVA_LANG_OBJECT.to_string();
+    }
+    let (first_name, second_name) = (first.replace('/', "."), second.replace('/', "."));
+    if inheritance::is_subtype(&first_name, &second_name, class_loader) {
+        return second.to_string();
+    }
+    if inheritance::is_subtype(&second_name, &first_name, class_loader) {
+        return first.to_string();
+    }
+    let mut superclass = super_class(&first_name, class_loader);
+    while let Some(candidate) = superclass {
+        if inheritance::is_subtype(&second_name, &candidate, class_loader) {
+            return candidate.replace('.', "/");
+        }
+        superclass = super_class(&candidate, class_loader);
+    }
+    JAVA_LANG_OBJECT.to_string()
+}
+
+fn super_class(class_name: &str, class_loader: &mut ClassLoader) -> Option<String> {
+    class_loader.load(class_name).and_then(|class| class.super_class()).map(str::to_string)
+}
+
+/// The instructions a branch or switch jumps to, which each need a frame. A conditional branch
+/// falls through to the instruction after it, which is the first of its successors.
+fn jump_targets(instruction: &Instruction, index: usize) -> CompileResult<Vec<usize>> {
+    let successors = successors(instruction, index)?;
+    let targets = match instruction {
+        Instruction::Goto(_) | Instruction::Goto_w(_) | Instruction::Tableswitch(_) | Instruction::Lookupswitch(_) => successors,
+        _ => successors.into_iter().skip(1).collect(),
+    };
+    Ok(targets)
+}
+
+/// The frame after the instruction at the index runs
+fn execute(code: &[Instruction], index: usize, mut frame: Frame, this_class: &str, constant_pool: &ConstantPool) -> CompileResult<Frame> {
+    use Instruction::*;
+    match &code[index] {
+        Nop | Iinc(..) | Iinc_w(..) | Goto(_) | Goto_w(_) | Return | Wide | Breakpoint | Impdep1 | Impdep2 | Jsr(_) | Jsr_w(_)
+        | Ret(_) | Ret_w(_) => {}
+        Aconst_null => frame.push(FrameType::Null),
+        Iconst_m1 | Iconst_0 | Iconst_1 | Iconst_2 | Iconst_3 | Iconst_4 | Iconst_5 | Bipush(_) | Sipush(_) => frame.push(FrameType::Int),
+        Fconst_0 | Fconst_1 | Fconst_2 => frame.push(FrameType::Float),
+        Lconst_0 | Lconst_1 => frame.push(FrameType::Long),
+        Dconst_0 | Dconst_1 => frame.push(FrameType::Double),
+        Ldc(constant) => frame.push(constant_type(u16::from(*constant), constant_pool)?),
+        Ldc_w(constant) | Ldc2_w(constant) => frame.push(constant_type(*constant, constant_pool)?),
+
+        Iload(slot) | Lload(slot) | Fload(slot) | Dload(slot) | Aload(slot) => frame.load(*slot)?,
+        Iload_w(slot) | Lload_w(slot) | Fload_w(slot) | Dload_w(slot) | Aload_w(slot) => frame.load(*slot)?,
+        Iload_0 | Lload_0 | Fload_0 | Dload_0 | Aload_0 => frame.load(0usize)?,
+        Iload_1 | Lload_1 | Fload_1 | Dload_1 | Aload_1 => frame.load(1usize)?,
+        Iload_2 | Lload_2 | Fload_2 | Dload_2 | Aload_2 => frame.load(2usize)?,
+        Iload_3 | Lload_3 | Fload_3 | Dload_3 | Aload_3 => frame.load(3usize)?,
+        Istore(slot) | Lstore(slot) | Fstore(slot) | Dstore(slot) | Astore(slot) => frame.store(*slot)?,
+        Istore_w(slot) | Lstore_w(slot) | Fstore_w(slot) | Dstore_w(slot) | Astore_w(slot) => frame.store(*slot)?,
+        Istore_0 | Lstore_0 | Fstore_0 | Dstore_0 | Astore_0 => frame.store(0usize)?,
+        Istore_1 | Lstore_1 | Fstore_1 | Dstore_1 | Astore_1 => frame.store(1usize)?,
+        Istore_2 | Lstore_2 | Fstore_2 | Dstore_2 | Astore_2 => frame.store(2usize)?,
+        Istore_3 | Lstore_3 | Fstore_3 | Dstore_3 | Astore_3 => frame.store(3usize)?,
+
+        Iaload | Baload | Caload | Saload => replace(&mut frame, 2, FrameType::Int)?,
+        Laload => replace(&mut frame, 2, FrameType::Long)?,
+        Faload => replace(&mut frame, 2, FrameType::Float)?,
+        Daload => replace(&mut frame, 2, FrameType::Double)?,
+        Aaload => {
+            frame.pop()?;
+            let component = match frame.pop()? {
+                FrameType::Object(array) if array.starts_with("[L") => FrameType::Object(array[2..array.len() - 1].to_string()),
+                FrameType::Object(array) if array.starts_with("[[") => FrameType::Object(array[1..].to_string()),
+                FrameType::Null => FrameType::Null,
+                other => return Err(internal_error(format!("aaload at {} reads from {:?}", index, other))),
+            };
+            frame.push(component);
+        }
+        Iastore | Lastore | Fastore | Dastore | Aastore | Bastore | Castore | Sastore => frame.pop_values(3)?,
+
+        Pop => frame.pop_slots(1).map(|_| ())?,
+        Pop2 => frame.pop_slots(2).map(|_| ())?,
+        Dup => frame.duplicate(1, 0)?,
+        Dup_x1 => frame.duplicate(1, 1)?,
+        Dup_x2 => frame.duplicate(1, 2)?,
+        Dup2 => frame.duplicate(2, 0)?,
+        Dup2_x1 => frame.duplicate(2, 1)?,
+        Dup2_x2 => frame.duplicate(2, 2)?,
+        Swap => {
+            let first = frame.pop()?;
+            let second = frame.pop()?;
+            frame.push(first);
+            frame.push(second);
+        }
+
+        Iadd | Isub | Imul | Idiv | Irem | Ishl | Ishr | Iushr | Iand | Ior | Ixor => replace(&mut frame, 2, FrameType::Int)?,
+        Ladd | Lsub | Lmul | Ldiv | Lrem | Lshl | Lshr | Lushr | Land | Lor | Lxor => replace(&mut frame, 2, FrameType::Long)?,
+        Fadd | Fsub | Fmul | Fdiv | Frem => replace(&mut frame, 2, FrameType::Float)?,
+        Dadd | Dsub | Dmul | Ddiv | Drem => replace(&mut frame, 2, FrameType::Double)?,
+        Ineg | L2i | F2i | D2i | I2b | I2c | I2s => replace(&mut frame, 1, FrameType::Int)?,
+        Lneg | I2l | F2l | D2l => replace(&mut frame, 1, FrameType::Long)?,
+        Fneg | I2f | L2f | D2f => replace(&mut frame, 1, FrameType::Float)?,
+        Dneg | I2d | L2d | F2d => replace(&mut frame, 1, FrameType::Double)?,
+        Lcmp | Fcmpl | Fcmpg | Dcmpl | Dcmpg => replace(&mut frame, 2, FrameType::Int)?,
+
+        Ifeq(_) | Ifne(_) | Iflt(_) | Ifge(_) | Ifgt(_) | Ifle(_) | Ifnull(_) | Ifnonnull(_) => frame.pop_values(1)?,
+        If_icmpeq(_) | If_icmpne(_) | If_icmplt(_) | If_icmpge(_) | If_icmpgt(_) | If_icmple(_) | If_acmpeq(_) | If_acmpne(_) => {
+            frame.pop_values(2)?
+        }
+        Tableswitch(_) | Lookupswitch(_) => frame.pop_values(1)?,
+        Ireturn | Lreturn | Freturn | Dreturn | Areturn | Athrow | Monitorenter | Monitorexit => frame.pop_values(1)?,
+
+        Getstatic(field_ref) => frame.push(field_type(*field_ref, constant_pool)?),
+        Putstatic(_) => frame.pop_values(1)?,
+        Getfield(field_ref) => replace(&mut frame, 1, field_type(*field_ref, constant_pool)?)?,
+        Putfield(_) => frame.pop_values(2)?,
+        Invokevirtual(method_ref) | Invokespecial(method_ref) | Invokeinterface(method_ref, _) | Invokestatic(method_ref)
+        | Invokedynamic(method_ref) => {
+            let (name, descriptor) = member_name_and_descriptor(*method_ref, constant_pool)?;
+            let (parameter_types, return_type) = JavaType::from_method_descriptor(descriptor)
+                .ok_or_else(|| internal_error(format!("bad method descriptor {}", descriptor)))?;
+            frame.pop_values(parameter_types.len())?;
+            if !matches!(code[index], Invokestatic(_) | Invokedynamic(_)) {
+                let receiver = frame.pop()?;
+                if name == "<init>" {
+                    let class = match receiver {
+                        FrameType::UninitializedThis => this_class.to_string(),
+                        FrameType::Uninitialized(new_index) => new_class(code, new_index, constant_pool)?,
+                        other => return Err(internal_error(format!("a constructor is called at {} on {:?}", index, other))),
+                    };
+                    frame.initialize(&receiver, &class);
+                }
+            }
+            if return_type != JavaType::Void {
+                frame.push(FrameType::from_java_type(&return_type));
+            }
+        }
+
+        New(_) => frame.push(FrameType::Uninitialized(index)),
+        Newarray(array_type) => replace(&mut frame, 1, FrameType::Object(primitive_array(array_type).to_string()))?,
+        Anewarray(class) => {
+            let component = wrap(constant_pool.try_get_class(*class))?;
+            let array = if component.starts_with('[') { format!("[{}", component) } else { format!("[L{};", component) };
+            replace(&mut frame, 1, FrameType::Object(array))?
+        }
+        Multianewarray(class, dimensions) => {
+            let array = wrap(constant_pool.try_get_class(*class))?.to_string();
+            replace(&mut frame, *dimensions as usize, FrameType::Object(array))?
+        }
+        Arraylength | Instanceof(_) => replace(&mut frame, 1, FrameType::Int)?,
+        Checkcast(class) => replace(&mut frame, 1, FrameType::Object(wrap(constant_pool.try_get_class(*class))?.to_string()))?,
+    }
+    Ok(frame)
+}
+
+/// Takes the operands of an instruction from the stack and puts its result there
+fn replace(frame: &mut Frame, operands: usize, result: FrameType) -> CompileResult<()> {
+    frame.pop_values(operands)?;
+    frame.push(result);
+    Ok(())
+}
+
+fn constant_type(index: u16, constant_pool: &ConstantPool) -> CompileResult<FrameType> {
+    let frame_type = match constant_pool.get(index) {
+        Some(Constant::Integer(_)) => FrameType::Int,
+        Some(Constant::Float(_)) => FrameType::Float,
+        Some(Constant::Long(_)) => FrameType::Long,
+        Some(Constant::Double(_)) => FrameType::Double,
+        Some(Constant::String(_)) => FrameType::Object("java/lang/String".to_string()),
+        Some(Constant::Class(_)) => FrameType::Object("java/lang/Class".to_string()),
+        Some(Constant::MethodType(_)) => FrameType::Object("java/lang/invoke/MethodType".to_string()),
+        Some(Constant::MethodHandle { .. }) => FrameType::Object("java/lang/invoke/MethodHandle".to_string()),
+        other => return Err(internal_error(format!("constant {} cannot be loaded, being {:?}", index, other))),
+    };
+    Ok(frame_type)
+}
+
+fn field_type(field_ref: u16, constant_pool: &ConstantPool) -> CompileResult<FrameType> {
+    let (_, descriptor) = member_name_and_descriptor(field_ref, constant_pool)?;
+    JavaType::from_descriptor(descriptor)
+        .map(|field_type| FrameType::from_java_type(&field_type))
+        .ok_or_else(|| internal_error(format!("bad field descriptor {}", descriptor)))
+}
+
+/// The class of the object made by the `new` at the index
+fn new_class(code: &[Instruction], index: usize, constant_pool: &ConstantPool) -> CompileResult<String> {
+    match code.get(index) {
+        Some(Instruction::New(class)) => Ok(wrap(constant_pool.try_get_class(*class))?.to_string()),
+        other => Err(internal_error(format!("expected new at {} but found {:?}", index, other))),
+    }
+}
+
+fn primitive_array(array_type: &ArrayType) -> &'static str {
+    match array_type {
+        ArrayType::Boolean => "[Z",
+        ArrayType::Char => "[C",
+        ArrayType::Float => "[F",
+        ArrayType::Double => "[D",
+        ArrayType::Byte => "[B",
+        ArrayType::Short => "[S",
+        ArrayType::Int => "[I",
+        ArrayType::Long => "[J",
+    }
+}
+
+/// The local variables as a frame lists them, where a long or double stands for both its slots
+/// and the unused slots at the end are left out
+fn listed_locals(frame: &Frame) -> Vec<FrameType> {
+    let mut locals = vec![];
+    let mut slot = 0;
+    while let Some(local) = frame.locals.get(slot) {
+        slot += local.slots();
+        locals.push(local.clone());
+    }
+    while locals.last() == Some(&FrameType::Top) {
+        locals.pop();
+    }
+    locals
+}
+
+/// Where each instruction starts in the bytes of the code, which depends on the instructions
+/// before it, as switches are padded to a multiple of four bytes
+fn byte_offsets(code: &[Instruction]) -> CompileResult<Vec<u16>> {
+    let mut bytes = Cursor::new(vec![]);
+    let mut offsets = vec![];
+    for instruction in code {
+        offsets.push(bytes.position() as u16);
+        wrap(instruction.to_bytes(&mut bytes))?;
+    }
+    Ok(offsets)
+}
+
+/// An uninitialized object is recorded by the offset of the `new` that made it
+fn verification_types(
+    frame_types: &[FrameType],
+    byte_offsets: &[u16],
+    compilation_context: &mut CompilationContext,
+) -> CompileResult<Vec<VerificationType>> {
+    let mut verification_types = vec![];
+    for frame_type in frame_types {
+        verification_types.push(match frame_type {
+            FrameType::Top => VerificationType::Top,
+            FrameType::Int => VerificationType::Integer,
+            FrameType::Float => VerificationType::Float,
+            FrameType::Long => VerificationType::Long,
+            FrameType::Double => VerificationType::Double,
+            FrameType::Null => VerificationType::Null,
+            FrameType::UninitializedThis => VerificationType::UninitializedThis,
+            FrameType::Uninitialized(index) => VerificationType::Uninitialized { offset: byte_offsets[*index] },
+            FrameType::Object(class) => VerificationType::Object {
+                cpool_index: compilation_context.add_class(class)?,
+            },
+        });
+    }
+    Ok(verification_types)
+}
+
+/// The most compact frame for the locals and stack, given the locals of the frame before it. Short
+/// forms give the offset in their frame type, so only fit when it is under 64 bytes. Offsets are
+/// given in instructions, which are converted to bytes as the class file is written.
+fn stack_frame(
+    previous_locals: &[VerificationType],
+    locals: &[VerificationType],
+    stack: Vec<VerificationType>,
+    instruction_delta: u16,
+    byte_delta: u16,
+) -> StackFrame {
+    let is_short = byte_delta < 64;
+    let same_locals = locals == previous_locals;
+    if same_locals && stack.is_empty() {
+        if is_short {
+            StackFrame::SameFrame { frame_type: instruction_delta as u8 }
+        } else {
+            StackFrame::SameFrameExtended { frame_type: 251, offset_delta: instruction_delta }
+        }
+    } else if same_locals && stack.len() == 1 {
+        if is_short {
+            StackFrame::SameLocals1StackItemFrame { frame_type: 64 + instruction_delta as u8, stack }
+        } else {
+            StackFrame::SameLocals1StackItemFrameExtended { frame_type: 247, offset_delta: instruction_delta, stack }
+        }
+    } else if stack.is_empty() && locals.len() < previous_locals.len() && previous_locals.len() - locals.len() <= 3 && previous_locals.starts_with(locals) {
+        StackFrame::ChopFrame {
+            frame_type: 251 - (previous_locals.len() - locals.len()) as u8,
+            offset_delta: instruction_delta,
+        }
+    } else if stack.is_empty() && locals.len() > previous_locals.len() && locals.len() - previous_locals.len() <= 3 && locals.starts_with(previous_locals) {
+        StackFrame::AppendFrame {
+            frame_type: 251 + (locals.len() - previous_locals.len()) as u8,
+            offset_delta: instruction_delta,
+            locals: locals[previous_locals.len()..].to_vec(),
+        }
+    } else {
+        StackFrame::FullFrame {
+            frame_type: 255,
+            offset_delta: instruction_delta,
+            locals: locals.to_vec(),
+            stack,
+        }
+    }
+}
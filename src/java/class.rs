@@ -3,41 +3,87 @@ use crate::java::Named;
 use crate::java::field::JavaField;
 use crate::java::method::JavaMethod;
 
+#[derive(Clone)]
 pub struct JavaClass {
-    name: &'static str,
-    path: &'static str,
-    descriptor: &'static str,
-    methods: HashMap<&'static str, JavaMethod>,
-    fields: HashMap<&'static str, JavaField>,
+    name: String,
+    path: String,
+    descriptor: String,
+    is_interface: bool,
+    is_final: bool,
+    super_class: Option<String>,
+    interfaces: Vec<String>,
+    enclosing_instance: Option<String>,
+    /// The names and descriptors of the local variables a local class captures
+    captured_variables: Vec<(String, String)>,
+    methods: Vec<JavaMethod>,
+    fields: HashMap<String, JavaField>,
 }
 impl Named for JavaClass {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
     }
 }
 impl JavaClass {
     pub fn new(
-        name: &'static str,
-        path: &'static str,
-        descriptor: &'static str,
-        methods: HashMap<&'static str, JavaMethod>,
-        fields: HashMap<&'static str, JavaField>,
+        name: impl Into<String>,
+        path: impl Into<String>,
+        descriptor: impl Into<String>,
+        methods: Vec<JavaMethod>,
+        fields: HashMap<String, JavaField>,
     ) -> Self {
         Self {
-            name,
-            path,
-            descriptor,
+            name: name.into(),
+            path: path.into(),
+            descriptor: descriptor.into(),
+            is_interface: false,
+            is_final: false,
+            super_class: None,
+            interfaces: vec![],
+            enclosing_instance: None,
+            captured_variables: vec![],
             methods,
             fields,
         }
     }
-    
-    pub fn name(&self) -> &'static str {
-        self.name
+
+    pub fn as_interface(self) -> Self {
+        Self { is_interface: true, ..self }
+    }
+
+    pub fn as_final(self) -> Self {
+        Self { is_final: true, ..self }
+    }
+
+    pub fn with_members(self, methods: Vec<JavaMethod>, fields: HashMap<String, JavaField>) -> Self {
+        Self { methods, fields, ..self }
+    }
+
+    pub fn extending(self, super_class: impl Into<String>) -> Self {
+        Self { super_class: Some(super_class.into()), ..self }
     }
 
-    pub fn path(&self) -> &'static str {
-        self.path
+    pub fn implementing<S: Into<String>>(self, interfaces: Vec<S>) -> Self {
+        let interfaces = interfaces.into_iter().map(Into::into).collect();
+        Self { interfaces, ..self }
+    }
+
+    /// An inner class, whose instances are created with an instance of the given enclosing class
+    pub fn with_enclosing_instance(self, enclosing_class: impl Into<String>) -> Self {
+        Self { enclosing_instance: Some(enclosing_class.into()), ..self }
+    }
+
+    /// A local class, whose instances are created with the values of the local variables it
+    /// captures, given by name and descriptor, after any enclosing instance
+    pub fn with_captured_variables(self, captured_variables: Vec<(String, String)>) -> Self {
+        Self { captured_variables, ..self }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
     }
 
     pub fn full_name(&self) -> String {
@@ -45,11 +91,40 @@ impl JavaClass {
     }
 
     pub fn descriptor(&self) -> &str {
-        self.descriptor
+        &self.descriptor
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.is_interface
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// The fully qualified name of the superclass where it is modelled; `java.lang.Object` and
+    /// interfaces have none
+    pub fn super_class(&self) -> Option<&str> {
+        self.super_class.as_deref()
+    }
+
+    /// The fully qualified names of the interfaces the class implements, or an interface extends
+    pub fn interfaces(&self) -> &Vec<String> {
+        &self.interfaces
+    }
+
+    /// The class of the enclosing instance an inner class is created with
+    pub fn enclosing_instance(&self) -> Option<&str> {
+        self.enclosing_instance.as_deref()
+    }
+
+    /// The names and descriptors of the local variables a local class is created with
+    pub fn captured_variables(&self) -> &[(String, String)] {
+        &self.captured_variables
     }
 
-    pub fn method_named(&self, name: &str) -> Option<&JavaMethod> {
-        self.methods.get(name)
+    pub fn methods_named(&self, name: &str) -> Vec<&JavaMethod> {
+        self.methods.iter().filter(|m| m.name() == name).collect()
     }
 
     pub fn field_named(&self, name: &str) -> Option<&JavaField> {
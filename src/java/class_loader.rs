@@ -1,31 +1,41 @@
 use std::collections::HashMap;
-use crate::java::{java_io, java_lang};
+use crate::java::{java_io, java_lang, java_util};
 use crate::java::class::JavaClass;
 use crate::java::package::Package;
 
+#[derive(Clone)]
 pub struct ClassLoader {
-    packages: HashMap<&'static str, Package>,
+    packages: HashMap<String, Package>,
 }
 impl ClassLoader {
     pub(super) fn new() -> Self {
         let mut packages = HashMap::new();
         // java.lang is available as an implicit import to every class
-        packages.insert("java.lang", java_lang::build());
+        packages.insert("java.lang".to_string(), java_lang::build());
         Self { packages }
     }
 
-    pub fn load(&mut self, fully_qualified_class_name: &str) -> Option<&JavaClass> {
-        let (package_name, class_name) = self.packagify(fully_qualified_class_name);
+    /// Makes a class being compiled loadable by the others compiled along with it, replacing any
+    /// earlier declaration of the same name
+    pub fn declare(&mut self, class: JavaClass) {
+        let (package_name, _) = packagify(class.path());
+        let package_name = package_name.to_string();
+        self.packages
+            .entry(package_name.clone())
+            .or_insert_with(|| Package::new(package_name))
+            .add_class(class);
+    }
 
-        if package_name == "java.io" {
-            if !self.packages.contains_key("java.io") {
-                self.packages.insert("java.io", Package::new("java.io"));
-            }
+    /// Loads a class by its fully qualified name. Simple names are resolved against the imports of
+    /// the compilation unit before reaching here.
+    pub fn load(&mut self, fully_qualified_class_name: &str) -> Option<&JavaClass> {
+        let (package_name, class_name) = packagify(fully_qualified_class_name);
 
-            let package = self.packages.get_mut(package_name).unwrap();
+        if let Some((name, contains_class, load_class)) = lazily_loaded_package(package_name) {
+            let package = self.packages.entry(name.to_string()).or_insert_with(|| Package::new(name));
 
-            if package.class_named(class_name).is_none() && java_io::contains_class(class_name) {
-                package.add_class(java_io::load_class(class_name));
+            if package.class_named(class_name).is_none() && contains_class(class_name) {
+                package.add_class(load_class(class_name));
             }
         }
 
@@ -37,16 +47,25 @@ impl ClassLoader {
         }
         None
     }
+}
 
-    fn packagify<'a>(&self, name: &'a str) -> (&'a str, &'a str) {
-        if let Some(last_dot) = name.rfind('.') {
-            return (&name[0..last_dot], &name[last_dot + 1..name.len()]);
-        }
-        if let Some(java_lang) = self.packages.get("java.lang") {
-            if java_lang.class_named(name).is_some() {
-                return ("java.lang", name);
-            }
-        }
-        ("", name)
+type ContainsClass = fn(&str) -> bool;
+type LoadClass = fn(&str) -> JavaClass;
+
+/// The packages whose classes are only built when first loaded
+fn lazily_loaded_package(package_name: &str) -> Option<(&'static str, ContainsClass, LoadClass)> {
+    match package_name {
+        "java.io" => Some(("java.io", java_io::contains_class, java_io::load_class)),
+        "java.util" => Some(("java.util", java_util::contains_class, java_util::load_class)),
+        _ => None,
+    }
+}
+
+/// Splits a fully qualified name into its package and class name. A name without a package is in
+/// the unnamed package.
+fn packagify(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(last_dot) => (&name[0..last_dot], &name[last_dot + 1..]),
+        None => ("", name),
     }
 }
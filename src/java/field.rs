@@ -1,27 +1,54 @@
 use crate::java::Named;
 
+#[derive(Clone)]
 pub struct JavaField {
-    name: &'static str,
-    class: &'static str,
+    name: String,
+    class: String,
+    descriptor: String,
+    is_static: bool,
+    is_final: bool,
 }
 impl Named for JavaField {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
     }
 }
 impl JavaField {
-    pub fn new(name: &'static str, class: &'static str) -> Self {
+    pub fn new(name: impl Into<String>, class: impl Into<String>, descriptor: impl Into<String>) -> Self {
         Self {
-            name,
-            class,
+            name: name.into(),
+            class: class.into(),
+            descriptor: descriptor.into(),
+            is_static: false,
+            is_final: false,
         }
     }
 
+    pub fn as_static(self) -> Self {
+        Self { is_static: true, ..self }
+    }
+
+    pub fn as_final(self) -> Self {
+        Self { is_final: true, ..self }
+    }
+
     pub fn name(&self) -> &str {
-        self.name
+        &self.name
     }
 
     pub fn class(&self) -> &str {
-        self.class
+        &self.class
+    }
+
+    pub fn descriptor(&self) -> &str {
+        &self.descriptor
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.is_final
     }
 }
@@ -17,9 +17,25 @@ pub fn load_class(name: &str) -> JavaClass {
             "PrintStream",
             "java.io.PrintStream",
             "Ljava/io/PrintStream;",
-            as_map(vec![
-                JavaMethod::new("println", "void", "(Ljava/lang/String;)V")
-            ]),
+            vec![
+                JavaMethod::new("println", "void", "()V"),
+                JavaMethod::new("println", "void", "(Z)V"),
+                JavaMethod::new("println", "void", "(C)V"),
+                JavaMethod::new("println", "void", "(I)V"),
+                JavaMethod::new("println", "void", "(J)V"),
+                JavaMethod::new("println", "void", "(F)V"),
+                JavaMethod::new("println", "void", "(D)V"),
+                JavaMethod::new("println", "void", "(Ljava/lang/String;)V"),
+                JavaMethod::new("println", "void", "(Ljava/lang/Object;)V"),
+                JavaMethod::new("print", "void", "(Z)V"),
+                JavaMethod::new("print", "void", "(C)V"),
+                JavaMethod::new("print", "void", "(I)V"),
+                JavaMethod::new("print", "void", "(J)V"),
+                JavaMethod::new("print", "void", "(F)V"),
+                JavaMethod::new("print", "void", "(D)V"),
+                JavaMethod::new("print", "void", "(Ljava/lang/String;)V"),
+                JavaMethod::new("print", "void", "(Ljava/lang/Object;)V"),
+            ],
             as_map(vec![]),
         ),
         _ => panic!("Class {:} does not exist in java.io", name),
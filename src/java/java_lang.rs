@@ -1,21 +1,167 @@
 use crate::java::as_map;
 use crate::java::class::JavaClass;
 use crate::java::field::JavaField;
+use crate::java::method::JavaMethod;
 use crate::java::package::Package;
 
+const OBJECT: &str = "java.lang.Object";
+
 pub fn build() -> Package {
     let system = JavaClass::new(
         "System",
         "java.lang.System",
         "Ljava/lang/System;",
-        as_map(vec![]),
+        vec![],
         as_map(vec![
-            JavaField::new("out", "java.io.PrintStream"),
-            JavaField::new("err", "java.io.PrintStream"),
+            JavaField::new("out", "java.io.PrintStream", "Ljava/io/PrintStream;").as_static().as_final(),
+            JavaField::new("err", "java.io.PrintStream", "Ljava/io/PrintStream;").as_static().as_final(),
         ]),
+    )
+    .as_final()
+    .extending(OBJECT);
+
+    let object = JavaClass::new(
+        "Object",
+        "java.lang.Object",
+        "Ljava/lang/Object;",
+        vec![JavaMethod::new("<init>", "void", "()V")],
+        as_map(vec![]),
     );
+    let string = JavaClass::new(
+        "String",
+        "java.lang.String",
+        "Ljava/lang/String;",
+        vec![
+            JavaMethod::new("length", "int", "()I"),
+            JavaMethod::new("compareTo", "int", "(Ljava/lang/Object;)I"),
+            JavaMethod::new("split", "java.lang.String[]", "(Ljava/lang/String;)[Ljava/lang/String;"),
+            JavaMethod::new("toCharArray", "char[]", "()[C"),
+            JavaMethod::new("valueOf", "java.lang.String", "(Z)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(C)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(I)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(J)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(F)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(D)Ljava/lang/String;").as_static(),
+            JavaMethod::new("valueOf", "java.lang.String", "(Ljava/lang/Object;)Ljava/lang/String;").as_static(),
+        ],
+        as_map(vec![JavaField::new("CASE_INSENSITIVE_ORDER", "java.util.Comparator", "Ljava/util/Comparator;").as_static().as_final()]),
+    )
+        .as_final()
+        .extending(OBJECT)
+        .implementing(vec!["java.lang.CharSequence", "java.lang.Comparable"]);
+
+    let char_sequence = JavaClass::new(
+        "CharSequence",
+        "java.lang.CharSequence",
+        "Ljava/lang/CharSequence;",
+        vec![JavaMethod::new("length", "int", "()I")],
+        as_map(vec![]),
+    )
+    .as_interface();
+    let comparable = JavaClass::new(
+        "Comparable",
+        "java.lang.Comparable",
+        "Ljava/lang/Comparable;",
+        vec![JavaMethod::new("compareTo", "int", "(Ljava/lang/Object;)I")],
+        as_map(vec![]),
+    )
+    .as_interface();
+    let runnable = JavaClass::new(
+        "Runnable",
+        "java.lang.Runnable",
+        "Ljava/lang/Runnable;",
+        vec![JavaMethod::new("run", "void", "()V")],
+        as_map(vec![]),
+    )
+    .as_interface();
+    let iterable = JavaClass::new(
+        "Iterable",
+        "java.lang.Iterable",
+        "Ljava/lang/Iterable;",
+        vec![JavaMethod::new("iterator", "java.util.Iterator", "()Ljava/util/Iterator;")],
+        as_map(vec![]),
+    )
+    .as_interface();
+    let cloneable = JavaClass::new("Cloneable", "java.lang.Cloneable", "Ljava/lang/Cloneable;", vec![], as_map(vec![])).as_interface();
+
+    let thread = JavaClass::new(
+        "Thread",
+        "java.lang.Thread",
+        "Ljava/lang/Thread;",
+        vec![
+            JavaMethod::new("<init>", "void", "()V"),
+            JavaMethod::new("<init>", "void", "(Ljava/lang/String;)V"),
+            JavaMethod::new("run", "void", "()V"),
+            JavaMethod::new("start", "void", "()V"),
+        ],
+        as_map(vec![]),
+    )
+    .extending(OBJECT)
+    .implementing(vec!["java.lang.Runnable"]);
+
+    let enum_class = JavaClass::new(
+        "Enum",
+        "java.lang.Enum",
+        "Ljava/lang/Enum;",
+        vec![
+            JavaMethod::new("<init>", "void", "(Ljava/lang/String;I)V"),
+            JavaMethod::new("name", "java.lang.String", "()Ljava/lang/String;"),
+            JavaMethod::new("ordinal", "int", "()I"),
+            JavaMethod::new("valueOf", "java.lang.Enum", "(Ljava/lang/Class;Ljava/lang/String;)Ljava/lang/Enum;").as_static(),
+        ],
+        as_map(vec![]),
+    )
+    .extending(OBJECT)
+    .implementing(vec!["java.lang.Comparable"]);
+
+    let record = JavaClass::new(
+        "Record",
+        "java.lang.Record",
+        "Ljava/lang/Record;",
+        vec![
+            JavaMethod::new("<init>", "void", "()V"),
+            JavaMethod::new("equals", "boolean", "(Ljava/lang/Object;)Z"),
+            JavaMethod::new("hashCode", "int", "()I"),
+            JavaMethod::new("toString", "java.lang.String", "()Ljava/lang/String;"),
+        ],
+        as_map(vec![]),
+    )
+    .extending(OBJECT);
+
+    let throwable = throwable_class("Throwable", "java.lang.Throwable", "Ljava/lang/Throwable;").extending(OBJECT);
+    let exception = throwable_class("Exception", "java.lang.Exception", "Ljava/lang/Exception;").extending("java.lang.Throwable");
+    let runtime_exception = throwable_class("RuntimeException", "java.lang.RuntimeException", "Ljava/lang/RuntimeException;")
+        .extending("java.lang.Exception");
 
     let mut package = Package::new("java/lang");
+    package.add_class(object);
+    package.add_class(string);
     package.add_class(system);
+    package.add_class(char_sequence);
+    package.add_class(comparable);
+    package.add_class(runnable);
+    package.add_class(iterable);
+    package.add_class(cloneable);
+    package.add_class(thread);
+    package.add_class(enum_class);
+    package.add_class(record);
+    package.add_class(throwable);
+    package.add_class(exception);
+    package.add_class(runtime_exception);
     package
 }
+
+/// `Throwable` and its subclasses share the same constructors and accessors
+fn throwable_class(name: &'static str, path: &'static str, descriptor: &'static str) -> JavaClass {
+    JavaClass::new(
+        name,
+        path,
+        descriptor,
+        vec![
+            JavaMethod::new("<init>", "void", "()V"),
+            JavaMethod::new("<init>", "void", "(Ljava/lang/String;)V"),
+            JavaMethod::new("getMessage", "java.lang.String", "()Ljava/lang/String;"),
+        ],
+        as_map(vec![]),
+    )
+}
@@ -0,0 +1,84 @@
+use crate::java::as_map;
+use lazy_static::lazy_static;
+use crate::java::class::JavaClass;
+use crate::java::method::JavaMethod;
+
+lazy_static! {
+    static ref CLASSES: Vec<&'static str> = vec!["Comparator", "Iterator", "Collection", "List", "ArrayList"];
+}
+
+pub fn contains_class(name: &str) -> bool {
+    CLASSES.contains(&name)
+}
+
+pub fn load_class(name: &str) -> JavaClass {
+    match name {
+        "Comparator" => JavaClass::new(
+            "Comparator",
+            "java.util.Comparator",
+            "Ljava/util/Comparator;",
+            vec![
+                JavaMethod::new("compare", "int", "(Ljava/lang/Object;Ljava/lang/Object;)I"),
+                JavaMethod::new("reversed", "java.util.Comparator", "()Ljava/util/Comparator;"),
+                JavaMethod::new("naturalOrder", "java.util.Comparator", "()Ljava/util/Comparator;").as_static(),
+                JavaMethod::new("reverseOrder", "java.util.Comparator", "()Ljava/util/Comparator;").as_static(),
+            ],
+            as_map(vec![]),
+        )
+        .as_interface(),
+        "Iterator" => JavaClass::new(
+            "Iterator",
+            "java.util.Iterator",
+            "Ljava/util/Iterator;",
+            vec![
+                JavaMethod::new("hasNext", "boolean", "()Z"),
+                JavaMethod::new("next", "java.lang.Object", "()Ljava/lang/Object;"),
+            ],
+            as_map(vec![]),
+        )
+        .as_interface(),
+        "Collection" => JavaClass::new(
+            "Collection",
+            "java.util.Collection",
+            "Ljava/util/Collection;",
+            vec![
+                JavaMethod::new("size", "int", "()I"),
+                JavaMethod::new("isEmpty", "boolean", "()Z"),
+                JavaMethod::new("add", "boolean", "(Ljava/lang/Object;)Z"),
+            ],
+            as_map(vec![]),
+        )
+        .as_interface()
+        .implementing(vec!["java.lang.Iterable"]),
+        "List" => JavaClass::new(
+            "List",
+            "java.util.List",
+            "Ljava/util/List;",
+            vec![
+                JavaMethod::new("get", "java.lang.Object", "(I)Ljava/lang/Object;"),
+                JavaMethod::new("of", "java.util.List", "()Ljava/util/List;").as_static(),
+                JavaMethod::new("of", "java.util.List", "(Ljava/lang/Object;)Ljava/util/List;").as_static(),
+                JavaMethod::new("of", "java.util.List", "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/util/List;").as_static(),
+                JavaMethod::new("of", "java.util.List", "(Ljava/lang/Object;Ljava/lang/Object;Ljava/lang/Object;)Ljava/util/List;").as_static(),
+            ],
+            as_map(vec![]),
+        )
+        .as_interface()
+        .implementing(vec!["java.util.Collection"]),
+        "ArrayList" => JavaClass::new(
+            "ArrayList",
+            "java.util.ArrayList",
+            "Ljava/util/ArrayList;",
+            vec![
+                JavaMethod::new("<init>", "void", "()V"),
+                JavaMethod::new("add", "boolean", "(Ljava/lang/Object;)Z"),
+                JavaMethod::new("get", "java.lang.Object", "(I)Ljava/lang/Object;"),
+                JavaMethod::new("size", "int", "()I"),
+            ],
+            as_map(vec![]),
+        )
+        .extending("java.lang.Object")
+        .implementing(vec!["java.util.List"]),
+        _ => panic!("Class {:} does not exist in java.util", name),
+    }
+}
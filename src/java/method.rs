@@ -1,36 +1,48 @@
 use crate::java::Named;
 
+#[derive(Clone)]
 pub struct JavaMethod {
-    name: &'static str,
-    return_type: &'static str,
-    descriptor: &'static str,
+    name: String,
+    return_type: String,
+    descriptor: String,
+    is_static: bool,
 }
 impl Named for JavaMethod {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
     }
 }
 impl JavaMethod {
     pub fn new(
-        name: &'static str,
-        return_type: &'static str,
-        descriptor: &'static str,
+        name: impl Into<String>,
+        return_type: impl Into<String>,
+        descriptor: impl Into<String>,
     ) -> Self {
         Self {
-            name,
-            return_type,
-            descriptor,
+            name: name.into(),
+            return_type: return_type.into(),
+            descriptor: descriptor.into(),
+            is_static: false,
         }
     }
+
+    pub fn as_static(self) -> Self {
+        Self { is_static: true, ..self }
+    }
+
     pub fn name(&self) -> &str {
-        self.name
+        &self.name
     }
 
     pub fn return_type(&self) -> &str {
-        self.return_type
+        &self.return_type
     }
 
     pub fn descriptor(&self) -> &str {
-        self.descriptor
+        &self.descriptor
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
     }
 }
@@ -4,6 +4,7 @@ pub(crate) use crate::java::class_loader::ClassLoader;
 mod class_loader;
 mod java_io;
 mod java_lang;
+mod java_util;
 mod package;
 pub mod class;
 pub mod method;
@@ -14,13 +15,13 @@ pub fn new_class_loader() -> ClassLoader {
 }
 
 pub trait Named {
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 }
 
-pub fn as_map<T: Named>(items: Vec<T>) -> HashMap<&'static str, T> {
-    let mut map: HashMap<&'static str, T> = HashMap::new();
+pub fn as_map<T: Named>(items: Vec<T>) -> HashMap<String, T> {
+    let mut map: HashMap<String, T> = HashMap::new();
     for item in items {
-        map.insert(&item.name(), item);
+        map.insert(item.name().to_string(), item);
     }
     map
 }
\ No newline at end of file
@@ -2,26 +2,27 @@ use std::collections::HashMap;
 use crate::java::Named;
 use crate::java::class::JavaClass;
 
+#[derive(Clone)]
 pub(crate) struct Package {
-    name: &'static str,
-    classes: HashMap<&'static str, JavaClass>,
+    name: String,
+    classes: HashMap<String, JavaClass>,
 }
 impl Named for Package {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
     }
 }
 
 impl Package {
-    pub fn new(name: &'static str) -> Self {
+    pub fn new(name: impl Into<String>) -> Self {
         Self {
-            name,
+            name: name.into(),
             classes: HashMap::new(),
         }
     }
 
     pub(crate) fn add_class(&mut self, class: JavaClass) {
-        self.classes.insert(class.name(), class);
+        self.classes.insert(class.name().to_string(), class);
     }
 
     pub fn class_named(&self, class_name: &str) -> Option<&JavaClass> {
@@ -1,5 +1,5 @@
 mod io;
-mod scanner;
+pub mod scanner;
 pub mod spike;
 pub mod ast;
 pub mod test_support;
@@ -7,32 +7,111 @@ pub mod compiler;
 pub mod java;
 
 use std::fs;
+use std::path::Path;
 use ristretto_classfile::ClassFile;
+use crate::ast::class::AstScope;
 use crate::ast::to_ast;
-use crate::ast::class::AstClass;
+use crate::ast::compilation_unit::AstCompilationUnit;
 use crate::compiler::{wrap, CompileError, CompileResult};
 use crate::io::read_file;
+use crate::scanner::{FileId, SourceFiles, TranslatedSource};
 
+/// Builds the AST of source whose Unicode escapes have already been translated, see
+/// [`scanner::translate_unicode_escapes`]
 #[allow(clippy::needless_lifetimes)]
-pub fn build_ast<'a>(source: &'a str) -> AstClass<'a> {
-    let tokens = scanner::scan(source);
-    to_ast(tokens)
+pub fn build_ast<'a>(source: &'a str) -> CompileResult<AstCompilationUnit<'a>> {
+    let (tokens, diagnostics) = scanner::scan(source);
+    if !diagnostics.is_empty() {
+        return Err(CompileError::Lexical(diagnostics));
+    }
+    to_ast(source, tokens).map_err(CompileError::Syntax)
 }
 
-pub fn compile(source_file_path: &str) -> CompileResult<()> {
-    let source = read_file(source_file_path);
-    let ast_class = build_ast(source.as_str());
-    let name = ast_class.name();
+/// Builds the AST of a source file, with spans giving the lines and columns of the file itself
+/// rather than of its translated text
+fn build_file_ast<'a>(file_id: FileId, source: &'a TranslatedSource) -> CompileResult<AstCompilationUnit<'a>> {
+    let (tokens, diagnostics) = scanner::scan_translated(file_id, source);
+    if !diagnostics.is_empty() {
+        return Err(CompileError::Lexical(diagnostics));
+    }
+    to_ast(source.text(), tokens).map_err(CompileError::Syntax)
+}
+
+/// Compiles a set of source files together, writing their classes to the current directory
+pub fn compile(source_file_paths: &[&str]) -> CompileResult<()> {
+    let sources: Vec<String> = source_file_paths.iter().map(|path| read_file(path)).collect();
+    let sources: Vec<(&str, &str)> = source_file_paths.iter().copied().zip(sources.iter().map(String::as_str)).collect();
+    compile_sources(&sources, Path::new("."))
+}
+
+/// Compiles the source of a single compilation unit that does not come from a file
+pub fn compile_source(source: &str, output_directory: &Path) -> CompileResult<()> {
+    let source = TranslatedSource::new(source);
+    let compilation_unit = build_file_ast(FileId::default(), &source)?;
+
+    write_all(output_directory, compiler::compile(&[compilation_unit])?)
+}
+
+/// Compiles a set of sources together, each given with the path of its file. A public top level
+/// type must be declared in the file named after it.
+pub fn compile_sources(sources: &[(&str, &str)], output_directory: &Path) -> CompileResult<()> {
+    let mut files = SourceFiles::default();
+    let translated: Vec<_> = sources.iter().map(|(path, source)| (files.add(path), TranslatedSource::new(source))).collect();
+
+    let mut compilation_units = vec![];
+    for (file_id, source) in &translated {
+        let compilation_unit = build_file_ast(*file_id, source).map_err(|error| in_source_file(&files, error))?;
+        check_public_types_are_in_named_file(&compilation_unit, files.path(*file_id))?;
+        compilation_units.push(compilation_unit);
+    }
 
-    compiler::compile(&ast_class)
-        .and_then(|cf| write(name, cf))
+    let class_files = compiler::compile(&compilation_units).map_err(|error| in_source_file(&files, error))?;
+    write_all(output_directory, class_files)
 }
 
-fn write(file_name: &str, class_file: ClassFile) -> CompileResult<()> {
+/// Attributes an error that knows where it was found to the path of its file
+fn in_source_file(files: &SourceFiles, error: CompileError) -> CompileError {
+    match error.file_id() {
+        Some(file_id) => CompileError::Source {
+            path: files.path(file_id).to_string(),
+            error: Box::new(error),
+        },
+        None => error,
+    }
+}
+
+fn check_public_types_are_in_named_file(compilation_unit: &AstCompilationUnit, path: &str) -> CompileResult<()> {
+    let file_name = Path::new(path).file_stem().and_then(|stem| stem.to_str());
+    let misplaced = compilation_unit
+        .types()
+        .iter()
+        .find(|class| class.scope() == AstScope::Public && Some(class.name()) != file_name);
+
+    match misplaced {
+        Some(class) => Err(CompileError::PublicTypeInWrongFile {
+            type_name: class.name().to_string(),
+            path: path.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn write_all(output_directory: &Path, class_files: Vec<ClassFile>) -> CompileResult<()> {
+    for class_file in class_files {
+        write(output_directory, class_file)?;
+    }
+    Ok(())
+}
+
+/// Writes the class file beneath the directory of its package, as `javac -d` does
+fn write(output_directory: &Path, class_file: ClassFile) -> CompileResult<()> {
     let mut buffer = Vec::new();
     // TODO: We shouldn't leak ristretto out of the compile module
     wrap(class_file.to_bytes(&mut buffer))?;
 
-    fs::write("{name}.class".replace("{name}", file_name), buffer)
-        .map_err(|e| CompileError::FileSystem(e))
-}
\ No newline at end of file
+    let class_path = output_directory.join(format!("{}.class", wrap(class_file.class_name())?));
+    if let Some(package_directory) = class_path.parent() {
+        fs::create_dir_all(package_directory).map_err(CompileError::FileSystem)?;
+    }
+    fs::write(class_path, buffer).map_err(CompileError::FileSystem)
+}
@@ -1,3 +1,4 @@
+use java_compiler::compiler::CompileError;
 use java_compiler::{compile, spike};
 
 mod io;
@@ -8,14 +9,29 @@ fn main_spike() {
 }
 
 fn main() {
-    let result = compile("samples/Simple.java");
-    
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let source_file_paths: Vec<&str> = match arguments.is_empty() {
+        true => vec!["samples/Simple.java"],
+        false => arguments.iter().map(String::as_str).collect(),
+    };
+    let result = compile(&source_file_paths);
+
     match result {
         Ok(_) => {
-            println!("File written successfully")
+            println!("Files written successfully")
         }
+        Err(CompileError::Source { path, error }) => match *error {
+            CompileError::Lexical(diagnostics) => {
+                for diagnostic in diagnostics {
+                    println!("{}:{}", path, diagnostic);
+                }
+            }
+            error => {
+                println!("{}:{}", path, error);
+            }
+        },
         Err(e) => {
-            println!("There was an error compiling {:?}", e);
+            println!("{}", e);
         }
     }
 }
@@ -0,0 +1,63 @@
+use crate::scanner::source_span::SourceSpan;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexicalErrorKind {
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    UnterminatedTextBlock,
+    TextBlockOpeningDelimiter,
+    UnterminatedCharacter,
+    UnterminatedComment,
+    InvalidEscape(String),
+    InvalidCharacterLiteral(String),
+    MalformedNumber(String),
+}
+
+/// A problem found while scanning, reported alongside the tokens rather than stopping the scan
+#[derive(Clone, Debug, PartialEq)]
+pub struct LexicalDiagnostic {
+    kind: LexicalErrorKind,
+    span: SourceSpan,
+}
+
+impl LexicalDiagnostic {
+    pub fn new(kind: LexicalErrorKind, span: SourceSpan) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn kind(&self) -> &LexicalErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+}
+
+impl Display for LexicalErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexicalErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            LexicalErrorKind::UnterminatedString => write!(f, "unterminated string literal"),
+            LexicalErrorKind::UnterminatedTextBlock => write!(f, "unterminated text block"),
+            LexicalErrorKind::TextBlockOpeningDelimiter => {
+                write!(f, "the opening delimiter of a text block must be followed by a line terminator")
+            }
+            LexicalErrorKind::UnterminatedCharacter => write!(f, "unterminated character literal"),
+            LexicalErrorKind::UnterminatedComment => write!(f, "unterminated comment"),
+            LexicalErrorKind::InvalidEscape(sequence) => write!(f, "invalid escape sequence {}", sequence),
+            LexicalErrorKind::InvalidCharacterLiteral(lexeme) => {
+                write!(f, "character literal {} must contain exactly one character", lexeme)
+            }
+            LexicalErrorKind::MalformedNumber(lexeme) => write!(f, "malformed numeric literal {}", lexeme),
+        }
+    }
+}
+
+/// Formats as `line:column: message`, to be prefixed with the file name when reported
+impl Display for LexicalDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.span, self.kind)
+    }
+}
@@ -0,0 +1,93 @@
+/// An escape sequence that could not be decoded, with its byte offset within the raw literal body.
+#[derive(Debug)]
+pub struct InvalidEscape {
+    pub offset: usize,
+    pub sequence: String,
+}
+
+/// Decodes the body of a string literal. Java strings are UTF-16, so surrogate pairs written as
+/// `\uXXXX` escapes are recombined; a lone surrogate cannot be represented and is reported as invalid.
+pub fn unescape(raw: &str) -> Result<String, InvalidEscape> {
+    let code_units = unescape_to_utf16(raw)?;
+
+    String::from_utf16(&code_units).map_err(|_| InvalidEscape {
+        offset: 0,
+        sequence: raw.to_string(),
+    })
+}
+
+/// Decodes the escape sequences of JLS §3.10.7 into UTF-16 code units.
+pub fn unescape_to_utf16(raw: &str) -> Result<Vec<u16>, InvalidEscape> {
+    let mut code_units: Vec<u16> = Vec::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0u16; 2];
+            code_units.extend_from_slice(c.encode_utf16(&mut buffer));
+            continue;
+        }
+
+        let invalid = |end: usize| InvalidEscape {
+            offset,
+            sequence: raw[offset..end].to_string(),
+        };
+
+        let Some((escape_offset, escape)) = chars.next() else {
+            return Err(invalid(raw.len()));
+        };
+        let escape_end = escape_offset + escape.len_utf8();
+
+        let code_unit = match escape {
+            'b' => 0x08,
+            't' => 0x09,
+            'n' => 0x0A,
+            'f' => 0x0C,
+            'r' => 0x0D,
+            's' => 0x20,
+            // A line continuation, only possible in a text block, joins the line with the next
+            '\n' => continue,
+            '"' => 0x22,
+            '\'' => 0x27,
+            '\\' => 0x5C,
+            '0'..='7' => {
+                // A three digit octal escape may only start with 0-3 so that it fits in a byte
+                let max_digits = if escape <= '3' { 3 } else { 2 };
+                let mut value = escape.to_digit(8).unwrap() as u16;
+                let mut digits = 1;
+                while digits < max_digits {
+                    match chars.peek() {
+                        Some((_, next)) if next.is_digit(8) => {
+                            value = value * 8 + next.to_digit(8).unwrap() as u16;
+                            chars.next();
+                            digits += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                value
+            }
+            'u' => {
+                // Any number of u's may follow the backslash (JLS §3.3)
+                while let Some((_, 'u')) = chars.peek() {
+                    chars.next();
+                }
+                let mut value: u16 = 0;
+                for _ in 0..4 {
+                    match chars.next() {
+                        Some((_, digit)) if digit.is_ascii_hexdigit() => {
+                            value = value * 16 + digit.to_digit(16).unwrap() as u16;
+                        }
+                        Some((digit_offset, digit)) => return Err(invalid(digit_offset + digit.len_utf8())),
+                        None => return Err(invalid(raw.len())),
+                    }
+                }
+                value
+            }
+            _ => return Err(invalid(escape_end)),
+        };
+        code_units.push(code_unit);
+    }
+
+    Ok(code_units)
+}
@@ -1,5 +1,17 @@
 
-#[derive(Debug, Copy, Clone)]
-pub enum Literal<'a> {
-    String(&'a str)
+/// The value carried by a literal token. String and character literals have had their escape
+/// sequences decoded.
+///
+/// Integer literals hold the value as written rather than as the final `int` or `long`, so that
+/// range checks (including the `-2147483648` special case of JLS §3.10.1) can be made once the
+/// surrounding expression is known. Hex, octal and binary literals that fit the type's bit width
+/// have already been wrapped into two's complement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    String(String),
+    Char(u16),
+    Int(i64),
+    Long(i128),
+    Float(f32),
+    Double(f64),
 }
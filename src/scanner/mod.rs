@@ -1,8 +1,17 @@
 mod token;
+mod diagnostic;
+mod escape;
 mod literal;
+mod numeric_literal;
 mod scanner;
+mod source_span;
+mod text_block;
+mod unicode_escape;
 
-pub use scanner::scan;
+pub use scanner::{scan, scan_file, scan_translated};
+pub use unicode_escape::{translate_unicode_escapes, TranslatedSource};
+pub use source_span::{FileId, SourceFiles, SourcePosition, SourceSpan};
 
-pub use token::{Token, TokenType};
+pub use token::{ContextualKeyword, Token, TokenType};
 pub use literal::Literal;
+pub use diagnostic::{LexicalDiagnostic, LexicalErrorKind};
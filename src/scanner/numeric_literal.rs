@@ -0,0 +1,124 @@
+use crate::scanner::literal::Literal;
+use crate::scanner::token::TokenType;
+
+/// Parses the lexeme of a numeric literal (JLS §3.10.1 and §3.10.2) into its token type and value.
+/// Returns `None` if the lexeme is not a well-formed literal.
+pub fn parse(lexeme: &str) -> Option<(TokenType, Literal)> {
+    if let Some(digits) = strip_radix_prefix(lexeme, 'x') {
+        return radix_integer(digits, 16);
+    }
+    if let Some(digits) = strip_radix_prefix(lexeme, 'b') {
+        return radix_integer(digits, 2);
+    }
+
+    if is_floating_point(lexeme) {
+        return floating_point(lexeme);
+    }
+
+    let (digits, is_long) = strip_long_suffix(lexeme);
+    if digits.len() > 1 && digits.starts_with('0') {
+        // Octal digits may directly follow the leading zero with an underscore, e.g. 0_17
+        let octal = &digits[1..];
+        if octal.ends_with('_') || !octal.chars().all(|c| c == '_' || c.is_digit(8)) {
+            return None;
+        }
+        return Some(integer_literal(magnitude(octal, 8), is_long, false));
+    }
+
+    if !is_valid_digit_sequence(digits, 10) {
+        return None;
+    }
+    Some(integer_literal(magnitude(digits, 10), is_long, true))
+}
+
+fn strip_radix_prefix(lexeme: &str, radix_char: char) -> Option<&str> {
+    let rest = lexeme.strip_prefix('0')?;
+    rest.strip_prefix(radix_char)
+        .or_else(|| rest.strip_prefix(radix_char.to_ascii_uppercase()))
+}
+
+fn strip_long_suffix(lexeme: &str) -> (&str, bool) {
+    match lexeme.strip_suffix(['l', 'L']) {
+        Some(digits) => (digits, true),
+        None => (lexeme, false),
+    }
+}
+
+fn is_floating_point(lexeme: &str) -> bool {
+    lexeme.contains(['.', 'e', 'E']) || lexeme.ends_with(['f', 'F', 'd', 'D'])
+}
+
+fn radix_integer(lexeme: &str, radix: u32) -> Option<(TokenType, Literal)> {
+    let (digits, is_long) = strip_long_suffix(lexeme);
+    if !is_valid_digit_sequence(digits, radix) {
+        return None;
+    }
+    Some(integer_literal(magnitude(digits, radix), is_long, false))
+}
+
+fn floating_point(lexeme: &str) -> Option<(TokenType, Literal)> {
+    let (body, is_float) = match lexeme.chars().last()? {
+        'f' | 'F' => (&lexeme[..lexeme.len() - 1], true),
+        'd' | 'D' => (&lexeme[..lexeme.len() - 1], false),
+        _ => (lexeme, false),
+    };
+
+    let (mantissa, exponent) = match body.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (body, None),
+    };
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if (!whole.is_empty() && !is_valid_digit_sequence(whole, 10)) || (!fraction.is_empty() && !is_valid_digit_sequence(fraction, 10)) {
+        return None;
+    }
+    if let Some(exponent) = exponent {
+        let exponent_digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        if !is_valid_digit_sequence(exponent_digits, 10) {
+            return None;
+        }
+    }
+
+    let normalised = body.replace('_', "");
+    if is_float {
+        normalised.parse::<f32>().ok().map(|value| (TokenType::FloatLiteral, Literal::Float(value)))
+    } else {
+        normalised.parse::<f64>().ok().map(|value| (TokenType::DoubleLiteral, Literal::Double(value)))
+    }
+}
+
+/// Underscores are only allowed between digits (JLS §3.10.1)
+fn is_valid_digit_sequence(digits: &str, radix: u32) -> bool {
+    !digits.is_empty()
+        && !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && digits.chars().all(|c| c == '_' || c.is_digit(radix))
+}
+
+fn magnitude(digits: &str, radix: u32) -> u128 {
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(radix))
+        .fold(0u128, |acc, digit| acc.saturating_mul(radix as u128).saturating_add(digit as u128))
+}
+
+fn integer_literal(magnitude: u128, is_long: bool, is_decimal: bool) -> (TokenType, Literal) {
+    if is_long {
+        let value = if !is_decimal && magnitude <= u64::MAX as u128 {
+            magnitude as u64 as i64 as i128
+        } else {
+            i128::try_from(magnitude).unwrap_or(i128::MAX)
+        };
+        (TokenType::LongLiteral, Literal::Long(value))
+    } else {
+        let value = if !is_decimal && magnitude <= u32::MAX as u128 {
+            magnitude as u32 as i32 as i64
+        } else {
+            i64::try_from(magnitude).unwrap_or(i64::MAX)
+        };
+        (TokenType::IntLiteral, Literal::Int(value))
+    }
+}
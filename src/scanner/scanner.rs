@@ -1,13 +1,70 @@
+use crate::scanner::diagnostic::{LexicalDiagnostic, LexicalErrorKind};
+use crate::scanner::escape;
 use crate::scanner::literal::Literal;
+use crate::scanner::numeric_literal;
+use crate::scanner::source_span::{FileId, SourcePosition, SourceSpan};
+use crate::scanner::text_block::text_block_value;
 use crate::scanner::token::{Token, TokenType};
+use crate::scanner::unicode_escape::TranslatedSource;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 
 lazy_static! {
     static ref IDENTIFIER_KEYWORDS: HashMap<&'static str, TokenType> = vec![
+        ("abstract", TokenType::Abstract),
+        ("assert", TokenType::Assert),
+        ("boolean", TokenType::Boolean),
+        ("break", TokenType::Break),
+        ("byte", TokenType::Byte),
+        ("case", TokenType::Case),
+        ("catch", TokenType::Catch),
+        ("char", TokenType::Char),
         ("class", TokenType::Class),
+        ("const", TokenType::Const),
+        ("continue", TokenType::Continue),
+        ("default", TokenType::Default),
+        ("do", TokenType::Do),
+        ("double", TokenType::Double),
+        ("else", TokenType::Else),
+        ("enum", TokenType::Enum),
+        ("extends", TokenType::Extends),
+        ("final", TokenType::Final),
+        ("finally", TokenType::Finally),
+        ("float", TokenType::Float),
+        ("for", TokenType::For),
+        ("goto", TokenType::Goto),
+        ("if", TokenType::If),
+        ("implements", TokenType::Implements),
+        ("import", TokenType::Import),
+        ("instanceof", TokenType::Instanceof),
+        ("int", TokenType::Int),
+        ("interface", TokenType::Interface),
+        ("long", TokenType::Long),
+        ("native", TokenType::Native),
+        ("new", TokenType::New),
+        ("package", TokenType::Package),
+        ("private", TokenType::Private),
+        ("protected", TokenType::Protected),
         ("public", TokenType::Public),
+        ("return", TokenType::Return),
+        ("short", TokenType::Short),
         ("static", TokenType::Static),
+        ("strictfp", TokenType::Strictfp),
+        ("super", TokenType::Super),
+        ("switch", TokenType::Switch),
+        ("synchronized", TokenType::Synchronized),
+        ("this", TokenType::This),
+        ("throw", TokenType::Throw),
+        ("throws", TokenType::Throws),
+        ("transient", TokenType::Transient),
+        ("try", TokenType::Try),
+        ("void", TokenType::Void),
+        ("volatile", TokenType::Volatile),
+        ("while", TokenType::While),
+        ("_", TokenType::Underscore),
+        ("true", TokenType::True),
+        ("false", TokenType::False),
+        ("null", TokenType::Null),
     ].into_iter().collect();
 
     static ref SINGLE_CHAR_TOKENS: HashMap<char, TokenType> = vec![
@@ -18,37 +75,126 @@ lazy_static! {
         ('[', TokenType::LeftSquareBracket),
         (']', TokenType::RightSquareBracket),
         (';', TokenType::SemiColon),
-        ('.', TokenType::Dot),
         (',', TokenType::Comma),
-        ('=', TokenType::Equal),
     ].into_iter().collect();
+
+    /// Operators and separators made of symbols, longest first so that matching the first entry
+    /// that prefixes the remaining source gives maximal munch (JLS §3.2).
+    static ref OPERATOR_TOKENS: Vec<(&'static str, TokenType)> = {
+        let mut operators = vec![
+            (">>>=", TokenType::GreaterGreaterGreaterEqual),
+            ("<<=", TokenType::LessLessEqual),
+            (">>=", TokenType::GreaterGreaterEqual),
+            (">>>", TokenType::GreaterGreaterGreater),
+            ("...", TokenType::Ellipsis),
+            ("==", TokenType::EqualEqual),
+            ("!=", TokenType::BangEqual),
+            ("<=", TokenType::LessEqual),
+            (">=", TokenType::GreaterEqual),
+            ("&&", TokenType::AmpAmp),
+            ("||", TokenType::PipePipe),
+            ("++", TokenType::PlusPlus),
+            ("--", TokenType::MinusMinus),
+            ("<<", TokenType::LessLess),
+            (">>", TokenType::GreaterGreater),
+            ("+=", TokenType::PlusEqual),
+            ("-=", TokenType::MinusEqual),
+            ("*=", TokenType::StarEqual),
+            ("/=", TokenType::SlashEqual),
+            ("%=", TokenType::PercentEqual),
+            ("&=", TokenType::AmpEqual),
+            ("|=", TokenType::PipeEqual),
+            ("^=", TokenType::CaretEqual),
+            ("->", TokenType::Arrow),
+            ("::", TokenType::ColonColon),
+            ("=", TokenType::Equal),
+            (".", TokenType::Dot),
+            ("+", TokenType::Plus),
+            ("-", TokenType::Minus),
+            ("*", TokenType::Star),
+            ("/", TokenType::Slash),
+            ("%", TokenType::Percent),
+            ("<", TokenType::Less),
+            (">", TokenType::Greater),
+            ("!", TokenType::Bang),
+            ("&", TokenType::Amp),
+            ("|", TokenType::Pipe),
+            ("^", TokenType::Caret),
+            ("~", TokenType::Tilde),
+            ("?", TokenType::Question),
+            (":", TokenType::Colon),
+            ("@", TokenType::At),
+        ];
+        operators.sort_by_key(|(operator, _)| std::cmp::Reverse(operator.len()));
+        operators
+    };
+}
+
+/// How a token of the type is written, for keywords, separators and operators, which are only
+/// ever written one way
+pub fn spelling(token_type: TokenType) -> Option<String> {
+    let keyword = IDENTIFIER_KEYWORDS.iter().find(|(_, keyword_type)| **keyword_type == token_type).map(|(keyword, _)| keyword.to_string());
+    let separator = SINGLE_CHAR_TOKENS.iter().find(|(_, separator_type)| **separator_type == token_type).map(|(separator, _)| separator.to_string());
+    let operator = OPERATOR_TOKENS.iter().find(|(_, operator_type)| *operator_type == token_type).map(|(operator, _)| operator.to_string());
+    keyword.or(separator).or(operator)
 }
 
+/// Scans the whole source, returning the tokens along with every lexical problem found on the way.
+/// Scanning carries on past a problem, so the tokens are still usable for reporting later errors.
 #[allow(clippy::needless_lifetimes)]
-pub fn scan<'a>(source: &'a str) -> Vec<Token<'a>> {
-    let mut scanner = crate::scanner::scanner::Scanner::for_source(source);
+pub fn scan<'a>(source: &'a str) -> (Vec<Token<'a>>, Vec<LexicalDiagnostic>) {
+    scan_file(FileId::default(), source)
+}
+
+/// Scans the source of the given file, so that the span of each token identifies where it came from
+#[allow(clippy::needless_lifetimes)]
+pub fn scan_file<'a>(file_id: FileId, source: &'a str) -> (Vec<Token<'a>>, Vec<LexicalDiagnostic>) {
+    let mut scanner = crate::scanner::scanner::Scanner::for_source(file_id, source);
     scanner.scan_tokens();
-    scanner.tokens()
+    scanner.finish()
+}
+
+/// Scans a file whose Unicode escapes have been translated. Lines and columns are those of the
+/// file itself, where each escape takes up as many columns as it is long.
+#[allow(clippy::needless_lifetimes)]
+pub fn scan_translated<'a>(file_id: FileId, source: &'a TranslatedSource) -> (Vec<Token<'a>>, Vec<LexicalDiagnostic>) {
+    let mut scanner = crate::scanner::scanner::Scanner::for_source(file_id, source.text());
+    scanner.escapes = source.escapes();
+    scanner.scan_tokens();
+    scanner.finish()
 }
 
 pub struct Scanner<'a> {
+    file_id: FileId,
     source: &'a str,
     token_start: usize,
+    token_start_position: SourcePosition,
     current_position: usize,
     end: usize,
-    line: i32,
+    line: u32,
+    column: u32,
     tokens: Vec<Token<'a>>,
+    diagnostics: Vec<LexicalDiagnostic>,
+    pending_doc_comment: Option<&'a str>,
+    /// Where the characters written as Unicode escapes are in the source, see [`TranslatedSource`]
+    escapes: &'a [(usize, usize)],
 }
 
 impl<'a> Scanner<'a> {
-    pub fn for_source(source: &'a str) -> Self {
+    pub fn for_source(file_id: FileId, source: &'a str) -> Self {
         Self {
+            file_id,
             source,
             token_start: 0,
+            token_start_position: SourcePosition::default(),
             current_position: 0,
             end: source.len(),
             line: 1,
+            column: 1,
             tokens: vec![],
+            diagnostics: vec![],
+            pending_doc_comment: None,
+            escapes: &[],
         }
     }
     pub fn scan_tokens(&mut self) {
@@ -57,100 +203,311 @@ impl<'a> Scanner<'a> {
 
             self.next_token();
         }
-        self.tokens.push(Token::empty(TokenType::Eof, self.current_position));
+        self.prepare_token_start();
+        self.add_token(Token::empty(TokenType::Eof, self.current_span()));
     }
 
-    pub fn tokens(self) -> Vec<Token<'a>> {
-        self.tokens
+    pub fn finish(self) -> (Vec<Token<'a>>, Vec<LexicalDiagnostic>) {
+        (self.tokens, self.diagnostics)
     }
 
     fn next_token(&mut self) {
         let next_char = self.next_char();
-        if Self::is_newline(next_char) {
-            self.line = self.line + 1;
+        if Self::is_newline(next_char) || Self::is_whitespace(next_char) {
+            return;
+        }
+
+        if next_char == '/' && self.peek() == '/' {
+            self.line_comment();
             return;
         }
 
-        if Self::is_whitespace(next_char) {
+        if next_char == '/' && self.peek() == '*' {
+            self.block_comment();
+            return;
+        }
+
+        if Self::is_digit(next_char) || (next_char == '.' && Self::is_digit(self.peek())) {
+            let token = self.number_token();
+            self.add_token(token);
+            return;
+        }
+
+        if let Some((operator, token_type)) = OPERATOR_TOKENS
+            .iter()
+            .find(|(operator, _)| self.source[self.token_start..].starts_with(operator))
+        {
+            // The first character has already been consumed
+            for _ in 1..operator.len() {
+                self.next_char();
+            }
+            let token = self.create_token(*token_type);
+            self.add_token(token);
             return;
         }
 
         if let Some(token_type) = SINGLE_CHAR_TOKENS.get(&next_char) {
-            self.tokens.push(self.create_token(*token_type));
+            let token = self.create_token(*token_type);
+            self.add_token(token);
+            return;
+        }
+
+        if next_char == '"' && self.source[self.token_start..].starts_with("\"\"\"") {
+            let token = self.text_block_token();
+            self.add_token(token);
             return;
         }
 
         if next_char == '"' {
-            if let Some(token) = self.string_token() {
-                self.tokens.push(token);
-            }
+            let token = self.string_token();
+            self.add_token(token);
             return;
         }
 
-        if Self::is_alpha(next_char) {
-            if let Some(token) = self.identifier_token() {
-                self.tokens.push(token);
-            }
+        if next_char == '\'' {
+            let token = self.char_token();
+            self.add_token(token);
             return;
         }
 
-        // Handle unexpected character
+        if Self::is_identifier_start(next_char) {
+            let token = self.identifier_token();
+            self.add_token(token);
+            return;
+        }
+
+        self.report(LexicalErrorKind::UnexpectedCharacter(next_char), self.current_span());
+    }
 
+    fn report(&mut self, kind: LexicalErrorKind, span: SourceSpan) {
+        self.diagnostics.push(LexicalDiagnostic::new(kind, span));
+    }
+
+    /// Attaches any Javadoc comment that preceded the token, so that it is available as trivia.
+    fn add_token(&mut self, token: Token<'a>) {
+        self.tokens.push(token.with_doc_comment(self.pending_doc_comment.take()));
+    }
+
+    fn line_comment(&mut self) {
+        while self.peek() != '\n' && !self.is_finished() {
+            self.next_char();
+        }
+    }
+
+    /// Block comments do not nest, so any `/*` inside the comment is ignored and the first `*/` ends it.
+    fn block_comment(&mut self) {
+        // Opening '*'
+        self.next_char();
+
+        loop {
+            if self.is_finished() {
+                self.report(LexicalErrorKind::UnterminatedComment, self.current_span());
+                return;
+            }
+            if self.next_char() == '*' && self.peek() == '/' {
+                self.next_char();
+                break;
+            }
+        }
+
+        let comment = &self.source[self.token_start..self.current_position];
+        // `/**/` is an empty block comment rather than an empty Javadoc comment
+        if comment.starts_with("/**") && comment.len() > 4 {
+            self.pending_doc_comment = Some(comment);
+        }
     }
 
     fn create_token(&self, token_type: TokenType) -> Token<'a> {
         Token::without_literal(
             token_type,
             &self.source[self.token_start..self.current_position],
-            self.token_start,
-            self.current_position,
+            self.current_span(),
         )
     }
 
-    fn create_token_with_literal(&self, token_type: TokenType, literal: Literal<'a>) -> Token<'a> {
+    fn create_token_with_literal(&self, token_type: TokenType, literal: Literal) -> Token<'a> {
         Token::with_literal(
             token_type,
             &self.source[self.token_start..self.current_position],
             literal,
-            self.token_start,
-            self.current_position,
+            self.current_span(),
         )
     }
 
-    fn string_token(&mut self) -> Option<Token<'a>> {
-        while self.peek() != '"' && !self.is_finished() {
-            if self.peek() == '\n' {
-                self.line = self.line + 1;
-            }
+    /// The span from the start of the current token up to the current position
+    fn current_span(&self) -> SourceSpan {
+        SourceSpan::new(self.file_id, self.token_start_position, self.position())
+    }
+
+    fn position(&self) -> SourcePosition {
+        SourcePosition::new(self.line, self.column, self.current_position)
+    }
+
+    /// The span of `length` bytes at `offset` into the current token, which must be on a single line
+    fn span_within_token(&self, offset: usize, length: usize) -> SourceSpan {
+        let token = &self.source[self.token_start..];
+        let position_at = |offset: usize| {
+            let column = self.token_start_position.column + self.columns_in(self.token_start, &token[..offset]);
+            SourcePosition::new(self.token_start_position.line, column, self.token_start + offset)
+        };
+        SourceSpan::new(self.file_id, position_at(offset), position_at(offset + length))
+    }
+
+    /// String literals end at the closing quote or, when unterminated, at the end of the line
+    fn string_token(&mut self) -> Token<'a> {
+        let body_end = self.literal_body('"');
+        if self.peek() == '"' {
             self.next_char();
+        } else {
+            self.report(LexicalErrorKind::UnterminatedString, self.current_span());
         }
 
+        let raw = &self.source[self.token_start + 1..body_end];
+        let value = escape::unescape(raw).unwrap_or_else(|e| {
+            self.report_invalid_escape(e);
+            raw.to_string()
+        });
+        self.create_token_with_literal(TokenType::String, Literal::String(value))
+    }
+
+    /// The opening `"""` must be followed by a line terminator, and the content runs from the next
+    /// line up to the closing `"""`. Quotes can appear unescaped in the content, as long as there are
+    /// fewer than three in a row.
+    fn text_block_token(&mut self) -> Token<'a> {
+        // The rest of the opening delimiter
+        self.next_char();
+        self.next_char();
+
+        while matches!(self.peek(), ' ' | '\t' | '\x0C') {
+            self.next_char();
+        }
+        match self.peek() {
+            '\n' | '\r' => {
+                if self.next_char() == '\r' && self.peek() == '\n' {
+                    self.next_char();
+                }
+            }
+            _ => self.report(LexicalErrorKind::TextBlockOpeningDelimiter, self.current_span()),
+        }
+
+        let content_start = self.current_position;
+        while !self.is_finished() && !self.source[self.current_position..].starts_with("\"\"\"") {
+            if self.next_char() == '\\' && !self.is_finished() {
+                self.next_char();
+            }
+        }
+        let content = &self.source[content_start..self.current_position];
+
         if self.is_finished() {
-            // TODO: handle unterminated string error
-            return None;
+            self.report(LexicalErrorKind::UnterminatedTextBlock, self.current_span());
+        } else {
+            for _ in 0..3 {
+                self.next_char();
+            }
         }
 
-        // Closing quote
-        self.next_char();
+        let value = text_block_value(content).unwrap_or_else(|e| {
+            // Stripping white space moves the escape, so the whole text block is reported
+            self.report(LexicalErrorKind::InvalidEscape(e.sequence), self.current_span());
+            content.to_string()
+        });
+        self.create_token_with_literal(TokenType::String, Literal::String(value))
+    }
 
-        let string_literal = &self.source[self.token_start + 1..self.current_position - 1];
-        Some(self.create_token_with_literal(TokenType::String, Literal::String(string_literal)))
+    fn char_token(&mut self) -> Token<'a> {
+        let body_end = self.literal_body('\'');
+        let is_terminated = self.peek() == '\'';
+        if is_terminated {
+            self.next_char();
+        } else {
+            self.report(LexicalErrorKind::UnterminatedCharacter, self.current_span());
+        }
+
+        let raw = &self.source[self.token_start + 1..body_end];
+        let value = match escape::unescape_to_utf16(raw) {
+            Ok(code_units) if code_units.len() == 1 => code_units[0],
+            Ok(_) => {
+                if is_terminated {
+                    let lexeme = self.source[self.token_start..self.current_position].to_string();
+                    self.report(LexicalErrorKind::InvalidCharacterLiteral(lexeme), self.current_span());
+                }
+                0
+            }
+            Err(e) => {
+                self.report_invalid_escape(e);
+                0
+            }
+        };
+        self.create_token_with_literal(TokenType::CharLiteral, Literal::Char(value))
+    }
+
+    /// Consumes the body of a string or character literal up to its closing quote or the end of the
+    /// line, skipping over escaped characters. Returns the offset where the body ends.
+    fn literal_body(&mut self, quote: char) -> usize {
+        let is_line_end = |c: char| c == '\n' || c == '\r';
+        while self.peek() != quote && !is_line_end(self.peek()) && !self.is_finished() {
+            if self.next_char() == '\\' && !is_line_end(self.peek()) && !self.is_finished() {
+                self.next_char();
+            }
+        }
+        self.current_position
     }
 
-    fn identifier_token(&mut self) -> Option<Token<'a>> {
-        while Self::is_alpha_numeric(self.peek()) {
+    fn report_invalid_escape(&mut self, e: escape::InvalidEscape) {
+        // The body starts after the opening quote
+        let span = self.span_within_token(1 + e.offset, e.sequence.len());
+        self.report(LexicalErrorKind::InvalidEscape(e.sequence), span);
+    }
+
+    fn number_token(&mut self) -> Token<'a> {
+        let is_radix_prefixed = self.source[self.token_start..].starts_with('0') && matches!(self.peek(), 'x' | 'X' | 'b' | 'B');
+        let mut has_fraction = self.source[self.token_start..].starts_with('.');
+
+        loop {
+            while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+                self.next_char();
+            }
+            if is_radix_prefixed {
+                break;
+            }
+
+            let lexeme = &self.source[self.token_start..self.current_position];
+            if lexeme.ends_with(['e', 'E']) && matches!(self.peek(), '+' | '-') {
+                self.next_char();
+            } else if !has_fraction && self.peek() == '.' {
+                has_fraction = true;
+                self.next_char();
+            } else {
+                break;
+            }
+        }
+
+        let lexeme = &self.source[self.token_start..self.current_position];
+        match numeric_literal::parse(lexeme) {
+            Some((token_type, literal)) => self.create_token_with_literal(token_type, literal),
+            None => {
+                self.report(LexicalErrorKind::MalformedNumber(lexeme.to_string()), self.current_span());
+                // A placeholder, so that parsing can carry on and report any later errors
+                self.create_token_with_literal(TokenType::IntLiteral, Literal::Int(0))
+            }
+        }
+    }
+
+    fn identifier_token(&mut self) -> Token<'a> {
+        while Self::is_identifier_part(self.peek()) {
             self.next_char();
         }
 
         let identifier = &self.source[self.token_start..self.current_position];
         match IDENTIFIER_KEYWORDS.get(&identifier) {
-            Some(identifier) => Some(self.create_token(*identifier)),
-            None => Some(self.create_token(TokenType::Identifier)),
+            Some(identifier) => self.create_token(*identifier),
+            None => self.create_token(TokenType::Identifier),
         }
     }
 
     fn prepare_token_start(&mut self) {
         self.token_start = self.current_position;
+        self.token_start_position = self.position();
     }
 
     fn is_finished(&self) -> bool {
@@ -161,28 +518,70 @@ impl<'a> Scanner<'a> {
         c == '\n'
     }
 
+    /// Spaces, tabs and form feeds separate tokens (JLS §3.6), as do line terminators
     fn is_whitespace(c: char) -> bool {
-        c == ' ' || c == '\r' || c == '\t'
+        c == ' ' || c == '\r' || c == '\t' || c == '\x0C'
     }
 
-    fn is_alpha(c: char) -> bool {
-        (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_'
+    /// Java letters (JLS §3.8) are the Unicode letters, plus currency symbols and connecting
+    /// punctuation such as `$` and `_`
+    fn is_identifier_start(c: char) -> bool {
+        if c.is_ascii() {
+            return c.is_ascii_alphabetic() || c == '_' || c == '$';
+        }
+        unicode_ident::is_xid_start(c) || Self::is_currency_symbol(c) || Self::is_connecting_punctuation(c)
     }
 
-    fn is_digit(c: char) -> bool {
-        c >= '0' && c <= '9'
+    fn is_identifier_part(c: char) -> bool {
+        if c.is_ascii() {
+            return c.is_ascii_alphanumeric() || c == '_' || c == '$';
+        }
+        unicode_ident::is_xid_continue(c) || Self::is_currency_symbol(c)
+    }
+
+    fn is_currency_symbol(c: char) -> bool {
+        matches!(c, '\u{A2}'..='\u{A5}' | '\u{58F}' | '\u{60B}' | '\u{9F2}' | '\u{9F3}' | '\u{E3F}' | '\u{17DB}' | '\u{20A0}'..='\u{20C0}' | '\u{FDFC}' | '\u{FE69}' | '\u{FF04}' | '\u{FFE0}' | '\u{FFE1}' | '\u{FFE5}' | '\u{FFE6}')
+    }
+
+    fn is_connecting_punctuation(c: char) -> bool {
+        matches!(c, '\u{203F}' | '\u{2040}' | '\u{2054}' | '\u{FE33}' | '\u{FE34}' | '\u{FE4D}'..='\u{FE4F}' | '\u{FF3F}')
     }
 
-    fn is_alpha_numeric(c: char) -> bool {
-        Self::is_alpha(c) || Self::is_digit(c)
+    fn is_digit(c: char) -> bool {
+        c.is_ascii_digit()
     }
 
+    /// Advances past the next character, which may be several bytes long. A line ends at `\n`,
+    /// `\r` or `\r\n` (JLS §3.4), and columns count characters rather than bytes.
     fn next_char(&mut self) -> char {
         let c = self.source[self.current_position..].chars().next().unwrap();
-        self.current_position = self.current_position + 1;
+        let escape_length = self.escape_length_at(self.current_position);
+        self.current_position += c.len_utf8();
+        if let Some(escape_length) = escape_length {
+            // An escaped line terminator ends a line of the translated text, but not of the file
+            self.column += escape_length as u32;
+        } else if Self::is_newline(c) || (c == '\r' && self.peek() != '\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
         c
     }
 
+    /// The length of the Unicode escape the character at the offset was written as, if it was
+    fn escape_length_at(&self, offset: usize) -> Option<usize> {
+        let index = self.escapes.binary_search_by_key(&offset, |(escape_offset, _)| *escape_offset).ok()?;
+        Some(self.escapes[index].1)
+    }
+
+    /// The columns the text starting at the offset takes up in the file
+    fn columns_in(&self, offset: usize, text: &str) -> u32 {
+        text.char_indices()
+            .map(|(index, _)| self.escape_length_at(offset + index).unwrap_or(1) as u32)
+            .sum()
+    }
+
     fn peek(&self) -> char {
         if self.is_finished() {
             return '\0';
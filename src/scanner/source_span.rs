@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter};
+
+/// Identifies the source file a span belongs to. Files are numbered in the order they are handed
+/// to the compiler, and the id is mapped back to a path when reporting diagnostics.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FileId(pub usize);
+
+/// The paths of the files compiled together, from which each [`FileId`] is handed out
+#[derive(Debug, Default)]
+pub struct SourceFiles {
+    paths: Vec<String>,
+}
+
+impl SourceFiles {
+    /// Adds the file at the path, returning the id its spans are to carry
+    pub fn add(&mut self, path: &str) -> FileId {
+        self.paths.push(path.to_string());
+        FileId(self.paths.len() - 1)
+    }
+
+    pub fn path(&self, file_id: FileId) -> &str {
+        &self.paths[file_id.0]
+    }
+}
+
+/// A position in a source file. Lines and columns are 1-based and count each Unicode escape as
+/// written, the offset is in bytes of the scanned text, after escapes have been translated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub line: u32,
+    pub column: u32,
+    pub offset: usize,
+}
+
+impl SourcePosition {
+    pub fn new(line: u32, column: u32, offset: usize) -> Self {
+        Self { line, column, offset }
+    }
+}
+
+impl Default for SourcePosition {
+    fn default() -> Self {
+        Self::new(1, 1, 0)
+    }
+}
+
+/// The region of a source file covered by a token or AST node. `end` is exclusive.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceSpan {
+    file_id: FileId,
+    start: SourcePosition,
+    end: SourcePosition,
+}
+
+impl SourceSpan {
+    pub fn new(file_id: FileId, start: SourcePosition, end: SourcePosition) -> Self {
+        Self { file_id, start, end }
+    }
+
+    pub fn file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub fn start(&self) -> SourcePosition {
+        self.start
+    }
+
+    pub fn end(&self) -> SourcePosition {
+        self.end
+    }
+
+    pub fn line(&self) -> u32 {
+        self.start.line
+    }
+
+    pub fn column(&self) -> u32 {
+        self.start.column
+    }
+
+    /// The span running from the start of this span to the end of `other`
+    pub fn to(&self, other: SourceSpan) -> SourceSpan {
+        SourceSpan::new(self.file_id, self.start, other.end)
+    }
+
+    /// The span of the last character of this span, such as the closing brace of a block
+    pub fn last_character(&self) -> SourceSpan {
+        let start = SourcePosition::new(self.end.line, self.end.column.saturating_sub(1), self.end.offset.saturating_sub(1));
+        SourceSpan::new(self.file_id, start, self.end)
+    }
+
+    /// Splits a single line span into the first `length` bytes and the remainder
+    pub fn split_at(&self, length: usize) -> (SourceSpan, SourceSpan) {
+        let middle = SourcePosition::new(self.start.line, self.start.column + length as u32, self.start.offset + length);
+        (
+            SourceSpan::new(self.file_id, self.start, middle),
+            SourceSpan::new(self.file_id, middle, self.end),
+        )
+    }
+}
+
+/// Formats as `line:column`, to be prefixed with the file name when reported
+impl Display for SourceSpan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.start.line, self.start.column)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::scanner::escape;
+use crate::scanner::escape::InvalidEscape;
+
+/// Computes the value of a text block from its content, the text between the line terminator
+/// after the opening `"""` and the closing `"""` (JLS §3.10.6). Line terminators are normalised
+/// to `\n`, incidental white space is stripped, and only then are escape sequences interpreted, so
+/// that `\s` and `\<line-terminator>` survive the stripping.
+pub fn text_block_value(content: &str) -> Result<String, InvalidEscape> {
+    let normalised = content.replace("\r\n", "\n").replace('\r', "\n");
+    escape::unescape(&strip_incidental_white_space(&normalised))
+}
+
+/// Removes the indentation common to every non-blank line, and to the line holding the closing
+/// delimiter, along with all trailing white space, as `String::stripIndent` does
+fn strip_incidental_white_space(content: &str) -> String {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let last_line = lines[lines.len() - 1];
+
+    let mut indentation = lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| leading_white_space(line))
+        .min()
+        .unwrap_or(usize::MAX);
+    // The closing delimiter counts towards the indentation even when it has a line of its own
+    if is_blank(last_line) {
+        indentation = indentation.min(last_line.chars().count());
+    }
+
+    lines
+        .iter()
+        .map(|line| {
+            if is_blank(line) {
+                ""
+            } else {
+                let start = line.char_indices().nth(indentation).map_or(line.len(), |(offset, _)| offset);
+                line[start..].trim_end_matches(is_white_space)
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+fn is_blank(line: &str) -> bool {
+    line.chars().all(is_white_space)
+}
+
+fn leading_white_space(line: &str) -> usize {
+    line.chars().take_while(|c| is_white_space(*c)).count()
+}
+
+fn is_white_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0C')
+}
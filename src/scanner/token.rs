@@ -1,11 +1,61 @@
 use crate::scanner::literal::Literal;
+use crate::scanner::scanner::spelling;
+use crate::scanner::source_span::SourceSpan;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum TokenType {
-    // Reserved keywords
+    // Reserved keywords (JLS §3.9)
+    Abstract,
+    Assert,
+    Boolean,
+    Break,
+    Byte,
+    Case,
+    Catch,
+    Char,
     Class,
+    Const,
+    Continue,
+    Default,
+    Do,
+    Double,
+    Else,
+    Enum,
+    Extends,
+    Final,
+    Finally,
+    Float,
+    For,
+    Goto,
+    If,
+    Implements,
+    Import,
+    Instanceof,
+    Int,
+    Interface,
+    Long,
+    Native,
+    New,
+    Package,
+    Private,
+    Protected,
     Public,
+    Return,
+    Short,
     Static,
+    Strictfp,
+    Super,
+    Switch,
+    Synchronized,
+    This,
+    Throw,
+    Throws,
+    Transient,
+    Try,
+    Void,
+    Volatile,
+    While,
+    Underscore,
 
     // Single character tokens
     LeftParen,
@@ -16,61 +66,185 @@ pub enum TokenType {
     RightSquareBracket,
     // DoubleQuote,
     SemiColon,
-    Dot,
     Comma,
+
+    // Operators and symbol separators
+    Dot,
+    Ellipsis,
+    At,
+    ColonColon,
+    Arrow,
+    Question,
+    Colon,
     Equal,
+    EqualEqual,
+    Bang,
+    BangEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    AmpAmp,
+    PipePipe,
+    PlusPlus,
+    MinusMinus,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Amp,
+    Pipe,
+    Caret,
+    Tilde,
+    LessLess,
+    GreaterGreater,
+    GreaterGreaterGreater,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    AmpEqual,
+    PipeEqual,
+    CaretEqual,
+    LessLessEqual,
+    GreaterGreaterEqual,
+    GreaterGreaterGreaterEqual,
 
     // Literals
     Identifier,
+    True,
+    False,
+    Null,
     String,
+    CharLiteral,
+    IntLiteral,
+    LongLiteral,
+    FloatLiteral,
+    DoubleLiteral,
 
     Eof,
 }
 
-#[derive(Debug, Copy, Clone)]
+impl TokenType {
+    /// The token as a syntax error names what was expected, in the same terms as what was found:
+    /// `')'`, or `an identifier` for a token that can be written many ways
+    pub fn description(&self) -> String {
+        match self {
+            TokenType::Identifier => "an identifier".to_string(),
+            TokenType::String => "a string literal".to_string(),
+            TokenType::CharLiteral => "a character literal".to_string(),
+            TokenType::IntLiteral => "an int literal".to_string(),
+            TokenType::LongLiteral => "a long literal".to_string(),
+            TokenType::FloatLiteral => "a float literal".to_string(),
+            TokenType::DoubleLiteral => "a double literal".to_string(),
+            TokenType::Eof => "end of file".to_string(),
+            token_type => match spelling(*token_type) {
+                Some(spelling) => format!("'{}'", spelling),
+                None => format!("{:?}", token_type),
+            },
+        }
+    }
+}
+
+/// Words that are only keywords in certain contexts (JLS §3.9). They are scanned as identifiers,
+/// leaving it to the parser to decide whether they act as keywords where they appear.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ContextualKeyword {
+    Permits,
+    Record,
+    Sealed,
+    Var,
+    Yield,
+}
+
+impl ContextualKeyword {
+    pub fn from_lexeme(lexeme: &str) -> Option<Self> {
+        match lexeme {
+            "permits" => Some(ContextualKeyword::Permits),
+            "record" => Some(ContextualKeyword::Record),
+            "sealed" => Some(ContextualKeyword::Sealed),
+            "var" => Some(ContextualKeyword::Var),
+            "yield" => Some(ContextualKeyword::Yield),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Token<'a> {
     token_type: TokenType,
     lexeme: Option<&'a str>,
-    literal: Option<Literal<'a>>,
-    start: usize,
-    end: usize,
+    literal: Option<Literal>,
+    span: SourceSpan,
+    doc_comment: Option<&'a str>,
 }
 
 impl<'a> Token<'a> {
-    pub fn empty(token_type: TokenType, position: usize) -> Self {
+    pub fn empty(token_type: TokenType, span: SourceSpan) -> Self {
         Self {
             token_type,
             lexeme: None,
             literal: None,
-            start: position,
-            end: position,
+            span,
+            doc_comment: None,
         }
     }
 
-    pub fn without_literal(token_type: TokenType, lexeme: &'a str, start: usize, end: usize) -> Self {
+    pub fn without_literal(token_type: TokenType, lexeme: &'a str, span: SourceSpan) -> Self {
         Self {
             token_type,
             lexeme: Some(lexeme),
             literal: None,
-            start,
-            end,
+            span,
+            doc_comment: None,
         }
     }
 
-    pub fn with_literal(token_type: TokenType, lexeme: &'a str, literal: Literal<'a>, start: usize, end: usize) -> Self {
+    pub fn with_literal(token_type: TokenType, lexeme: &'a str, literal: Literal, span: SourceSpan) -> Self {
         Self {
             token_type,
             lexeme: Some(lexeme),
             literal: Some(literal),
-            start,
-            end,
+            span,
+            doc_comment: None,
         }
     }
 
+    pub fn with_doc_comment(self, doc_comment: Option<&'a str>) -> Self {
+        Self { doc_comment, ..self }
+    }
+
+    /// Splits a token starting with `>`, such as `>>` or `>>=`, into a single `>` and the token
+    /// made up of the remaining characters. Maximal munch scans the `>>` of `List<List<String>>`
+    /// as a shift, so the parser uses this to close one level of type arguments at a time.
+    pub fn split_leading_greater(&self) -> Option<(Token<'a>, Token<'a>)> {
+        let remainder_type = match self.token_type {
+            TokenType::GreaterGreater => TokenType::Greater,
+            TokenType::GreaterGreaterGreater => TokenType::GreaterGreater,
+            TokenType::GreaterEqual => TokenType::Equal,
+            TokenType::GreaterGreaterEqual => TokenType::GreaterEqual,
+            TokenType::GreaterGreaterGreaterEqual => TokenType::GreaterGreaterEqual,
+            _ => return None,
+        };
+        let lexeme = self.lexeme();
+        let (greater_span, remainder_span) = self.span.split_at(1);
+
+        Some((
+            Token::without_literal(TokenType::Greater, &lexeme[..1], greater_span).with_doc_comment(self.doc_comment),
+            Token::without_literal(remainder_type, &lexeme[1..], remainder_span),
+        ))
+    }
+
     pub fn token_type(&self) -> TokenType {
         self.token_type
     }
 
+    pub fn is_contextual_keyword(&self, keyword: ContextualKeyword) -> bool {
+        self.token_type == TokenType::Identifier && ContextualKeyword::from_lexeme(self.lexeme()) == Some(keyword)
+    }
+
     pub fn lexeme(&self) -> &'a str {
         match self.lexeme {
             Some(l) => l,
@@ -78,18 +252,27 @@ impl<'a> Token<'a> {
         }
     }
     
-    pub fn literal(&self) -> &Literal<'a> {
+    pub fn literal(&self) -> &Literal {
         match &self.literal {
             Some(l) => l,
             None => panic!("Unavailable for token {:?}", self.token_type)
         }
     }
     
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
     pub fn start(&self) -> usize {
-        self.start
+        self.span.start().offset
     }
 
     pub fn end(&self) -> usize {
-        self.end
+        self.span.end().offset
+    }
+
+    /// The `/** ... */` Javadoc comment immediately preceding this token, if any
+    pub fn doc_comment(&self) -> Option<&'a str> {
+        self.doc_comment
     }
 }
@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+
+/// Source whose Unicode escapes have been translated, remembering where each escape was so that a
+/// position in the translated text can be reported where it is in the file
+pub struct TranslatedSource<'a> {
+    text: Cow<'a, str>,
+    /// The offset in the text of each character written as an escape, with the length of the escape
+    escapes: Vec<(usize, usize)>,
+}
+
+impl<'a> TranslatedSource<'a> {
+    /// Translates `\uXXXX` Unicode escapes into the characters they represent, as the first step of
+    /// lexical translation (JLS §3.3). This happens before tokens are recognised, so escapes can
+    /// spell identifiers, keywords or even line terminators anywhere in the source.
+    ///
+    /// A backslash only starts an escape when it is preceded by an even number of backslashes, so
+    /// `\\u0041` is left alone. A surrogate pair written as two escapes is combined into one
+    /// character, while a lone surrogate cannot be represented in the translated text and is left
+    /// escaped, for the literal it appears in to decode into a single UTF-16 code unit.
+    pub fn new(source: &'a str) -> Self {
+        if !source.contains("\\u") {
+            return Self { text: Cow::Borrowed(source), escapes: vec![] };
+        }
+
+        let mut translated = String::with_capacity(source.len());
+        let mut escapes = vec![];
+        let mut position = 0;
+        let mut preceding_backslashes = 0;
+
+        while let Some(c) = source[position..].chars().next() {
+            if c == '\\'
+                && preceding_backslashes % 2 == 0
+                && let Some((code_unit, length)) = unicode_escape_at(source, position)
+            {
+                let (character, length) = combine_surrogates(source, position, code_unit, length);
+                match character {
+                    Some(character) => {
+                        escapes.push((translated.len(), length));
+                        translated.push(character);
+                    }
+                    None => translated.push_str(&source[position..position + length]),
+                }
+                position += length;
+                preceding_backslashes = 0;
+                continue;
+            }
+
+            preceding_backslashes = if c == '\\' { preceding_backslashes + 1 } else { 0 };
+            translated.push(c);
+            position += c.len_utf8();
+        }
+
+        Self { text: Cow::Owned(translated), escapes }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn escapes(&self) -> &[(usize, usize)] {
+        &self.escapes
+    }
+}
+
+/// Translates the Unicode escapes of the source, see [`TranslatedSource::new`]
+pub fn translate_unicode_escapes(source: &str) -> Cow<'_, str> {
+    TranslatedSource::new(source).text
+}
+
+/// Combines a high surrogate with an immediately following low surrogate escape
+fn combine_surrogates(source: &str, position: usize, code_unit: u16, length: usize) -> (Option<char>, usize) {
+    if (0xD800..0xDC00).contains(&code_unit)
+        && let Some((low, low_length)) = unicode_escape_at(source, position + length)
+        && let Some(Ok(character)) = char::decode_utf16([code_unit, low]).next()
+    {
+        return (Some(character), length + low_length);
+    }
+    (char::from_u32(code_unit as u32), length)
+}
+
+/// Reads a `\u+XXXX` escape starting at `position`, returning its code unit and length in bytes.
+/// A `\u` without four hexadecimal digits is left for the literal or token it is in to report.
+fn unicode_escape_at(source: &str, position: usize) -> Option<(u16, usize)> {
+    let rest = source[position..].strip_prefix('\\')?;
+    let digits = rest.trim_start_matches('u');
+    let u_count = rest.len() - digits.len();
+    if u_count == 0 {
+        return None;
+    }
+
+    let hex = digits.get(..4).filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))?;
+    let code_unit = u16::from_str_radix(hex, 16).ok()?;
+    Some((code_unit, 1 + u_count + 4))
+}
@@ -1,9 +1,9 @@
-use std::fmt::format;
-use crate::ast::class::{AstClass, AstMethod, AstParameter};
+use crate::ast::class::{AstClass, AstEnumConstant, AstField, AstInitializer, AstMethod, AstParameter};
 use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
 use crate::build_ast;
 use crate::io::read_file;
+use crate::scanner::translate_unicode_escapes;
 use crate::test_support::{check_and_report_difference, check_and_report_difference_nested, do_comparison, ComparisonResult};
 
 const CLASS_WRAPPER: &str = r"
@@ -14,17 +14,25 @@ const CLASS_WRAPPER: &str = r"
 
 pub fn build_class_from_source_file_and_compare(file_path: &str, expected_class: AstClass) {
     let source = read_file(file_path);
-    let actual_class = build_ast(source.as_str());
+    let source = translate_unicode_escapes(source.as_str());
+    let compilation_unit = build_ast(&source).unwrap_or_else(|e| panic!("Could not build the AST: {:?}", e));
+    let actual_class = compilation_unit
+        .types()
+        .iter()
+        .find(|class| class.name() == expected_class.name())
+        .unwrap_or_else(|| panic!("No type named {:}", expected_class.name()));
 
-    let result = do_comparison(&expected_class, &actual_class, "Class", check_and_report_difference_in_class);
+    let result = do_comparison(&expected_class, actual_class, "Class", check_and_report_difference_in_class);
 
     assert_eq!(ComparisonResult::Match, result, "Not a match: {:}", result)
 }
 
 pub fn build_method_only_and_compare(content: &str, expected_method: AstMethod) {
     let source = CLASS_WRAPPER.replace("%%", content);
+    let source = translate_unicode_escapes(source.as_str());
 
-    let actual_class = build_ast(source.as_str());
+    let compilation_unit = build_ast(&source).unwrap_or_else(|e| panic!("Could not build the AST: {:?}", e));
+    let actual_class = &compilation_unit.types()[0];
 
     assert_eq!(1, actual_class.methods().len());
 
@@ -53,12 +61,83 @@ fn check_and_report_difference_in_class(expected_class: &AstClass, actual_class:
         format!("{:}.static", name).as_str(),
         differences,
     );
+    check_and_report_difference(
+        expected_class.is_abstract(),
+        actual_class.is_abstract(),
+        format!("{:}.abstract", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_class.is_interface(),
+        actual_class.is_interface(),
+        format!("{:}.interface", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_class.is_enum(),
+        actual_class.is_enum(),
+        format!("{:}.enum", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_class.is_record(),
+        actual_class.is_record(),
+        format!("{:}.record", name).as_str(),
+        differences,
+    );
+    check_and_report_difference_nested(
+        expected_class.record_components(),
+        actual_class.record_components(),
+        format!("{:}.record_component", name).as_str(),
+        differences,
+        check_and_report_differences_in_parameters,
+    );
     check_and_report_difference(
         expected_class.scope(),
         actual_class.scope(),
         format!("{:}.scope", name).as_str(),
         differences,
     );
+    check_and_report_difference(
+        expected_class.super_class(),
+        actual_class.super_class(),
+        format!("{:}.super_class", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_class.interfaces(),
+        actual_class.interfaces(),
+        format!("{:}.interfaces", name).as_str(),
+        differences,
+    );
+    check_and_report_difference_nested(
+        expected_class.enum_constants(),
+        actual_class.enum_constants(),
+        format!("{:}.enum_constant", name).as_str(),
+        differences,
+        check_and_report_differences_in_enum_constants,
+    );
+    check_and_report_difference_nested(
+        expected_class.fields(),
+        actual_class.fields(),
+        format!("{:}.field", name).as_str(),
+        differences,
+        check_and_report_differences_in_fields,
+    );
+    check_and_report_difference_nested(
+        expected_class.constructors(),
+        actual_class.constructors(),
+        format!("{:}.constructor", name).as_str(),
+        differences,
+        check_and_report_differences_in_methods,
+    );
+    check_and_report_difference_nested(
+        expected_class.initializers(),
+        actual_class.initializers(),
+        format!("{:}.initializer", name).as_str(),
+        differences,
+        check_and_report_differences_in_initializers,
+    );
     check_and_report_difference_nested(
         expected_class.methods(),
         actual_class.methods(),
@@ -66,6 +145,99 @@ fn check_and_report_difference_in_class(expected_class: &AstClass, actual_class:
         differences,
         check_and_report_differences_in_methods,
     );
+    check_and_report_difference_nested(
+        expected_class.member_types(),
+        actual_class.member_types(),
+        format!("{:}.member_type", name).as_str(),
+        differences,
+        check_and_report_difference_in_class,
+    );
+}
+
+fn check_and_report_differences_in_enum_constants(
+    expected_constant: &AstEnumConstant,
+    actual_constant: &AstEnumConstant,
+    name: &str,
+    differences: &mut Vec<String>,
+) {
+    check_and_report_difference(
+        expected_constant.name(),
+        actual_constant.name(),
+        format!("{:}.name", name).as_str(),
+        differences,
+    );
+    check_and_report_difference_nested(
+        expected_constant.arguments(),
+        actual_constant.arguments(),
+        format!("{:}.arguments", name).as_str(),
+        differences,
+        check_and_report_differences_in_expressions,
+    );
+    match (expected_constant.body(), actual_constant.body()) {
+        (Some(expected_body), Some(actual_body)) => {
+            check_and_report_difference_in_class(expected_body, actual_body, format!("{:}.body", name).as_str(), differences)
+        }
+        (None, None) => {}
+        (expected, actual) => differences.push(format!("{:}.body is different. Expected {:?} but was {:?}", name, expected, actual)),
+    }
+}
+
+fn check_and_report_differences_in_fields(expected_field: &AstField, actual_field: &AstField, name: &str, differences: &mut Vec<String>) {
+    check_and_report_difference(
+        expected_field.name(),
+        actual_field.name(),
+        format!("{:}.name", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_field.field_type(),
+        actual_field.field_type(),
+        format!("{:}.field_type", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_field.scope(),
+        actual_field.scope(),
+        format!("{:}.scope", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        (expected_field.is_static(), expected_field.is_final(), expected_field.is_transient(), expected_field.is_volatile()),
+        (actual_field.is_static(), actual_field.is_final(), actual_field.is_transient(), actual_field.is_volatile()),
+        format!("{:}.(static, final, transient, volatile)", name).as_str(),
+        differences,
+    );
+    match (expected_field.initializer(), actual_field.initializer()) {
+        (Some(expected_initializer), Some(actual_initializer)) => check_and_report_differences_in_expressions(
+            expected_initializer,
+            actual_initializer,
+            format!("{:}.initializer", name).as_str(),
+            differences,
+        ),
+        (None, None) => {}
+        (expected, actual) => differences.push(format!("{:}.initializer is different. Expected {:?} but was {:?}", name, expected, actual)),
+    }
+}
+
+fn check_and_report_differences_in_initializers(
+    expected_initializer: &AstInitializer,
+    actual_initializer: &AstInitializer,
+    name: &str,
+    differences: &mut Vec<String>,
+) {
+    check_and_report_difference(
+        expected_initializer.is_static(),
+        actual_initializer.is_static(),
+        format!("{:}.static", name).as_str(),
+        differences,
+    );
+    check_and_report_difference_nested(
+        expected_initializer.statements(),
+        actual_initializer.statements(),
+        format!("{:}.statements", name).as_str(),
+        differences,
+        check_and_report_differences_in_statements,
+    );
 }
 
 fn check_and_report_differences_in_methods(expected_method: &AstMethod, actual_method: &AstMethod, name: &str, differences: &mut Vec<String>) {
@@ -87,6 +259,18 @@ fn check_and_report_differences_in_methods(expected_method: &AstMethod, actual_m
         format!("{:}.final", name).as_str(),
         differences,
     );
+    check_and_report_difference(
+        (expected_method.is_abstract(), expected_method.is_synchronized(), expected_method.is_native(), expected_method.is_compact()),
+        (actual_method.is_abstract(), actual_method.is_synchronized(), actual_method.is_native(), actual_method.is_compact()),
+        format!("{:}.(abstract, synchronized, native, compact)", name).as_str(),
+        differences,
+    );
+    check_and_report_difference(
+        expected_method.scope(),
+        actual_method.scope(),
+        format!("{:}.scope", name).as_str(),
+        differences,
+    );
     check_and_report_difference(
         expected_method.return_type(),
         actual_method.return_type(),
@@ -129,6 +313,12 @@ fn check_and_report_differences_in_parameters(
         format!("{:}.param_type", name).as_str(),
         differences,
     );
+    check_and_report_difference(
+        expected_parameter.is_final(),
+        actual_parameter.is_final(),
+        format!("{:}.final", name).as_str(),
+        differences,
+    );
 }
 
 #[allow(unreachable_patterns)]
@@ -142,9 +332,11 @@ fn check_and_report_differences_in_statements(
         (
             Statement::Expression {
                 expression: expected_expression,
+                ..
             },
             Statement::Expression {
                 expression: actual_expression,
+                ..
             },
         ) => check_and_report_differences_in_expressions(
             expected_expression,
@@ -152,6 +344,191 @@ fn check_and_report_differences_in_statements(
             format!("{:}.expression", name).as_str(),
             differences,
         ),
+        (
+            Statement::ConstructorCall {
+                is_super: expected_is_super,
+                arguments: expected_arguments,
+                ..
+            },
+            Statement::ConstructorCall {
+                is_super: actual_is_super,
+                arguments: actual_arguments,
+                ..
+            },
+        ) => {
+            check_and_report_difference(expected_is_super, actual_is_super, format!("{:}.super", name).as_str(), differences);
+            check_and_report_difference_nested(
+                expected_arguments,
+                actual_arguments,
+                format!("{:}.arguments", name).as_str(),
+                differences,
+                |expected_argument, actual_argument, name, differences| {
+                    check_and_report_differences_in_expressions(expected_argument, actual_argument, name, differences)
+                },
+            );
+        }
+        (
+            Statement::VariableAssignment {
+                name: expected_name,
+                var_type: expected_var_type,
+                is_final: expected_is_final,
+                value: expected_value,
+                ..
+            },
+            Statement::VariableAssignment {
+                name: actual_name,
+                var_type: actual_var_type,
+                is_final: actual_is_final,
+                value: actual_value,
+                ..
+            },
+        ) => {
+            check_and_report_difference(expected_name, actual_name, format!("{:}.name", name).as_str(), differences);
+            check_and_report_difference(expected_var_type, actual_var_type, format!("{:}.var_type", name).as_str(), differences);
+            check_and_report_difference(expected_is_final, actual_is_final, format!("{:}.final", name).as_str(), differences);
+            match (expected_value, actual_value) {
+                (Some(expected_value), Some(actual_value)) => check_and_report_differences_in_expressions(
+                    expected_value,
+                    actual_value,
+                    format!("{:}.value", name).as_str(),
+                    differences,
+                ),
+                (None, None) => {}
+                (expected, actual) => differences.push(format!("{:}.value is different. Expected {:?} but was {:?}", name, expected, actual)),
+            }
+        }
+        (
+            Statement::Block {
+                statements: expected_statements,
+                ..
+            },
+            Statement::Block {
+                statements: actual_statements,
+                ..
+            },
+        ) => check_and_report_difference_nested(
+            expected_statements,
+            actual_statements,
+            format!("{:}.statements", name).as_str(),
+            differences,
+            |expected_statement, actual_statement, name, differences| {
+                check_and_report_differences_in_statements(expected_statement, actual_statement, name, differences)
+            },
+        ),
+        (Statement::Empty { .. }, Statement::Empty { .. }) => {}
+        (
+            Statement::If { condition: expected_condition, then_statement: expected_then, else_statement: expected_else, .. },
+            Statement::If { condition: actual_condition, then_statement: actual_then, else_statement: actual_else, .. },
+        ) => {
+            check_and_report_differences_in_expressions(expected_condition, actual_condition, format!("{:}.condition", name).as_str(), differences);
+            check_and_report_differences_in_statements(expected_then, actual_then, format!("{:}.then", name).as_str(), differences);
+            match (expected_else, actual_else) {
+                (Some(expected_else), Some(actual_else)) => {
+                    check_and_report_differences_in_statements(expected_else, actual_else, format!("{:}.else", name).as_str(), differences)
+                }
+                (None, None) => {}
+                (expected, actual) => differences.push(format!("{:}.else is different. Expected {:?} but was {:?}", name, expected, actual)),
+            }
+        }
+        (
+            Statement::While { condition: expected_condition, body: expected_body, .. },
+            Statement::While { condition: actual_condition, body: actual_body, .. },
+        )
+        | (
+            Statement::DoWhile { condition: expected_condition, body: expected_body, .. },
+            Statement::DoWhile { condition: actual_condition, body: actual_body, .. },
+        ) => {
+            check_and_report_differences_in_expressions(expected_condition, actual_condition, format!("{:}.condition", name).as_str(), differences);
+            check_and_report_differences_in_statements(expected_body, actual_body, format!("{:}.body", name).as_str(), differences);
+        }
+        (
+            Statement::For {
+                initializers: expected_initializers,
+                condition: expected_condition,
+                updates: expected_updates,
+                body: expected_body,
+                ..
+            },
+            Statement::For {
+                initializers: actual_initializers,
+                condition: actual_condition,
+                updates: actual_updates,
+                body: actual_body,
+                ..
+            },
+        ) => {
+            check_and_report_difference_nested(
+                expected_initializers,
+                actual_initializers,
+                format!("{:}.initializers", name).as_str(),
+                differences,
+                |expected_initializer, actual_initializer, name, differences| {
+                    check_and_report_differences_in_statements(expected_initializer, actual_initializer, name, differences)
+                },
+            );
+            match (expected_condition, actual_condition) {
+                (Some(expected_condition), Some(actual_condition)) => check_and_report_differences_in_expressions(
+                    expected_condition,
+                    actual_condition,
+                    format!("{:}.condition", name).as_str(),
+                    differences,
+                ),
+                (None, None) => {}
+                (expected, actual) => differences.push(format!("{:}.condition is different. Expected {:?} but was {:?}", name, expected, actual)),
+            }
+            check_and_report_difference_nested(
+                expected_updates,
+                actual_updates,
+                format!("{:}.updates", name).as_str(),
+                differences,
+                |expected_update, actual_update, name, differences| {
+                    check_and_report_differences_in_expressions(expected_update, actual_update, name, differences)
+                },
+            );
+            check_and_report_differences_in_statements(expected_body, actual_body, format!("{:}.body", name).as_str(), differences);
+        }
+        (
+            Statement::ForEach {
+                name: expected_name,
+                var_type: expected_var_type,
+                is_final: expected_is_final,
+                iterable: expected_iterable,
+                body: expected_body,
+                ..
+            },
+            Statement::ForEach {
+                name: actual_name,
+                var_type: actual_var_type,
+                is_final: actual_is_final,
+                iterable: actual_iterable,
+                body: actual_body,
+                ..
+            },
+        ) => {
+            check_and_report_difference(expected_name, actual_name, format!("{:}.name", name).as_str(), differences);
+            check_and_report_difference(expected_var_type, actual_var_type, format!("{:}.var_type", name).as_str(), differences);
+            check_and_report_difference(expected_is_final, actual_is_final, format!("{:}.final", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_iterable, actual_iterable, format!("{:}.iterable", name).as_str(), differences);
+            check_and_report_differences_in_statements(expected_body, actual_body, format!("{:}.body", name).as_str(), differences);
+        }
+        (Statement::Break { label: expected_label, .. }, Statement::Break { label: actual_label, .. })
+        | (Statement::Continue { label: expected_label, .. }, Statement::Continue { label: actual_label, .. }) => {
+            check_and_report_difference(expected_label, actual_label, format!("{:}.label", name).as_str(), differences);
+        }
+        (
+            Statement::Labeled { label: expected_label, statement: expected_statement, .. },
+            Statement::Labeled { label: actual_label, statement: actual_statement, .. },
+        ) => {
+            check_and_report_difference(expected_label, actual_label, format!("{:}.label", name).as_str(), differences);
+            check_and_report_differences_in_statements(expected_statement, actual_statement, format!("{:}.statement", name).as_str(), differences);
+        }
+        (Statement::Return { value: expected_value, .. }, Statement::Return { value: actual_value, .. }) => match (expected_value, actual_value) {
+            (Some(expected_value), Some(actual_value)) => {
+                check_and_report_differences_in_expressions(expected_value, actual_value, format!("{:}.value", name).as_str(), differences)
+            }
+            (None, None) => {}
+            (expected, actual) => differences.push(format!("{:}.value is different. Expected {:?} but was {:?}", name, expected, actual)),
+        },
         (expected, actual) => differences.push(format!("{:} is different. Expected {:?} but was {:?}", name, expected, actual).to_string()),
     }
 }
@@ -168,11 +545,13 @@ fn check_and_report_differences_in_expressions(
                 target: expected_target,
                 method_name: expected_method_name,
                 arguments: expected_arguments,
+                ..
             },
             Expression::Call {
                 target: actual_target,
                 method_name: actual_method_name,
                 arguments: actual_arguments,
+                ..
             },
         ) => {
             check_and_report_differences_in_expressions(expected_target, actual_target, format!("{:}.target", name).as_str(), differences);
@@ -192,30 +571,76 @@ fn check_and_report_differences_in_expressions(
                 },
             );
         }
-        (Expression::StringLiteral { value: expected_value }, Expression::StringLiteral { value: actual_value }) => {
+        (Expression::StringLiteral { value: expected_value, .. }, Expression::StringLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::BooleanLiteral { value: expected_value, .. }, Expression::BooleanLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::CharLiteral { value: expected_value, .. }, Expression::CharLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::IntLiteral { value: expected_value, .. }, Expression::IntLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::LongLiteral { value: expected_value, .. }, Expression::LongLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::FloatLiteral { value: expected_value, .. }, Expression::FloatLiteral { value: actual_value, .. }) => {
+            check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (Expression::DoubleLiteral { value: expected_value, .. }, Expression::DoubleLiteral { value: actual_value, .. }) => {
             check_and_report_difference(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
         }
         (
             Expression::Variable {
                 name: expected_name,
                 type_def: expected_type_def,
+                ..
             },
             Expression::Variable {
                 name: actual_name,
                 type_def: actual_type_def,
+                ..
             },
         ) => {
             check_and_report_difference(expected_name, actual_name, format!("{:}.name", name).as_str(), differences);
             check_and_report_difference(expected_type_def, actual_type_def, format!("{:}.type_def", name).as_str(), differences);
         }
+        (Expression::This { .. }, Expression::This { .. }) => {}
+        (
+            Expression::New {
+                class_type: expected_class_type,
+                arguments: expected_arguments,
+                ..
+            },
+            Expression::New {
+                class_type: actual_class_type,
+                arguments: actual_arguments,
+                ..
+            },
+        ) => {
+            check_and_report_difference(expected_class_type, actual_class_type, format!("{:}.class_type", name).as_str(), differences);
+            check_and_report_difference_nested(
+                expected_arguments,
+                actual_arguments,
+                format!("{:}.arguments", name).as_str(),
+                differences,
+                |expected_argument, actual_argument, name, differences| {
+                    check_and_report_differences_in_expressions(expected_argument, actual_argument, name, differences)
+                },
+            );
+        }
         (
             Expression::ChildIdentifier {
                 parent: expected_parent,
                 name: expected_name,
+                ..
             },
             Expression::ChildIdentifier {
                 parent: actual_parent,
                 name: actual_name,
+                ..
             },
         ) => {
             check_and_report_differences_in_expressions(expected_parent, actual_parent, format!("{:}.parent", name).as_str(), differences);
@@ -226,17 +651,115 @@ fn check_and_report_differences_in_expressions(
                 name: expected_name,
                 type_def: expected_type_def,
                 value: expected_value,
+                ..
             },
             Expression::Assignment {
                 name: actual_name,
                 type_def: actual_type_def,
                 value: actual_value,
+                ..
             },
         ) => {
             check_and_report_difference(expected_name, actual_name, format!("{:}.name", name).as_str(), differences);
             check_and_report_difference(expected_type_def, actual_type_def, format!("{:}.type_def", name).as_str(), differences);
             check_and_report_differences_in_expressions(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
         }
+        (
+            Expression::FieldAssignment {
+                qualifier: expected_qualifier,
+                name: expected_name,
+                value: expected_value,
+                ..
+            },
+            Expression::FieldAssignment {
+                qualifier: actual_qualifier,
+                name: actual_name,
+                value: actual_value,
+                ..
+            },
+        ) => {
+            check_and_report_differences_in_expressions(expected_qualifier, actual_qualifier, format!("{:}.qualifier", name).as_str(), differences);
+            check_and_report_difference(expected_name, actual_name, format!("{:}.name", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_value, actual_value, format!("{:}.value", name).as_str(), differences);
+        }
+        (
+            Expression::Binary {
+                left: expected_left,
+                operator: expected_operator,
+                right: expected_right,
+                ..
+            },
+            Expression::Binary {
+                left: actual_left,
+                operator: actual_operator,
+                right: actual_right,
+                ..
+            },
+        ) => {
+            check_and_report_differences_in_expressions(expected_left, actual_left, format!("{:}.left", name).as_str(), differences);
+            check_and_report_difference(expected_operator, actual_operator, format!("{:}.operator", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_right, actual_right, format!("{:}.right", name).as_str(), differences);
+        }
+        (
+            Expression::Unary {
+                operator: expected_operator,
+                operand: expected_operand,
+                ..
+            },
+            Expression::Unary {
+                operator: actual_operator,
+                operand: actual_operand,
+                ..
+            },
+        ) => {
+            check_and_report_difference(expected_operator, actual_operator, format!("{:}.operator", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_operand, actual_operand, format!("{:}.operand", name).as_str(), differences);
+        }
+        (
+            Expression::PrefixIncrement {
+                operand: expected_operand,
+                is_decrement: expected_is_decrement,
+                ..
+            },
+            Expression::PrefixIncrement {
+                operand: actual_operand,
+                is_decrement: actual_is_decrement,
+                ..
+            },
+        )
+        | (
+            Expression::PostfixIncrement {
+                operand: expected_operand,
+                is_decrement: expected_is_decrement,
+                ..
+            },
+            Expression::PostfixIncrement {
+                operand: actual_operand,
+                is_decrement: actual_is_decrement,
+                ..
+            },
+        ) => {
+            check_and_report_differences_in_expressions(expected_operand, actual_operand, format!("{:}.operand", name).as_str(), differences);
+            check_and_report_difference(expected_is_decrement, actual_is_decrement, format!("{:}.is_decrement", name).as_str(), differences);
+        }
+        (
+            Expression::Conditional {
+                condition: expected_condition,
+                when_true: expected_when_true,
+                when_false: expected_when_false,
+                ..
+            },
+            Expression::Conditional {
+                condition: actual_condition,
+                when_true: actual_when_true,
+                when_false: actual_when_false,
+                ..
+            },
+        ) => {
+            check_and_report_differences_in_expressions(expected_condition, actual_condition, format!("{:}.condition", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_when_true, actual_when_true, format!("{:}.when_true", name).as_str(), differences);
+            check_and_report_differences_in_expressions(expected_when_false, actual_when_false, format!("{:}.when_false", name).as_str(), differences);
+        }
         (unknown_expected, unknown_actual) => {
             differences.push(format!("{:} is different. Expected {:?} but was {:?}", name, unknown_expected, unknown_actual).to_string())
         }
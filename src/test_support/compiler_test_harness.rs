@@ -1,17 +1,118 @@
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use crate::compile;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use ristretto_classfile::ClassFile;
+use crate::compiler::CompileError;
+use crate::{build_ast, compile, compile_source, compile_sources, compiler};
+
+const CLASS_WRAPPER: &str = r"
+    public class Simple {
+        %%
+    }
+";
+
+static OUTPUT_DIRECTORY_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub fn compile_source_and_assert_output_is(source_file_path: &str, class_name: &str, expected_output: &str) {
-    let result = compile(source_file_path);
+    let result = compile(&[source_file_path]);
 
     result.unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
 
+    assert_output_is(Path::new("."), class_name, expected_output);
+}
+
+/// Compiles a whole compilation unit, running the class named by its fully qualified name
+pub fn compile_and_assert_output_is(source: &str, class_name: &str, expected_output: &str) {
+    let output_directory = new_output_directory();
+
+    compile_source(source, &output_directory).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+
+    assert_output_is(&output_directory, class_name, expected_output);
+}
+
+/// Compiles a whole compilation unit, then runs a driver, written in Java and launched from source by
+/// `java`, against the compiled class. This exercises what the compiler cannot yet express itself,
+/// such as creating instances.
+pub fn compile_and_assert_driver_output_is(source: &str, driver_source: &str, expected_output: &str) {
+    let output_directory = new_output_directory();
+
+    compile_source(source, &output_directory).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+    let driver_path = output_directory.join("Driver.java");
+    fs::write(&driver_path, driver_source).expect("failed to write driver");
+
+    assert_output_is(&output_directory, driver_path.to_str().unwrap(), expected_output);
+}
+
+/// Compiles several source files together, each given with its path, running the named class
+pub fn compile_sources_and_assert_output_is(sources: &[(&str, &str)], class_name: &str, expected_output: &str) {
+    let output_directory = new_output_directory();
+
+    compile_sources(sources, &output_directory).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+
+    assert_output_is(&output_directory, class_name, expected_output);
+}
+
+pub fn compile_method_and_assert_output_is(content: &str, expected_output: &str) {
+    let source = CLASS_WRAPPER.replace("%%", content);
+    let output_directory = new_output_directory();
+
+    compile_source(source.as_str(), &output_directory).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+
+    assert_output_is(&output_directory, "Simple", expected_output);
+}
+
+/// Compiles a method in the `Simple` wrapper class, returning the class file rather than running it
+pub fn compile_method_to_class_file(content: &str) -> ClassFile {
+    let source = CLASS_WRAPPER.replace("%%", content);
+    let compilation_unit = build_ast(&source).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+    let mut class_files = compiler::compile(&[compilation_unit]).unwrap_or_else(|e| panic!("Error encountered: {:?}", e));
+    class_files.remove(0)
+}
+
+pub fn compile_and_expect_error(source: &str) -> CompileError {
+    let output_directory = new_output_directory();
+
+    match compile_source(source, &output_directory) {
+        Ok(_) => panic!("Expected compilation to fail"),
+        Err(e) => e,
+    }
+}
+
+pub fn compile_sources_and_expect_error(sources: &[(&str, &str)]) -> CompileError {
+    let output_directory = new_output_directory();
+
+    match compile_sources(sources, &output_directory) {
+        Ok(_) => panic!("Expected compilation to fail"),
+        Err(e) => e,
+    }
+}
+
+pub fn compile_method_and_expect_error(content: &str) -> CompileError {
+    compile_and_expect_error(&CLASS_WRAPPER.replace("%%", content))
+}
+
+fn new_output_directory() -> PathBuf {
+    let id = OUTPUT_DIRECTORY_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let directory = std::env::temp_dir().join(format!("java-compiler-test-{:}-{:}", std::process::id(), id));
+    fs::create_dir_all(&directory).expect("failed to create output directory");
+    directory
+}
+
+fn assert_output_is(class_path: &Path, class_name: &str, expected_output: &str) {
     let output = Command::new("java")
+        .arg("-cp")
+        .arg(class_path)
         .arg(class_name)
         .output()
         .expect("failed to execute process");
 
     let actual_output = str::from_utf8(output.stdout.as_slice()).unwrap().trim();
 
-    assert_eq!(expected_output, actual_output);
-}
\ No newline at end of file
+    assert_eq!(
+        expected_output,
+        actual_output,
+        "stderr: {:}",
+        str::from_utf8(output.stderr.as_slice()).unwrap()
+    );
+}
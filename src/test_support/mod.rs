@@ -2,7 +2,9 @@ pub use comparator::{ComparisonResult, do_comparison, check_and_report_differenc
 
 pub use ast_test_harness::build_class_from_source_file_and_compare;
 pub use ast_test_harness::build_method_only_and_compare;
-pub use compiler_test_harness::compile_source_and_assert_output_is;
+pub use compiler_test_harness::{compile_and_assert_driver_output_is, compile_and_assert_output_is, compile_source_and_assert_output_is};
+pub use compiler_test_harness::{compile_and_expect_error, compile_method_and_assert_output_is, compile_method_and_expect_error};
+pub use compiler_test_harness::{compile_sources_and_assert_output_is, compile_sources_and_expect_error, compile_method_to_class_file};
 
 mod ast_test_harness;
 mod comparator;
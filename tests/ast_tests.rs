@@ -1,6 +1,9 @@
-use java_compiler::ast::class::{AstClass, AstMethod, AstParameter, AstScope};
-use java_compiler::ast::expression::Expression;
+use java_compiler::ast::class::{AstClass, AstEnumConstant, AstField, AstInitializer, AstMethod, AstParameter, AstScope, AstType};
+use java_compiler::ast::compilation_unit::AstImport;
+use java_compiler::ast::expression::{BinaryOperator, Expression, UnaryOperator};
 use java_compiler::ast::statement::Statement;
+use java_compiler::build_ast;
+use java_compiler::compiler::CompileError;
 use java_compiler::test_support::{build_class_from_source_file_and_compare, build_method_only_and_compare};
 
 #[test]
@@ -18,7 +21,7 @@ fn should_build_simple_ast() {
                 false,
                 true,
                 "void",
-                vec![AstParameter::new("args", "String", false)],
+                vec![AstParameter::new("args", AstType::new("String", 1))],
                 vec![Statement::new_expression_statement(Expression::new_call(
                     Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
                     "println",
@@ -44,9 +47,9 @@ fn should_build_method_with_string_variable_assignment() {
             false,
             true,
             "void",
-            vec![AstParameter::new("args", "String", false)],
+            vec![AstParameter::new("args", AstType::new("String", 1))],
             vec![
-                Statement::new_expression_statement(Expression::new_assignment("message", Some("String"), Expression::new_string_literal("hello"))),
+                Statement::new_var_assignment("message", "String".into(), false, Some(Expression::new_string_literal("hello"))),
                 Statement::new_expression_statement(Expression::new_call(
                     Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
                     "println",
@@ -56,3 +59,810 @@ fn should_build_method_with_string_variable_assignment() {
         ),
     );
 }
+
+#[test]
+fn should_build_method_with_numeric_literals() {
+    build_method_only_and_compare(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(42);
+            System.out.println(0xFFL);
+            System.out.println(1.5f);
+            System.out.println(2e3);
+        }
+        "#,
+        AstMethod::new(
+            "main",
+            AstScope::Public,
+            false,
+            true,
+            "void",
+            vec![AstParameter::new("args", AstType::new("String", 1))],
+            vec![
+                Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+                    "println",
+                    vec![Expression::new_int_literal(42)],
+                )),
+                Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+                    "println",
+                    vec![Expression::new_long_literal(255)],
+                )),
+                Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+                    "println",
+                    vec![Expression::new_float_literal(1.5)],
+                )),
+                Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+                    "println",
+                    vec![Expression::new_double_literal(2000.0)],
+                )),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn should_build_qualified_field_reads_and_assignments() {
+    build_method_only_and_compare(
+        r#"
+        void run() {
+            this.list.size();
+            int x = new P().x;
+            this.x = x;
+        }
+        "#,
+        AstMethod::new(
+            "run",
+            AstScope::Default,
+            false,
+            false,
+            "void",
+            vec![],
+            vec![
+                Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_this(), "list"),
+                    "size",
+                    vec![],
+                )),
+                Statement::new_var_assignment(
+                    "x",
+                    "int".into(),
+                    false,
+                    Some(Expression::new_child_identifier(Expression::new_instance_creation("P", vec![], None), "x")),
+                ),
+                Statement::new_expression_statement(Expression::new_field_assignment(Expression::new_this(), "x", Expression::new_variable("x", None))),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn should_build_class_with_comments() {
+    build_class_from_source_file_and_compare(
+        "samples/Comments.java",
+        AstClass::new(
+            "Comments",
+            AstScope::Public,
+            false,
+            false,
+            vec![AstMethod::new(
+                "main",
+                AstScope::Public,
+                false,
+                true,
+                "void",
+                vec![AstParameter::new("args", AstType::new("String", 1))],
+                vec![Statement::new_expression_statement(Expression::new_call(
+                    Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+                    "println",
+                    vec![Expression::new_string_literal("// not a comment /* either */")],
+                ))],
+            )],
+        ),
+    );
+}
+
+#[test]
+fn should_carry_source_spans_to_ast_nodes() {
+    let source = "public class Simple {\n    public static void main(String[] args) {\n        System.out.println(\"Hi\");\n    }\n}";
+    let compilation_unit = build_ast(source).unwrap();
+    let class = &compilation_unit.types()[0];
+
+    assert_eq!(("1:1", 5, 2), (class.span().to_string().as_str(), class.span().end().line, class.span().end().column));
+
+    let method = &class.methods()[0];
+    assert_eq!("2:5", method.span().to_string());
+    assert_eq!((4, 6), (method.span().end().line, method.span().end().column));
+    assert_eq!("2:29", method.parameters()[0].span().to_string());
+
+    let Statement::Expression { expression, span } = &method.statements()[0] else {
+        panic!("Expected an expression statement")
+    };
+    assert_eq!(("3:9", 3, 34), (span.to_string().as_str(), span.end().line, span.end().column));
+    let Expression::Call { target, arguments, .. } = expression else {
+        panic!("Expected a call")
+    };
+    assert_eq!("3:9", target.span().to_string());
+    assert_eq!("3:28", arguments[0].span().to_string());
+}
+
+#[test]
+fn should_build_class_declarations() {
+    let println = |argument| {
+        Statement::new_expression_statement(Expression::new_call(
+            Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+            "println",
+            vec![argument],
+        ))
+    };
+
+    build_class_from_source_file_and_compare(
+        "samples/Declarations.java",
+        AstClass::new(
+            "Declarations",
+            AstScope::Public,
+            false,
+            false,
+            vec![
+                AstMethod::new("describe", AstScope::Protected, false, false, "void", vec![], vec![]).as_abstract(),
+                AstMethod::new(
+                    "counts",
+                    AstScope::Public,
+                    false,
+                    true,
+                    AstType::new("int", 2),
+                    vec![
+                        AstParameter::new("first", "String"),
+                        AstParameter::new("rest", AstType::new("String", 1)),
+                    ],
+                    vec![println(Expression::new_variable("first", None))],
+                )
+                .as_synchronized(),
+                AstMethod::new("now", AstScope::Default, false, false, "long", vec![], vec![]).as_native(),
+            ],
+        )
+        .as_abstract()
+        .extending("Object")
+        .implementing(vec!["Runnable", "java.lang.Comparable"])
+        .with_fields(vec![
+            AstField::new("GREETING", "String".into(), AstScope::Private, true, true, Some(Expression::new_string_literal("Hello"))),
+            AstField::new("count", "int".into(), AstScope::Protected, false, false, None).as_transient(),
+            AstField::new("limit", "int".into(), AstScope::Protected, false, false, Some(Expression::new_int_literal(10))).as_transient(),
+            AstField::new("index", "java.util.Map".into(), AstScope::Default, false, false, None),
+        ])
+        .with_initializers(vec![
+            AstInitializer::new(true, vec![println(Expression::new_variable("GREETING", None))]),
+            AstInitializer::new(false, vec![println(Expression::new_string_literal("instance"))]),
+        ])
+        .with_constructors(vec![
+            AstMethod::new(
+                "<init>",
+                AstScope::Public,
+                false,
+                false,
+                "void",
+                vec![AstParameter::new("count", "int").as_final()],
+                vec![println(Expression::new_variable("count", None))],
+            ),
+            AstMethod::new(
+                "<init>",
+                AstScope::Default,
+                false,
+                false,
+                "void",
+                vec![],
+                vec![Statement::new_constructor_call(false, vec![Expression::new_int_literal(10)])],
+            ),
+        ])
+        .with_member_types(vec![
+            AstClass::new("Nested", AstScope::Default, true, true, vec![])
+                .with_fields(vec![AstField::new("value", "T".into(), AstScope::Private, false, false, None)]),
+        ]),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors() {
+    let error_for = |body: &str| {
+        let source = format!("public class Errors {{\n    {}\n}}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("2:12: repeated modifier 'static'", error_for("static static void run() {}"));
+    assert_eq!("2:12: conflicting access modifiers", error_for("public private void run() {}"));
+    assert_eq!("2:5: modifier 'native' not allowed on a field", error_for("native int count;"));
+    assert_eq!("2:5: illegal combination of modifiers 'abstract' and 'final'", error_for("abstract final void run();"));
+    assert_eq!("2:15: missing method body, or declare abstract", error_for("void run();"));
+    assert_eq!("2:5: invalid method declaration; return type required", error_for("run() {}"));
+    assert_eq!("2:9: expected an identifier but found '('", error_for("int (;"));
+    assert_eq!("2:5: annotation interface declarations are not supported yet", error_for("@interface Inner {}"));
+    assert_eq!("2:38: call to super must be first statement in constructor", error_for("Errors() { System.out.println(); super(); }"));
+
+    let Err(CompileError::Syntax(error)) = build_ast("class Unterminated {\n    void run() {}\n") else {
+        panic!("Expected a syntax error")
+    };
+    assert_eq!("expected '}' but found end of file", error.message());
+}
+
+#[test]
+fn should_build_interface_declarations() {
+    let println = |argument| {
+        Statement::new_expression_statement(Expression::new_call(
+            Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+            "println",
+            vec![argument],
+        ))
+    };
+
+    build_class_from_source_file_and_compare(
+        "samples/Greeter.java",
+        AstClass::new(
+            "Greeter",
+            AstScope::Public,
+            false,
+            false,
+            vec![
+                AstMethod::new("name", AstScope::Public, false, false, "String", vec![], vec![]).as_abstract(),
+                AstMethod::new("greet", AstScope::Public, false, false, "void", vec![], vec![println(Expression::new_variable("GREETING", None))]),
+                AstMethod::new("announce", AstScope::Public, false, true, "void", vec![], vec![println(Expression::new_string_literal("announcing"))]),
+                AstMethod::new("whisper", AstScope::Private, false, false, "void", vec![], vec![println(Expression::new_string_literal("psst"))]),
+            ],
+        )
+        .as_interface()
+        .implementing(vec!["Runnable", "Comparable"])
+        .with_fields(vec![
+            AstField::new("GREETING", "String".into(), AstScope::Public, true, true, Some(Expression::new_string_literal("Hello"))),
+            AstField::new("FAREWELL", "String".into(), AstScope::Public, true, true, Some(Expression::new_string_literal("Bye"))),
+        ]),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_interfaces() {
+    let error_for = |body: &str| {
+        let source = format!("interface Errors {{\n    {}\n}}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("2:16: interface abstract methods cannot have a body", error_for("void run() {}"));
+    assert_eq!("2:23: missing method body, or declare abstract", error_for("default void run();"));
+    assert_eq!("2:5: illegal combination of modifiers 'default' and 'static'", error_for("default static void run() {}"));
+    assert_eq!("2:5: modifier 'protected' not allowed on an interface method", error_for("protected void run();"));
+    assert_eq!("2:5: modifier 'private' not allowed on an interface field", error_for("private int COUNT = 1;"));
+    assert_eq!("2:14: expected '=' but found ';'", error_for("int COUNT;"));
+    assert_eq!("2:5: initializers not allowed in interfaces", error_for("static {}"));
+    assert_eq!("2:5: invalid method declaration; return type required", error_for("Errors() {}"));
+
+    let Err(CompileError::Syntax(error)) = build_ast("final interface Errors {}") else {
+        panic!("Expected a syntax error")
+    };
+    assert_eq!("1:1: modifier 'final' not allowed on an interface", error.to_string());
+}
+
+#[test]
+fn should_build_enum_declarations() {
+    let println = |argument| {
+        Statement::new_expression_statement(Expression::new_call(
+            Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+            "println",
+            vec![argument],
+        ))
+    };
+    let run = |message| AstMethod::new("run", AstScope::Public, false, false, "void", vec![], vec![println(Expression::new_string_literal(message))]);
+
+    build_class_from_source_file_and_compare(
+        "samples/Operation.java",
+        AstClass::new("Operation", AstScope::Public, false, false, vec![run("running")])
+            .as_enum()
+            .implementing(vec!["Runnable"])
+            .with_enum_constants(vec![
+                AstEnumConstant::new("PLUS", vec![Expression::new_string_literal("+")])
+                    .with_body(AstClass::new("", AstScope::Default, false, false, vec![run("adding")])),
+                AstEnumConstant::new("TIMES", vec![Expression::new_string_literal("*")]),
+                AstEnumConstant::new("NONE", vec![]),
+            ])
+            .with_constructors(vec![
+                AstMethod::new(
+                    "<init>",
+                    AstScope::Private,
+                    false,
+                    false,
+                    "void",
+                    vec![],
+                    vec![Statement::new_constructor_call(false, vec![Expression::new_string_literal("?")])],
+                ),
+                AstMethod::new(
+                    "<init>",
+                    AstScope::Private,
+                    false,
+                    false,
+                    "void",
+                    vec![AstParameter::new("symbol", "String")],
+                    vec![println(Expression::new_variable("symbol", None))],
+                ),
+            ]),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_enums() {
+    let error_for = |body: &str| {
+        let source = format!("enum Errors {{\n    ONE;\n    {}\n}}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("3:5: modifier 'public' not allowed on an enum constructor", error_for("public Errors() {}"));
+    assert_eq!("3:16: call to super not allowed in enum constructor", error_for("Errors() { super(); }"));
+
+    let Err(CompileError::Syntax(error)) = build_ast("final enum Errors { ONE }") else {
+        panic!("Expected a syntax error")
+    };
+    assert_eq!("1:1: modifier 'final' not allowed on an enum", error.to_string());
+}
+
+#[test]
+fn should_build_record_declarations() {
+    let println = |message| {
+        Statement::new_expression_statement(Expression::new_call(
+            Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+            "println",
+            vec![Expression::new_string_literal(message)],
+        ))
+    };
+    let components = || vec![AstParameter::new("x", "int"), AstParameter::new("y", "int")];
+
+    build_class_from_source_file_and_compare(
+        "samples/Point.java",
+        AstClass::new(
+            "Point",
+            AstScope::Public,
+            false,
+            false,
+            vec![AstMethod::new("describe", AstScope::Public, false, false, "void", vec![], vec![println("a point")])],
+        )
+        .as_record(components())
+        .implementing(vec!["Comparable"])
+        .with_fields(vec![AstField::new("created", "int".into(), AstScope::Default, true, false, Some(Expression::new_int_literal(0)))])
+        .with_constructors(vec![
+            AstMethod::new("<init>", AstScope::Public, false, false, "void", components(), vec![println("validating")]).as_compact(),
+            AstMethod::new(
+                "<init>",
+                AstScope::Public,
+                false,
+                false,
+                "void",
+                vec![AstParameter::new("x", "int")],
+                vec![Statement::new_constructor_call(
+                    false,
+                    vec![Expression::new_variable("x", None), Expression::new_int_literal(0)],
+                )],
+            ),
+        ])
+        .with_initializers(vec![AstInitializer::new(true, vec![println("loaded")])]),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_records() {
+    let error_for = |body: &str| {
+        let source = format!("record Errors(int count) {{\n    {}\n}}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("2:9: field declaration must be static", error_for("int total;"));
+    assert_eq!("2:5: instance initializers not allowed in records", error_for("{}"));
+    assert_eq!("2:14: canonical constructor must not contain explicit constructor invocation", error_for("Errors { super(); }"));
+    assert_eq!("2:25: canonical constructor must not contain explicit constructor invocation", error_for("Errors(int count) { this(); }"));
+    assert_eq!(
+        "2:5: constructor is not canonical, so its first statement must invoke another constructor of class Errors",
+        error_for("Errors() {}")
+    );
+
+    let error_for_declaration = |source: &str| match build_ast(source) {
+        Err(CompileError::Syntax(error)) => error.to_string(),
+        other => panic!("Expected a syntax error but was {:?}", other),
+    };
+    assert_eq!("1:1: modifier 'abstract' not allowed on a record", error_for_declaration("abstract record Errors() {}"));
+    assert_eq!("1:15: record components cannot have modifiers", error_for_declaration("record Errors(final int count) {}"));
+}
+
+#[test]
+fn should_build_package_and_imports() {
+    let source = r#"
+        package com.example.app;
+
+        import java.util.List;
+        import java.util.*;
+        import static java.lang.System.out;
+        import static java.lang.Math.*;
+
+        public class App {}
+    "#;
+    let compilation_unit = build_ast(source).unwrap();
+
+    assert_eq!(Some("com.example.app"), compilation_unit.package());
+    assert_eq!(
+        vec![
+            (AstImport::new("java.util.List", false, false), "List"),
+            (AstImport::new("java.util", false, true), "util"),
+            (AstImport::new("java.lang.System.out", true, false), "out"),
+            (AstImport::new("java.lang.Math", true, true), "Math"),
+        ],
+        compilation_unit
+            .imports()
+            .iter()
+            .map(|import| (AstImport::new(import.name(), import.is_static(), import.is_on_demand()), import.simple_name()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec!["App"], compilation_unit.types().iter().map(|class| class.name()).collect::<Vec<_>>());
+}
+
+#[test]
+fn should_build_every_top_level_type_of_a_compilation_unit() {
+    let source = r#"
+        package com.example.app;
+
+        public class App {};
+        interface Service {}
+        ;
+        enum Mode { ON, OFF }
+        record Pair(int left, int right) {}
+    "#;
+    let compilation_unit = build_ast(source).unwrap();
+
+    let types = compilation_unit.types().iter().map(|class| (class.name(), class.scope())).collect::<Vec<_>>();
+    assert_eq!(
+        vec![("App", AstScope::Public), ("Service", AstScope::Default), ("Mode", AstScope::Default), ("Pair", AstScope::Default)],
+        types
+    );
+}
+
+#[test]
+fn should_report_syntax_error_after_last_top_level_type() {
+    let Err(CompileError::Syntax(error)) = build_ast("class One {}\nclass Two {}\nvoid three() {}") else {
+        panic!("Expected a syntax error")
+    };
+
+    assert_eq!("3:1: expected a class declaration but found 'void'", error.to_string());
+}
+
+#[test]
+fn should_build_member_local_and_anonymous_classes() {
+    let source = r#"
+        public class Outer {
+            static class Nested {}
+
+            void run() {
+                final class Local {
+                    void show() {}
+                }
+                new Runnable() {
+                    public void run() {}
+                }.run();
+                new Local();
+            }
+        }
+    "#;
+    let compilation_unit = build_ast(source).unwrap();
+    let outer = &compilation_unit.types()[0];
+
+    assert_eq!(vec![("Nested", true)], outer.member_types().iter().map(|class| (class.name(), class.is_static())).collect::<Vec<_>>());
+
+    let statements = outer.methods()[0].statements();
+    let Statement::LocalClass { class, .. } = &statements[0] else {
+        panic!("Expected a local class but was {:?}", statements[0]);
+    };
+    assert_eq!(("Local", true, 1), (class.name(), class.is_final(), class.methods().len()));
+
+    let Statement::Expression { expression: Expression::Call { target, method_name, .. }, .. } = &statements[1] else {
+        panic!("Expected a call but was {:?}", statements[1]);
+    };
+    let Expression::New { class_type, arguments, body: Some(body), .. } = &**target else {
+        panic!("Expected an anonymous class but was {:?}", target);
+    };
+    assert_eq!(("Runnable", 0, "run", "run"), (*class_type, arguments.len(), body.methods()[0].name(), *method_name));
+
+    assert!(matches!(&statements[2], Statement::Expression { expression: Expression::New { class_type: "Local", body: None, .. }, .. }));
+}
+
+#[test]
+fn should_report_syntax_errors_in_local_classes() {
+    let Err(CompileError::Syntax(error)) = build_ast("class Outer { void run() { public class Local {} } }") else {
+        panic!("Expected a syntax error")
+    };
+
+    assert_eq!("1:28: expected an expression but found 'public'", error.to_string());
+}
+
+#[test]
+fn should_build_operators_with_java_precedence_and_associativity() {
+    let println = |argument| {
+        Statement::new_expression_statement(Expression::new_call(
+            Expression::new_child_identifier(Expression::new_variable("System", None), "out"),
+            "println",
+            vec![argument],
+        ))
+    };
+    let binary = |left, operator, right| Expression::new_binary(left, operator, right);
+    let int = Expression::new_int_literal;
+    let variable = |name| Expression::new_variable(name, None);
+
+    build_method_only_and_compare(
+        r#"
+        void run() {
+            System.out.println(1 + 2 * 3 - 4);
+            System.out.println((1 + 2) * 3);
+            System.out.println(a < b == c >= d && !e || f & g ^ h | i);
+            System.out.println(-a++ << ~--b >>> 1);
+            System.out.println(a ? b : c ? 1 : 2);
+            count++;
+        }
+        "#,
+        AstMethod::new(
+            "run",
+            AstScope::Default,
+            false,
+            false,
+            "void",
+            vec![],
+            vec![
+                println(binary(
+                    binary(int(1), BinaryOperator::Add, binary(int(2), BinaryOperator::Multiply, int(3))),
+                    BinaryOperator::Subtract,
+                    int(4),
+                )),
+                println(binary(binary(int(1), BinaryOperator::Add, int(2)), BinaryOperator::Multiply, int(3))),
+                println(binary(
+                    binary(
+                        binary(
+                            binary(variable("a"), BinaryOperator::Less, variable("b")),
+                            BinaryOperator::Equal,
+                            binary(variable("c"), BinaryOperator::GreaterEqual, variable("d")),
+                        ),
+                        BinaryOperator::And,
+                        Expression::new_unary(UnaryOperator::Not, variable("e")),
+                    ),
+                    BinaryOperator::Or,
+                    binary(
+                        binary(
+                            binary(variable("f"), BinaryOperator::BitwiseAnd, variable("g")),
+                            BinaryOperator::BitwiseXor,
+                            variable("h"),
+                        ),
+                        BinaryOperator::BitwiseOr,
+                        variable("i"),
+                    ),
+                )),
+                println(binary(
+                    binary(
+                        Expression::new_unary(UnaryOperator::Minus, Expression::new_postfix_increment(variable("a"), false)),
+                        BinaryOperator::ShiftLeft,
+                        Expression::new_unary(UnaryOperator::BitwiseComplement, Expression::new_prefix_increment(variable("b"), true)),
+                    ),
+                    BinaryOperator::UnsignedShiftRight,
+                    int(1),
+                )),
+                println(Expression::new_conditional(
+                    variable("a"),
+                    variable("b"),
+                    Expression::new_conditional(variable("c"), int(1), int(2)),
+                )),
+                Statement::new_expression_statement(Expression::new_postfix_increment(variable("count"), false)),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_operator_expressions() {
+    let error_in = |body: &str| {
+        let source = format!("class Broken {{ void run() {{ {} }} }}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("1:37: expected an expression but found ')'", error_in("run(1 + );"));
+    assert_eq!("1:31: the operand of an increment or decrement must be a variable", error_in("++5;"));
+    assert_eq!("1:29: not a statement", error_in("1 + 2;"));
+    assert_eq!("1:38: expected ':' but found ';'", error_in("a = b ? c;"));
+}
+
+#[test]
+fn should_build_local_variable_declarations_and_blocks() {
+    let variable = |name| Expression::new_variable(name, None);
+
+    build_method_only_and_compare(
+        r#"
+        void run(int count) {
+            int a = 1, b[], c;
+            final java.util.List<Map<String, int[]>> list = lists;
+            var total = a + count;
+            {
+                long[][] grid;
+                c = total;
+            }
+            String s = "x";
+        }
+        "#,
+        AstMethod::new(
+            "run",
+            AstScope::Default,
+            false,
+            false,
+            "void",
+            vec![AstParameter::new("count", "int")],
+            vec![
+                Statement::new_var_assignment("a", "int".into(), false, Some(Expression::new_int_literal(1))),
+                Statement::new_var_assignment("b", AstType::new("int", 1), false, None),
+                Statement::new_var_assignment("c", "int".into(), false, None),
+                Statement::new_var_assignment("list", "java.util.List".into(), true, Some(variable("lists"))),
+                Statement::new_var_assignment(
+                    "total",
+                    "var".into(),
+                    false,
+                    Some(Expression::new_binary(variable("a"), BinaryOperator::Add, variable("count"))),
+                ),
+                Statement::new_block(vec![
+                    Statement::new_var_assignment("grid", AstType::new("long", 2), false, None),
+                    Statement::new_expression_statement(Expression::new_assignment("c", None, variable("total"))),
+                ]),
+                Statement::new_var_assignment("s", "String".into(), false, Some(Expression::new_string_literal("x"))),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_local_variable_declarations() {
+    let error_in = |body: &str| {
+        let source = format!("class Broken {{ void run() {{ {} }} }}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("1:33: cannot use 'var' on variable without initializer", error_in("var x;"));
+    assert_eq!("1:40: 'var' is not allowed in a compound declaration", error_in("var x = 1, y = 2;"));
+    assert_eq!("1:39: array initializers are not supported yet", error_in("int[] x = {1};"));
+    assert_eq!("1:35: repeated modifier 'final'", error_in("final final int x = 1;"));
+    assert_eq!("1:35: expected ';' but found '}'", error_in("int x"));
+}
+
+#[test]
+fn should_build_control_flow_statements() {
+    let variable = |name| Expression::new_variable(name, None);
+    let int = Expression::new_int_literal;
+    let less = |name, limit| Expression::new_binary(variable(name), BinaryOperator::Less, int(limit));
+    let increment = |name| Expression::new_postfix_increment(variable(name), false);
+
+    build_method_only_and_compare(
+        r#"
+        void run(int n) {
+            if (n < 1) n++; else if (n < 2) ; else { n--; }
+            while (n < 3) n++;
+            do { continue; } while (false);
+            for (int i = 0, j = 1; i < 4; i++, j++) break;
+            for (n = 0, i++; ; ) {}
+            outer: for (final String word : words) continue outer;
+            inner: { break inner; }
+        }
+        "#,
+        AstMethod::new(
+            "run",
+            AstScope::Default,
+            false,
+            false,
+            "void",
+            vec![AstParameter::new("n", "int")],
+            vec![
+                Statement::new_if(
+                    less("n", 1),
+                    Statement::new_expression_statement(increment("n")),
+                    Some(Statement::new_if(
+                        less("n", 2),
+                        Statement::new_empty(),
+                        Some(Statement::new_block(vec![Statement::new_expression_statement(
+                            Expression::new_postfix_increment(variable("n"), true),
+                        )])),
+                    )),
+                ),
+                Statement::new_while(less("n", 3), Statement::new_expression_statement(increment("n"))),
+                Statement::new_do_while(
+                    Statement::new_block(vec![Statement::new_continue(None)]),
+                    Expression::new_boolean_literal(false),
+                ),
+                Statement::new_for(
+                    vec![
+                        Statement::new_var_assignment("i", "int".into(), false, Some(int(0))),
+                        Statement::new_var_assignment("j", "int".into(), false, Some(int(1))),
+                    ],
+                    Some(less("i", 4)),
+                    vec![increment("i"), increment("j")],
+                    Statement::new_break(None),
+                ),
+                Statement::new_for(
+                    vec![
+                        Statement::new_expression_statement(Expression::new_assignment("n", None, int(0))),
+                        Statement::new_expression_statement(increment("i")),
+                    ],
+                    None,
+                    vec![],
+                    Statement::new_block(vec![]),
+                ),
+                Statement::new_labeled(
+                    "outer",
+                    Statement::new_for_each("word", "String".into(), true, variable("words"), Statement::new_continue(Some("outer"))),
+                ),
+                Statement::new_labeled("inner", Statement::new_block(vec![Statement::new_break(Some("inner"))])),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_in_control_flow_statements() {
+    let error_in = |body: &str| {
+        let source = format!("class Broken {{ void run() {{ {} }} }}", body);
+        match build_ast(&source) {
+            Err(CompileError::Syntax(error)) => error.to_string(),
+            other => panic!("Expected a syntax error but was {:?}", other),
+        }
+    };
+
+    assert_eq!("1:32: expected '(' but found 'true'", error_in("if true {}"));
+    assert_eq!("1:38: expected ')' but found '{'", error_in("if (true {}"));
+    assert_eq!("1:42: declaration not allowed here", error_in("while (true) int x = 1;"));
+    assert_eq!("1:35: expected 'while' but found 'until'", error_in("do {} until (false);"));
+    assert_eq!("1:37: not a statement", error_in("for (;; x + 1) {}"));
+    assert_eq!("1:35: expected ';' but found '1'", error_in("break 1;"));
+}
+
+#[test]
+fn should_build_return_statements() {
+    build_method_only_and_compare(
+        r#"
+        int run(int n) {
+            if (n > 0) return n + 1;
+            return;
+        }
+        "#,
+        AstMethod::new(
+            "run",
+            AstScope::Default,
+            false,
+            false,
+            "int",
+            vec![AstParameter::new("n", "int")],
+            vec![
+                Statement::new_if(
+                    Expression::new_binary(Expression::new_variable("n", None), BinaryOperator::Greater, Expression::new_int_literal(0)),
+                    Statement::new_return(Some(Expression::new_binary(
+                        Expression::new_variable("n", None),
+                        BinaryOperator::Add,
+                        Expression::new_int_literal(1),
+                    ))),
+                    None,
+                ),
+                Statement::new_return(None),
+            ],
+        ),
+    );
+}
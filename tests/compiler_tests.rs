@@ -1,4 +1,9 @@
-use java_compiler::test_support::compile_source_and_assert_output_is;
+use java_compiler::compiler::CompileError;
+use java_compiler::scanner::{FileId, LexicalErrorKind};
+use java_compiler::test_support::{compile_and_assert_driver_output_is, compile_and_assert_output_is, compile_and_expect_error, compile_method_and_assert_output_is, compile_method_and_expect_error, compile_source_and_assert_output_is};
+use java_compiler::test_support::{compile_method_to_class_file, compile_sources_and_assert_output_is, compile_sources_and_expect_error};
+use ristretto_classfile::attributes::{Attribute, StackFrame, VerificationType};
+use ristretto_classfile::Constant;
 
 #[test]
 fn should_compile_simple_hello_world() {
@@ -6,7 +11,2015 @@ fn should_compile_simple_hello_world() {
 }
 
 #[test]
-#[ignore]
 fn should_compile_string_variable_assignment() {
     compile_source_and_assert_output_is("samples/StringVariableAssignment.java", "StringVariableAssignment", "a string variable");
-}
\ No newline at end of file
+}
+#[test]
+fn should_compile_small_int_literal_to_iconst() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(5);
+        }
+        "#,
+        "5",
+    );
+}
+
+#[test]
+fn should_compile_byte_sized_int_literal_to_bipush() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(0x7F);
+        }
+        "#,
+        "127",
+    );
+}
+
+#[test]
+fn should_compile_short_sized_int_literal_to_sipush() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(1_000);
+        }
+        "#,
+        "1000",
+    );
+}
+
+#[test]
+fn should_compile_large_int_literal_to_ldc() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(0xFFFFFFFF);
+        }
+        "#,
+        "-1",
+    );
+}
+
+#[test]
+fn should_compile_long_literal_to_ldc2_w() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(9_223_372_036_854_775_807L);
+        }
+        "#,
+        "9223372036854775807",
+    );
+}
+
+#[test]
+fn should_compile_float_literal() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(2.5f);
+        }
+        "#,
+        "2.5",
+    );
+}
+
+#[test]
+fn should_compile_double_literal() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(1.5e-3);
+        }
+        "#,
+        "0.0015",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_out_of_range_int_literal() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(2147483648);
+        }
+        "#,
+    );
+
+    assert!(matches!(error.kind(), CompileError::LiteralOutOfRange(_)), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_fail_to_compile_out_of_range_long_literal() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(0x1_0000_0000_0000_0000L);
+        }
+        "#,
+    );
+
+    assert!(matches!(error.kind(), CompileError::LiteralOutOfRange(_)), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_char_literal() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println('A');
+        }
+        "#,
+        "A",
+    );
+}
+
+#[test]
+fn should_compile_string_literal_with_escape_sequences() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println("\"quoted\"\t\\\101");
+        }
+        "#,
+        "\"quoted\"\t\\A",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_source_with_lexical_errors() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println("unterminated);
+        }
+        "#,
+    );
+
+    let CompileError::Lexical(diagnostics) = error else {
+        panic!("Expected a lexical error, but was {:?}", error)
+    };
+    assert_eq!(vec![&LexicalErrorKind::UnterminatedString], diagnostics.iter().map(|d| d.kind()).collect::<Vec<_>>());
+}
+
+#[test]
+fn should_compile_text_block() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println("""
+                SELECT name
+                  FROM users\s
+                 WHERE id = ?""");
+        }
+        "#,
+        "SELECT name\n  FROM users \n WHERE id = ?",
+    );
+}
+
+#[test]
+fn should_compile_class_in_package_using_imports() {
+    compile_and_assert_output_is(
+        r#"
+        package com.example;
+
+        import java.io.PrintStream;
+        import static java.lang.System.out;
+
+        public class Greeter {
+            public static void main(String[] args) {
+                out.println("Hello from a package");
+            }
+        }
+        "#,
+        "com.example.Greeter",
+        "Hello from a package",
+    );
+}
+
+#[test]
+fn should_compile_reads_of_statically_imported_fields() {
+    compile_sources_and_assert_output_is(
+        &[
+            (
+                "com/acme/util/Consts.java",
+                r#"
+                package com.acme.util;
+
+                public class Consts {
+                    public static final String GREETING = "Hello";
+                    public static int count = 2;
+                }
+                "#,
+            ),
+            (
+                "app/Main.java",
+                r#"
+                package app;
+
+                import static com.acme.util.Consts.GREETING;
+                import static com.acme.util.Consts.*;
+
+                public class Main {
+                    public static void main(String[] args) {
+                        System.out.println(GREETING);
+                        System.out.println(count);
+                    }
+                }
+                "#,
+            ),
+        ],
+        "app.Main",
+        "Hello\n2",
+    );
+}
+
+#[test]
+fn should_compile_static_on_demand_import() {
+    compile_and_assert_output_is(
+        r#"
+        import static java.lang.System.*;
+
+        public class Greeter {
+            public static void main(String[] args) {
+                out.println("Hello");
+            }
+        }
+        "#,
+        "Greeter",
+        "Hello",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_unresolvable_parameter_type() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(Strin[] args) {
+            System.out.println("Hello");
+        }
+        "#,
+    );
+
+    assert!(matches!(error, CompileError::UnknownClass(name) if name == "Strin"), "Unexpected error");
+}
+
+#[test]
+fn should_compile_field_initializers_and_static_blocks_in_declaration_order() {
+    compile_and_assert_output_is(
+        r#"
+        public class Fields {
+            static final String GREETING = "Hello";
+            static final long BIG = 10;
+            static double ratio = 1.5f;
+            static int count = 3;
+            private final String name = "instance";
+
+            static {
+                System.out.println("static block");
+                System.out.println(count);
+            }
+
+            public Fields() {
+                System.out.println(name);
+            }
+
+            public static void main(String[] args) {
+                System.out.println(GREETING);
+                System.out.println(BIG);
+                System.out.println(ratio);
+            }
+        }
+        "#,
+        "Fields",
+        "static block\n3\nHello\n10\n1.5",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_field_initializer_of_incompatible_type() {
+    let error = compile_method_and_expect_error(r#"static int count = "three";"#);
+
+    assert!(
+        matches!(error.kind(), CompileError::IncompatibleTypes { expected, found } if expected == "int" && found == "java.lang.String"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_fail_to_compile_constant_that_does_not_fit_its_type() {
+    let error = compile_method_and_expect_error("static final byte LIMIT = 300;");
+
+    assert!(matches!(&error, CompileError::IncompatibleTypes { expected, .. } if expected == "byte"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_fail_to_compile_instance_field_read_from_static_method() {
+    let error = compile_method_and_expect_error(
+        r#"
+        String name = "instance";
+
+        public static void main(String[] args) {
+            System.out.println(name);
+        }
+        "#,
+    );
+
+    assert!(matches!(error.kind(), CompileError::NonStaticReference(name) if name == "name"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_default_constructor_running_instance_initializers() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public class Defaults {
+            String greeting = "Hello";
+
+            {
+                System.out.println(greeting);
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                new Defaults();
+            }
+        }
+        "#,
+        "Hello",
+    );
+}
+
+#[test]
+fn should_compile_constructors_chained_with_this_and_super() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public class Chained {
+            String label = "initialized once";
+
+            public Chained() {
+                this("from no-args", 1);
+                System.out.println("no-args body");
+            }
+
+            public Chained(String origin, int count) {
+                super();
+                System.out.println(label);
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                new Chained();
+            }
+        }
+        "#,
+        "initialized once\nno-args body",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_call_to_missing_constructor() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public Simple() {
+            this(42);
+        }
+        "#,
+    );
+
+    assert!(
+        matches!(&error, CompileError::UnknownMethod { class, method } if class == "Simple" && method == "<init>"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_fail_to_compile_call_on_missing_field_of_static_field() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            int code = System.out.checkError.hashCode();
+        }
+        "#,
+    );
+
+    assert!(
+        matches!(error.kind(), CompileError::UnknownField { class, field } if class == "java.io.PrintStream" && field == "checkError"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_compile_qualified_field_reads() {
+    compile_and_assert_output_is(
+        r#"
+        import java.util.ArrayList;
+        import java.util.List;
+
+        class Other {
+            static final int CONST = 42;
+        }
+
+        class P {
+            int x = 7;
+        }
+
+        class Fields {
+            private final List list = new ArrayList();
+            private int count = 3;
+
+            public static void main(String[] args) {
+                System.out.println(Other.CONST);
+                P p = new P();
+                System.out.println(p.x);
+                System.out.println(new P().x);
+                System.out.println(args.length);
+                new Fields().report();
+            }
+
+            void report() {
+                list.add("a");
+                System.out.println(this.list.size());
+                System.out.println(this.count);
+            }
+        }
+        "#,
+        "Fields",
+        "42\n7\n7\n0\n1\n3",
+    );
+}
+
+#[test]
+fn should_compile_reads_of_inherited_fields_by_simple_name() {
+    compile_and_assert_output_is(
+        r#"
+        class Base {
+            int x = 5;
+            static int total = 10;
+        }
+
+        class Derived extends Base {
+            int get() {
+                return x + total;
+            }
+
+            public static void main(String[] args) {
+                Derived derived = new Derived();
+                System.out.println(derived.get());
+                total++;
+                System.out.println(total);
+            }
+        }
+        "#,
+        "Derived",
+        "15\n11",
+    );
+}
+
+#[test]
+fn should_fail_to_read_instance_field_through_its_class() {
+    let error = compile_and_expect_error(
+        r#"
+        class P {
+            int x = 7;
+
+            static int read() {
+                return P.x;
+            }
+        }
+        "#,
+    );
+
+    assert!(matches!(error.kind(), CompileError::NonStaticReference(name) if name == "P.x"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_class_extending_superclass_and_implementing_interfaces() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public class Failure extends RuntimeException implements Runnable, Cloneable {
+            public Failure() {
+                super("boom");
+            }
+
+            public void run() {
+                System.out.println("running");
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                Failure failure = new Failure();
+                Runnable runnable = failure;
+                runnable.run();
+                System.out.println(failure.getMessage());
+                System.out.println(failure instanceof Cloneable);
+            }
+        }
+        "#,
+        "running\nboom\ntrue",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_invalid_inheritance() {
+    let error_for = |header: &str| compile_and_expect_error(&format!("{} {{}}", header));
+
+    let error = error_for("class Named extends String");
+    assert!(matches!(&error, CompileError::FinalSuperclass(name) if name == "java.lang.String"), "Unexpected error {:?}", error);
+
+    let error = error_for("class Itself extends Itself");
+    assert!(matches!(&error, CompileError::CyclicInheritance(name) if name == "Itself"), "Unexpected error {:?}", error);
+
+    let error = error_for("class Task extends Runnable");
+    assert!(matches!(&error, CompileError::UnexpectedInterface(name) if name == "java.lang.Runnable"), "Unexpected error {:?}", error);
+
+    let error = error_for("class Task implements Thread");
+    assert!(matches!(&error, CompileError::InterfaceExpected(name) if name == "java.lang.Thread"), "Unexpected error {:?}", error);
+
+    let error = error_for("class Task implements Runnable, java.lang.Runnable");
+    assert!(matches!(&error, CompileError::RepeatedInterface(name) if name == "java.lang.Runnable"), "Unexpected error {:?}", error);
+
+    let error = error_for("class Task extends Missing");
+    assert!(matches!(&error, CompileError::UnknownClass(name) if name == "Missing"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error("interface A extends B {} interface B extends A {}");
+    assert!(matches!(&error, CompileError::CyclicInheritance(_)), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error("interface A extends B {} interface B extends C {} interface C extends A {}");
+    assert!(matches!(&error, CompileError::CyclicInheritance(_)), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_interface_with_constants_and_default_static_and_private_methods() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public interface Greeter extends Runnable {
+            String GREETING = "Hello";
+
+            String name();
+
+            default void greet() {
+                System.out.println(GREETING);
+            }
+
+            static void announce() {
+                System.out.println("announcing");
+            }
+
+            private void whisper() {
+                System.out.println("psst");
+            }
+        }
+        "#,
+        r#"
+        public class Driver implements Greeter {
+            public String name() {
+                return "driver";
+            }
+
+            public void run() {
+                greet();
+            }
+
+            public static void main(String[] args) {
+                Greeter greeter = new Driver();
+                greeter.run();
+                Greeter.announce();
+                System.out.println(greeter.name());
+                System.out.println(java.lang.reflect.Modifier.toString(Greeter.class.getModifiers()));
+            }
+        }
+        "#,
+        "Hello\nannouncing\ndriver\npublic abstract interface",
+    );
+}
+
+#[test]
+fn should_compile_reads_of_inherited_interface_constants_by_simple_name() {
+    compile_and_assert_output_is(
+        r#"
+        interface Named {
+            String SUFFIX = "!";
+        }
+
+        interface Greeter extends Named {
+            String PREFIX = "Hello, ";
+        }
+
+        class Polite implements Greeter {
+            String greet(String name) {
+                return PREFIX + name + SUFFIX;
+            }
+
+            public static void main(String[] args) {
+                System.out.println(new Polite().greet("Ada"));
+            }
+        }
+        "#,
+        "Polite",
+        "Hello, Ada!",
+    );
+}
+
+#[test]
+fn should_compile_calls_to_static_methods_of_classes_and_interfaces() {
+    compile_and_assert_output_is(
+        r#"
+        import java.util.Comparator;
+
+        public class Calls {
+            public static void main(String[] args) {
+                System.out.println(String.valueOf(42));
+                System.out.println(Comparator.naturalOrder());
+            }
+        }
+        "#,
+        "Calls",
+        "42\nINSTANCE",
+    );
+}
+
+#[test]
+fn should_compile_call_to_interface_method() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(String.CASE_INSENSITIVE_ORDER.compare("a", "A"));
+        }
+        "#,
+        "0",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_interface_extending_class() {
+    let error = compile_and_expect_error("interface Task extends Thread {}");
+
+    assert!(matches!(&error, CompileError::InterfaceExpected(name) if name == "java.lang.Thread"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_enum_with_constructors_and_constant_bodies() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public enum Operation implements Runnable {
+            PLUS("+") {
+                public void run() {
+                    System.out.println("adding");
+                }
+            },
+            TIMES("*"),
+            NONE;
+
+            static {
+                System.out.println("initialized");
+            }
+
+            private Operation() {
+                this("?");
+                System.out.println("without a symbol");
+            }
+
+            Operation(String symbol) {
+                System.out.println("with a symbol");
+            }
+
+            public void run() {
+                System.out.println("running");
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                for (Operation operation : Operation.values()) {
+                    System.out.println(operation.ordinal() + " " + operation.name());
+                    operation.run();
+                }
+                System.out.println(Operation.valueOf("TIMES") == Operation.TIMES);
+                System.out.println(Operation.PLUS.getDeclaringClass() == Operation.class);
+                System.out.println(java.lang.reflect.Modifier.toString(Operation.class.getModifiers()));
+            }
+        }
+        "#,
+        "with a symbol\nwith a symbol\nwith a symbol\nwithout a symbol\ninitialized\n\
+        0 PLUS\nadding\n1 TIMES\nrunning\n2 NONE\nrunning\ntrue\ntrue\npublic",
+    );
+}
+
+#[test]
+fn should_compile_final_enum_without_constant_bodies() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public enum Colour {
+            RED, GREEN, BLUE,
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                System.out.println(java.util.Arrays.toString(Colour.values()));
+                System.out.println(Colour.values() != Colour.values());
+                System.out.println(Colour.class.isEnum() + " " + java.lang.reflect.Modifier.isFinal(Colour.class.getModifiers()));
+            }
+        }
+        "#,
+        "[RED, GREEN, BLUE]\ntrue\ntrue true",
+    );
+}
+
+#[test]
+fn should_compile_enum_constants_used_as_values() {
+    compile_and_assert_output_is(
+        r#"
+        enum Color {
+            RED, GREEN;
+
+            Color next() {
+                return this == RED ? GREEN : RED;
+            }
+        }
+
+        class Palette {
+            public static void main(String[] args) {
+                System.out.println(Color.RED);
+                Color c = Color.GREEN;
+                System.out.println(c.ordinal() + " " + c);
+                System.out.println(Color.RED.next());
+            }
+        }
+        "#,
+        "Palette",
+        "RED\n1 GREEN\nGREEN",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_enum_constant_without_matching_constructor() {
+    let error = compile_and_expect_error("enum Colour { RED(1) }");
+
+    assert!(
+        matches!(&error, CompileError::UnknownMethod { class, method } if class == "Colour" && method == "<init>"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_compile_record_with_compact_constructor_accessors_and_object_methods() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public record Point(int x, long y, String label) {
+            public Point {
+                System.out.println("validating");
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                Point point = new Point(1, 2L, "a");
+                Point same = new Point(1, 2L, "a");
+                Point other = new Point(7, 2L, "b");
+                System.out.println(point.x() + " " + point.y() + " " + point.label());
+                System.out.println(point + " " + other);
+                System.out.println(point.equals(same) + " " + point.equals(other) + " " + (point.hashCode() == same.hashCode()));
+                System.out.println(Point.class.isRecord() + " " + Point.class.getRecordComponents().length);
+                System.out.println(java.lang.reflect.Modifier.toString(Point.class.getModifiers()));
+            }
+        }
+        "#,
+        "validating\nvalidating\nvalidating\n1 2 a\nPoint[x=1, y=2, label=a] Point[x=7, y=2, label=b]\n\
+        true false true\ntrue 3\npublic final",
+    );
+}
+
+#[test]
+fn should_compile_records_with_implicit_and_explicit_canonical_constructors() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public record Pair(String first, String second) {
+            public Pair(String first, String second) {
+                System.out.println("explicit");
+                this.first = first;
+                this.second = second + "!";
+            }
+
+            public Pair() {
+                this("a", "b");
+                System.out.println("delegated");
+            }
+
+            public void describe() {
+                System.out.println("a pair");
+            }
+        }
+        "#,
+        r#"
+        public class Driver {
+            public static void main(String[] args) {
+                Pair pair = new Pair();
+                pair.describe();
+                System.out.println(pair);
+            }
+        }
+        "#,
+        "explicit\ndelegated\na pair\nPair[first=a, second=b!]",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_canonical_constructors_leaving_components_unassigned() {
+    let error = compile_and_expect_error("record R(int a) { R(int a) {} }");
+    assert!(matches!(&error, CompileError::UninitializedVariable { name, .. } if name == "a"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        record Range(int low, int high) {
+            Range(int low, int high) {
+                this.low = low;
+                if (low > high) {
+                    return;
+                }
+                this.high = high;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::UninitializedVariable { name, .. } if name == "high"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        record Range(int low, int high) {
+            Range(int low, int high) {
+                this.low = low;
+                if (low < high) {
+                    this.high = high;
+                }
+            }
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::UninitializedVariable { name, .. } if name == "high"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error("record R(int a) { R(int a) { this.a = a; this.a = 0; } }");
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "a"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_types_referring_to_each_other_across_files_in_any_order() {
+    compile_sources_and_assert_output_is(
+        &[
+            (
+                "app/Main.java",
+                r#"
+                package app;
+
+                public class Main {
+                    public static void main(String[] args) {
+                        Helper.greet();
+                        System.out.println(Colour.valueOf("GREEN"));
+                    }
+                }
+
+                class Derived extends Base {}
+
+                class Helper {
+                    static void greet() {
+                        System.out.println("Hello");
+                    }
+                }
+                "#,
+            ),
+            (
+                "app/Base.java",
+                r#"
+                package app;
+
+                public class Base {}
+
+                enum Colour { RED, GREEN }
+                "#,
+            ),
+        ],
+        "app.Main",
+        "Hello\nGREEN",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_public_type_in_file_of_another_name() {
+    let error = compile_sources_and_expect_error(&[("src/Main.java", "class Main {}\npublic class Other {}")]);
+
+    assert!(
+        matches!(&error, CompileError::PublicTypeInWrongFile { type_name, path } if type_name == "Other" && path == "src/Main.java"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_fail_to_compile_type_declared_twice() {
+    let error = compile_sources_and_expect_error(&[("One.java", "class Twice {}"), ("Two.java", "class Twice {}")]);
+
+    assert!(matches!(&error, CompileError::DuplicateClass(name) if name == "Twice"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_report_syntax_errors_with_the_path_of_their_file() {
+    let error = compile_sources_and_expect_error(&[("Good.java", "class Good {}"), ("Bad.java", "class Bad {")]);
+
+    assert!(
+        matches!(&error, CompileError::Source { path, error } if path == "Bad.java" && matches!(**error, CompileError::Syntax(_))),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_give_each_source_file_its_own_file_id() {
+    let error = compile_sources_and_expect_error(&[("One.java", "class One {}"), ("Two.java", "class Two {}"), ("Three.java", "class Three {")]);
+
+    assert!(
+        matches!(&error, CompileError::Source { path, error } if path == "Three.java"
+            && matches!(&**error, CompileError::Syntax(syntax_error) if syntax_error.span().file_id() == FileId(2))),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_report_syntax_errors_after_unicode_escapes_at_their_column_in_the_file() {
+    let error = compile_and_expect_error("class A { char c = '\\u0041'; int x = ; }");
+
+    assert!(
+        matches!(&error, CompileError::Syntax(syntax_error) if syntax_error.to_string().starts_with("1:38: ")),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_report_errors_in_method_bodies_with_their_file_position_and_method() {
+    let source = "class Broken {\n    int count(boolean flag) {\n        if (flag) return 1;\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:4:5: missing return statement in method count");
+
+    let source = "class Broken {\n    void run() {\n        return;\n        int y = 1;\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:4:9: unreachable statement in method run");
+
+    let source = "class Broken {\n    void run() {\n        final int x = 1;\n        x = 2;\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:4:9: cannot assign a value to final variable x in method run");
+
+    let source = "class Broken {\n    int run() {\n        int x = 1;\n        return missing;\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:4:9: cannot find field missing in class Broken in method run");
+    assert!(matches!(error.kind(), CompileError::UnknownField { field, .. } if field == "missing"), "Unexpected error {:?}", error);
+
+    let source = "class Broken {\n    void run() {\n        int x = \"one\";\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:3:9: incompatible types: java.lang.String cannot be converted to int in method run");
+
+    let source = "class Broken {\n    Broken() {\n        break;\n    }\n}\n";
+    let error = compile_sources_and_expect_error(&[("Broken.java", source)]);
+    assert_eq!(error.to_string(), "Broken.java:3:9: break outside switch or loop in method <init>");
+}
+
+#[test]
+fn should_compile_nested_inner_local_and_anonymous_classes() {
+    compile_and_assert_driver_output_is(
+        r#"
+        public class Outer {
+            private static String secret = "secret";
+            private String name = "outer";
+
+            private static void reveal() {
+                System.out.println("revealed");
+            }
+
+            public void run() {
+                new Inner().show();
+                new Runnable() {
+                    public void run() {
+                        System.out.println(name);
+                    }
+                }.run();
+                class Local {
+                    void show() {
+                        System.out.println(name);
+                    }
+                }
+                new Local().show();
+                new Greeting("hello") {
+                    void greet() {
+                        System.out.println(secret);
+                    }
+                }.greet();
+            }
+
+            public static void runStatic() {
+                new Runnable() {
+                    public void run() {
+                        Nested.show();
+                    }
+                }.run();
+            }
+
+            public void capture(int offset) {
+                int base = 100;
+                final String label = "sum";
+                long big = 5000000000L;
+                class Adder {
+                    int calc(int value) {
+                        return base + offset + value;
+                    }
+                }
+                System.out.println(new Adder().calc(1));
+                new Runnable() {
+                    public void run() {
+                        System.out.println(label + " " + new Adder().calc(2) + " " + big + " " + name);
+                    }
+                }.run();
+            }
+
+            public static class Nested {
+                public static void show() {
+                    System.out.println(secret);
+                    Outer.reveal();
+                }
+            }
+
+            public class Inner {
+                private String label = "inner";
+
+                public void show() {
+                    System.out.println(name);
+                    new Deeper().show();
+                }
+
+                class Deeper {
+                    void show() {
+                        System.out.println(label);
+                        System.out.println(name);
+                    }
+                }
+            }
+
+            abstract static class Greeting {
+                Greeting(String text) {}
+
+                abstract void greet();
+            }
+        }
+        "#,
+        r#"
+        import java.lang.reflect.Modifier;
+
+        public class Driver {
+            public static void main(String[] args) throws Exception {
+                Outer.Nested.show();
+                new Outer().run();
+                Outer.runStatic();
+                new Outer().capture(10);
+
+                Class<?> deeper = Class.forName("Outer$Inner$Deeper");
+                System.out.println(deeper.getNestHost().getName() + " " + Outer.class.getNestMembers().length);
+                System.out.println(Outer.Inner.class.isMemberClass() + " " + deeper.getDeclaringClass().getSimpleName());
+                Class<?> greeting = Class.forName("Outer$Greeting");
+                System.out.println(Modifier.toString(Outer.Nested.class.getModifiers()) + "|" + Modifier.toString(greeting.getModifiers()));
+                Class<?> anonymous = Class.forName("Outer$1");
+                System.out.println(anonymous.isAnonymousClass() + " " + anonymous.getEnclosingMethod().getName());
+                Class<?> local = Class.forName("Outer$1Local");
+                System.out.println(local.isLocalClass() + " " + local.getSimpleName() + " " + local.getEnclosingMethod().getName());
+                System.out.println(Class.forName("Outer$3").getEnclosingMethod().getName() + " " + Class.forName("Outer$2").getSuperclass().getSimpleName());
+            }
+        }
+        "#,
+        "secret\nrevealed\nouter\ninner\nouter\nouter\nouter\nsecret\nsecret\nrevealed\n111\nsum 112 5000000000 outer\n\
+        Outer 11\ntrue Inner\npublic static|abstract static\ntrue run\ntrue Local run\nrunStatic Greeting",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_captures_of_variables_that_are_not_effectively_final() {
+    let error = compile_method_and_expect_error("void run() { int count = 0; count++; class Local { int get() { return count; } } }");
+    assert!(matches!(&error, CompileError::NotEffectivelyFinal { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error("void run() { int count = 0; Runnable r = new Runnable() { public void run() { System.out.println(count); } }; count = 1; }");
+    assert!(matches!(&error, CompileError::NotEffectivelyFinal { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error("void run() { int count; class Local { int get() { return count; } } }");
+    assert!(matches!(&error, CompileError::UninitializedVariable { name, .. } if name == "count"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_fail_to_compile_inner_class_use_without_enclosing_instance() {
+    let error_for = |body: &str| {
+        let source = format!("public class Outer {{ private String name; class Inner {{}} {} }}", body);
+        compile_and_expect_error(&source)
+    };
+
+    let error = error_for("static class Nested { void show() { System.out.println(name); } }");
+    assert!(matches!(error.kind(), CompileError::NonStaticReference(name) if name == "name"), "Unexpected error {:?}", error);
+
+    let error = error_for("static void create() { new Inner(); }");
+    assert!(matches!(error.kind(), CompileError::NonStaticReference(name) if name == "Outer.this"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_int_arithmetic_and_bitwise_operators() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(1 + 2 * 3 - 4);
+            System.out.println((1 + 2) * 3);
+            System.out.println(17 / 5 % 2);
+            System.out.println(-7 >> 1);
+            System.out.println(-1 >>> 28);
+            System.out.println(~5 ^ 3);
+            System.out.println(6 & 3 | 8);
+            System.out.println(-2147483648);
+            System.out.println('a' + 1);
+        }
+        "#,
+        "3\n9\n1\n-4\n15\n-7\n10\n-2147483648\n98",
+    );
+}
+
+#[test]
+fn should_compile_string_concatenation() {
+    compile_and_assert_output_is(
+        r#"
+        public class Greeter {
+            static int count = 3;
+
+            public static void main(String[] args) {
+                System.out.println("count: " + count + '!' + 1.5f);
+                System.out.println(1 + 2 + "3" + 4 + 5);
+                System.out.println("con" + "stant");
+            }
+        }
+        "#,
+        "Greeter",
+        "count: 3!1.5\n3345\nconstant",
+    );
+}
+
+#[test]
+fn should_compile_increments_and_decrements_of_fields() {
+    compile_and_assert_output_is(
+        r#"
+        public class Counter {
+            static int count;
+            static byte small = 127;
+            static long big;
+            int instances;
+
+            public static void main(String[] args) {
+                count++;
+                ++count;
+                System.out.println(count++);
+                System.out.println(--count);
+                small++;
+                System.out.println(small);
+                big--;
+                System.out.println(big);
+                new Counter().increment();
+            }
+
+            void increment() {
+                instances++;
+                System.out.println(instances);
+            }
+        }
+        "#,
+        "Counter",
+        "2\n2\n-128\n-1\n1",
+    );
+}
+
+#[test]
+fn should_compile_increments_and_decrements_of_qualified_fields() {
+    compile_and_assert_output_is(
+        r#"
+        class Inc {
+            static int count;
+            long x = 5;
+
+            public static void main(String[] args) {
+                Inc.count++;
+                System.out.println(++Inc.count);
+                Inc inc = new Inc();
+                System.out.println(inc.x--);
+                inc.bump();
+                System.out.println(inc.x);
+            }
+
+            void bump() {
+                this.x++;
+                System.out.println(++this.x);
+            }
+        }
+        "#,
+        "Inc",
+        "2\n5\n6\n6",
+    );
+}
+
+#[test]
+fn should_fail_to_increment_the_length_of_an_array() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            args.length++;
+        }
+        "#,
+    );
+
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "length"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_comparisons_logical_operators_and_conditionals() {
+    compile_and_assert_output_is(
+        r#"
+        public class Logic {
+            static int small = 1;
+            static double nan = 0.0 / 0.0;
+            static String text = "text";
+
+            public static void main(String[] args) {
+                System.out.println(small < 2);
+                System.out.println(small >= 2L);
+                System.out.println(nan < 1.0 || nan > 1.0);
+                System.out.println(nan != nan);
+                System.out.println(small == 1 && !(small > 1));
+                System.out.println(text == text);
+                System.out.println(small > 0 ? "positive" : "not positive");
+                System.out.println(small > 5 ? 1 : 2.5f);
+                System.out.println(!true || false);
+            }
+        }
+        "#,
+        "Logic",
+        "true\nfalse\nfalse\ntrue\ntrue\ntrue\npositive\n2.5\nfalse",
+    );
+}
+
+#[test]
+fn should_compile_long_and_double_arithmetic() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(2147483647 + 1L);
+            System.out.println(1 / 2.0);
+            System.out.println(-1L >>> 60);
+        }
+        "#,
+        "2147483648\n0.5\n15",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_operators_applied_to_the_wrong_types() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println("a" - 1);
+        }
+        "#,
+    );
+    assert!(
+        matches!(error.kind(), CompileError::BadOperandTypes { operator, left, right } if operator == "-" && left == "java.lang.String" && right == "int"),
+        "Unexpected error {:?}",
+        error
+    );
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(!5);
+        }
+        "#,
+    );
+    assert!(
+        matches!(error.kind(), CompileError::BadOperandType { operator, operand } if operator == "!" && operand == "int"),
+        "Unexpected error {:?}",
+        error
+    );
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(1 ? 2 : 3);
+        }
+        "#,
+    );
+    assert!(
+        matches!(error.kind(), CompileError::IncompatibleTypes { expected, found } if expected == "boolean" && found == "int"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_compile_local_variables_assignments_and_increments() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            int count = 3;
+            String greeting = "Hello";
+            long big = 10000000000L;
+            count = count * 2;
+            System.out.println(count);
+            System.out.println(greeting + " " + count);
+            System.out.println(big);
+            count++;
+            ++count;
+            count--;
+            System.out.println(count);
+            System.out.println(count++);
+            System.out.println(--count);
+            var total = count + 1;
+            System.out.println(total);
+            int x, y;
+            x = y = 5;
+            System.out.println(x + y);
+            double d = 1;
+            d++;
+            System.out.println(d);
+        }
+        "#,
+        "6\nHello 6\n10000000000\n7\n7\n7\n8\n10\n2.0",
+    );
+}
+
+#[test]
+fn should_compile_parameters_blocks_and_locals_in_initializers() {
+    compile_and_assert_output_is(
+        r#"
+        public class Locals {
+            private int value = 1;
+            private static String label;
+
+            static {
+                String prefix = "label";
+                label = prefix + "!";
+            }
+
+            {
+                int doubled = value * 2;
+                value = doubled;
+            }
+
+            Locals(long start) {
+                value = value + 3;
+            }
+
+            public static void main(String[] args) {
+                Locals locals = new Locals(7L);
+                locals.run(40, 2L);
+            }
+
+            void run(int a, long b) {
+                System.out.println(value);
+                {
+                    int inner = a + 1;
+                    System.out.println(inner);
+                }
+                {
+                    String other = "reused";
+                    System.out.println(other);
+                }
+                int value = 6;
+                System.out.println(value);
+                System.out.println(b);
+                System.out.println(label);
+            }
+        }
+        "#,
+        "Locals",
+        "5\n41\nreused\n6\n2\nlabel!",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_misused_local_variables() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            int count = 1;
+            {
+                int count = 2;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::DuplicateVariable { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            int count;
+            System.out.println(count);
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UninitializedVariable { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            final int count = 1;
+            count++;
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::AssignmentToFinal { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            String text = 1;
+        }
+        "#,
+    );
+    assert!(
+        matches!(error.kind(), CompileError::IncompatibleTypes { expected, found } if expected == "java.lang.String" && found == "int"),
+        "Unexpected error {:?}",
+        error
+    );
+}
+
+#[test]
+fn should_assign_final_fields_while_initializing() {
+    compile_and_assert_output_is(
+        r#"
+        class Finals {
+            static final int LIMIT;
+            final String name;
+
+            static {
+                LIMIT = 3;
+            }
+
+            Finals() {
+                name = "finals";
+            }
+
+            public static void main(String[] args) {
+                System.out.println(new Finals().name + " " + LIMIT);
+            }
+        }
+        "#,
+        "Finals",
+        "finals 3",
+    );
+}
+
+#[test]
+fn should_compile_assignments_to_qualified_fields() {
+    compile_and_assert_output_is(
+        r#"
+        class Point {
+            final int x;
+            long y;
+            static int created;
+
+            Point(int x) {
+                this.x = x;
+                Point.created = Point.created + 1;
+            }
+
+            public static void main(String[] args) {
+                Point point = new Point(3);
+                long y = point.y = 4L;
+                new Point(5).y = 6L;
+                System.out.println(point.x + " " + point.y + " " + y + " " + created);
+            }
+        }
+        "#,
+        "Point",
+        "3 4 4 2",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_assignments_to_final_fields() {
+    let error = compile_method_and_expect_error(
+        r#"
+        static final int X = 1;
+
+        static void m() {
+            X = 2;
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "X"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        final int y = 1;
+
+        void m() {
+            y = 2;
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "y"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        static final int X;
+
+        static {
+            X = 1;
+        }
+
+        Simple() {
+            X = 2;
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "X"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        class Other {
+            static final int COUNT = 1;
+        }
+
+        class Simple {
+            static void m() {
+                Other.COUNT++;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "COUNT"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        final int y;
+
+        Simple(int y) {
+            this.y = y;
+        }
+
+        void reset() {
+            this.y = 0;
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "y"), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        class Other {
+            final int count = 1;
+        }
+
+        class Simple {
+            final int count;
+
+            Simple(Other other) {
+                other.count = 2;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        static void m(String[] args) {
+            args.length = 0;
+        }
+        "#,
+    );
+    assert!(matches!(&error, CompileError::AssignmentToFinal { name, .. } if name == "length"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_size_the_operand_stack_for_deeply_nested_expressions() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            System.out.println(1 + (2 * (3 + (4 * (5 + (6 * 7))))));
+            System.out.println(String.valueOf(1L + (2L * (3L + 10000000000L))));
+            double half = 0.5;
+            long big = 3L;
+            System.out.println("total " + (big + (half + (big * 2.0))));
+        }
+        "#,
+        "383\n20000000007\ntotal 9.5",
+    );
+}
+
+#[test]
+fn should_record_stack_map_frames_where_conditional_paths_join() {
+    compile_and_assert_output_is(
+        r#"
+        public class Frames {
+            int size;
+
+            Frames(boolean big) {
+                this(big ? 10 : 1);
+            }
+
+            Frames(int start) {
+                size = start;
+            }
+
+            void print(String label) {
+                System.out.println(label + " " + size);
+            }
+
+            public static void main(String[] args) {
+                long total = 5L;
+                double ratio = 0.5;
+                String label = total > 3 ? "big" : "small";
+                Frames frames = new Frames(total > 9 ? 8 : 7);
+                frames.print(label);
+                new Frames(total > 3).print(ratio > 1.0 ? "wide" : "narrow");
+                System.out.println(ratio < 1.0 ? total * 2 : total);
+                int last = 1 + (2 * (3 + (4 * (5 + (6 * (7 + (8 * (9 + (10 * (11 + (12 * (13 + 14))))))))))));
+                System.out.println(last > 0 ? label + " " + last : "none");
+            }
+        }
+        "#,
+        "Frames",
+        "big 7\nnarrow 10\n10\nbig 1290239",
+    );
+}
+
+#[test]
+fn should_choose_the_most_compact_stack_map_frames() {
+    let class_file = compile_method_to_class_file(
+        r#"
+        public static void main(String[] args) {
+            String label = "none";
+            if (args.length > 0) {
+                label = "some";
+            }
+            for (int i = 0; i < 2; i++) {
+                label = label + i;
+            }
+            System.out.println(label);
+        }
+        "#,
+    );
+
+    let main = class_file.methods.iter().find(|method| class_file.constant_pool.try_get_utf8(method.name_index).ok() == Some("main")).unwrap();
+    let Some(Attribute::Code { attributes, .. }) = main.attributes.first() else {
+        panic!("main has no code");
+    };
+    let Some(Attribute::StackMapTable { frames, .. }) = attributes.first() else {
+        panic!("main has no stack map frames");
+    };
+    // The object type of `label` is the same class constant in every frame, so that frames
+    // after the first can be given relative to the one before
+    assert!(
+        matches!(
+            frames.as_slice(),
+            [
+                StackFrame::AppendFrame { frame_type: 252, locals: label, .. },
+                StackFrame::AppendFrame { frame_type: 252, locals: index, .. },
+                StackFrame::SameFrame { .. },
+            ] if matches!(label.as_slice(), [VerificationType::Object { .. }]) && index == &[VerificationType::Integer]
+        ),
+        "Unexpected frames {:?}",
+        frames
+    );
+    let strings = class_file
+        .constant_pool
+        .iter()
+        .filter(|constant| matches!(constant, Constant::Class(name_index) if class_file.constant_pool.try_get_utf8(*name_index).ok() == Some("java/lang/String")))
+        .count();
+    assert_eq!(strings, 1);
+}
+
+#[test]
+fn should_compile_loops_labels_and_conditional_statements() {
+    compile_method_and_assert_output_is(
+        r#"
+        public static void main(String[] args) {
+            int total = 0;
+            for (int i = 0; i < 5; i++) {
+                if (i == 3) continue;
+                total = total + i;
+            }
+            System.out.println(total);
+
+            int n = 10;
+            while (n > 1) n = n / 2;
+            System.out.println(n);
+
+            int count = 0;
+            do {
+                count++;
+            } while (count < 3);
+            System.out.println(count);
+
+            outer:
+            for (int row = 0; row < 3; row++) {
+                for (int column = 0; column < 3; column++) {
+                    if (column > row) continue outer;
+                    if (row == 2) break outer;
+                    System.out.println(row + "," + column);
+                }
+            }
+
+            int found;
+            while (true) {
+                found = 42;
+                break;
+            }
+            System.out.println(found);
+
+            String kind;
+            if (total > 5) kind = "big"; else kind = "small";
+            System.out.println(kind);
+
+            block: {
+                if (count == 3) break block;
+                System.out.println("skipped");
+            }
+            for (;;) {
+                break;
+            }
+            System.out.println("done");
+        }
+        "#,
+        "7\n1\n3\n0,0\n1,0\n1,1\n42\nbig\ndone",
+    );
+}
+
+#[test]
+fn should_compile_enhanced_for_statements_over_arrays_and_iterables() {
+    compile_and_assert_output_is(
+        r#"
+        import java.util.ArrayList;
+        import java.util.List;
+
+        public class Elements {
+            public static void main(String[] args) {
+                for (String word : "to be".split(" ")) System.out.println(word);
+                int sum = 0;
+                for (int code : "ab".toCharArray()) sum = sum + code;
+                System.out.println(sum);
+                for (long wide : "c".toCharArray()) System.out.println(wide);
+
+                List names = List.of("x", "y");
+                for (Object name : names) System.out.println(name);
+                ArrayList letters = new ArrayList();
+                letters.add("p");
+                letters.add("q");
+                for (final String letter : letters) {
+                    if (letters.size() > 5) continue;
+                    System.out.println(letter + "!");
+                }
+                for (var item : List.of("z")) System.out.println(item);
+                for (String arg : args) System.out.println(arg);
+                System.out.println("end");
+            }
+        }
+        "#,
+        "Elements",
+        "to\nbe\n195\n99\nx\ny\np!\nq!\nz\nend",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_misplaced_jumps_and_unreachable_statements() {
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            break;
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::BreakOutsideLoop), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            block: {
+                continue block;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::NotALoopLabel(label) if label == "block"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            while (true) {
+                break missing;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UndefinedLabel(label) if label == "missing"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            again: while (true) {
+                again: for (;;) {}
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::DuplicateLabel(label) if label == "again"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            for (int i = 0; i < 3; i++) {
+                break;
+                System.out.println(i);
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UnreachableStatement { .. }), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            while (true) {}
+            System.out.println("never");
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UnreachableStatement { .. }), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            int count;
+            int limit = 3;
+            while (limit > 5) {
+                count = 1;
+            }
+            System.out.println(count);
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UninitializedVariable { name, .. } if name == "count"), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            for (int i : 5) {}
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::NotIterable(found) if found == "int"), "Unexpected error {:?}", error);
+}
+
+#[test]
+fn should_compile_return_statements_with_values_of_the_return_type() {
+    compile_and_assert_output_is(
+        r#"
+        public class Returns {
+            static int sign(int value) {
+                if (value < 0) return -1;
+                else if (value == 0) return 0;
+                return 1;
+            }
+
+            static long doubled(int value) {
+                return value * 2;
+            }
+
+            static double half(double value) {
+                return value / 2;
+            }
+
+            static String describe(boolean flag) {
+                return flag ? "yes" : "no";
+            }
+
+            static boolean isEven(int value) {
+                while (true) {
+                    if (value < 2) return value == 0;
+                    value = value - 2;
+                }
+            }
+
+            static Object first(String text) {
+                for (String word : text.split(" ")) {
+                    return word;
+                }
+                return "none";
+            }
+
+            static void countTo(int limit) {
+                for (int i = 1; ; i++) {
+                    System.out.println(i);
+                    if (i == limit) return;
+                }
+            }
+
+            public static void main(String[] args) {
+                System.out.println(Returns.sign(-5));
+                System.out.println(Returns.sign(0));
+                System.out.println(Returns.doubled(21));
+                System.out.println(Returns.half(3));
+                System.out.println(Returns.describe(Returns.isEven(10)));
+                System.out.println(Returns.describe(Returns.isEven(7)));
+                System.out.println(Returns.first("alpha beta"));
+                Returns.countTo(2);
+                System.out.println("end");
+            }
+        }
+        "#,
+        "Returns",
+        "-1\n0\n42\n1.5\nyes\nno\nalpha\n1\n2\nend",
+    );
+}
+
+#[test]
+fn should_fail_to_compile_missing_and_misplaced_returns() {
+    let error = compile_and_expect_error(
+        r#"
+        public class Broken {
+            int count(boolean flag) {
+                if (flag) return 1;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::MissingReturn { .. }), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        public class Broken {
+            String name() {
+                return;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::MissingReturnValue), "Unexpected error {:?}", error);
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            return 1;
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UnexpectedReturnValue), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        public class Broken {
+            String name() {
+                return 1;
+            }
+        }
+        "#,
+    );
+    assert!(
+        matches!(error.kind(), CompileError::IncompatibleTypes { expected, found } if expected == "java.lang.String" && found == "int"),
+        "Unexpected error {:?}",
+        error
+    );
+
+    let error = compile_method_and_expect_error(
+        r#"
+        public static void main(String[] args) {
+            return;
+            System.out.println("never");
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::UnreachableStatement { .. }), "Unexpected error {:?}", error);
+
+    let error = compile_and_expect_error(
+        r#"
+        public class Broken {
+            static {
+                return;
+            }
+        }
+        "#,
+    );
+    assert!(matches!(error.kind(), CompileError::ReturnOutsideMethod), "Unexpected error {:?}", error);
+}
@@ -55,4 +55,18 @@ fn should_load_system_class() {
     assert_eq!("out", sys_out.name());
     assert_eq!("java.io.PrintStream", sys_out.class());
 
-}
\ No newline at end of file
+}
+#[test]
+fn should_load_class_hierarchy() {
+    let mut harness = JavaApiHarness::new();
+
+    let runtime_exception = harness.load_class("java.lang.RuntimeException");
+    assert_eq!(Some("java.lang.Exception"), runtime_exception.super_class());
+    assert!(!runtime_exception.is_interface());
+
+    let string = harness.load_class("java.lang.String");
+    assert!(string.is_final());
+    assert!(string.interfaces().iter().any(|interface| interface == "java.lang.Comparable"));
+
+    assert!(harness.load_class("java.lang.Runnable").is_interface());
+}
@@ -0,0 +1,653 @@
+use java_compiler::scanner::{scan, scan_file, scan_translated, translate_unicode_escapes, ContextualKeyword, FileId, LexicalErrorKind, Literal, Token, TokenType, TranslatedSource};
+
+/// Scans source that is expected to be free of lexical errors
+fn tokens_of(source: &str) -> Vec<Token<'_>> {
+    let (tokens, diagnostics) = scan(source);
+    assert!(diagnostics.is_empty(), "Unexpected diagnostics for {:?}: {:?}", source, diagnostics);
+    tokens
+}
+
+fn diagnostics(source: &str) -> Vec<String> {
+    let (_, diagnostics) = scan(source);
+    diagnostics.iter().map(|d| d.to_string()).collect()
+}
+
+fn assert_single_literal(source: &str, expected_type: TokenType, expected_literal: Literal) {
+    let tokens = tokens_of(source);
+
+    assert_eq!(2, tokens.len(), "Expected a single token for {:?} but was {:?}", source, tokens);
+    assert_eq!(expected_type, tokens[0].token_type(), "Token type for {:?}", source);
+    assert_eq!(&expected_literal, tokens[0].literal(), "Literal for {:?}", source);
+    assert_eq!(TokenType::Eof, tokens[1].token_type());
+}
+
+#[test]
+fn should_scan_integer_literals() {
+    let cases = vec![
+        ("0", Literal::Int(0)),
+        ("42", Literal::Int(42)),
+        ("1_000_000", Literal::Int(1_000_000)),
+        ("2147483647", Literal::Int(2147483647)),
+        ("2147483648", Literal::Int(2147483648)),
+        ("0x7fff_ffff", Literal::Int(2147483647)),
+        ("0xFFFFFFFF", Literal::Int(-1)),
+        ("0X1F", Literal::Int(31)),
+        ("017", Literal::Int(15)),
+        ("0_17", Literal::Int(15)),
+        ("0b1010", Literal::Int(10)),
+        ("0B1000_0000_0000_0000_0000_0000_0000_0000", Literal::Int(i32::MIN as i64)),
+        ("0x1_0000_0000", Literal::Int(0x1_0000_0000)),
+    ];
+
+    for (source, expected) in cases {
+        assert_single_literal(source, TokenType::IntLiteral, expected);
+    }
+}
+
+#[test]
+fn should_scan_long_literals() {
+    let cases = vec![
+        ("0L", Literal::Long(0)),
+        ("42l", Literal::Long(42)),
+        ("9223372036854775807L", Literal::Long(i64::MAX as i128)),
+        ("9223372036854775808L", Literal::Long(9223372036854775808)),
+        ("0xFFFF_FFFF_FFFF_FFFFL", Literal::Long(-1)),
+        ("0x8000000000000000L", Literal::Long(i64::MIN as i128)),
+        ("0777L", Literal::Long(511)),
+        ("0b11L", Literal::Long(3)),
+    ];
+
+    for (source, expected) in cases {
+        assert_single_literal(source, TokenType::LongLiteral, expected);
+    }
+}
+
+#[test]
+fn should_scan_floating_point_literals() {
+    let cases = vec![
+        ("1.5f", TokenType::FloatLiteral, Literal::Float(1.5)),
+        ("3F", TokenType::FloatLiteral, Literal::Float(3.0)),
+        ("1e3f", TokenType::FloatLiteral, Literal::Float(1000.0)),
+        ("1e40f", TokenType::FloatLiteral, Literal::Float(f32::INFINITY)),
+        ("1.5", TokenType::DoubleLiteral, Literal::Double(1.5)),
+        ("1.", TokenType::DoubleLiteral, Literal::Double(1.0)),
+        (".25", TokenType::DoubleLiteral, Literal::Double(0.25)),
+        ("2d", TokenType::DoubleLiteral, Literal::Double(2.0)),
+        ("6.022e23", TokenType::DoubleLiteral, Literal::Double(6.022e23)),
+        ("1.5E-3", TokenType::DoubleLiteral, Literal::Double(1.5e-3)),
+        ("1e+2D", TokenType::DoubleLiteral, Literal::Double(100.0)),
+        ("1_000.000_1", TokenType::DoubleLiteral, Literal::Double(1000.0001)),
+        ("09.5", TokenType::DoubleLiteral, Literal::Double(9.5)),
+    ];
+
+    for (source, expected_type, expected) in cases {
+        assert_single_literal(source, expected_type, expected);
+    }
+}
+
+#[test]
+fn should_scan_numeric_literal_followed_by_punctuation() {
+    let tokens = tokens_of("foo(1, 2.5);");
+    let token_types: Vec<TokenType> = tokens.iter().map(|t| t.token_type()).collect();
+
+    assert_eq!(
+        vec![
+            TokenType::Identifier,
+            TokenType::LeftParen,
+            TokenType::IntLiteral,
+            TokenType::Comma,
+            TokenType::DoubleLiteral,
+            TokenType::RightParen,
+            TokenType::SemiColon,
+            TokenType::Eof,
+        ],
+        token_types
+    );
+}
+
+#[test]
+fn should_decode_escape_sequences_in_string_literals() {
+    let cases = vec![
+        (r#""plain""#, "plain"),
+        (r#""tab\there""#, "tab\there"),
+        (r#""line\nbreak""#, "line\nbreak"),
+        (r#""\b\f\r\s""#, "\u{8}\u{c}\r "),
+        (r#""say \"hi\"""#, "say \"hi\""),
+        (r#""it\'s""#, "it's"),
+        (r#""back\\slash""#, "back\\slash"),
+        (r#""\101\60\0""#, "A0\0"),
+        (r#""\377\400""#, "\u{ff} 0"),
+        (r#""A\uuu00e9""#, "Aé"),
+    ];
+
+    for (source, expected) in cases {
+        assert_single_literal(source, TokenType::String, Literal::String(expected.to_string()));
+    }
+}
+
+#[test]
+fn should_scan_character_literals() {
+    let cases = vec![
+        ("'a'", 'a' as u16),
+        ("'\\n'", '\n' as u16),
+        ("'\\''", '\'' as u16),
+        ("'\"'", '"' as u16),
+        ("'\\\\'", '\\' as u16),
+        ("'\\0'", 0),
+        ("'\\u00e9'", 0xE9),
+        ("'\\uD800'", 0xD800),
+    ];
+
+    for (source, expected) in cases {
+        assert_single_literal(source, TokenType::CharLiteral, Literal::Char(expected));
+    }
+}
+
+#[test]
+fn should_report_invalid_escape_with_its_position() {
+    assert_eq!(vec!["2:5: invalid escape sequence \\q"], diagnostics("\n\"abc\\q\""));
+}
+
+#[test]
+fn should_report_malformed_unicode_escape() {
+    assert_eq!(vec!["1:2: invalid escape sequence \\u00g"], diagnostics("'\\u00g1'"));
+}
+
+#[test]
+fn should_reject_empty_character_literal() {
+    assert_eq!(vec!["1:1: character literal '' must contain exactly one character"], diagnostics("''"));
+}
+
+fn token_types(source: &str) -> Vec<TokenType> {
+    tokens_of(source).iter().map(|t| t.token_type()).collect()
+}
+
+#[test]
+fn should_skip_spaces_tabs_and_form_feeds() {
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Identifier, TokenType::Identifier, TokenType::Identifier, TokenType::Eof],
+        token_types("a \tb\x0Cc\r\n\x0C d")
+    );
+}
+
+#[test]
+fn should_skip_line_comments() {
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::SemiColon, TokenType::Identifier, TokenType::Eof],
+        token_types("a; // trailing comment with \"quotes\" and /* markers\nb")
+    );
+}
+
+#[test]
+fn should_skip_block_comments() {
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Identifier, TokenType::Eof],
+        token_types("a /* spans\n * several // lines\n */ b")
+    );
+}
+
+#[test]
+fn should_end_block_comment_at_first_terminator_even_if_it_looks_nested() {
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Identifier, TokenType::SemiColon, TokenType::Eof],
+        token_types("a /* outer /* inner */ b;")
+    );
+}
+
+#[test]
+fn should_attach_javadoc_to_following_token() {
+    let tokens = tokens_of("/** Entry point. */\n// not documentation\npublic class /* plain */ Simple");
+
+    assert_eq!(Some("/** Entry point. */"), tokens[0].doc_comment());
+    assert_eq!(TokenType::Public, tokens[0].token_type());
+    assert_eq!(None, tokens[1].doc_comment());
+    assert_eq!(None, tokens[2].doc_comment());
+}
+
+#[test]
+fn should_not_treat_empty_block_comment_as_javadoc() {
+    let tokens = tokens_of("/**/ class");
+
+    assert_eq!(None, tokens[0].doc_comment());
+}
+
+#[test]
+fn should_report_unterminated_block_comment() {
+    assert_eq!(vec!["1:3: unterminated comment"], diagnostics("a /* never closed"));
+}
+
+#[test]
+fn should_scan_every_reserved_keyword() {
+    let cases = vec![
+        ("abstract", TokenType::Abstract),
+        ("assert", TokenType::Assert),
+        ("boolean", TokenType::Boolean),
+        ("break", TokenType::Break),
+        ("byte", TokenType::Byte),
+        ("case", TokenType::Case),
+        ("catch", TokenType::Catch),
+        ("char", TokenType::Char),
+        ("class", TokenType::Class),
+        ("const", TokenType::Const),
+        ("continue", TokenType::Continue),
+        ("default", TokenType::Default),
+        ("do", TokenType::Do),
+        ("double", TokenType::Double),
+        ("else", TokenType::Else),
+        ("enum", TokenType::Enum),
+        ("extends", TokenType::Extends),
+        ("final", TokenType::Final),
+        ("finally", TokenType::Finally),
+        ("float", TokenType::Float),
+        ("for", TokenType::For),
+        ("goto", TokenType::Goto),
+        ("if", TokenType::If),
+        ("implements", TokenType::Implements),
+        ("import", TokenType::Import),
+        ("instanceof", TokenType::Instanceof),
+        ("int", TokenType::Int),
+        ("interface", TokenType::Interface),
+        ("long", TokenType::Long),
+        ("native", TokenType::Native),
+        ("new", TokenType::New),
+        ("package", TokenType::Package),
+        ("private", TokenType::Private),
+        ("protected", TokenType::Protected),
+        ("public", TokenType::Public),
+        ("return", TokenType::Return),
+        ("short", TokenType::Short),
+        ("static", TokenType::Static),
+        ("strictfp", TokenType::Strictfp),
+        ("super", TokenType::Super),
+        ("switch", TokenType::Switch),
+        ("synchronized", TokenType::Synchronized),
+        ("this", TokenType::This),
+        ("throw", TokenType::Throw),
+        ("throws", TokenType::Throws),
+        ("transient", TokenType::Transient),
+        ("try", TokenType::Try),
+        ("void", TokenType::Void),
+        ("volatile", TokenType::Volatile),
+        ("while", TokenType::While),
+        ("_", TokenType::Underscore),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(vec![expected, TokenType::Eof], token_types(source), "Keyword {:?}", source);
+    }
+}
+
+#[test]
+fn should_scan_boolean_and_null_literals() {
+    assert_eq!(
+        vec![TokenType::True, TokenType::False, TokenType::Null, TokenType::Eof],
+        token_types("true false null")
+    );
+}
+
+#[test]
+fn should_scan_keyword_prefixes_as_identifiers() {
+    for source in ["classy", "publicly", "int32", "_underscored", "__", "Void", "nullable"] {
+        assert_eq!(vec![TokenType::Identifier, TokenType::Eof], token_types(source), "Identifier {:?}", source);
+    }
+}
+
+#[test]
+fn should_scan_contextual_keywords_as_identifiers() {
+    let cases = vec![
+        ("var", ContextualKeyword::Var),
+        ("record", ContextualKeyword::Record),
+        ("sealed", ContextualKeyword::Sealed),
+        ("permits", ContextualKeyword::Permits),
+        ("yield", ContextualKeyword::Yield),
+    ];
+
+    for (source, expected) in cases {
+        let tokens = tokens_of(source);
+
+        assert_eq!(TokenType::Identifier, tokens[0].token_type(), "Contextual keyword {:?}", source);
+        assert!(tokens[0].is_contextual_keyword(expected), "Contextual keyword {:?}", source);
+        assert_eq!(Some(expected), ContextualKeyword::from_lexeme(source));
+    }
+}
+
+#[test]
+fn should_not_treat_ordinary_identifiers_as_contextual_keywords() {
+    let tokens = tokens_of("variable");
+
+    assert!(!tokens[0].is_contextual_keyword(ContextualKeyword::Var));
+    assert_eq!(None, ContextualKeyword::from_lexeme("variable"));
+}
+
+#[test]
+fn should_scan_every_operator() {
+    let cases = vec![
+        ("+", TokenType::Plus),
+        ("-", TokenType::Minus),
+        ("*", TokenType::Star),
+        ("/", TokenType::Slash),
+        ("%", TokenType::Percent),
+        ("++", TokenType::PlusPlus),
+        ("--", TokenType::MinusMinus),
+        ("==", TokenType::EqualEqual),
+        ("!=", TokenType::BangEqual),
+        ("<", TokenType::Less),
+        ("<=", TokenType::LessEqual),
+        (">", TokenType::Greater),
+        (">=", TokenType::GreaterEqual),
+        ("&&", TokenType::AmpAmp),
+        ("||", TokenType::PipePipe),
+        ("!", TokenType::Bang),
+        ("&", TokenType::Amp),
+        ("|", TokenType::Pipe),
+        ("^", TokenType::Caret),
+        ("~", TokenType::Tilde),
+        ("<<", TokenType::LessLess),
+        (">>", TokenType::GreaterGreater),
+        (">>>", TokenType::GreaterGreaterGreater),
+        ("=", TokenType::Equal),
+        ("+=", TokenType::PlusEqual),
+        ("-=", TokenType::MinusEqual),
+        ("*=", TokenType::StarEqual),
+        ("/=", TokenType::SlashEqual),
+        ("%=", TokenType::PercentEqual),
+        ("&=", TokenType::AmpEqual),
+        ("|=", TokenType::PipeEqual),
+        ("^=", TokenType::CaretEqual),
+        ("<<=", TokenType::LessLessEqual),
+        (">>=", TokenType::GreaterGreaterEqual),
+        (">>>=", TokenType::GreaterGreaterGreaterEqual),
+        ("?", TokenType::Question),
+        (":", TokenType::Colon),
+        ("->", TokenType::Arrow),
+        ("::", TokenType::ColonColon),
+        ("@", TokenType::At),
+        ("...", TokenType::Ellipsis),
+        (".", TokenType::Dot),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(vec![expected, TokenType::Eof], token_types(source), "Operator {:?}", source);
+    }
+}
+
+#[test]
+fn should_scan_operators_with_maximal_munch() {
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::PlusPlus, TokenType::Plus, TokenType::Identifier, TokenType::Eof],
+        token_types("a+++b")
+    );
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::MinusMinus, TokenType::Minus, TokenType::Identifier, TokenType::Eof],
+        token_types("a---b")
+    );
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::GreaterGreaterGreaterEqual, TokenType::IntLiteral, TokenType::Eof],
+        token_types("x>>>=2")
+    );
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Ellipsis, TokenType::Identifier, TokenType::Eof],
+        token_types("String...args")
+    );
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Arrow, TokenType::Minus, TokenType::Identifier, TokenType::Eof],
+        token_types("x->-x")
+    );
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Slash, TokenType::Identifier, TokenType::Eof],
+        token_types("a/b")
+    );
+}
+
+#[test]
+fn should_split_shift_operator_when_closing_type_arguments() {
+    let tokens = tokens_of("List<List<String>>");
+    let closing = &tokens[5];
+    assert_eq!(TokenType::GreaterGreater, closing.token_type());
+
+    let (first, remainder) = closing.split_leading_greater().unwrap();
+
+    assert_eq!(TokenType::Greater, first.token_type());
+    assert_eq!(">", first.lexeme());
+    assert_eq!((16, 17), (first.start(), first.end()));
+    assert_eq!(TokenType::Greater, remainder.token_type());
+    assert_eq!(">", remainder.lexeme());
+    assert_eq!((17, 18), (remainder.start(), remainder.end()));
+}
+
+#[test]
+fn should_split_remaining_characters_into_the_matching_operator() {
+    let cases = vec![
+        (">>>", TokenType::GreaterGreater),
+        (">=", TokenType::Equal),
+        (">>=", TokenType::GreaterEqual),
+        (">>>=", TokenType::GreaterGreaterEqual),
+    ];
+
+    for (source, expected_remainder) in cases {
+        let (_, remainder) = tokens_of(source)[0].split_leading_greater().unwrap();
+        assert_eq!(expected_remainder, remainder.token_type(), "Splitting {:?}", source);
+    }
+
+    assert!(tokens_of(">")[0].split_leading_greater().is_none());
+}
+
+#[test]
+fn should_record_line_and_column_of_each_token() {
+    let tokens = tokens_of("class A {\n    /* a\n comment */ int x;\n}");
+
+    let positions: Vec<(u32, u32, u32, u32)> = tokens
+        .iter()
+        .map(|t| (t.span().start().line, t.span().start().column, t.span().end().line, t.span().end().column))
+        .collect();
+
+    assert_eq!(
+        vec![(1, 1, 1, 6), (1, 7, 1, 8), (1, 9, 1, 10), (3, 13, 3, 16), (3, 17, 3, 18), (3, 18, 3, 19), (4, 1, 4, 2), (4, 2, 4, 2)],
+        positions
+    );
+    assert_eq!("3:13", tokens[3].span().to_string());
+}
+
+#[test]
+fn should_record_file_of_each_token() {
+    let (tokens, _) = scan_file(FileId(3), "int x;");
+
+    assert!(tokens.iter().all(|t| t.span().file_id() == FileId(3)));
+    assert_eq!(FileId::default(), tokens_of("int x;")[0].span().file_id());
+}
+
+#[test]
+fn should_scan_non_ascii_string_content() {
+    assert_single_literal("\"héllo\"", TokenType::String, Literal::String("héllo".to_string()));
+    assert_single_literal("\"日本語\"", TokenType::String, Literal::String("日本語".to_string()));
+    assert_single_literal("\"😀 done\"", TokenType::String, Literal::String("😀 done".to_string()));
+    assert_single_literal("'é'", TokenType::CharLiteral, Literal::Char(0xE9));
+}
+
+#[test]
+fn should_scan_unicode_identifiers() {
+    let tokens = tokens_of("café 变量 $price π_2 a€");
+
+    let lexemes: Vec<&str> = tokens.iter().filter(|t| t.token_type() == TokenType::Identifier).map(|t| t.lexeme()).collect();
+    assert_eq!(vec!["café", "变量", "$price", "π_2", "a€"], lexemes);
+}
+
+#[test]
+fn should_count_columns_in_characters() {
+    let tokens = tokens_of("/* 注释 */ x = \"héllo\";\ny");
+
+    let columns: Vec<(u32, u32)> = tokens.iter().map(|t| (t.span().start().line, t.span().start().column)).collect();
+    assert_eq!(vec![(1, 10), (1, 12), (1, 14), (1, 21), (2, 1), (2, 2)], columns);
+    assert_eq!("\"héllo\"", tokens[2].lexeme());
+}
+
+#[test]
+fn should_count_carriage_returns_as_line_terminators() {
+    let tokens = tokens_of("a\r\nb\rc");
+
+    let lines: Vec<u32> = tokens.iter().map(|t| t.span().start().line).collect();
+    assert_eq!(vec![1, 2, 3, 3], lines);
+}
+
+#[test]
+fn should_translate_unicode_escapes_before_scanning() {
+    let source = translate_unicode_escapes("\\u0069nt caf\\u00e9 = \\uuu0031;");
+
+    let tokens = tokens_of(&source);
+
+    assert_eq!(
+        vec![TokenType::Int, TokenType::Identifier, TokenType::Equal, TokenType::IntLiteral, TokenType::SemiColon, TokenType::Eof],
+        tokens.iter().map(|t| t.token_type()).collect::<Vec<_>>()
+    );
+    assert_eq!("café", tokens[1].lexeme());
+}
+
+#[test]
+fn should_only_translate_unicode_escapes_after_an_even_number_of_backslashes() {
+    assert_eq!("\"\\\\u0041\"", translate_unicode_escapes("\"\\\\u0041\""));
+    assert_eq!("\"\\\\A\"", translate_unicode_escapes("\"\\\\\\u0041\""));
+
+    let source = translate_unicode_escapes("\"\\\\u0041\"");
+    assert_single_literal(&source, TokenType::String, Literal::String("\\u0041".to_string()));
+}
+
+#[test]
+fn should_end_line_comment_at_escaped_line_terminator() {
+    let source = translate_unicode_escapes("// comment \\u000a x");
+
+    assert_eq!(vec![TokenType::Identifier, TokenType::Eof], token_types(&source));
+}
+
+#[test]
+fn should_count_columns_of_unicode_escapes_as_written() {
+    let source = TranslatedSource::new("\\u0041 = #\\u000a b");
+
+    let (tokens, diagnostics) = scan_translated(FileId::default(), &source);
+
+    let positions: Vec<(u32, u32)> = tokens.iter().map(|t| (t.span().start().line, t.span().start().column)).collect();
+    assert_eq!(vec![(1, 1), (1, 8), (1, 18), (1, 19)], positions);
+    assert_eq!(vec!["1:10: unexpected character '#'"], diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>());
+}
+
+#[test]
+fn should_translate_escaped_surrogate_pairs_and_keep_lone_surrogates() {
+    let source = translate_unicode_escapes("\"\\uD83D\\uDE00\" '\\uD800'");
+
+    let tokens = tokens_of(&source);
+
+    assert_eq!(&Literal::String("😀".to_string()), tokens[0].literal());
+    assert_eq!(&Literal::Char(0xD800), tokens[1].literal());
+}
+
+#[test]
+fn should_report_unexpected_characters_and_carry_on() {
+    let (tokens, diagnostics) = scan("a # b\n  `c");
+
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::Identifier, TokenType::Identifier, TokenType::Eof],
+        tokens.iter().map(|t| t.token_type()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![&LexicalErrorKind::UnexpectedCharacter('#'), &LexicalErrorKind::UnexpectedCharacter('`')],
+        diagnostics.iter().map(|d| d.kind()).collect::<Vec<_>>()
+    );
+    assert_eq!(("1:3", "2:3"), (diagnostics[0].span().to_string().as_str(), diagnostics[1].span().to_string().as_str()));
+}
+
+#[test]
+fn should_report_unterminated_string_at_end_of_line() {
+    let (tokens, diagnostics) = scan("x = \"never closed;\ny;");
+
+    assert_eq!(&LexicalErrorKind::UnterminatedString, diagnostics[0].kind());
+    assert_eq!(("1:5", 1, 19), (diagnostics[0].span().to_string().as_str(), diagnostics[0].span().end().line, diagnostics[0].span().end().column));
+    assert_eq!(&Literal::String("never closed;".to_string()), tokens[2].literal());
+    assert_eq!(
+        vec![TokenType::Identifier, TokenType::SemiColon, TokenType::Eof],
+        tokens[3..].iter().map(|t| t.token_type()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn should_report_unterminated_character_literal() {
+    assert_eq!(vec!["1:1: unterminated character literal"], diagnostics("'a\nb"));
+}
+
+#[test]
+fn should_report_malformed_numbers() {
+    assert_eq!(
+        vec!["1:1: malformed numeric literal 0x", "1:4: malformed numeric literal 1__", "1:8: malformed numeric literal 09"],
+        diagnostics("0x 1__ 09")
+    );
+}
+
+#[test]
+fn should_report_every_lexical_problem_in_one_scan() {
+    assert_eq!(
+        vec![
+            "1:8: invalid escape sequence \\q",
+            "2:1: unexpected character '#'",
+            "3:5: character literal 'ab' must contain exactly one character",
+            "4:1: unterminated comment",
+        ],
+        diagnostics("s = \"a \\q\";\n#\nc = 'ab';\n/* open")
+    );
+}
+
+fn text_block(source: &str) -> String {
+    match tokens_of(source)[0].literal() {
+        Literal::String(value) => value.clone(),
+        literal => panic!("Expected a string literal, but was {:?}", literal),
+    }
+}
+
+#[test]
+fn should_strip_incidental_white_space_from_text_blocks() {
+    let source = "\"\"\"\n        SELECT *\n          FROM t\n        \"\"\"";
+
+    assert_eq!("SELECT *\n  FROM t\n", text_block(source));
+}
+
+#[test]
+fn should_indent_text_block_relative_to_closing_delimiter() {
+    let source = "\"\"\"\n        {\n          \"a\": 1\n        }\n    \"\"\"";
+
+    assert_eq!("    {\n      \"a\": 1\n    }\n", text_block(source));
+}
+
+#[test]
+fn should_not_end_text_block_with_newline_when_delimiter_closes_last_line() {
+    assert_eq!("one\ntwo", text_block("\"\"\"\n    one\n    two\"\"\""));
+}
+
+#[test]
+fn should_remove_trailing_white_space_and_keep_blank_lines_empty() {
+    assert_eq!("a\n\nb\n", text_block("\"\"\"  \t\n    a   \n      \n    b\t\n    \"\"\""));
+}
+
+#[test]
+fn should_interpret_escapes_after_stripping_text_blocks() {
+    assert_eq!("one two\nkeep  \nq\"\"\"\n", text_block("\"\"\"\n    one \\\n    two\n    keep \\s\n    q\\\"\"\"\n    \"\"\""));
+}
+
+#[test]
+fn should_normalise_line_terminators_in_text_blocks() {
+    assert_eq!("a\nb\n", text_block("\"\"\"\r\n  a\r\n  b\r\n  \"\"\""));
+}
+
+#[test]
+fn should_track_lines_across_text_blocks() {
+    let tokens = tokens_of("x = \"\"\"\n  a\n  \"\"\"; y");
+
+    assert_eq!(TokenType::String, tokens[2].token_type());
+    assert_eq!((1, 3), (tokens[2].span().start().line, tokens[2].span().end().line));
+    assert_eq!("3:8", tokens[4].span().to_string());
+}
+
+#[test]
+fn should_report_malformed_text_blocks() {
+    assert_eq!(
+        vec!["1:1: the opening delimiter of a text block must be followed by a line terminator"],
+        diagnostics("\"\"\"inline\"\"\"")
+    );
+    assert_eq!(vec!["1:1: unterminated text block"], diagnostics("\"\"\"\n  never closed"));
+}